@@ -102,7 +102,7 @@ pub enum Commands {
         #[arg(short, long, default_value = "10")]
         limit: usize,
 
-        /// Filter by source type (github, awesome, all)
+        /// Filter by source type (github, awesome, marketplace, codex, local, profiles, all) or channel name
         #[arg(short, long, default_value = "all")]
         source: String,
 
@@ -122,13 +122,41 @@ pub enum Commands {
         #[arg(long)]
         preset: Option<String>,
 
-        /// Sort order (stars, updated, forks)
-        #[arg(long, default_value = "stars")]
+        /// Sort order (relevance, stars, updated)
+        ///
+        /// relevance combines text match, stars, recency and channel weight
+        #[arg(long, default_value = "relevance")]
         sort: String,
 
         /// Refresh cache for Awesome Lists
         #[arg(long)]
         refresh: bool,
+
+        /// Search the local index only (cached channels and local profiles, no network)
+        #[arg(long)]
+        offline: bool,
+
+        /// Rebuild the offline index from cached content and local profiles (implies --offline)
+        #[arg(long)]
+        reindex: bool,
+    },
+
+    /// Find skills, commands, agents and rules in local and installed profiles
+    Find {
+        /// Text to look for in component names, descriptions and rule text
+        query: String,
+
+        /// Only this component kind (skill, command, agent, rule)
+        #[arg(short, long)]
+        kind: Option<String>,
+
+        /// Only this profile
+        #[arg(short, long)]
+        profile: Option<String>,
+
+        /// Additional install targets to check (history and ~/.claude are always checked)
+        #[arg(long)]
+        path: Vec<PathBuf>,
     },
 
     /// Manage hubs (repositories that aggregate channels)
@@ -202,6 +230,18 @@ pub enum Commands {
         /// Exclude additional directories (e.g., --exclude=node_modules)
         #[arg(long, value_name = "DIR")]
         exclude: Vec<String>,
+
+        /// Only install files in these categories (repeatable or comma-separated)
+        #[arg(long = "category", value_name = "NAME", value_delimiter = ',')]
+        category: Vec<String>,
+
+        /// Skip files in these categories (repeatable or comma-separated)
+        #[arg(long, value_name = "NAME", value_delimiter = ',')]
+        exclude_category: Vec<String>,
+
+        /// Drop a recorded category selection and install every file
+        #[arg(long, conflicts_with_all = ["category", "exclude_category"])]
+        all_categories: bool,
     },
 
     /// Upgrade installed profile to latest
@@ -252,6 +292,18 @@ pub enum Commands {
         /// Exclude additional directories (e.g., --exclude=node_modules)
         #[arg(long, value_name = "DIR")]
         exclude: Vec<String>,
+
+        /// Only upgrade files in these categories (repeatable or comma-separated)
+        #[arg(long = "category", value_name = "NAME", value_delimiter = ',')]
+        category: Vec<String>,
+
+        /// Skip files in these categories (repeatable or comma-separated)
+        #[arg(long, value_name = "NAME", value_delimiter = ',')]
+        exclude_category: Vec<String>,
+
+        /// Drop a recorded category selection and upgrade every file
+        #[arg(long, conflicts_with_all = ["category", "exclude_category"])]
+        all_categories: bool,
     },
 
     /// Show diff between profile and installed files
@@ -286,6 +338,14 @@ pub enum Commands {
         /// Exclude additional directories (e.g., --exclude=node_modules)
         #[arg(long, value_name = "DIR")]
         exclude: Vec<String>,
+
+        /// Only compare files in these categories (repeatable or comma-separated)
+        #[arg(long = "category", value_name = "NAME", value_delimiter = ',')]
+        category: Vec<String>,
+
+        /// Skip files in these categories (repeatable or comma-separated)
+        #[arg(long, value_name = "NAME", value_delimiter = ',')]
+        exclude_category: Vec<String>,
     },
 
     /// Remove installed profile
@@ -355,6 +415,10 @@ pub enum Commands {
         /// Status for all platforms (Claude + Codex)
         #[arg(long, group = "platform")]
         all: bool,
+
+        /// Estimate the context tokens of installed files
+        #[arg(long)]
+        budget: bool,
     },
 
     /// Copy an existing profile to a new name
@@ -383,9 +447,13 @@ pub enum Commands {
         #[arg(long)]
         path: Option<PathBuf>,
 
-        /// Apply without confirmation
-        #[arg(short, long)]
+        /// Skip the confirmation and apply every change without reviewing hunks
+        #[arg(short, long, visible_alias = "yes", short_alias = 'y')]
         force: bool,
+
+        /// Rule parameter (repeatable)
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param_arg)]
+        params: Vec<(String, String)>,
     },
 
     /// Manage customization rules
@@ -394,6 +462,12 @@ pub enum Commands {
         action: RuleAction,
     },
 
+    /// Manage rule pipelines (source profile -> rules -> output profile)
+    Pipeline {
+        #[command(subcommand)]
+        action: PipelineAction,
+    },
+
     /// Manage snapshots of installed files
     Snapshot {
         #[command(subcommand)]
@@ -416,19 +490,27 @@ pub enum Commands {
         /// Show detailed file list
         #[arg(short, long)]
         detailed: bool,
+
+        /// Re-classify all files instead of using cached LLM results
+        #[arg(long)]
+        refresh: bool,
     },
 
     /// Fuse multiple profiles by category into a new profile
     ///
     /// Example: dot-agent fusion profile-a:plan profile-b:execute -o my-fused
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Fusion {
+        #[command(subcommand)]
+        action: Option<FusionAction>,
+
         /// Profile:category pairs (e.g., "superpowers:plan", "rust-tdd:execute")
         #[arg(required = true)]
         specs: Vec<String>,
 
         /// Output profile name
         #[arg(short, long, required = true)]
-        output: String,
+        output: Option<String>,
 
         /// Force overwrite if output profile exists
         #[arg(short, long)]
@@ -441,6 +523,11 @@ pub enum Commands {
         /// Include uncategorized files from first profile
         #[arg(long)]
         include_uncategorized: bool,
+
+        /// How to resolve files provided by several inputs: first-wins, last-wins,
+        /// rename-both, concat (markdown), merge-json (hooks/mcp/settings) or interactive
+        #[arg(short, long, default_value = "last-wins")]
+        strategy: String,
     },
 
     /// Sync modified installed files back to the source profile
@@ -642,7 +729,9 @@ pub enum ProfileAction {
 
     /// Import a directory or git repository as a profile
     Import {
-        /// Source directory or git URL (https://... or git@...)
+        /// Source directory, git URL (https://... or git@...), <host>:<repo>
+        /// for a git host configured in config.toml, or name@channel for a
+        /// marketplace or local channel
         source: String,
 
         /// Profile name (default: directory/repo name)
@@ -662,6 +751,69 @@ pub enum ProfileAction {
         force: bool,
     },
 
+    /// Copy a single skill, command, agent or rule into a profile
+    ///
+    /// The origin is recorded in the profile's .dot-agent.toml so the
+    /// component can be updated later with 'profile update-component'.
+    AddComponent {
+        /// Target profile
+        profile: String,
+
+        /// Component path in the source (e.g. skills/tdd, commands/review.md);
+        /// optional for Codex catalog skills
+        path: Option<PathBuf>,
+
+        /// Source: profile name, directory, git URL, <host>:<repo>,
+        /// plugin@marketplace, entry@local-channel or skill@codex-channel
+        #[arg(long)]
+        from: String,
+
+        /// Path in the target profile (default: same as in the source)
+        #[arg(long = "as")]
+        dest: Option<PathBuf>,
+
+        /// Git branch/tag to fetch (git sources)
+        #[arg(short, long)]
+        branch: Option<String>,
+
+        /// Overwrite the component if it already exists
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Pull upstream changes for components added with 'add-component'
+    UpdateComponent {
+        /// Profile name
+        profile: String,
+
+        /// Component path (default: all recorded components)
+        path: Option<String>,
+
+        /// Overwrite components that were modified locally
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Show where a profile's files came from and which have drifted or changed upstream
+    Provenance {
+        /// Profile name
+        name: String,
+
+        /// Fetch git and channel origins to check them for updates
+        #[arg(long)]
+        fetch: bool,
+
+        /// List unchanged files too
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// Estimate context tokens per file and category
+    Stats {
+        /// Profile name
+        name: String,
+    },
+
     /// Apply rule to profile, creating new customized profile (alias for 'rule apply')
     ApplyRule {
         /// Source profile name
@@ -682,6 +834,10 @@ pub enum ProfileAction {
         /// Skip confirmation prompt
         #[arg(short, long)]
         yes: bool,
+
+        /// Rule parameter (repeatable)
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param_arg)]
+        params: Vec<(String, String)>,
     },
 
     /// Manage profile snapshots
@@ -844,9 +1000,13 @@ pub enum RuleAction {
         #[arg(short, long)]
         dry_run: bool,
 
-        /// Skip confirmation prompt
+        /// Accept every change without reviewing hunks
         #[arg(short, long)]
         yes: bool,
+
+        /// Rule parameter (repeatable)
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param_arg)]
+        params: Vec<(String, String)>,
     },
 
     /// Apply rule to installed files (alias for top-level 'apply')
@@ -863,10 +1023,115 @@ pub enum RuleAction {
         #[arg(long)]
         path: Option<PathBuf>,
 
-        /// Apply without confirmation
+        /// Skip the confirmation and apply every change without reviewing hunks
+        #[arg(short, long, visible_alias = "yes", short_alias = 'y')]
+        force: bool,
+
+        /// Rule parameter (repeatable)
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param_arg)]
+        params: Vec<(String, String)>,
+    },
+
+    /// Import a rule from a URL, a channel or a hub
+    Import {
+        /// Rule URL, or name@channel / name@hub
+        source: String,
+
+        /// Local rule name (default: the published name)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Overwrite an existing rule
         #[arg(short, long)]
         force: bool,
     },
+
+    /// Search rules published by channels and hubs
+    Search {
+        /// Search query (matches name, description or tag; empty lists all)
+        #[arg(default_value = "")]
+        query: String,
+
+        /// Only search these channels or hubs (repeatable)
+        #[arg(short, long = "channel")]
+        channels: Vec<String>,
+    },
+
+    /// Pull new revisions of imported rules
+    Update {
+        /// Rule name (default: every imported rule)
+        name: Option<String>,
+
+        /// Overwrite local edits
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Run the rule's test cases (rules/<name>.tests/)
+    Test {
+        /// Rule name
+        name: String,
+
+        /// Run only this test case
+        #[arg(short, long)]
+        case: Option<String>,
+
+        /// Replay recorded responses instead of calling the LLM
+        #[arg(long, conflicts_with = "record")]
+        recorded: bool,
+
+        /// Call the LLM and save its responses for later --recorded runs
+        #[arg(long)]
+        record: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PipelineAction {
+    /// List pipelines
+    List,
+
+    /// Add or replace a pipeline
+    Add {
+        /// Pipeline name
+        name: String,
+
+        /// Source profile
+        #[arg(short, long)]
+        source: String,
+
+        /// Rules to apply in order (repeatable or comma-separated)
+        #[arg(short, long, required = true, value_delimiter = ',')]
+        rules: Vec<String>,
+
+        /// Output profile (default: {source}-{name})
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Parameter for a rule's steps (repeatable)
+        #[arg(long = "param", value_name = "RULE:KEY=VALUE", value_parser = parse_step_param_arg)]
+        params: Vec<(String, (String, String))>,
+    },
+
+    /// Remove a pipeline (the output profile is kept)
+    Remove {
+        /// Pipeline name
+        name: String,
+    },
+
+    /// Re-apply the pipeline if its source or rules changed
+    Run {
+        /// Pipeline name
+        name: String,
+
+        /// Run even if nothing changed, replacing the output profile (even if edited by hand)
+        #[arg(short, long)]
+        force: bool,
+
+        /// Ignore cached step outputs (call the LLM again)
+        #[arg(long)]
+        refresh: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -918,26 +1183,64 @@ pub enum HubAction {
     },
 
     /// Refresh hub cache (fetch latest channel list)
+    ///
+    /// Content is trusted on first use; later changes are shown as a diff
+    /// and must be approved before they replace the cache.
     Refresh {
         /// Hub name (refresh all if not specified)
         name: Option<String>,
+
+        /// Approve changed content without prompting
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Validate a hub index (hub.toml or hub.json) in a directory
+    Validate {
+        /// Hub directory
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+    },
+
+    /// Scaffold a new hub directory with an example hub index
+    Init {
+        /// Hub directory (created if missing)
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Hub name (default: directory name)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Write hub.json instead of hub.toml
+        #[arg(long)]
+        json: bool,
+
+        /// Overwrite an existing hub index
+        #[arg(short, long)]
+        force: bool,
     },
 }
 
 #[derive(Subcommand)]
 pub enum ChannelAction {
-    /// Discover available channels from all hubs
+    /// Discover available channels from all hubs (run 'hub refresh' first)
     Discover,
 
     /// Add/enable a channel
     ///
-    /// Use type flags (-m, -a, -d, -H) for explicit channel type:
+    /// Use type flags (-m, -a, -d, -H, -g) for explicit channel type:
     ///   -m/--marketplace: Claude Code Plugin Marketplace
     ///   -a/--awesome: Awesome List (curated markdown)
-    ///   -d/--direct: Direct GitHub repository
+    ///   -d/--direct: Direct repository
     ///   -H/--hub: Channel from a registered Hub
+    ///   -g/--git-host: Repository search on a host from config.toml [[hosts]]
+    ///   -l/--local: Local directory or file:// share (offline)
+    ///
+    /// Repositories may be given as owner/repo (GitHub), <host>:<repo>
+    /// or a URL on any configured git host.
     Add {
-        /// URL or name (GitHub repo, marketplace, awesome list, or hub channel)
+        /// URL or name (repo, marketplace, awesome list, hub channel, or git host)
         source: String,
 
         /// Add as Marketplace (Claude Code Plugin)
@@ -956,9 +1259,21 @@ pub enum ChannelAction {
         #[arg(short = 'H', long, group = "channel_type")]
         hub: Option<String>,
 
+        /// Add repository search for a configured git host (source is the host name)
+        #[arg(short = 'g', long, group = "channel_type")]
+        git_host: bool,
+
+        /// Add a local directory or file:// share (profiles, marketplace.json or hub index)
+        #[arg(short = 'l', long, group = "channel_type")]
+        local: bool,
+
         /// Custom name for the channel
         #[arg(short, long)]
         name: Option<String>,
+
+        /// Search ranking weight (default 1.0; higher ranks this channel's results first)
+        #[arg(short, long)]
+        weight: Option<f64>,
     },
 
     /// List enabled channels, or list importable profiles from a specific channel
@@ -986,9 +1301,35 @@ pub enum ChannelAction {
     },
 
     /// Refresh channel cache
+    ///
+    /// Content is trusted on first use; later changes are shown as a diff
+    /// and must be approved before they replace the cache.
     Refresh {
         /// Channel name (refresh all if not specified)
         name: Option<String>,
+
+        /// Approve changed content without prompting
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FusionAction {
+    /// Re-run a fused profile's recipe and apply upstream changes
+    ///
+    /// Files edited in the fused profile are kept unless --force is given.
+    Refresh {
+        /// Fused profile name
+        output: String,
+
+        /// Overwrite files edited in the fused profile
+        #[arg(short, long)]
+        force: bool,
+
+        /// Dry run (show what would change)
+        #[arg(short, long)]
+        dry_run: bool,
     },
 }
 
@@ -1101,3 +1442,17 @@ pub enum HistoryAction {
         file: PathBuf,
     },
 }
+
+/// Parse a `--param key=value` argument
+fn parse_param_arg(arg: &str) -> Result<(String, String), String> {
+    dot_agent_core::rule::parse_param(arg).map_err(|e| e.to_string())
+}
+
+/// Parse a `--param rule:key=value` argument
+fn parse_step_param_arg(arg: &str) -> Result<(String, (String, String)), String> {
+    let (rule, param) = arg
+        .split_once(':')
+        .filter(|(rule, _)| !rule.is_empty())
+        .ok_or_else(|| format!("invalid parameter '{}': expected RULE:KEY=VALUE", arg))?;
+    Ok((rule.to_string(), parse_param_arg(param)?))
+}
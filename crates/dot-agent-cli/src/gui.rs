@@ -893,7 +893,11 @@ impl DotAgentApp {
             }
         };
 
-        let rule = match self.rule_manager.get(rule_name) {
+        let rule = match self
+            .rule_manager
+            .get(rule_name)
+            .and_then(|r| r.with_params(&Default::default()))
+        {
             Ok(r) => r,
             Err(e) => {
                 self.status_message = Some((format!("Rule error: {e}"), MessageType::Error));
@@ -901,7 +905,17 @@ impl DotAgentApp {
             }
         };
 
-        let executor = RuleExecutor::new(&rule, &self.profile_manager);
+        let backend = match dot_agent_core::Config::load(&self.base_dir)
+            .and_then(|config| config.llm_backend())
+        {
+            Ok(backend) => backend,
+            Err(e) => {
+                self.status_message = Some((format!("LLM error: {e}"), MessageType::Error));
+                return;
+            }
+        };
+
+        let executor = RuleExecutor::new(&rule, &self.profile_manager).with_backend(backend);
         let new_name = if self.new_profile_name.is_empty() {
             None
         } else {
@@ -6,19 +6,28 @@ use clap::{CommandFactory, Parser};
 use clap_complete::generate;
 use colored::Colorize;
 
-use dot_agent_core::channel::ChannelManager;
+use dot_agent_core::channel::{
+    ChannelManager, DiffLine, LocalChannel, RefreshOutcome, TrustDiff, TrustKind, TrustStore,
+};
 use dot_agent_core::config::Config;
 use dot_agent_core::install::{
     ConflictResolver, FileStatus, InstallOptions, Installer, Resolution,
 };
+use dot_agent_core::path::validate_relative_path;
 use dot_agent_core::platform::InstallTarget;
-use dot_agent_core::profile::{IgnoreConfig, ProfileManager};
-use dot_agent_core::{DotAgentError, Metadata, Result};
+use dot_agent_core::profile::{
+    ComponentFinder, ComponentKind, ComponentOrigin, ComponentProvenance, IgnoreConfig, MatchField,
+    ProfileManager,
+};
+use dot_agent_core::{
+    CategoryFilter, CollectedFile, DotAgentError, FileChange, FileChangeKind, FusionConflict,
+    FusionResolver, FusionStrategy, GitHostRegistry, Metadata, Result,
+};
 
 mod args;
 use args::{
-    ChannelAction, Cli, Commands, ConfigAction, HistoryAction, HubAction, ProfileAction,
-    ProfileSnapshotAction, RuleAction, Shell, SnapshotAction,
+    ChannelAction, Cli, Commands, ConfigAction, FusionAction, HistoryAction, HubAction,
+    PipelineAction, ProfileAction, ProfileSnapshotAction, RuleAction, Shell, SnapshotAction,
 };
 
 #[cfg(feature = "gui")]
@@ -92,6 +101,65 @@ impl ConflictResolver for InteractiveResolver {
     }
 }
 
+/// Interactive resolver that prompts for each fusion conflict.
+struct InteractiveFusionResolver;
+
+impl FusionResolver for InteractiveFusionResolver {
+    fn resolve(
+        &self,
+        conflict: &FusionConflict,
+        candidates: &[CollectedFile],
+    ) -> dot_agent_core::Result<FusionStrategy> {
+        let profiles: Vec<_> = candidates
+            .iter()
+            .map(|c| c.source_profile.as_str())
+            .collect();
+        println!();
+        println!(
+            "  {} -- provided by {}",
+            conflict.path.display().to_string().yellow(),
+            profiles.join(", ")
+        );
+
+        let mut choices = vec![
+            ("f", FusionStrategy::FirstWins, "first wins"),
+            ("l", FusionStrategy::LastWins, "last wins"),
+            ("r", FusionStrategy::RenameBoth, "rename both"),
+        ];
+        if FusionStrategy::Concat.applies_to(&conflict.path) {
+            choices.push(("c", FusionStrategy::Concat, "concatenate"));
+        }
+        if FusionStrategy::MergeJson.applies_to(&conflict.path) {
+            choices.push(("m", FusionStrategy::MergeJson, "merge JSON"));
+        }
+        let labels: Vec<_> = choices
+            .iter()
+            .map(|(key, _, label)| format!("[{}] {}", key, label))
+            .collect();
+        println!("    {}  [a]bort", labels.join("  "));
+
+        loop {
+            print!("    > ");
+            Write::flush(&mut io::stdout())?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+
+            let input = input.trim().to_lowercase();
+            if input == "a" {
+                return Err(DotAgentError::FusionConflict {
+                    path: conflict.path.clone(),
+                });
+            }
+            if let Some((_, strategy, _)) = choices.iter().find(|(key, _, _)| *key == input) {
+                return Ok(*strategy);
+            }
+            let keys: Vec<_> = choices.iter().map(|(key, _, _)| *key).collect();
+            println!("    Invalid choice. Enter {} or a.", keys.join(", "));
+        }
+    }
+}
+
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
@@ -121,9 +189,13 @@ fn main() -> ExitCode {
     let result = match cli.command {
         // Top-level aliases
         Some(Commands::List) => handle_profile(ProfileAction::List, &base_dir),
-        Some(Commands::Installed { path, global }) => {
-            handle_status(&base_dir, path.as_deref(), global, InstallTarget::default())
-        }
+        Some(Commands::Installed { path, global }) => handle_status(
+            &base_dir,
+            path.as_deref(),
+            global,
+            InstallTarget::default(),
+            false,
+        ),
         Some(Commands::Default { profile, clear }) => handle_default(&base_dir, profile, clear),
         Some(Commands::Outdated { path, global }) => {
             handle_outdated(&base_dir, path.as_deref(), global)
@@ -142,9 +214,18 @@ fn main() -> ExitCode {
             preset,
             sort,
             refresh,
+            offline,
+            reindex,
         }) => handle_search(
             &base_dir, &query, limit, &source, min_stars, keywords, topic, preset, &sort, refresh,
+            offline, reindex,
         ),
+        Some(Commands::Find {
+            query,
+            kind,
+            profile,
+            path,
+        }) => handle_find(&base_dir, &query, kind.as_deref(), profile, path),
         Some(Commands::Hub { action }) => handle_hub(action, &base_dir),
         Some(Commands::Channel { action }) => handle_channel(action, &base_dir),
         Some(Commands::Completions { shell, install }) => {
@@ -163,6 +244,9 @@ fn main() -> ExitCode {
             no_merge,
             include,
             exclude,
+            category,
+            exclude_category,
+            all_categories,
         }) => {
             let target = resolve_install_target(codex, claude, all, path.as_deref());
             handle_install(
@@ -175,6 +259,7 @@ fn main() -> ExitCode {
                 no_prefix,
                 no_merge,
                 build_ignore_config(&base_dir, &include, &exclude),
+                build_category_filter(&base_dir, category, exclude_category, all_categories),
                 target,
             )
         }
@@ -191,6 +276,9 @@ fn main() -> ExitCode {
             no_merge,
             include,
             exclude,
+            category,
+            exclude_category,
+            all_categories,
         }) => {
             let target = resolve_install_target(codex, claude, all, path.as_deref());
             handle_upgrade(
@@ -203,6 +291,7 @@ fn main() -> ExitCode {
                 no_prefix,
                 no_merge,
                 build_ignore_config(&base_dir, &include, &exclude),
+                build_category_filter(&base_dir, category, exclude_category, all_categories),
                 target,
             )
         }
@@ -215,6 +304,8 @@ fn main() -> ExitCode {
             all,
             include,
             exclude,
+            category,
+            exclude_category,
         }) => {
             let target = resolve_install_target(codex, claude, all, path.as_deref());
             handle_diff(
@@ -223,6 +314,7 @@ fn main() -> ExitCode {
                 path.as_deref(),
                 global,
                 build_ignore_config(&base_dir, &include, &exclude),
+                build_category_filter(&base_dir, category, exclude_category, false),
                 target,
             )
         }
@@ -258,9 +350,10 @@ fn main() -> ExitCode {
             codex,
             claude,
             all,
+            budget,
         }) => {
             let target = resolve_install_target(codex, claude, all, path.as_deref());
-            handle_status(&base_dir, path.as_deref(), global, target)
+            handle_status(&base_dir, path.as_deref(), global, target, budget)
         }
         Some(Commands::Copy {
             source,
@@ -272,34 +365,57 @@ fn main() -> ExitCode {
             profile,
             path,
             force,
-        }) => handle_apply(&base_dir, &rule, profile.as_deref(), path.as_deref(), force),
+            params,
+        }) => handle_apply(
+            &base_dir,
+            &rule,
+            profile.as_deref(),
+            path.as_deref(),
+            force,
+            params,
+        ),
         Some(Commands::Rule { action }) => handle_rule(action, &base_dir),
+        Some(Commands::Pipeline { action }) => handle_pipeline(action, &base_dir),
         Some(Commands::Snapshot { action }) => handle_snapshot(action, &base_dir),
         Some(Commands::Categories {
             profile,
             uncategorized,
             category,
             detailed,
+            refresh,
         }) => handle_categories(
             &base_dir,
             &profile,
             uncategorized,
             category.as_deref(),
             detailed,
+            refresh,
         ),
         Some(Commands::Fusion {
+            action:
+                Some(FusionAction::Refresh {
+                    output,
+                    force,
+                    dry_run,
+                }),
+            ..
+        }) => handle_fusion_refresh(&base_dir, &output, force, dry_run),
+        Some(Commands::Fusion {
+            action: None,
             specs,
             output,
             force,
             dry_run,
             include_uncategorized,
+            strategy,
         }) => handle_fusion(
             &base_dir,
             &specs,
-            &output,
+            output.as_deref().unwrap_or_default(),
             force,
             dry_run,
             include_uncategorized,
+            &strategy,
         ),
         Some(Commands::Switch {
             profile,
@@ -453,6 +569,83 @@ fn resolve_base_dir(cli_base: Option<PathBuf>) -> PathBuf {
         .unwrap_or_else(|| PathBuf::from(".dot-agent"))
 }
 
+fn handle_find(
+    base_dir: &Path,
+    query: &str,
+    kind: Option<&str>,
+    profile: Option<String>,
+    targets: Vec<PathBuf>,
+) -> Result<()> {
+    let mut finder = ComponentFinder::new(base_dir.to_path_buf()).with_targets(targets);
+    if let Some(kind) = kind {
+        let kind: ComponentKind = kind
+            .parse()
+            .map_err(|message| DotAgentError::ConfigParseSimple { message })?;
+        finder = finder.with_kind(kind);
+    }
+    if let Some(profile) = profile {
+        finder = finder.with_profile(profile);
+    }
+
+    let matches = finder.find(query)?;
+    if matches.is_empty() {
+        println!("No components matching '{}'", query);
+        return Ok(());
+    }
+
+    println!("{} components matching '{}'", matches.len(), query.cyan());
+    println!();
+
+    let home = dirs::home_dir();
+    for m in &matches {
+        let badge = match m.component.kind {
+            ComponentKind::Skill => "[SKILL]".green(),
+            ComponentKind::Command => "[CMD]".cyan(),
+            ComponentKind::Agent => "[AGENT]".magenta(),
+            ComponentKind::Rule => "[RULE]".blue(),
+        };
+        let origin = if m.local {
+            String::new()
+        } else {
+            format!(" {}", "(installed only)".dimmed())
+        };
+        println!(
+            "{} {} {}:{}{}",
+            badge,
+            m.component.name.bold(),
+            m.profile.yellow(),
+            m.component.path.display(),
+            origin
+        );
+        if let Some(description) = &m.component.description {
+            println!("    {}", description);
+        }
+        if m.matched == MatchField::Content {
+            println!("    {}", "matched in rule text".dimmed());
+        }
+        if !m.categories.is_empty() {
+            println!("    Categories: {}", m.categories.join(", "));
+        }
+        let targets: Vec<String> = m
+            .targets
+            .iter()
+            .map(
+                |t| match home.as_ref().and_then(|h| t.strip_prefix(h).ok()) {
+                    Some(rel) => format!("~/{}", rel.display()),
+                    None => t.display().to_string(),
+                },
+            )
+            .collect();
+        if targets.is_empty() {
+            println!("    Installed: {}", "not installed".dimmed());
+        } else {
+            println!("    Installed: {}", targets.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn handle_search(
     base_dir: &Path,
@@ -465,8 +658,10 @@ fn handle_search(
     _preset: Option<String>,
     sort: &str,
     _refresh: bool,
+    offline: bool,
+    reindex: bool,
 ) -> Result<()> {
-    use dot_agent_core::channel::{ChannelManager, ChannelType, SearchOptions};
+    use dot_agent_core::channel::{ChannelManager, ChannelType, SearchOptions, PROFILES_SOURCE};
 
     // Create channel manager first to resolve channel type filters
     let manager = ChannelManager::new(base_dir.to_path_buf())?;
@@ -505,6 +700,17 @@ fn handle_search(
                 .map(|c| c.name.clone())
                 .collect()
         }
+        "profiles" => vec![PROFILES_SOURCE.to_string()],
+        "local" => {
+            // Filter to all local directory channels
+            manager
+                .registry()
+                .list_enabled()
+                .iter()
+                .filter(|c| c.channel_type == ChannelType::Local)
+                .map(|c| c.name.clone())
+                .collect()
+        }
         other => vec![other.to_string()],
     };
 
@@ -517,8 +723,22 @@ fn handle_search(
         sort: Some(sort.to_string()),
     };
 
-    // Perform search
-    let results = manager.search(query, &options)?;
+    // Perform search (local profiles are only in the offline index)
+    let offline = offline || reindex || channel_filter == "profiles";
+    if reindex {
+        let index = manager.rebuild_index()?;
+        println!(
+            "{} Indexed {} entries from {} sources",
+            "[OK]".green(),
+            index.len(),
+            index.sources().len()
+        );
+    }
+    let results = if offline {
+        manager.search_offline(query, &options)?
+    } else {
+        manager.search(query, &options)?
+    };
 
     if results.is_empty() {
         println!("No results found for: {}", query);
@@ -552,10 +772,13 @@ fn handle_search(
             .unwrap_or(ChannelType::Direct);
 
         let source_badge = match channel_type {
+            _ if profile_ref.channel == PROFILES_SOURCE => "[PROFILE]".yellow(),
             ChannelType::GitHubGlobal => "[GH]".green(),
             ChannelType::AwesomeList => "[AL]".blue(),
             ChannelType::Marketplace => "[MP]".magenta(),
             ChannelType::CodexCatalog => "[CX]".cyan(),
+            ChannelType::GitHost => "[GIT]".green(),
+            ChannelType::Local => "[LOCAL]".blue(),
             _ => "[??]".dimmed(),
         };
 
@@ -575,7 +798,17 @@ fn handle_search(
             stars_str,
             desc
         );
-        println!("   {}", profile_ref.url.dimmed());
+        let also_in = profile_ref
+            .metadata
+            .get("channels")
+            .map(|c| format!("  (in: {})", c))
+            .unwrap_or_default();
+        println!(
+            "   {}  {}{}",
+            profile_ref.url.dimmed(),
+            format!("score {:.2}", profile_ref.score).dimmed(),
+            also_in.dimmed()
+        );
         println!();
     }
 
@@ -584,12 +817,16 @@ fn handle_search(
         "  {}",
         "dot-agent profile import <url> --name <profile-name>".dimmed()
     );
+    println!(
+        "  {}",
+        "dot-agent profile import <name>@<channel>  # marketplace/local channels".dimmed()
+    );
 
     Ok(())
 }
 
 fn handle_hub(action: HubAction, base_dir: &Path) -> Result<()> {
-    use dot_agent_core::channel::{Hub, HubRegistry};
+    use dot_agent_core::channel::{Hub, HubFormat, HubIndex, HubRegistry};
 
     let mut registry = HubRegistry::load(base_dir).unwrap_or_else(|_| HubRegistry::with_official());
 
@@ -641,11 +878,12 @@ fn handle_hub(action: HubAction, base_dir: &Path) -> Result<()> {
         HubAction::Remove { name } => {
             let removed = registry.remove(&name)?;
             registry.save(base_dir)?;
+            forget_trust(base_dir, TrustKind::Hub, &removed.name)?;
 
             println!();
             println!("{} {}", "Removed hub:".red(), removed.name);
         }
-        HubAction::Refresh { name } => {
+        HubAction::Refresh { name, yes } => {
             let hubs: Vec<_> = if let Some(n) = name {
                 registry.get(&n).into_iter().cloned().collect()
             } else {
@@ -657,14 +895,78 @@ fn handle_hub(action: HubAction, base_dir: &Path) -> Result<()> {
                 return Ok(());
             }
 
+            let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
+
             println!();
             println!("Refreshing hubs...");
 
             for hub in &hubs {
                 print!("  {} ... ", hub.name);
-                // TODO: Implement actual refresh (fetch channels.toml from hub)
-                println!("{}", "OK".green());
+                io::stdout().flush()?;
+
+                match channel_mgr.refresh_hub(hub) {
+                    Ok(outcome) => report_refresh(
+                        outcome,
+                        "hub",
+                        &hub.name,
+                        yes,
+                        || channel_mgr.approve_hub(&hub.name),
+                        || channel_mgr.reject_hub(&hub.name),
+                    )?,
+                    Err(e) => println!("{} {}", "FAILED".red(), e),
+                }
+            }
+        }
+        HubAction::Validate { dir } => {
+            let index = HubIndex::load_dir(&dir)?;
+
+            println!();
+            println!(
+                "{} {} (schema v{}, {} channel(s), {} rule(s))",
+                "[OK]".green(),
+                index.name.cyan(),
+                index.schema_version,
+                index.channels.len(),
+                index.rules.len()
+            );
+            for channel in &index.channels {
+                println!(
+                    "  {} [{}] {}",
+                    channel.name.cyan(),
+                    channel.channel_type,
+                    channel.url.dimmed()
+                );
             }
+            for rule in &index.rules {
+                println!("  {} [rule] {}", rule.name.cyan(), rule.url.dimmed());
+            }
+        }
+        HubAction::Init {
+            dir,
+            name,
+            json,
+            force,
+        } => {
+            let hub_name = name.unwrap_or_else(|| {
+                std::fs::canonicalize(&dir)
+                    .unwrap_or_else(|_| dir.clone())
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "hub".to_string())
+            });
+            let format = if json {
+                HubFormat::Json
+            } else {
+                HubFormat::Toml
+            };
+            let path = HubIndex::init_dir(&dir, &hub_name, format, force)?;
+
+            println!();
+            println!("{} {}", "Created hub index:".green(), path.display());
+            println!();
+            println!("{}", "Next steps:".dimmed());
+            println!("  {}", "Edit the [[channels]] entries, then run:".dimmed());
+            println!("  dot-agent hub validate {}", dir.display());
         }
     }
 
@@ -726,10 +1028,35 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
             println!("{}", "Available Channels:".cyan().bold());
             println!();
 
+            let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
             for hub in hub_registry.list() {
                 println!("From hub: {} {}", hub.name.yellow(), hub.url.dimmed());
-                // TODO: Fetch and parse channels from hub
-                println!("  (channel discovery not yet implemented)");
+                match channel_mgr.hub_index(&hub.name) {
+                    Ok(Some(index)) if !index.channels.is_empty() => {
+                        for channel in &index.channels {
+                            let tags = if channel.tags.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" #{}", channel.tags.join(" #"))
+                            };
+                            println!(
+                                "  {} [{}]{}",
+                                channel.name.cyan(),
+                                channel.channel_type,
+                                tags.dimmed()
+                            );
+                            if let Some(desc) = &channel.description {
+                                println!("    {}", desc);
+                            }
+                            println!("    dot-agent channel add {} -H {}", channel.name, hub.name);
+                        }
+                    }
+                    Ok(Some(_)) => println!("  (no channels listed)"),
+                    Ok(None) => {
+                        println!("  (not cached; run 'dot-agent hub refresh {}')", hub.name)
+                    }
+                    Err(e) => println!("  {} {}", "[WARN]".yellow(), e),
+                }
                 println!();
             }
 
@@ -748,7 +1075,10 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
             awesome,
             direct,
             hub,
+            git_host,
+            local,
             name,
+            weight,
         } => {
             // Determine channel type from flags or auto-detect
             let (channel, type_hint) = if marketplace {
@@ -787,10 +1117,53 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
                         .to_string()
                 });
                 (Channel::from_url(&channel_name, &source), "direct")
+            } else if git_host {
+                // Repository search on a configured git host
+                let config = Config::load(base_dir)?;
+                if GitHostRegistry::from_config(&config).get(&source).is_none() {
+                    return Err(DotAgentError::GitHostNotFound { name: source });
+                }
+                let channel_name = name.unwrap_or_else(|| source.clone());
+                (Channel::git_host(&channel_name, &source), "git-host")
+            } else if local {
+                // Local directory or file:// share
+                let location = if source.starts_with("file://") {
+                    source.clone()
+                } else {
+                    std::fs::canonicalize(&source)
+                        .map_err(|_| DotAgentError::TargetNotFound {
+                            path: PathBuf::from(&source),
+                        })?
+                        .display()
+                        .to_string()
+                };
+                LocalChannel::open(&location)?;
+                let channel_name = name.unwrap_or_else(|| {
+                    dot_agent_core::channel::local_path(&location)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "local".to_string())
+                });
+                (Channel::local(&channel_name, &location), "local")
             } else if let Some(hub_name) = hub {
-                // From Hub
+                // From Hub: use the channel definition from the cached hub index
                 let channel_name = name.unwrap_or_else(|| source.clone());
-                (Channel::from_hub(&channel_name, &hub_name, &source), "hub")
+                let hub_root = HubRegistry::load(base_dir)
+                    .ok()
+                    .and_then(|r| r.get(&hub_name).map(|h| h.url.clone()))
+                    .map(|url| dot_agent_core::channel::local_path(&url))
+                    .filter(|p| p.is_dir());
+                let listed = ChannelManager::new(base_dir.to_path_buf())?
+                    .hub_index(&hub_name)?
+                    .and_then(|index| {
+                        index
+                            .get(&source)
+                            .and_then(|c| c.to_channel(&channel_name, hub_root.as_deref()))
+                    });
+                match listed {
+                    Some(channel) => (channel, "hub"),
+                    None => (Channel::from_hub(&channel_name, &hub_name, &source), "hub"),
+                }
             } else {
                 // Auto-detect (deprecated)
                 eprintln!(
@@ -826,6 +1199,10 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
                 }
             };
 
+            let channel = match weight {
+                Some(w) => channel.with_weight(w),
+                None => channel,
+            };
             let channel_name = channel.name.clone();
             registry.add(channel)?;
             registry.save(base_dir)?;
@@ -1000,7 +1377,30 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
                             println!("  {}", "(no cached data)".dimmed());
                         }
                     }
-                    ChannelType::GitHubGlobal | ChannelType::Hub => {
+                    ChannelType::Local => {
+                        let manager = ChannelManager::new(base_dir.to_path_buf())?;
+                        let local = manager.local_channel(&channel_name)?;
+                        let entries = local.entries()?;
+                        for entry in &entries {
+                            let version_str = entry
+                                .version
+                                .as_ref()
+                                .map(|v| format!(" ({})", v.dimmed()))
+                                .unwrap_or_default();
+                            println!("  {}{}", entry.name.cyan(), version_str);
+                            if let Some(desc) = &entry.description {
+                                println!("    {}", desc.dimmed());
+                            }
+                            if entry.path.is_none() {
+                                if let Some(url) = &entry.url {
+                                    println!("    {} {}", "(remote)".yellow(), url.dimmed());
+                                }
+                            }
+                        }
+                        println!();
+                        println!("{} {}", "Total:".dimmed(), entries.len());
+                    }
+                    ChannelType::GitHubGlobal | ChannelType::GitHost | ChannelType::Hub => {
                         println!("  {}", "List not supported for this channel type.".yellow());
                         println!("  {}", "Use 'dot-agent search <query>' instead.".dimmed());
                     }
@@ -1028,6 +1428,8 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
                 println!("{}", "Registered Channels:".cyan().bold());
                 println!();
 
+                let trust_store = TrustStore::load(base_dir).unwrap_or_default();
+
                 for channel in channels {
                     let status = if channel.enabled {
                         "enabled".green()
@@ -1043,6 +1445,22 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
                     if let Some(url) = channel.source.url() {
                         println!("    URL: {}", url.dimmed());
                     }
+                    if let Some(weight) = channel.weight {
+                        println!("    Weight: {}", weight);
+                    }
+                    if let Some(record) = trust_store.get(TrustKind::Channel, &channel.name) {
+                        let pending = if record.pending.is_some() {
+                            " (changes pending approval)".yellow().to_string()
+                        } else {
+                            String::new()
+                        };
+                        println!(
+                            "    Trusted: {} at {}{}",
+                            short_fingerprint(&record.fingerprint).dimmed(),
+                            record.trusted_at.dimmed(),
+                            pending
+                        );
+                    }
                     println!();
                 }
             }
@@ -1050,6 +1468,7 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
         ChannelAction::Remove { name } => {
             let removed = registry.remove(&name)?;
             registry.save(base_dir)?;
+            forget_trust(base_dir, TrustKind::Channel, &removed.name)?;
 
             println!();
             println!("{} {}", "Removed channel:".red(), removed.name);
@@ -1068,7 +1487,7 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
             println!();
             println!("{} {}", "Disabled channel:".yellow(), name);
         }
-        ChannelAction::Refresh { name } => {
+        ChannelAction::Refresh { name, yes } => {
             let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
 
             let channels: Vec<_> = if let Some(n) = name {
@@ -1090,7 +1509,14 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
                 io::stdout().flush()?;
 
                 match channel_mgr.refresh_channel(&channel.name) {
-                    Ok(()) => println!("{}", "OK".green()),
+                    Ok(outcome) => report_refresh(
+                        outcome,
+                        "channel",
+                        &channel.name,
+                        yes,
+                        || channel_mgr.approve_channel(&channel.name),
+                        || channel_mgr.reject_channel(&channel.name),
+                    )?,
                     Err(e) => println!("{} {}", "FAILED".red(), e),
                 }
             }
@@ -1100,6 +1526,102 @@ fn handle_channel(action: ChannelAction, base_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Print the result of a channel/hub refresh, prompting to approve changed content
+fn report_refresh(
+    outcome: RefreshOutcome,
+    kind: &str,
+    name: &str,
+    yes: bool,
+    approve: impl Fn() -> Result<String>,
+    reject: impl Fn() -> Result<()>,
+) -> Result<()> {
+    match outcome {
+        RefreshOutcome::FirstUse { fingerprint } => {
+            println!(
+                "{} (trusted on first use: {})",
+                "OK".green(),
+                short_fingerprint(&fingerprint).dimmed()
+            );
+        }
+        RefreshOutcome::Unchanged => println!("{}", "OK".green()),
+        RefreshOutcome::Skipped => println!("{}", "SKIP".dimmed()),
+        RefreshOutcome::PendingApproval(diff) => {
+            println!("{}", "CHANGED".yellow().bold());
+            print_trust_diff(&diff);
+
+            let approved = if yes {
+                true
+            } else {
+                print!("  Approve changes to {} '{}'? [y/N]: ", kind, name);
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+            };
+
+            if approved {
+                let fingerprint = approve()?;
+                println!(
+                    "  {} now trusting {}",
+                    "[OK]".green(),
+                    short_fingerprint(&fingerprint).dimmed()
+                );
+            } else {
+                reject()?;
+                println!("  {} kept previously trusted content", "[SKIP]".yellow());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a diff between trusted and newly fetched content
+fn print_trust_diff(diff: &TrustDiff) {
+    const MAX_LINES: usize = 40;
+
+    println!(
+        "  {} {} -> {} (+{} -{})",
+        diff.file.cyan(),
+        short_fingerprint(&diff.previous_fingerprint).dimmed(),
+        short_fingerprint(&diff.new_fingerprint).dimmed(),
+        diff.added(),
+        diff.removed()
+    );
+    for line in diff.lines.iter().take(MAX_LINES) {
+        match line {
+            DiffLine::Added(l) => println!("    {}", format!("+ {}", l).green()),
+            DiffLine::Removed(l) => println!("    {}", format!("- {}", l).red()),
+        }
+    }
+    if diff.lines.len() > MAX_LINES {
+        println!(
+            "    {}",
+            format!("... and {} more lines", diff.lines.len() - MAX_LINES).dimmed()
+        );
+    }
+}
+
+/// Shorten "sha256:<hex>" to "sha256:<first 12 hex chars>"
+fn short_fingerprint(fingerprint: &str) -> &str {
+    let end = fingerprint
+        .find(':')
+        .map(|i| i + 13)
+        .unwrap_or(12)
+        .min(fingerprint.len());
+    &fingerprint[..end]
+}
+
+/// Drop the trust record of a removed channel or hub
+fn forget_trust(base_dir: &Path, kind: TrustKind, name: &str) -> Result<()> {
+    let mut store = TrustStore::load(base_dir)?;
+    if store.remove(kind, name).is_some() {
+        store.save(base_dir)?;
+    }
+    Ok(())
+}
+
 /// Build IgnoreConfig from global config + CLI options
 /// Priority: CLI options > config file > defaults
 fn build_ignore_config(base_dir: &Path, include: &[String], exclude: &[String]) -> IgnoreConfig {
@@ -1124,6 +1646,23 @@ fn build_ignore_config(base_dir: &Path, include: &[String], exclude: &[String])
     config
 }
 
+/// Build a category selection from CLI options
+/// None = keep the selection recorded in the target
+fn build_category_filter(
+    base_dir: &Path,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    all_categories: bool,
+) -> Option<CategoryFilter> {
+    if all_categories {
+        return Some(CategoryFilter::default());
+    }
+    if include.is_empty() && exclude.is_empty() {
+        return None;
+    }
+    Some(CategoryFilter::new(include, exclude).with_mode(get_classification_mode(base_dir)))
+}
+
 fn handle_config(action: ConfigAction, base_dir: &Path) -> Result<()> {
     match action {
         ConfigAction::Get { key } => {
@@ -1324,7 +1863,39 @@ fn handle_profile(action: ProfileAction, base_dir: &Path) -> Result<()> {
             branch,
             force,
         } => {
-            handle_import(&manager, &source, name, path, branch, force)?;
+            handle_import(base_dir, &manager, &source, name, path, branch, force)?;
+        }
+        ProfileAction::AddComponent {
+            profile,
+            path,
+            from,
+            dest,
+            branch,
+            force,
+        } => {
+            handle_add_component(
+                base_dir,
+                &manager,
+                &profile,
+                path.as_deref(),
+                &from,
+                dest.as_deref(),
+                branch.as_deref(),
+                force,
+            )?;
+        }
+        ProfileAction::UpdateComponent {
+            profile,
+            path,
+            force,
+        } => {
+            handle_update_component(base_dir, &manager, &profile, path.as_deref(), force)?;
+        }
+        ProfileAction::Provenance { name, fetch, all } => {
+            handle_provenance(base_dir, &manager, &name, fetch, all)?;
+        }
+        ProfileAction::Stats { name } => {
+            handle_profile_stats(base_dir, &manager, &name)?;
         }
         ProfileAction::ApplyRule {
             profile,
@@ -1332,6 +1903,7 @@ fn handle_profile(action: ProfileAction, base_dir: &Path) -> Result<()> {
             name,
             dry_run,
             yes,
+            params,
         } => {
             // Delegate to rule apply (creates new profile)
             handle_rule(
@@ -1341,6 +1913,7 @@ fn handle_profile(action: ProfileAction, base_dir: &Path) -> Result<()> {
                     name,
                     dry_run,
                     yes,
+                    params,
                 },
                 base_dir,
             )?;
@@ -1364,6 +1937,7 @@ fn handle_install(
     no_prefix: bool,
     no_merge: bool,
     ignore_config: IgnoreConfig,
+    categories: Option<CategoryFilter>,
     install_target: InstallTarget,
 ) -> Result<()> {
     use dot_agent_core::platform::Platform;
@@ -1375,8 +1949,8 @@ fn handle_install(
     let (actual_profile_name, profile) = if let Some((plugin, marketplace)) =
         parse_marketplace_ref(profile_name)
     {
-        // Fetch and import plugin from marketplace
-        let profile = import_marketplace_plugin(base_dir, &plugin, &marketplace, &manager, force)?;
+        // Fetch and import plugin from marketplace (or local channel)
+        let profile = import_channel_entry(base_dir, &plugin, &marketplace, None, &manager, force)?;
         (profile.name.clone(), profile)
     } else {
         // Normal local profile
@@ -1421,6 +1995,9 @@ fn handle_install(
     if no_merge {
         println!("{}", "(no merge)".yellow());
     }
+    if let Some(filter) = categories.as_ref().filter(|f| !f.is_empty()) {
+        println!("Categories: {}", filter.to_string().cyan());
+    }
 
     let on_file = |status: &str, path: &str| {
         let status_str = match status {
@@ -1466,6 +2043,9 @@ fn handle_install(
         if let Some(p) = platform {
             opts = opts.platform(*p);
         }
+        if let Some(filter) = &categories {
+            opts = opts.categories(filter.clone());
+        }
 
         let result = installer.install(&profile, target_dir, &opts)?;
 
@@ -1614,6 +2194,7 @@ fn handle_upgrade(
     no_prefix: bool,
     no_merge: bool,
     ignore_config: IgnoreConfig,
+    categories: Option<CategoryFilter>,
     _install_target: InstallTarget, // TODO: Implement multi-platform support
 ) -> Result<()> {
     let manager = ProfileManager::new(base_dir.to_path_buf());
@@ -1631,6 +2212,7 @@ fn handle_upgrade(
     if no_merge {
         println!("{}", "(no merge)".yellow());
     }
+    print_category_selection(&target_dir, profile_name, categories.as_ref());
     println!();
     println!("Checking for updates...");
 
@@ -1647,13 +2229,16 @@ fn handle_upgrade(
         println!("  {} {}", status_str, path);
     };
 
-    let opts = InstallOptions::new()
+    let mut opts = InstallOptions::new()
         .force(force)
         .dry_run(dry_run)
         .no_prefix(no_prefix)
         .no_merge(no_merge)
         .ignore_config(ignore_config.clone())
         .on_file(Some(&on_file));
+    if let Some(filter) = categories {
+        opts = opts.categories(filter);
+    }
     let (updated, new, skipped, unchanged) = installer.upgrade(&profile, &target_dir, &opts)?;
 
     println!();
@@ -1704,6 +2289,7 @@ fn handle_diff(
     target: Option<&Path>,
     global: bool,
     ignore_config: IgnoreConfig,
+    categories: Option<CategoryFilter>,
     _install_target: InstallTarget, // TODO: Implement multi-platform support
 ) -> Result<()> {
     let manager = ProfileManager::new(base_dir.to_path_buf());
@@ -1715,9 +2301,15 @@ fn handle_diff(
     println!();
     println!("Profile: {}", profile_name.cyan());
     println!("Target: {}", target_dir.display());
+    print_category_selection(&target_dir, profile_name, categories.as_ref());
     println!();
 
-    let result = installer.diff(&profile, &target_dir, &ignore_config)?;
+    let result = installer.diff_with_categories(
+        &profile,
+        &target_dir,
+        &ignore_config,
+        categories.as_ref(),
+    )?;
 
     for file in &result.files {
         let status_str = match file.status {
@@ -1744,6 +2336,24 @@ fn handle_diff(
     Ok(())
 }
 
+/// Print the category selection in effect (requested or recorded in the target)
+fn print_category_selection(
+    target_dir: &Path,
+    profile_name: &str,
+    requested: Option<&CategoryFilter>,
+) {
+    let recorded = Metadata::load(target_dir)
+        .ok()
+        .flatten()
+        .and_then(|m| m.get_categories(profile_name).cloned());
+    match requested.or(recorded.as_ref()) {
+        Some(filter) if !filter.is_empty() => {
+            println!("Categories: {}", filter.to_string().cyan())
+        }
+        _ => {}
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn handle_remove(
     base_dir: &Path,
@@ -1868,50 +2478,22 @@ fn handle_apply(
     profile_filter: Option<&str>,
     target: Option<&Path>,
     force: bool,
+    params: Vec<(String, String)>,
 ) -> Result<()> {
-    use dot_agent_core::rule::RuleManager;
+    use dot_agent_core::rule::{InstalledRuleExecutor, RuleManager, Staging};
 
     let rule_manager = RuleManager::new(base_dir.to_path_buf());
     let installer = Installer::new(base_dir.to_path_buf());
 
-    let rule = rule_manager.get(rule_name)?;
+    let rule = rule_manager
+        .get(rule_name)?
+        .with_params(&params.into_iter().collect())?;
     let target_dir = installer.resolve_target(target, false)?;
 
-    // Load metadata to find installed files
-    let metadata = Metadata::load(&target_dir)?;
-    let meta = metadata.ok_or_else(|| DotAgentError::TargetNotFound {
-        path: target_dir.clone(),
-    })?;
-
-    // Collect target files
-    let target_files: Vec<PathBuf> = if let Some(profile) = profile_filter {
-        // Filter to specific profile's files
-        if !meta.installed.profiles.contains(&profile.to_string()) {
-            return Err(DotAgentError::ProfileNotFound {
-                name: profile.to_string(),
-            });
-        }
-        meta.files
-            .keys()
-            .filter(|f| f.starts_with(&format!("{}:", profile)))
-            .map(|f| {
-                // Remove profile prefix to get actual filename
-                let parts: Vec<&str> = f.splitn(2, ':').collect();
-                target_dir.join(parts.get(1).unwrap_or(&f.as_str()))
-            })
-            .filter(|p| p.exists())
-            .collect()
-    } else {
-        // All installed files
-        meta.files
-            .keys()
-            .map(|f| {
-                let parts: Vec<&str> = f.splitn(2, ':').collect();
-                target_dir.join(parts.get(1).unwrap_or(&f.as_str()))
-            })
-            .filter(|p| p.exists())
-            .collect()
-    };
+    let mut executor =
+        InstalledRuleExecutor::new(&rule, base_dir.to_path_buf(), target_dir.clone())
+            .profile(profile_filter);
+    let target_files = executor.files()?;
 
     if target_files.is_empty() {
         println!("No files to apply rule to.");
@@ -1927,90 +2509,90 @@ fn handle_apply(
     println!();
     println!("Files to modify ({}):", target_files.len());
     for f in &target_files {
-        if let Ok(rel) = f.strip_prefix(&target_dir) {
-            println!("  {}", rel.display());
+        println!("  {}", f.display());
+    }
+    let skipped = executor.skipped_files()?;
+    if !skipped.is_empty() {
+        println!("Skipped, not text ({}):", skipped.len());
+        for f in &skipped {
+            println!("  {}", f.display().to_string().dimmed());
         }
     }
     println!();
 
-    if !force {
-        print!("Apply rule to these files? [y/N]: ");
-        io::stdout().flush()?;
+    match rule.ops()? {
+        Some(ops) => println!(
+            "Planning changes (declarative rule, {} operation(s))...",
+            ops.ops.len()
+        ),
+        None => {
+            let backend = get_llm_backend(base_dir)?;
+            if !force {
+                print!(
+                    "Send these files to {} to apply the rule? [y/N]: ",
+                    backend.name()
+                );
+                io::stdout().flush()?;
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
 
-        if !input.trim().eq_ignore_ascii_case("y") {
-            println!("Aborted.");
-            return Ok(());
+                if !input.trim().eq_ignore_ascii_case("y") {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+            println!("Planning changes ({})...", backend.name());
+            executor = executor.with_backend(backend);
         }
     }
 
-    // Apply rule to each file
-    println!("Applying rule...");
-    let mut modified = 0;
-
-    for file_path in &target_files {
-        if let Ok(rel) = file_path.strip_prefix(&target_dir) {
-            print!("  {} ... ", rel.display());
-            io::stdout().flush()?;
+    let mut staging = Staging::new(executor.plan()?);
+    println!();
+    if staging.is_empty() {
+        println!("{} The rule proposed no changes.", "[OK]".green().bold());
+        return Ok(());
+    }
 
-            match apply_rule_to_file(&rule.content, file_path) {
-                Ok(changed) => {
-                    if changed {
-                        println!("{}", "modified".green());
-                        modified += 1;
-                    } else {
-                        println!("{}", "unchanged".yellow());
-                    }
-                }
-                Err(e) => {
-                    println!("{}: {}", "error".red(), e);
-                }
-            }
+    if force {
+        print_rule_changes(&staging.changes());
+    } else {
+        println!("Review {} change(s):", staging.hunk_count());
+        if !review_staging(&mut staging)? {
+            println!("Aborted.");
+            return Ok(());
         }
     }
 
-    println!();
-    println!("{} {} file(s) modified.", "Done:".green(), modified);
-
-    Ok(())
-}
-
-fn apply_rule_to_file(rule_content: &str, file_path: &Path) -> Result<bool> {
-    use std::process::Command;
-
-    let original = std::fs::read_to_string(file_path)?;
+    let changes = staging.changes();
+    let Some(result) = executor.commit(&changes)? else {
+        println!();
+        println!("No changes accepted.");
+        return Ok(());
+    };
 
-    // Use claude CLI to apply the rule
-    let prompt = format!(
-        "Apply the following customization rule to the file content. \
-         Return ONLY the modified file content, no explanations.\n\n\
-         === RULE ===\n{}\n\n=== FILE CONTENT ===\n{}",
-        rule_content, original
+    println!();
+    println!("{} {}", "Snapshot:".dimmed(), result.snapshot_id.dimmed());
+    println!(
+        "{} Recorded: {}",
+        "[HISTORY]".dimmed(),
+        result.operation_id.dimmed()
+    );
+    println!();
+    println!(
+        "{} {} file(s) modified.",
+        "Done:".green(),
+        result.files_modified
+    );
+    let path_arg = target
+        .map(|p| format!(" --path {}", p.display()))
+        .unwrap_or_default();
+    println!(
+        "Undo with: dot-agent snapshot restore {}{}",
+        result.snapshot_id, path_arg
     );
 
-    let output = Command::new("claude")
-        .arg("-p")
-        .arg(&prompt)
-        .output()
-        .map_err(DotAgentError::Io)?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(DotAgentError::ClaudeExecutionFailed {
-            message: stderr.to_string(),
-        });
-    }
-
-    let modified = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-    if modified == original {
-        return Ok(false);
-    }
-
-    std::fs::write(file_path, &modified)?;
-    Ok(true)
+    Ok(())
 }
 
 fn handle_copy(base_dir: &Path, source: &str, dest: &str, force: bool) -> Result<()> {
@@ -2032,6 +2614,7 @@ fn handle_status(
     target: Option<&Path>,
     global: bool,
     _install_target: InstallTarget, // TODO: Implement multi-platform support
+    budget: bool,
 ) -> Result<()> {
     let installer = Installer::new(base_dir.to_path_buf());
     let target_dir = installer.resolve_target(target, global)?;
@@ -2054,6 +2637,9 @@ fn handle_status(
                 let prefix = format!("{}:", profile);
                 let file_count = meta.files.keys().filter(|f| f.starts_with(&prefix)).count();
                 println!("  {} ({} files)", profile.cyan(), file_count);
+                if let Some(filter) = meta.get_categories(profile) {
+                    println!("    {} {}", "categories:".dimmed(), filter);
+                }
             }
             println!();
             println!("Total tracked files: {}", meta.files.len());
@@ -2070,6 +2656,14 @@ fn handle_status(
         println!("CLAUDE.md: {} (user-managed)", "present".green());
     }
 
+    if budget {
+        let config = Config::load(base_dir)?;
+        let classifier = dot_agent_core::CategoryClassifier::builtin(config.classification.mode)?;
+        let report = dot_agent_core::BudgetReport::for_target(&target_dir, &classifier)?;
+        println!();
+        print_budget(&report, &config.budget, true);
+    }
+
     Ok(())
 }
 
@@ -2233,6 +2827,7 @@ fn handle_outdated(base_dir: &Path, target: Option<&Path>, global: bool) -> Resu
 }
 
 fn handle_import(
+    base_dir: &Path,
     manager: &ProfileManager,
     source: &str,
     name: Option<String>,
@@ -2245,8 +2840,29 @@ fn handle_import(
         || source.starts_with("http://")
         || source.starts_with("ssh://");
 
+    // <host>:<repo> shorthand for a git host configured in config.toml
+    let hosted = source.split_once(':').and_then(|(host, _)| {
+        let hosts = GitHostRegistry::from_config(&Config::load(base_dir).ok()?);
+        hosts.get(host)?;
+        hosts.resolve(source)
+    });
+
+    // name@channel for a registered channel
+    let channel_ref = parse_marketplace_ref(source).filter(|(_, channel)| {
+        dot_agent_core::channel::ChannelRegistry::load(base_dir)
+            .map(|r| r.contains(channel))
+            .unwrap_or(false)
+    });
+
     if is_git_url {
         import_from_git(manager, source, name, subpath, branch, force)
+    } else if let Some((entry, channel)) = channel_ref {
+        let profile = import_channel_entry(base_dir, &entry, &channel, name, manager, force)?;
+        println!();
+        println!("Contents: {}", profile.contents_summary());
+        Ok(())
+    } else if let Some(hosted) = hosted {
+        import_from_git(manager, &hosted.clone_url(), name, subpath, branch, force)
     } else {
         import_from_path(manager, source, name, subpath, force)
     }
@@ -2378,8 +2994,8 @@ fn import_from_git(
         url,
         profile.path.display()
     );
-    if branch.is_some() {
-        println!("Branch: {}", branch.unwrap().cyan());
+    if let Some(b) = &branch {
+        println!("Branch: {}", b.cyan());
     }
     println!();
     println!("Contents: {}", profile.contents_summary());
@@ -2616,8 +3232,132 @@ fn handle_profile_snapshot(
     Ok(())
 }
 
+/// Print the per-file preview of changes planned by a rule
+fn print_rule_changes(changes: &[FileChange]) {
+    const MAX_LINES: usize = 20;
+
+    for change in changes {
+        let diff = change.diff();
+        let added = diff
+            .iter()
+            .filter(|l| matches!(l, DiffLine::Added(_)))
+            .count();
+        let badge = match change.kind {
+            FileChangeKind::Create => "[CREATE]".green(),
+            FileChangeKind::Modify => "[MODIFY]".cyan(),
+            FileChangeKind::Delete => "[DELETE]".red(),
+        };
+        println!(
+            "{} {} {}",
+            badge,
+            change.path.display(),
+            format!("(+{} -{})", added, diff.len() - added).dimmed()
+        );
+        for line in diff.iter().take(MAX_LINES) {
+            match line {
+                DiffLine::Added(l) => println!("    {}", format!("+ {}", l).green()),
+                DiffLine::Removed(l) => println!("    {}", format!("- {}", l).red()),
+            }
+        }
+        if diff.len() > MAX_LINES {
+            println!(
+                "    {}",
+                format!("... and {} more lines", diff.len() - MAX_LINES).dimmed()
+            );
+        }
+    }
+}
+
+/// Review staged changes hunk by hunk. Returns `false` when aborted.
+fn review_staging(staging: &mut dot_agent_core::Staging) -> Result<bool> {
+    use dot_agent_core::HunkDecision;
+
+    let total = staging.hunk_count();
+    let mut index = 0;
+    let mut accept_rest = false;
+
+    for file in &mut staging.files {
+        let badge = match file.kind {
+            FileChangeKind::Create => "[CREATE]".green(),
+            FileChangeKind::Modify => "[MODIFY]".cyan(),
+            FileChangeKind::Delete => "[DELETE]".red(),
+        };
+        let file_hunks = file.hunks.len();
+
+        for i in 0..file_hunks {
+            index += 1;
+            if accept_rest {
+                file.hunks[i].decision = HunkDecision::Accept;
+                continue;
+            }
+
+            println!();
+            println!(
+                "{} {} {}",
+                badge,
+                file.path.display(),
+                format!("(hunk {}/{}, {}/{} total)", i + 1, file_hunks, index, total).dimmed()
+            );
+            for line in file.hunks[i].diff() {
+                match line {
+                    DiffLine::Added(l) => println!("    {}", format!("+ {}", l).green()),
+                    DiffLine::Removed(l) => println!("    {}", format!("- {}", l).red()),
+                }
+            }
+            println!("    [y]es  [n]o  [e]dit  [a]ccept all remaining  [q]uit");
+
+            loop {
+                print!("    > ");
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+
+                match input.trim().to_lowercase().as_str() {
+                    "y" => file.hunks[i].decision = HunkDecision::Accept,
+                    "n" => file.hunks[i].decision = HunkDecision::Reject,
+                    "e" if file.kind == FileChangeKind::Delete => {
+                        println!("    A deletion cannot be edited. Enter y or n.");
+                        continue;
+                    }
+                    "e" => {
+                        let edited = edit_in_editor(&file.hunks[i].added.concat())?;
+                        file.edit(i, &edited)?;
+                    }
+                    "a" => {
+                        file.hunks[i].decision = HunkDecision::Accept;
+                        accept_rest = true;
+                    }
+                    "q" => return Ok(false),
+                    _ => {
+                        println!("    Invalid choice. Enter y, n, e, a, or q.");
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Open `content` in $EDITOR and return the edited text
+fn edit_in_editor(content: &str) -> Result<String> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
+    let path = std::env::temp_dir().join(format!("dot-agent-hunk-{}.md", std::process::id()));
+    std::fs::write(&path, content)?;
+
+    let status = Command::new(&editor).arg(&path).status();
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+    status.map_err(DotAgentError::Io)?;
+
+    Ok(edited?)
+}
+
 fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
-    use dot_agent_core::rule::{extract_rule, generate_rule, RuleExecutor, RuleManager};
+    use dot_agent_core::rule::{extract_rule, generate_rule, RuleExecutor, RuleManager, Staging};
 
     let manager = RuleManager::new(base_dir.to_path_buf());
     let profile_manager = ProfileManager::new(base_dir.to_path_buf());
@@ -2646,11 +3386,24 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
                 return Ok(());
             }
 
+            let sources = manager.sources()?;
+
             println!();
             println!("Available rules:");
             println!();
             for r in rules {
-                println!("  {}", r.name.cyan().bold());
+                let mut tags = Vec::new();
+                if r.ops().ok().flatten().is_some() {
+                    tags.push("[declarative]".to_string());
+                }
+                if let Some(source) = sources.rules.get(&r.name) {
+                    tags.push(format!("[{}]", source.origin));
+                }
+                if tags.is_empty() {
+                    println!("  {}", r.name.cyan().bold());
+                } else {
+                    println!("  {} {}", r.name.cyan().bold(), tags.join(" ").dimmed());
+                }
                 println!("    {}", r.summary());
                 println!();
             }
@@ -2660,6 +3413,36 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
             println!();
             println!("Rule: {}", rule.name.cyan().bold());
             println!("Path: {}", rule.path.display());
+            if let Some(source) = manager.source(&name)? {
+                match &source.version {
+                    Some(version) => println!("Source: {} (v{})", source.origin, version),
+                    None => println!("Source: {}", source.origin),
+                }
+                println!("Imported: {}", source.imported_at);
+            }
+            let params = rule.declared_params()?;
+            if !params.is_empty() {
+                println!("Parameters:");
+                for param in params {
+                    let mut details = Vec::new();
+                    if let Some(default) = &param.default {
+                        details.push(format!("default: {}", default));
+                    } else {
+                        details.push("required".to_string());
+                    }
+                    if !param.values.is_empty() {
+                        details.push(format!("one of: {}", param.values.join(", ")));
+                    }
+                    println!(
+                        "  {} {}",
+                        param.name.cyan(),
+                        format!("({})", details.join("; ")).dimmed()
+                    );
+                    if let Some(description) = &param.description {
+                        println!("    {}", description);
+                    }
+                }
+            }
             println!();
             println!("--- Content ---");
             println!("{}", rule.content);
@@ -2706,7 +3489,8 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
             println!("Extracting rule from profile '{}'...", profile.cyan());
             println!();
 
-            let rule = extract_rule(&source_profile, &name, &manager)?;
+            let backend = get_llm_backend(base_dir)?;
+            let rule = extract_rule(&source_profile, &name, &manager, backend.as_ref())?;
 
             println!("{} {}", "Created:".green(), rule.path.display());
             println!();
@@ -2722,7 +3506,8 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
             }
             println!();
 
-            let rule = generate_rule(&instruction, name.as_deref(), &manager)?;
+            let backend = get_llm_backend(base_dir)?;
+            let rule = generate_rule(&instruction, name.as_deref(), &manager, backend.as_ref())?;
 
             println!("{} {}", "Created:".green(), rule.path.display());
             println!("  Name: {}", rule.name.cyan());
@@ -2748,15 +3533,17 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
             name,
             dry_run,
             yes,
+            params,
         } => {
             let source_profile = profile_manager.get_profile(&profile)?;
-            let r = manager.get(&rule)?;
+            let r = manager
+                .get(&rule)?
+                .with_params(&params.into_iter().collect())?;
+            let ops = r.ops()?;
+            let mut executor = RuleExecutor::new(&r, &profile_manager);
 
             // Calculate new profile name
-            let new_profile_name = name
-                .as_deref()
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| format!("{}-{}", profile, rule));
+            let new_profile_name = executor.output_name(&source_profile, name.as_deref());
 
             // Display rich information
             println!();
@@ -2768,48 +3555,69 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
             println!("{}", "─".repeat(50).dimmed());
             println!("  Contents: {}", source_profile.contents_summary());
             println!("{}", "─".repeat(50).dimmed());
-            println!(
-                "  {}  This operation uses {} to generate",
-                "⚠️".yellow(),
-                "Claude CLI (LLM)".yellow().bold()
-            );
-            println!("     profile customizations.");
+            match &ops {
+                Some(ops) => {
+                    println!("  Declarative rule: {} operation(s), no LLM", ops.ops.len());
+                }
+                None => {
+                    let backend = get_llm_backend(base_dir)?;
+                    println!(
+                        "  {}  This operation uses {} to generate",
+                        "⚠️".yellow(),
+                        format!("{} (LLM)", backend.name()).yellow().bold()
+                    );
+                    println!("     profile customizations.");
+                    executor = executor.with_backend(backend);
+                }
+            }
             println!("{}", "─".repeat(50).dimmed());
 
+            println!();
+            println!("{}", "Planning changes...".dimmed());
+
+            let mut staging = Staging::new(executor.plan(&source_profile)?);
+
+            println!();
+            if staging.is_empty() {
+                println!("{} The rule proposed no changes.", "[OK]".green().bold());
+                return Ok(());
+            }
+
+            if dry_run || yes {
+                print_rule_changes(&staging.changes());
+            }
+
             if dry_run {
                 println!();
                 println!("{}", "(dry run - no changes will be made)".yellow());
-                let executor = RuleExecutor::new(&r, &profile_manager);
-                let result = executor.apply(&source_profile, Some(&new_profile_name), true)?;
-                println!();
+                println!("Would create new profile: {}", new_profile_name.green());
                 println!(
-                    "Would create new profile: {}",
-                    result.new_profile_name.green()
+                    "  Path: {}",
+                    profile_manager
+                        .profiles_dir()
+                        .join(&new_profile_name)
+                        .display()
                 );
-                println!("  Path: {}", result.new_profile_path.display());
                 return Ok(());
             }
 
-            // Confirmation prompt (unless --yes)
+            // Review each hunk (unless --yes)
             if !yes {
-                println!();
-                print!("Proceed? [y/N]: ");
-                io::stdout().flush()?;
-
-                let mut input = String::new();
-                io::stdin().read_line(&mut input)?;
-
-                if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+                println!("Review {} change(s):", staging.hunk_count());
+                if !review_staging(&mut staging)? {
                     println!("Aborted.");
                     return Ok(());
                 }
             }
 
-            println!();
-            println!("{}", "Executing...".dimmed());
+            let changes = staging.changes();
+            if changes.is_empty() {
+                println!();
+                println!("No changes accepted.");
+                return Ok(());
+            }
 
-            let executor = RuleExecutor::new(&r, &profile_manager);
-            let result = executor.apply(&source_profile, Some(&new_profile_name), false)?;
+            let result = executor.commit(&source_profile, Some(&new_profile_name), changes)?;
 
             println!();
             println!("{}", "✓ Customization complete!".green().bold());
@@ -2827,6 +3635,7 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
                         rule_name: rule.clone(),
                         source_profile: profile.clone(),
                         output_profile: result.new_profile_name.clone(),
+                        params: r.params.clone(),
                     };
 
                     if let Ok(op) =
@@ -2852,71 +3661,427 @@ fn handle_rule(action: RuleAction, base_dir: &Path) -> Result<()> {
             profile,
             path,
             force,
+            params,
         } => {
             // Delegate to top-level apply (applies to installed files)
-            handle_apply(base_dir, &rule, profile.as_deref(), path.as_deref(), force)?;
+            handle_apply(
+                base_dir,
+                &rule,
+                profile.as_deref(),
+                path.as_deref(),
+                force,
+                params,
+            )?;
         }
-    }
+        RuleAction::Import {
+            source,
+            name,
+            force,
+        } => {
+            let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
 
-    Ok(())
-}
+            println!();
+            println!("Fetching {} ...", source.cyan());
+            let fetched = channel_mgr.fetch_rule(&source)?;
+            let rule_name = match name {
+                Some(name) => name,
+                None => match &fetched.origin {
+                    dot_agent_core::RuleOrigin::Channel { name, .. }
+                    | dot_agent_core::RuleOrigin::Hub { name, .. } => name.clone(),
+                    dot_agent_core::RuleOrigin::Url { url } => url
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or_default()
+                        .trim_end_matches(".md")
+                        .to_string(),
+                },
+            };
+            let rule = manager.install(&rule_name, &fetched, force)?;
 
-fn handle_snapshot(action: SnapshotAction, base_dir: &Path) -> Result<()> {
-    use dot_agent_core::{SnapshotManager, SnapshotTrigger};
+            println!("{} {}", "Imported:".green(), rule.path.display());
+            println!("  Source:  {}", fetched.origin);
+            if let Some(version) = &fetched.version {
+                println!("  Version: {}", version);
+            }
+            println!();
+            println!(
+                "Apply with: {}",
+                format!("dot-agent rule apply -p <profile> -r {}", rule.name).cyan()
+            );
+        }
+        RuleAction::Search { query, channels } => {
+            let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
+            let results = channel_mgr.search_rules(&query, &channels)?;
+            if results.is_empty() {
+                println!("No rules found.");
+                return Ok(());
+            }
 
-    let snapshot_manager = SnapshotManager::new(base_dir.to_path_buf());
-    let installer = Installer::new(base_dir.to_path_buf());
+            println!();
+            println!("Found {} rule(s):", results.len());
+            println!();
+            for r in results {
+                let version = r
+                    .version
+                    .as_ref()
+                    .map(|v| format!(" v{}", v))
+                    .unwrap_or_default();
+                println!("  {}{}", r.origin.spec().cyan().bold(), version.dimmed());
+                if let Some(description) = &r.description {
+                    println!("    {}", description);
+                }
+            }
+            println!();
+            println!(
+                "Import with: {}",
+                "dot-agent rule import <name@channel>".cyan()
+            );
+        }
+        RuleAction::Update { name, force } => {
+            use dot_agent_core::RuleUpdate;
+
+            let sources = manager.sources()?;
+            let names: Vec<String> = match name {
+                Some(name) => {
+                    manager.get(&name)?;
+                    vec![name]
+                }
+                None => sources.rules.keys().cloned().collect(),
+            };
+            if names.is_empty() {
+                println!("No imported rules.");
+                return Ok(());
+            }
 
-    match action {
-        SnapshotAction::Save {
-            message,
-            path,
-            global,
+            let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
+            let mut failed = 0;
+            println!();
+            for name in names {
+                let Some(source) = sources.rules.get(&name) else {
+                    return Err(DotAgentError::InvalidRule {
+                        name,
+                        message: "not imported from a channel, hub or URL".to_string(),
+                    });
+                };
+                let update = channel_mgr
+                    .fetch_rule(&source.origin.spec())
+                    .and_then(|fetched| manager.update_from(&name, &fetched, force));
+                match update {
+                    Ok(RuleUpdate::UpToDate) => {
+                        println!("  {} {}", "[OK]".green(), name);
+                    }
+                    Ok(RuleUpdate::Updated { from, to }) => {
+                        let version = |v: Option<String>| v.unwrap_or_else(|| "?".to_string());
+                        println!(
+                            "  {} {} {} -> {}",
+                            "[UPDATED]".cyan().bold(),
+                            name,
+                            version(from),
+                            version(to).green()
+                        );
+                    }
+                    Ok(RuleUpdate::LocallyModified) => {
+                        failed += 1;
+                        println!(
+                            "  {} {} (edited locally; use --force to overwrite)",
+                            "[SKIPPED]".yellow().bold(),
+                            name
+                        );
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        println!("  {} {}: {}", "[ERROR]".red().bold(), name, e);
+                    }
+                }
+            }
+            if failed > 0 {
+                println!();
+                println!("{} rule(s) not updated.", failed);
+            }
+        }
+        RuleAction::Test {
+            name,
+            case,
+            recorded,
+            record,
         } => {
-            let target_dir = installer.resolve_target(path.as_deref(), global)?;
+            use dot_agent_core::{ResponseMode, RuleTestRunner};
 
-            if !target_dir.exists() {
-                return Err(DotAgentError::TargetNotFound { path: target_dir });
+            let r = manager.get(&name)?;
+            let mode = if recorded {
+                ResponseMode::Recorded
+            } else if record {
+                ResponseMode::Record
+            } else {
+                ResponseMode::Live
+            };
+            let mut runner = RuleTestRunner::new(&r, base_dir.to_path_buf()).mode(mode);
+            if mode != ResponseMode::Recorded && r.ops()?.is_none() {
+                runner = runner.with_backend(get_llm_backend(base_dir)?);
+            }
+
+            if runner.cases()?.is_empty() {
+                println!("No test cases for rule '{}'.", name);
+                println!();
+                println!(
+                    "Add one under: {}",
+                    r.tests_dir().join("<case>/test.toml").display()
+                );
+                return Ok(());
             }
 
             println!();
-            println!("Creating snapshot of {}...", target_dir.display());
+            println!("{} {}", "Rule Test:".bold(), name.cyan());
+            println!("{}", "─".repeat(50).dimmed());
 
-            let snapshot = snapshot_manager.save_target(
-                &target_dir,
-                SnapshotTrigger::Manual,
-                message.as_deref(),
-                &[],
-            )?;
+            let results = runner.run(case.as_deref())?;
+            for result in &results {
+                if result.passed() {
+                    println!("  {} {}", "[PASS]".green().bold(), result.case);
+                    continue;
+                }
+                println!("  {} {}", "[FAIL]".red().bold(), result.case);
+                if let Some(error) = &result.error {
+                    println!("      {}", error.red());
+                }
+                for failure in &result.failures {
+                    println!("      - {}", failure);
+                }
+            }
 
-            println!();
-            println!("{} {}", "Snapshot saved:".green(), snapshot.id.cyan());
-            println!("  Time: {}", snapshot.display_time());
-            println!("  Files: {}", snapshot.file_count);
-            if let Some(msg) = &snapshot.message {
-                println!("  Message: {}", msg);
+            let failed = results.iter().filter(|r| !r.passed()).count();
+            println!("{}", "─".repeat(50).dimmed());
+            println!(
+                "{} passed, {} failed",
+                (results.len() - failed).to_string().green(),
+                failed.to_string().red()
+            );
+
+            if failed > 0 {
+                return Err(DotAgentError::RuleTestFailed {
+                    name,
+                    failed,
+                    total: results.len(),
+                });
             }
         }
-        SnapshotAction::List { path, global } => {
-            let target_dir = installer.resolve_target(path.as_deref(), global)?;
+    }
 
-            let snapshots = snapshot_manager.list_target(&target_dir)?;
+    Ok(())
+}
 
-            if snapshots.is_empty() {
-                println!("No snapshots found.");
+fn handle_pipeline(action: PipelineAction, base_dir: &Path) -> Result<()> {
+    use dot_agent_core::{Pipeline, PipelineRule, PipelineRunner, PipelineStore};
+
+    match action {
+        PipelineAction::List => {
+            let store = PipelineStore::load(base_dir)?;
+            if store.pipelines.is_empty() {
+                println!("No pipelines defined.");
                 println!();
-                println!("Create one with: dot-agent snapshot save");
+                println!(
+                    "Add one with: {}",
+                    "dot-agent pipeline add <name> -s <profile> -r <rule>,<rule>".cyan()
+                );
                 return Ok(());
             }
 
             println!();
-            println!("Snapshots for {}:", target_dir.display());
+            println!("Pipelines:");
             println!();
-
-            for snap in snapshots {
+            for (name, pipeline) in &store.pipelines {
+                println!("  {}", name.cyan().bold());
                 println!(
-                    "  {} {} [{}] ({} files)",
-                    snap.id.cyan(),
+                    "    {} -> [{}] -> {}",
+                    pipeline.source,
+                    pipeline.describe_rules(),
+                    pipeline.output.green()
+                );
+            }
+        }
+        PipelineAction::Add {
+            name,
+            source,
+            rules,
+            output,
+            params,
+        } => {
+            if let Some((rule, _)) = params.iter().find(|(rule, _)| !rules.contains(rule)) {
+                return Err(DotAgentError::ConfigParseSimple {
+                    message: format!("--param for '{}', which is not in --rules", rule),
+                });
+            }
+            let rules = rules
+                .into_iter()
+                .map(|rule| {
+                    let values = params
+                        .iter()
+                        .filter(|(r, _)| *r == rule)
+                        .map(|(_, param)| param.clone())
+                        .collect();
+                    PipelineRule::new(rule, values)
+                })
+                .collect();
+
+            let output = output.unwrap_or_else(|| format!("{}-{}", source, name));
+            let mut store = PipelineStore::load(base_dir)?;
+            store.insert(
+                base_dir,
+                &name,
+                Pipeline {
+                    source,
+                    rules,
+                    output,
+                },
+            )?;
+            store.save(base_dir)?;
+
+            let pipeline = store.get(&name)?;
+            println!(
+                "{} {}: {} -> [{}] -> {}",
+                "Saved pipeline".green(),
+                name.cyan(),
+                pipeline.source,
+                pipeline.describe_rules(),
+                pipeline.output.green()
+            );
+            println!(
+                "Run with: {}",
+                format!("dot-agent pipeline run {}", name).cyan()
+            );
+        }
+        PipelineAction::Remove { name } => {
+            let mut store = PipelineStore::load(base_dir)?;
+            store.remove(&name)?;
+            store.save(base_dir)?;
+            println!("{} {}", "Removed pipeline".green(), name.cyan());
+        }
+        PipelineAction::Run {
+            name,
+            force,
+            refresh,
+        } => {
+            let store = PipelineStore::load(base_dir)?;
+            let pipeline = store.get(&name)?;
+
+            println!();
+            println!("Pipeline: {}", name.cyan());
+            println!(
+                "  {} -> [{}] -> {}",
+                pipeline.source,
+                pipeline.describe_rules(),
+                pipeline.output.green()
+            );
+            println!();
+
+            let run = PipelineRunner::new(base_dir.to_path_buf(), &name, pipeline)
+                .with_backend(get_llm_backend(base_dir)?)
+                .force(force)
+                .refresh(refresh)
+                .run()?;
+
+            if run.up_to_date {
+                println!(
+                    "{} '{}' is up to date (source and rules unchanged).",
+                    "[OK]".green().bold(),
+                    run.output_profile
+                );
+                println!("Use {} to re-run anyway.", "--force".cyan());
+                return Ok(());
+            }
+
+            let total = run.steps.len();
+            for (i, step) in run.steps.iter().enumerate() {
+                let origin = if step.cached {
+                    "[CACHED]".dimmed()
+                } else {
+                    "[APPLIED]".green()
+                };
+                println!(
+                    "  {} {}/{} {} ({} file(s) changed)",
+                    origin,
+                    i + 1,
+                    total,
+                    step.rule.cyan(),
+                    step.changes.len()
+                );
+            }
+
+            println!();
+            println!(
+                "{} {}",
+                "✓ Output profile updated:".green().bold(),
+                run.output_profile.cyan()
+            );
+            println!("  Path: {}", run.output_path.display());
+
+            println!(
+                "{} Recorded {} step(s)",
+                "[HISTORY]".dimmed(),
+                run.operation_ids.len()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_snapshot(action: SnapshotAction, base_dir: &Path) -> Result<()> {
+    use dot_agent_core::{SnapshotManager, SnapshotTrigger};
+
+    let snapshot_manager = SnapshotManager::new(base_dir.to_path_buf());
+    let installer = Installer::new(base_dir.to_path_buf());
+
+    match action {
+        SnapshotAction::Save {
+            message,
+            path,
+            global,
+        } => {
+            let target_dir = installer.resolve_target(path.as_deref(), global)?;
+
+            if !target_dir.exists() {
+                return Err(DotAgentError::TargetNotFound { path: target_dir });
+            }
+
+            println!();
+            println!("Creating snapshot of {}...", target_dir.display());
+
+            let snapshot = snapshot_manager.save_target(
+                &target_dir,
+                SnapshotTrigger::Manual,
+                message.as_deref(),
+                &[],
+            )?;
+
+            println!();
+            println!("{} {}", "Snapshot saved:".green(), snapshot.id.cyan());
+            println!("  Time: {}", snapshot.display_time());
+            println!("  Files: {}", snapshot.file_count);
+            if let Some(msg) = &snapshot.message {
+                println!("  Message: {}", msg);
+            }
+        }
+        SnapshotAction::List { path, global } => {
+            let target_dir = installer.resolve_target(path.as_deref(), global)?;
+
+            let snapshots = snapshot_manager.list_target(&target_dir)?;
+
+            if snapshots.is_empty() {
+                println!("No snapshots found.");
+                println!();
+                println!("Create one with: dot-agent snapshot save");
+                return Ok(());
+            }
+
+            println!();
+            println!("Snapshots for {}:", target_dir.display());
+            println!();
+
+            for snap in snapshots {
+                println!(
+                    "  {} {} [{}] ({} files)",
+                    snap.id.cyan(),
                     snap.display_time(),
                     snap.trigger.as_str().yellow(),
                     snap.file_count
@@ -3361,6 +4526,567 @@ fn handle_switch(
     Ok(())
 }
 
+/// Where a component for `profile add-component` is read from
+struct ComponentSource {
+    /// Directory component paths are relative to
+    root: PathBuf,
+    origin: ComponentOrigin,
+    /// Path used when none is given (Codex skills are the root itself)
+    default_path: Option<PathBuf>,
+    /// Destination used when none is given
+    default_dest: Option<PathBuf>,
+    /// Temporary clone to remove when done
+    temp: Option<PathBuf>,
+}
+
+impl ComponentSource {
+    fn cleanup(&self) {
+        if let Some(temp) = &self.temp {
+            let _ = std::fs::remove_dir_all(temp);
+        }
+    }
+}
+
+/// Shallow-clone a repository into a temp directory, returning it and its HEAD commit
+fn clone_to_temp(url: &str, branch: Option<&str>) -> Result<(PathBuf, Option<String>)> {
+    let temp = std::env::temp_dir().join(format!(
+        "dot-agent-component-{}-{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--depth", "1"]);
+    if let Some(branch) = branch {
+        cmd.arg("--branch").arg(branch);
+    }
+    let output = cmd
+        .arg(url)
+        .arg(&temp)
+        .output()
+        .map_err(DotAgentError::Io)?;
+    if !output.status.success() {
+        let _ = std::fs::remove_dir_all(&temp);
+        return Err(DotAgentError::Git(format!(
+            "git clone failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(&temp)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+
+    Ok((temp, commit))
+}
+
+/// Resolve `--from` to a directory on disk
+fn resolve_component_source(
+    base_dir: &Path,
+    manager: &ProfileManager,
+    from: &str,
+    branch: Option<&str>,
+) -> Result<ComponentSource> {
+    use dot_agent_core::channel::{ChannelSource, ChannelType};
+
+    let source = |root: PathBuf, origin: ComponentOrigin| ComponentSource {
+        root,
+        origin,
+        default_path: None,
+        default_dest: None,
+        temp: None,
+    };
+
+    // entry@channel
+    if let Some((entry, channel_name)) = parse_marketplace_ref(from) {
+        let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
+        if let Some(channel) = channel_mgr.registry().get(&channel_name).cloned() {
+            match channel.channel_type {
+                ChannelType::Local => {
+                    let found = channel_mgr
+                        .local_channel(&channel_name)?
+                        .find(&entry)?
+                        .ok_or_else(|| DotAgentError::ProfileNotFound {
+                            name: from.to_string(),
+                        })?;
+                    let root = found
+                        .path
+                        .clone()
+                        .ok_or_else(|| DotAgentError::TargetNotFound {
+                            path: PathBuf::from(found.url.clone().unwrap_or_default()),
+                        })?;
+                    let origin = match &found.plugin {
+                        Some(plugin) => ComponentOrigin::Marketplace {
+                            channel: channel_name,
+                            plugin: entry,
+                            version: plugin.version.clone().unwrap_or_else(|| "unknown".into()),
+                        },
+                        None => ComponentOrigin::Channel {
+                            channel: channel_name,
+                            name: entry,
+                        },
+                    };
+                    return Ok(source(root, origin));
+                }
+                ChannelType::Marketplace => {
+                    let plugin = fetch_marketplace_plugin(base_dir, &entry, &channel_name)?;
+                    return Ok(ComponentSource {
+                        temp: Some(plugin.clone_path),
+                        ..source(
+                            plugin.source_path,
+                            ComponentOrigin::Marketplace {
+                                channel: channel_name,
+                                plugin: entry,
+                                version: plugin.version,
+                            },
+                        )
+                    });
+                }
+                ChannelType::CodexCatalog => {
+                    let ChannelSource::CodexCatalog { repo, .. } = &channel.source else {
+                        return Err(DotAgentError::ChannelNotFound { name: channel_name });
+                    };
+                    let skill = channel_mgr
+                        .get_codex_skills(&channel_name)?
+                        .into_iter()
+                        .find(|s| s.name == entry)
+                        .ok_or_else(|| {
+                            DotAgentError::NotFound(format!(
+                                "Skill '{}' not found in '{}'. Run 'channel refresh {}' first.",
+                                entry, channel_name, channel_name
+                            ))
+                        })?;
+                    let url = channel_mgr.resolve_repo(repo)?.url();
+                    println!("  Fetching from: {}", url);
+                    let (temp, _) = clone_to_temp(&url, None)?;
+                    return Ok(ComponentSource {
+                        root: temp.join(&skill.path),
+                        origin: ComponentOrigin::Codex {
+                            channel: channel_name,
+                            skill: entry.clone(),
+                        },
+                        default_path: Some(PathBuf::from(".")),
+                        default_dest: Some(PathBuf::from("skills").join(&entry)),
+                        temp: Some(temp),
+                    });
+                }
+                other => {
+                    return Err(DotAgentError::NotFound(format!(
+                        "Cannot take components from {} channel '{}'",
+                        other, channel_name
+                    )))
+                }
+            }
+        }
+    }
+
+    // Another local profile
+    if let Ok(profile) = manager.get_profile(from) {
+        return Ok(source(
+            profile.path,
+            ComponentOrigin::Profile {
+                profile: from.to_string(),
+            },
+        ));
+    }
+
+    // Git URL or <host>:<repo>
+    let is_git_url = ["https://", "http://", "git@", "ssh://"]
+        .iter()
+        .any(|p| from.starts_with(p));
+    let hosted = from.split_once(':').and_then(|(host, _)| {
+        let hosts = GitHostRegistry::from_config(&Config::load(base_dir).ok()?);
+        hosts.get(host)?;
+        hosts.resolve(from)
+    });
+    if is_git_url || hosted.is_some() {
+        let url = hosted.map_or_else(|| from.to_string(), |h| h.clone_url());
+        println!("  Fetching from: {}", url);
+        let (temp, commit) = clone_to_temp(&url, branch)?;
+        return Ok(ComponentSource {
+            temp: Some(temp.clone()),
+            ..source(
+                temp,
+                ComponentOrigin::Git {
+                    url,
+                    branch: branch.map(str::to_string),
+                    commit,
+                },
+            )
+        });
+    }
+
+    // Plain directory
+    let path = PathBuf::from(from);
+    if path.is_dir() {
+        let path = path.canonicalize()?;
+        return Ok(source(
+            path.clone(),
+            ComponentOrigin::Path {
+                path: path.display().to_string(),
+            },
+        ));
+    }
+
+    Err(DotAgentError::ProfileNotFound {
+        name: from.to_string(),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_add_component(
+    base_dir: &Path,
+    manager: &ProfileManager,
+    profile: &str,
+    path: Option<&Path>,
+    from: &str,
+    dest: Option<&Path>,
+    branch: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    // Fail early, before fetching anything
+    manager.get_profile(profile)?;
+
+    println!();
+    println!(
+        "Adding component to {} from {}...",
+        profile.cyan(),
+        from.yellow()
+    );
+
+    let source = resolve_component_source(base_dir, manager, from, branch)?;
+    let result = (|| {
+        let path = path
+            .map(Path::to_path_buf)
+            .or_else(|| source.default_path.clone())
+            .ok_or_else(|| DotAgentError::InvalidComponentPath {
+                path: String::new(),
+            })?;
+        let dest = dest
+            .map(Path::to_path_buf)
+            .or_else(|| source.default_dest.clone())
+            .unwrap_or_else(|| path.clone());
+        manager.add_component(
+            profile,
+            &source.root,
+            &path,
+            &dest,
+            source.origin.clone(),
+            force,
+        )
+    })();
+    source.cleanup();
+    let provenance = result?;
+
+    println!(
+        "  {} {} (from {})",
+        "[OK]".green(),
+        provenance.path,
+        provenance.origin
+    );
+    Ok(())
+}
+
+fn handle_update_component(
+    base_dir: &Path,
+    manager: &ProfileManager,
+    profile_name: &str,
+    path: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    use dot_agent_core::profile::tree_hash;
+
+    let profile = manager.get_profile(profile_name)?;
+    let components: Vec<ComponentProvenance> = profile
+        .metadata()?
+        .map(|m| m.components.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| path.map_or(true, |p| c.path == p.trim_end_matches('/')))
+        .collect();
+
+    if components.is_empty() {
+        return Err(DotAgentError::NotFound(match path {
+            Some(p) => format!("No recorded component '{}' in {}", p, profile_name),
+            None => format!(
+                "No components were added to {} with add-component",
+                profile_name
+            ),
+        }));
+    }
+
+    let (mut updated, mut current, mut skipped) = (0, 0, 0);
+    for component in components {
+        let local_path = profile.path.join(&component.path);
+        let modified = tree_hash(&local_path).map_or(true, |h| h != component.hash);
+
+        let branch = match &component.origin {
+            ComponentOrigin::Git { branch, .. } => branch.clone(),
+            _ => None,
+        };
+        let source = match resolve_component_source(
+            base_dir,
+            manager,
+            &component.origin.spec(),
+            branch.as_deref(),
+        ) {
+            Ok(source) => source,
+            Err(e) => {
+                println!("  {} {}: {}", "[ERROR]".red(), component.path, e);
+                skipped += 1;
+                continue;
+            }
+        };
+
+        let upstream = match validate_relative_path(Path::new(&component.source_path)) {
+            Ok(rel) => source.root.join(rel),
+            Err(e) => {
+                println!("  {} {}: {}", "[ERROR]".red(), component.path, e);
+                skipped += 1;
+                source.cleanup();
+                continue;
+            }
+        };
+        let result = match tree_hash(&upstream) {
+            Ok(hash) if hash == component.hash => {
+                println!("  {} {}", "[CURRENT]".dimmed(), component.path);
+                current += 1;
+                Ok(())
+            }
+            Ok(_) if modified && !force => {
+                println!(
+                    "  {} {} (modified locally, use --force to overwrite)",
+                    "[SKIP]".yellow(),
+                    component.path
+                );
+                skipped += 1;
+                Ok(())
+            }
+            Ok(_) => manager
+                .add_component(
+                    profile_name,
+                    &source.root,
+                    Path::new(&component.source_path),
+                    Path::new(&component.path),
+                    source.origin.clone(),
+                    true,
+                )
+                .map(|p| {
+                    println!("  {} {} (from {})", "[UPDATE]".green(), p.path, p.origin);
+                    updated += 1;
+                }),
+            Err(e) => Err(e),
+        };
+        source.cleanup();
+        result?;
+    }
+
+    println!();
+    println!(
+        "Updated: {}, up to date: {}, skipped: {}",
+        updated, current, skipped
+    );
+    Ok(())
+}
+
+fn handle_profile_stats(base_dir: &Path, manager: &ProfileManager, name: &str) -> Result<()> {
+    use dot_agent_core::category::{CategoryClassifier, ClassificationMode};
+
+    let profile = manager.get_profile(name)?;
+    let mode = get_classification_mode(base_dir);
+    let mut classifier = CategoryClassifier::from_profile(&profile, mode)?;
+    if mode == ClassificationMode::Llm {
+        classifier = classifier.with_backend(get_llm_backend(base_dir)?);
+    }
+    let classified = classifier.classify(&profile)?;
+    for warning in &classified.warnings {
+        println!("{} {}", "[WARN]".yellow(), warning);
+    }
+
+    let report = dot_agent_core::BudgetReport::from_classified(&profile.path, &classified.files)?;
+
+    println!();
+    println!("Profile: {}", profile.name.cyan().bold());
+    println!("Classification: {}", mode);
+    println!();
+    print_budget(&report, &Config::load(base_dir)?.budget, true);
+
+    Ok(())
+}
+
+/// Print estimated context tokens (per file when `detailed`) and budget warnings
+fn print_budget(
+    report: &dot_agent_core::BudgetReport,
+    config: &dot_agent_core::BudgetConfig,
+    detailed: bool,
+) {
+    use dot_agent_core::ContextLoad;
+
+    println!("{}", "Context budget (estimated tokens)".bold());
+    println!(
+        "  Always loaded: {:>8}  {}",
+        report.tokens_for(ContextLoad::Always),
+        "(CLAUDE.md, rules)".dimmed()
+    );
+    println!(
+        "  On demand:     {:>8}  {}",
+        report.tokens_for(ContextLoad::OnDemand),
+        "(skills, commands, agents)".dimmed()
+    );
+    println!(
+        "  Other:         {:>8}",
+        report.tokens_for(ContextLoad::Other)
+    );
+    println!("  Total:         {:>8}", report.total_tokens());
+
+    if detailed {
+        println!();
+        println!("{}", "By category".bold());
+        for (category, tokens) in report.by_category() {
+            println!("  {:<14} {:>8}", category, tokens);
+        }
+
+        for load in [
+            ContextLoad::Always,
+            ContextLoad::OnDemand,
+            ContextLoad::Other,
+        ] {
+            let files: Vec<_> = report.files.iter().filter(|f| f.load == load).collect();
+            if files.is_empty() {
+                continue;
+            }
+            println!();
+            println!("{}", format!("Files ({})", load).bold());
+            for file in files {
+                println!("  {:>8}  {}", file.tokens, file.path.display());
+            }
+        }
+    }
+
+    let warnings = report.warnings(config);
+    if !warnings.is_empty() {
+        println!();
+        for warning in &warnings {
+            println!("{} {}", "[WARN]".yellow(), warning);
+        }
+    }
+}
+
+fn handle_provenance(
+    base_dir: &Path,
+    manager: &ProfileManager,
+    name: &str,
+    fetch: bool,
+    all: bool,
+) -> Result<()> {
+    use dot_agent_core::profile::{LocalState, UpstreamState};
+
+    let mut fetched: Vec<ComponentSource> = Vec::new();
+    let statuses = manager.check_file_provenance(name, |origin| {
+        if !fetch {
+            return None;
+        }
+        let branch = match origin {
+            ComponentOrigin::Git { branch, .. } => branch.as_deref(),
+            _ => None,
+        };
+        match resolve_component_source(base_dir, manager, &origin.spec(), branch) {
+            Ok(source) => {
+                let root = source.root.clone();
+                fetched.push(source);
+                Some(root)
+            }
+            Err(e) => {
+                eprintln!("  {} {}: {}", "[WARN]".yellow(), origin, e);
+                None
+            }
+        }
+    });
+    for source in &fetched {
+        source.cleanup();
+    }
+    let statuses = statuses?;
+
+    println!();
+    println!("{} {}", "Provenance:".bold(), name.cyan());
+    if statuses.is_empty() {
+        println!("  No recorded provenance (files were not copied, fused, imported or generated by a rule).");
+        return Ok(());
+    }
+
+    let (mut drifted, mut updates, mut unknown) = (0, 0, 0);
+    for status in &statuses {
+        let local = match status.local {
+            LocalState::Unchanged => None,
+            LocalState::Modified => Some("[MODIFIED]".yellow()),
+            LocalState::Missing => Some("[MISSING]".red()),
+        };
+        let upstream = match status.upstream {
+            UpstreamState::Unchanged => None,
+            UpstreamState::Updated => Some("[UPSTREAM UPDATED]".cyan()),
+            UpstreamState::Removed => Some("[UPSTREAM REMOVED]".red()),
+            UpstreamState::Unknown => {
+                unknown += 1;
+                None
+            }
+        };
+        drifted += usize::from(status.drifted());
+        updates += usize::from(status.has_upstream_update());
+        if local.is_none() && upstream.is_none() && !all {
+            continue;
+        }
+
+        let p = &status.provenance;
+        let badges: Vec<String> = local
+            .into_iter()
+            .chain(upstream)
+            .map(|b| b.to_string())
+            .collect();
+        println!(
+            "  {} {}",
+            status.path,
+            if badges.is_empty() {
+                "[OK]".green().to_string()
+            } else {
+                badges.join(" ")
+            }
+        );
+        let commit = p
+            .commit
+            .as_deref()
+            .map(|c| format!(" @ {}", &c[..c.len().min(7)]))
+            .unwrap_or_default();
+        println!(
+            "    {} {}:{}{} via {} at {}",
+            "from".dimmed(),
+            p.origin,
+            p.source_path,
+            commit,
+            p.via,
+            p.recorded_at
+        );
+    }
+
+    println!();
+    println!(
+        "{} files tracked, {} drifted, {} with upstream changes",
+        statuses.len(),
+        drifted.to_string().yellow(),
+        updates.to_string().cyan()
+    );
+    if unknown > 0 {
+        println!(
+            "{} files have remote origins that were not checked (use --fetch)",
+            unknown
+        );
+    }
+    Ok(())
+}
+
 /// Parse a marketplace plugin reference in the format "plugin@marketplace"
 /// Returns (plugin_name, marketplace_name) if the format matches
 fn parse_marketplace_ref(input: &str) -> Option<(String, String)> {
@@ -3372,24 +5098,107 @@ fn parse_marketplace_ref(input: &str) -> Option<(String, String)> {
     }
 }
 
-/// Import a plugin from a marketplace channel as a local profile
-fn import_marketplace_plugin(
+/// Import `entry@channel` from a marketplace or local channel as a profile
+fn import_channel_entry(
     base_dir: &Path,
-    plugin_name: &str,
-    marketplace_name: &str,
+    entry_name: &str,
+    channel_name: &str,
+    profile_name: Option<String>,
     manager: &ProfileManager,
     force: bool,
 ) -> Result<dot_agent_core::profile::Profile> {
-    use dot_agent_core::channel::{ChannelSource, ChannelType};
-    use std::fs;
+    use dot_agent_core::channel::ChannelType;
+
+    let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
+    let channel_type = channel_mgr
+        .registry()
+        .get(channel_name)
+        .map(|c| c.channel_type)
+        .ok_or_else(|| DotAgentError::ChannelNotFound {
+            name: channel_name.to_string(),
+        })?;
+
+    if channel_type != ChannelType::Local {
+        return import_marketplace_plugin(base_dir, entry_name, channel_name, manager, force);
+    }
 
     println!();
     println!(
-        "Fetching plugin {} from marketplace {}...",
-        plugin_name.cyan(),
-        marketplace_name.yellow()
+        "Importing {} from local channel {}...",
+        entry_name.cyan(),
+        channel_name.yellow()
     );
 
+    let local = channel_mgr.local_channel(channel_name)?;
+    let entry = local
+        .find(entry_name)?
+        .ok_or_else(|| DotAgentError::ProfileNotFound {
+            name: format!("{}@{}", entry_name, channel_name),
+        })?;
+    let source_path = entry
+        .path
+        .clone()
+        .ok_or_else(|| DotAgentError::TargetNotFound {
+            path: PathBuf::from(entry.url.clone().unwrap_or_default()),
+        })?;
+    let profile_name = profile_name.unwrap_or_else(|| entry_name.to_string());
+    println!("  From: {}", source_path.display());
+
+    let profile = match &entry.plugin {
+        Some(plugin) => {
+            let version = plugin.version.as_deref().unwrap_or("unknown");
+            let profile = manager.import_profile_from_marketplace(
+                &source_path,
+                &profile_name,
+                force,
+                channel_name,
+                entry_name,
+                version,
+            )?;
+            if plugin.has_inline_config() {
+                let written = plugin.write_config_files(&profile.path)?;
+                if !written.is_empty() {
+                    println!(
+                        "  {} Generated config files: {}",
+                        "[OK]".green(),
+                        written.join(", ")
+                    );
+                }
+            }
+            profile
+        }
+        None => manager.import_profile_from_channel(
+            &source_path,
+            &profile_name,
+            force,
+            channel_name,
+            entry_name,
+        )?,
+    };
+
+    println!("  {} Imported as profile: {}", "[OK]".green(), profile_name);
+    Ok(profile)
+}
+
+/// A marketplace plugin cloned to a temporary directory
+struct FetchedPlugin {
+    /// Clone root (remove when done)
+    clone_path: PathBuf,
+    /// Plugin directory within the clone
+    source_path: PathBuf,
+    version: String,
+    info: dot_agent_core::channel::MarketplacePlugin,
+}
+
+/// Clone the repository holding a marketplace plugin
+fn fetch_marketplace_plugin(
+    base_dir: &Path,
+    plugin_name: &str,
+    marketplace_name: &str,
+) -> Result<FetchedPlugin> {
+    use dot_agent_core::channel::{ChannelSource, ChannelType};
+    use std::fs;
+
     // Get channel manager and find the marketplace
     let channel_mgr = ChannelManager::new(base_dir.to_path_buf())?;
 
@@ -3440,24 +5249,20 @@ fn import_marketplace_plugin(
     // Determine the source URL for the plugin
     let (plugin_url, is_external) = if let Some(github_repo) = plugin_info.source_github_repo() {
         // External GitHub repo (source: "github", repo: "owner/repo")
-        (format!("https://github.com/{}", github_repo), true)
+        (channel_mgr.resolve_repo(github_repo)?.url(), true)
     } else if let Some(url) = plugin_info.source_url() {
         // External URL (source: "url", url: "https://...")
         (url.to_string(), true)
     } else if plugin_info.source_path().is_some() {
-        // Relative path within the marketplace repo
-        (format!("https://github.com/{}", repo), false)
+        // Relative path within the marketplace repo (on any configured git host)
+        (channel_mgr.resolve_repo(&repo)?.url(), false)
     } else {
         return Err(DotAgentError::GitHubApiError {
             message: format!("Plugin '{}' has unsupported source type", plugin_name),
         });
     };
 
-    // Profile name for the imported plugin (use plugin name only, @ not allowed in profile names)
-    let profile_name = plugin_name.to_string();
-
-    // Import the plugin as a profile
-    println!("  Importing from: {}", plugin_url);
+    println!("  Fetching from: {}", plugin_url);
 
     // Determine the subdirectory path for the plugin
     let subdir = if is_external {
@@ -3511,27 +5316,57 @@ fn import_marketplace_plugin(
         return Err(DotAgentError::TargetNotFound { path: source_path });
     }
 
+    Ok(FetchedPlugin {
+        clone_path,
+        source_path,
+        version,
+        info: plugin_info,
+    })
+}
+
+/// Import a plugin from a marketplace channel as a local profile
+fn import_marketplace_plugin(
+    base_dir: &Path,
+    plugin_name: &str,
+    marketplace_name: &str,
+    manager: &ProfileManager,
+    force: bool,
+) -> Result<dot_agent_core::profile::Profile> {
+    use std::fs;
+
+    println!();
+    println!(
+        "Fetching plugin {} from marketplace {}...",
+        plugin_name.cyan(),
+        marketplace_name.yellow()
+    );
+
+    let plugin = fetch_marketplace_plugin(base_dir, plugin_name, marketplace_name)?;
+
+    // Profile name for the imported plugin (use plugin name only, @ not allowed in profile names)
+    let profile_name = plugin_name.to_string();
+
     // Import the plugin directory as a profile using marketplace source
     let result = manager.import_profile_from_marketplace(
-        &source_path,
+        &plugin.source_path,
         &profile_name,
         force,
         marketplace_name,
         plugin_name,
-        &version,
+        &plugin.version,
     );
 
     // Cleanup temp directory
-    let _ = fs::remove_dir_all(&clone_path);
+    let _ = fs::remove_dir_all(&plugin.clone_path);
 
     result?;
 
     println!("  {} Imported as profile: {}", "[OK]".green(), profile_name);
 
     // Write inline configuration files if plugin has them (strict: false pattern)
-    if plugin_info.has_inline_config() {
+    if plugin.info.has_inline_config() {
         let profile = manager.get_profile(&profile_name)?;
-        let written = plugin_info.write_config_files(&profile.path)?;
+        let written = plugin.info.write_config_files(&profile.path)?;
         if !written.is_empty() {
             println!(
                 "  {} Generated config files: {}",
@@ -3551,14 +5386,14 @@ fn import_marketplace_plugin(
 
 /// Determine classification mode from config
 fn get_classification_mode(base_dir: &Path) -> dot_agent_core::ClassificationMode {
-    use dot_agent_core::{ClassificationMode, Config};
+    dot_agent_core::Config::load(base_dir)
+        .unwrap_or_default()
+        .classification_mode()
+}
 
-    let config = Config::load(base_dir).unwrap_or_default();
-    if config.llm.enabled {
-        ClassificationMode::Llm
-    } else {
-        ClassificationMode::Glob
-    }
+/// LLM backend selected by `[llm]` in config.toml
+fn get_llm_backend(base_dir: &Path) -> Result<std::sync::Arc<dyn dot_agent_core::LlmBackend>> {
+    dot_agent_core::Config::load(base_dir)?.llm_backend()
 }
 
 fn handle_categories(
@@ -3567,8 +5402,9 @@ fn handle_categories(
     show_uncategorized: bool,
     filter_category: Option<&str>,
     detailed: bool,
+    refresh: bool,
 ) -> Result<()> {
-    use dot_agent_core::category::CategoryClassifier;
+    use dot_agent_core::category::{CategoryClassifier, ClassificationMode};
 
     let manager = ProfileManager::new(base_dir.to_path_buf());
     let profile = manager.get_profile(profile_name)?;
@@ -3576,9 +5412,16 @@ fn handle_categories(
     let mode = get_classification_mode(base_dir);
 
     // Build classifier from profile (includes any custom category definitions)
-    let classifier = CategoryClassifier::from_profile(&profile, mode)?;
+    let mut classifier = CategoryClassifier::from_profile(&profile, mode)?.with_refresh(refresh);
+    if mode == ClassificationMode::Llm {
+        classifier = classifier.with_backend(get_llm_backend(base_dir)?);
+    }
     let result = classifier.classify(&profile)?;
 
+    for warning in &result.warnings {
+        println!("{} {}", "[WARN]".yellow(), warning);
+    }
+
     // If filtering by specific category
     if let Some(cat_name) = filter_category {
         let cat_def =
@@ -3617,6 +5460,17 @@ fn handle_categories(
         profile_name.bold(),
         result.files.len()
     );
+    if mode == ClassificationMode::Llm {
+        println!(
+            "  {}",
+            format!(
+                "LLM: {} cached, {} classified",
+                result.cached,
+                result.files.len() - result.cached
+            )
+            .dimmed()
+        );
+    }
     println!();
 
     // Get category counts
@@ -3652,9 +5506,19 @@ fn handle_categories(
 
             // Show files if detailed mode
             if detailed && count > 0 {
-                let files = result.files_in_category(cat_name);
+                let files: Vec<_> = result
+                    .files
+                    .iter()
+                    .filter(|f| f.categories.iter().any(|c| c == *cat_name))
+                    .collect();
                 for file in files.iter().take(5) {
-                    println!("      {}", file.display().to_string().dimmed());
+                    let label = match file.confidence {
+                        Some(confidence) => {
+                            format!("{} ({:.2})", file.path.display(), confidence)
+                        }
+                        None => file.path.display().to_string(),
+                    };
+                    println!("      {}", label.dimmed());
                 }
                 if files.len() > 5 {
                     println!(
@@ -3693,11 +5557,15 @@ fn handle_fusion(
     force: bool,
     dry_run: bool,
     include_uncategorized: bool,
+    strategy: &str,
 ) -> Result<()> {
-    use dot_agent_core::{FusionConfig, FusionExecutor, FusionSpec};
+    use dot_agent_core::{FusionConfig, FusionExecutor, FusionSpec, FusionStrategy};
 
     let manager = ProfileManager::new(base_dir.to_path_buf());
     let mode = get_classification_mode(base_dir);
+    let strategy: FusionStrategy = strategy
+        .parse()
+        .map_err(|message| DotAgentError::ConfigParseSimple { message })?;
 
     // Parse specs
     let parsed_specs: Vec<FusionSpec> = specs
@@ -3740,8 +5608,13 @@ fn handle_fusion(
         force,
         include_uncategorized,
         dry_run,
+        strategy,
     };
-    let executor = FusionExecutor::new(parsed_specs, config);
+    let mut executor = FusionExecutor::new(parsed_specs, config)
+        .with_resolver(Box::new(InteractiveFusionResolver));
+    if mode == dot_agent_core::ClassificationMode::Llm {
+        executor = executor.with_backend(get_llm_backend(base_dir)?);
+    }
 
     // Get plan first
     let plan = executor.plan(&manager)?;
@@ -3749,27 +5622,24 @@ fn handle_fusion(
     // Report conflicts
     if !plan.conflicts.is_empty() {
         println!(
-            "{} {} conflicts (later spec wins):",
+            "{} {} conflicts (strategy: {}):",
             "[WARN]".yellow(),
-            plan.conflicts.len()
+            plan.conflicts.len(),
+            strategy
         );
         for conflict in &plan.conflicts {
-            let profiles: Vec<_> = conflict
-                .sources
-                .iter()
-                .map(|s| s.profile_name.as_str())
-                .collect();
-            println!(
-                "  {} <- {}",
-                conflict.path.display().to_string().dimmed(),
-                profiles.join(", ")
-            );
+            print_fusion_conflict(conflict);
         }
         println!();
     }
 
     // Summary
     println!("{} {} files to copy", "[Summary]".cyan(), plan.files.len());
+    print_budget(
+        &plan.budget,
+        &dot_agent_core::Config::load(base_dir)?.budget,
+        false,
+    );
 
     if dry_run {
         println!();
@@ -3844,6 +5714,131 @@ fn handle_fusion(
     Ok(())
 }
 
+/// Print a resolved fusion conflict
+fn print_fusion_conflict(conflict: &dot_agent_core::FusionConflict) {
+    let profiles: Vec<_> = conflict
+        .sources
+        .iter()
+        .map(|s| s.profile_name.as_str())
+        .collect();
+    println!(
+        "  {} <- {} {}",
+        conflict.path.display().to_string().dimmed(),
+        profiles.join(", "),
+        format!("[{}]", conflict.strategy).cyan()
+    );
+    if conflict.outputs.len() > 1
+        || conflict
+            .outputs
+            .iter()
+            .any(|o| Path::new(o) != conflict.path)
+    {
+        for output in &conflict.outputs {
+            println!("    {} {}", "->".dimmed(), output);
+        }
+    }
+}
+
+fn handle_fusion_refresh(
+    base_dir: &Path,
+    output_name: &str,
+    force: bool,
+    dry_run: bool,
+) -> Result<()> {
+    use dot_agent_core::{FusionChangeKind, FusionExecutor, ProfileMetadata};
+
+    const MAX_LINES: usize = 20;
+
+    let manager = ProfileManager::new(base_dir.to_path_buf());
+    let profile = manager.get_profile(output_name)?;
+    let recipe = ProfileMetadata::load(&profile.path)?
+        .and_then(|m| m.fusion)
+        .ok_or_else(|| DotAgentError::ConfigParseSimple {
+            message: format!(
+                "Profile '{}' has no fusion recipe (was it created by `dot-agent fusion`?)",
+                output_name
+            ),
+        })?;
+
+    println!(
+        "{} Refreshing '{}' from {}",
+        "[Fusion]".cyan().bold(),
+        output_name.bold(),
+        recipe.specs.join(" ")
+    );
+    println!();
+
+    let mut executor = FusionExecutor::from_recipe(&recipe, force, dry_run)?
+        .with_resolver(Box::new(InteractiveFusionResolver));
+    if recipe.mode == dot_agent_core::ClassificationMode::Llm {
+        executor = executor.with_backend(get_llm_backend(base_dir)?);
+    }
+    let refresh = executor.refresh(&manager, output_name)?;
+
+    if refresh.changes.is_empty() {
+        println!(
+            "{} Up to date ({} files unchanged)",
+            "[OK]".green().bold(),
+            refresh.unchanged
+        );
+        return Ok(());
+    }
+
+    for change in &refresh.changes {
+        let badge = match change.kind {
+            FusionChangeKind::Added => "[ADDED]".green(),
+            FusionChangeKind::Updated => "[UPDATED]".cyan(),
+            FusionChangeKind::Removed => "[REMOVED]".red(),
+            FusionChangeKind::KeptLocal => "[KEPT LOCAL]".yellow(),
+        };
+        println!(
+            "{} {} {}",
+            badge,
+            change.path,
+            format!("(from {})", change.source_profile).dimmed()
+        );
+        for line in change.diff.iter().take(MAX_LINES) {
+            match line {
+                DiffLine::Added(l) => println!("    {}", format!("+ {}", l).green()),
+                DiffLine::Removed(l) => println!("    {}", format!("- {}", l).red()),
+            }
+        }
+        if change.diff.len() > MAX_LINES {
+            println!(
+                "    {}",
+                format!("... and {} more lines", change.diff.len() - MAX_LINES).dimmed()
+            );
+        }
+    }
+
+    let kept = refresh.changes.len() - refresh.applied();
+    println!();
+    if dry_run {
+        println!(
+            "{} {} changes would be applied, {} unchanged",
+            "[DRY RUN]".yellow(),
+            refresh.applied(),
+            refresh.unchanged
+        );
+    } else {
+        println!(
+            "{} Applied {} changes, {} unchanged",
+            "[OK]".green().bold(),
+            refresh.applied(),
+            refresh.unchanged
+        );
+    }
+    if kept > 0 {
+        println!(
+            "{} {} locally edited files kept (use --force to overwrite)",
+            "[WARN]".yellow(),
+            kept
+        );
+    }
+
+    Ok(())
+}
+
 fn handle_history(action: HistoryAction, base_dir: &Path) -> Result<()> {
     use dot_agent_core::HistoryManager;
 
@@ -3967,12 +5962,40 @@ fn handle_history(action: HistoryAction, base_dir: &Path) -> Result<()> {
                     rule_name,
                     source_profile,
                     output_profile,
+                    params,
                 } => {
                     println!("  Type:    Rule Apply");
                     println!("  Rule:    {}", rule_name);
+                    for (key, value) in params {
+                        println!("  Param:   {}={}", key, value);
+                    }
                     println!("  Source:  {}", source_profile);
                     println!("  Output:  {}", output_profile);
                 }
+                dot_agent_core::OperationType::RuleApplyInstalled {
+                    rule_name,
+                    target,
+                    profile,
+                    params,
+                    files_changed,
+                } => {
+                    println!("  Type:    Rule Apply (installed)");
+                    println!("  Rule:    {}", rule_name);
+                    for (key, value) in params {
+                        println!("  Param:   {}={}", key, value);
+                    }
+                    if let Some(profile) = profile {
+                        println!("  Profile: {}", profile);
+                    }
+                    println!("  Target:  {}", target.display());
+                    println!("  Files changed: {}", files_changed.len());
+                    for file in files_changed.iter().take(10) {
+                        println!("    - {}", file);
+                    }
+                    if files_changed.len() > 10 {
+                        println!("    ... and {} more", files_changed.len() - 10);
+                    }
+                }
                 dot_agent_core::OperationType::UserEdit {
                     target,
                     files_changed,
@@ -4153,6 +6176,10 @@ fn handle_history(action: HistoryAction, base_dir: &Path) -> Result<()> {
                         output_profile,
                         ..
                     } => source_profile == profile_filter || output_profile == profile_filter,
+                    dot_agent_core::OperationType::RuleApplyInstalled {
+                        profile: Some(profile),
+                        ..
+                    } => profile == profile_filter,
                     _ => true,
                 });
             }
@@ -4388,7 +6415,7 @@ fn handle_history(action: HistoryAction, base_dir: &Path) -> Result<()> {
             if !pack.graph.operations.is_empty() {
                 println!("{}", "Recent Operations (up to 5)".bold());
                 let mut ops: Vec<_> = pack.graph.operations.values().collect();
-                ops.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+                ops.sort_by_key(|op| std::cmp::Reverse(op.timestamp));
 
                 for op in ops.iter().take(5) {
                     println!(
@@ -0,0 +1,323 @@
+//! Context budget analysis
+//!
+//! Estimates how many tokens a profile (or an installed target) puts into
+//! the agent's context window. Root `CLAUDE.md`/`AGENTS.md` and `rules/` are
+//! loaded into every session; skills, commands and agents are only read when
+//! they are invoked. Estimates use a character heuristic (about four ASCII
+//! characters per token, one token per other character), not a tokenizer.
+//!
+//! Warning thresholds come from `[budget]` in config.toml:
+//!
+//! ```toml
+//! [budget]
+//! always_loaded_tokens = 10000
+//! file_tokens = 5000
+//! total_tokens = 50000
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::category::{CategoryClassifier, ClassificationMode, FileClassification};
+use crate::error::{DotAgentError, Result};
+use crate::install::{parse_meta_key, Metadata};
+
+/// Warning thresholds (config.toml `[budget]`, 0 disables a check)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// Always-loaded content (CLAUDE.md, rules)
+    #[serde(default = "default_always_loaded_tokens")]
+    pub always_loaded_tokens: usize,
+
+    /// Any single file
+    #[serde(default = "default_file_tokens")]
+    pub file_tokens: usize,
+
+    /// Everything together
+    #[serde(default = "default_total_tokens")]
+    pub total_tokens: usize,
+}
+
+fn default_always_loaded_tokens() -> usize {
+    10_000
+}
+
+fn default_file_tokens() -> usize {
+    5_000
+}
+
+fn default_total_tokens() -> usize {
+    50_000
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            always_loaded_tokens: default_always_loaded_tokens(),
+            file_tokens: default_file_tokens(),
+            total_tokens: default_total_tokens(),
+        }
+    }
+}
+
+/// When a file enters the agent's context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ContextLoad {
+    /// Every session (root CLAUDE.md/AGENTS.md, rules/)
+    Always,
+    /// When invoked (skills, commands, agents)
+    OnDemand,
+    /// Not read as instructions (settings, hooks, scripts, ...)
+    Other,
+}
+
+impl ContextLoad {
+    /// Classify a path relative to the profile or target root
+    pub fn of(path: &Path) -> Self {
+        let mut components = path.components().map(|c| c.as_os_str().to_string_lossy());
+        let first = components.next().unwrap_or_default();
+        let nested = components.next().is_some();
+        let is_markdown = path.extension().is_some_and(|ext| ext == "md");
+
+        match first.as_ref() {
+            "CLAUDE.md" | "AGENTS.md" if !nested => Self::Always,
+            "rules" if nested && is_markdown => Self::Always,
+            "skills" | "commands" | "agents" if nested => Self::OnDemand,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::OnDemand => "on-demand",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ContextLoad {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Estimate the token count of `text`
+pub fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.bytes().filter(u8::is_ascii).count();
+    let other = text.chars().filter(|c| !c.is_ascii()).count();
+    ascii.div_ceil(4) + other
+}
+
+/// Token estimate for one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileEstimate {
+    /// Path relative to the profile or target root
+    pub path: PathBuf,
+    pub bytes: usize,
+    pub tokens: usize,
+    pub load: ContextLoad,
+    pub categories: Vec<String>,
+}
+
+impl FileEstimate {
+    /// Estimate `content` (binary files count as 0 tokens)
+    pub fn new(path: PathBuf, content: &[u8], categories: Vec<String>) -> Self {
+        let tokens = std::str::from_utf8(content).map_or(0, estimate_tokens);
+        Self {
+            load: ContextLoad::of(&path),
+            path,
+            bytes: content.len(),
+            tokens,
+            categories,
+        }
+    }
+}
+
+/// Token estimates for a set of files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetReport {
+    /// Sorted by path
+    pub files: Vec<FileEstimate>,
+}
+
+impl BudgetReport {
+    pub fn new(mut files: Vec<FileEstimate>) -> Self {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { files }
+    }
+
+    /// Estimate classified files under `root`
+    pub fn from_classified(root: &Path, files: &[FileClassification]) -> Result<Self> {
+        let estimates = files
+            .iter()
+            .map(|f| {
+                let content = fs::read(root.join(&f.path))?;
+                Ok(FileEstimate::new(
+                    f.path.clone(),
+                    &content,
+                    f.categories.clone(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(estimates))
+    }
+
+    /// Estimate the files installed in `target_dir`
+    ///
+    /// Installed files have no classification cache, so LLM mode falls back
+    /// to content classification.
+    pub fn for_target(target_dir: &Path, classifier: &CategoryClassifier) -> Result<Self> {
+        let meta = Metadata::load(target_dir)?.ok_or_else(|| DotAgentError::TargetNotFound {
+            path: target_dir.to_path_buf(),
+        })?;
+
+        let mut files: Vec<PathBuf> = meta
+            .files
+            .keys()
+            .map(|key| PathBuf::from(parse_meta_key(key).1))
+            .filter(|rel| target_dir.join(rel).is_file())
+            .collect();
+        files.sort();
+        files.dedup();
+
+        let classified = match classifier.mode() {
+            ClassificationMode::Glob => classifier.classify_by_glob(&files)?,
+            _ => classifier.classify_by_content(target_dir, &files)?,
+        };
+        Self::from_classified(target_dir, &classified)
+    }
+
+    pub fn total_tokens(&self) -> usize {
+        self.files.iter().map(|f| f.tokens).sum()
+    }
+
+    /// Tokens of files loaded as `load`
+    pub fn tokens_for(&self, load: ContextLoad) -> usize {
+        self.files
+            .iter()
+            .filter(|f| f.load == load)
+            .map(|f| f.tokens)
+            .sum()
+    }
+
+    /// Tokens per category (files in several categories count in each,
+    /// files without one under "uncategorized")
+    pub fn by_category(&self) -> BTreeMap<String, usize> {
+        let mut totals = BTreeMap::new();
+        for file in &self.files {
+            if file.categories.is_empty() {
+                *totals.entry("uncategorized".to_string()).or_insert(0) += file.tokens;
+            }
+            for category in &file.categories {
+                *totals.entry(category.clone()).or_insert(0) += file.tokens;
+            }
+        }
+        totals
+    }
+
+    /// Thresholds from `config` that this report exceeds
+    pub fn warnings(&self, config: &BudgetConfig) -> Vec<String> {
+        let exceeds = |tokens: usize, limit: usize| limit > 0 && tokens > limit;
+        let mut warnings = Vec::new();
+
+        let always = self.tokens_for(ContextLoad::Always);
+        if exceeds(always, config.always_loaded_tokens) {
+            warnings.push(format!(
+                "always-loaded content is ~{} tokens (budget {})",
+                always, config.always_loaded_tokens
+            ));
+        }
+        for file in &self.files {
+            if exceeds(file.tokens, config.file_tokens) {
+                warnings.push(format!(
+                    "{} is ~{} tokens (budget {} per file)",
+                    file.path.display(),
+                    file.tokens,
+                    config.file_tokens
+                ));
+            }
+        }
+        let total = self.total_tokens();
+        if exceeds(total, config.total_tokens) {
+            warnings.push(format!(
+                "total content is ~{} tokens (budget {})",
+                total, config.total_tokens
+            ));
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_load_and_estimate() {
+        assert_eq!(ContextLoad::of(Path::new("CLAUDE.md")), ContextLoad::Always);
+        assert_eq!(
+            ContextLoad::of(Path::new("rules/style.md")),
+            ContextLoad::Always
+        );
+        assert_eq!(
+            ContextLoad::of(Path::new("skills/tdd/SKILL.md")),
+            ContextLoad::OnDemand
+        );
+        assert_eq!(
+            ContextLoad::of(Path::new("commands/review.md")),
+            ContextLoad::OnDemand
+        );
+        assert_eq!(
+            ContextLoad::of(Path::new("docs/CLAUDE.md")),
+            ContextLoad::Other
+        );
+        assert_eq!(
+            ContextLoad::of(Path::new("settings.json")),
+            ContextLoad::Other
+        );
+
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("日本語"), 3);
+        assert_eq!(
+            FileEstimate::new("a.png".into(), &[0xff, 0xfe], vec![]).tokens,
+            0
+        );
+    }
+
+    #[test]
+    fn test_report_totals_and_warnings() {
+        let report = BudgetReport::new(vec![
+            FileEstimate::new(
+                "skills/tdd/SKILL.md".into(),
+                "x".repeat(400).as_bytes(),
+                vec!["execute".to_string(), "review".to_string()],
+            ),
+            FileEstimate::new("CLAUDE.md".into(), "x".repeat(4000).as_bytes(), vec![]),
+        ]);
+        assert_eq!(report.files[0].path, PathBuf::from("CLAUDE.md"));
+        assert_eq!(report.total_tokens(), 1100);
+        assert_eq!(report.tokens_for(ContextLoad::Always), 1000);
+        assert_eq!(report.tokens_for(ContextLoad::OnDemand), 100);
+
+        let categories = report.by_category();
+        assert_eq!(categories["uncategorized"], 1000);
+        assert_eq!(categories["execute"], 100);
+        assert_eq!(categories["review"], 100);
+
+        assert!(report.warnings(&BudgetConfig::default()).is_empty());
+        let tight = BudgetConfig {
+            always_loaded_tokens: 500,
+            file_tokens: 0,
+            total_tokens: 1000,
+        };
+        let warnings = report.warnings(&tight);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("always-loaded content is ~1000 tokens"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Classification Cache
+//!
+//! LLM分類の結果をProfileごとに保存し、内容が変わったファイルだけを
+//! 再分類する。キーはファイルのハッシュとカテゴリ定義のバージョン。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::install::metadata::compute_hash;
+
+use super::builtin::CategoryDef;
+
+/// キャッシュファイル名（Profileディレクトリ直下）
+pub(crate) const CLASSIFICATION_CACHE_FILE: &str = ".dot-agent-classification.json";
+
+/// キャッシュされた分類結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CachedClassification {
+    /// 分類時のファイルハッシュ
+    pub hash: String,
+    pub categories: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+/// Profile単位の分類キャッシュ
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct ClassificationCache {
+    /// カテゴリ定義のバージョン（定義が変わると全件無効）
+    pub version: String,
+    /// Profile相対パス → 分類結果
+    #[serde(default)]
+    pub files: BTreeMap<String, CachedClassification>,
+}
+
+impl ClassificationCache {
+    /// キャッシュを読み込む
+    ///
+    /// ファイルがない・壊れている・カテゴリ定義が変わった場合は空のキャッシュ。
+    pub fn load(profile_dir: &Path, version: &str) -> Self {
+        fs::read_to_string(profile_dir.join(CLASSIFICATION_CACHE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|cache| cache.version == version)
+            .unwrap_or_else(|| Self::new(version))
+    }
+
+    /// 空のキャッシュ
+    pub fn new(version: &str) -> Self {
+        Self {
+            version: version.to_string(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    pub fn save(&self, profile_dir: &Path) -> Result<()> {
+        fs::write(
+            profile_dir.join(CLASSIFICATION_CACHE_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// ハッシュが一致する場合のみ結果を返す
+    pub fn get(&self, path: &Path, hash: &str) -> Option<&CachedClassification> {
+        self.files
+            .get(&path.to_string_lossy().to_string())
+            .filter(|c| c.hash == hash)
+    }
+
+    pub fn insert(&mut self, path: &Path, entry: CachedClassification) {
+        self.files.insert(path.to_string_lossy().to_string(), entry);
+    }
+
+    /// Profileから消えたファイルのエントリを削除
+    pub fn retain(&mut self, files: &[PathBuf]) {
+        let keep: Vec<String> = files
+            .iter()
+            .map(|f| f.to_string_lossy().to_string())
+            .collect();
+        self.files.retain(|path, _| keep.contains(path));
+    }
+}
+
+/// カテゴリ定義のバージョン（名前・説明・パターンのハッシュ）
+pub(crate) fn category_set_version(defs: &[&CategoryDef]) -> String {
+    let mut defs = defs.to_vec();
+    defs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut key = String::new();
+    for def in defs {
+        key.push_str(&def.name);
+        key.push('\n');
+        key.push_str(&def.description);
+        key.push('\n');
+        key.push_str(&def.patterns.join("\n"));
+        key.push_str("\n\n");
+    }
+    compute_hash(key.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category::CategoryStore;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_roundtrip_and_invalidation() {
+        let temp = TempDir::new().unwrap();
+        let store = CategoryStore::builtin();
+        let version = category_set_version(&store.all());
+        let path = Path::new("skills/x/SKILL.md");
+
+        let mut cache = ClassificationCache::load(temp.path(), &version);
+        cache.insert(
+            path,
+            CachedClassification {
+                hash: "sha256:a".to_string(),
+                categories: vec!["plan".to_string()],
+                confidence: Some(0.8),
+            },
+        );
+        cache.save(temp.path()).unwrap();
+
+        let cache = ClassificationCache::load(temp.path(), &version);
+        assert_eq!(cache.get(path, "sha256:a").unwrap().confidence, Some(0.8));
+        assert!(cache.get(path, "sha256:b").is_none());
+
+        let cache = ClassificationCache::load(temp.path(), "other-version");
+        assert!(cache.files.is_empty());
+    }
+}
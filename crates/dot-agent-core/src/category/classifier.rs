@@ -3,26 +3,88 @@
 //! ProfileのファイルをCategoryStoreの定義に基づいて分類する。
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use glob::Pattern;
+use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
+use crate::install::metadata::compute_hash;
+use crate::llm::{ClaudeCliBackend, LlmBackend};
 use crate::Profile;
 
 use super::builtin::CategoryDef;
+use super::cache::{category_set_version, CachedClassification, ClassificationCache};
+use super::content::{ContentSignals, KeywordIndex};
 use super::store::CategoryStore;
 
 /// 分類モード
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ClassificationMode {
     /// Globパターンマッチング（高速、デフォルト）
     #[default]
     Glob,
+    /// Glob + フロントマター・見出しのキーワード照合（決定的、LLM不要）
+    Content,
     /// LLMによるセマンティック分類（高精度）
     Llm,
 }
 
+impl ClassificationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Glob => "glob",
+            Self::Content => "content",
+            Self::Llm => "llm",
+        }
+    }
+}
+
+impl std::fmt::Display for ClassificationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ClassificationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "glob" => Ok(Self::Glob),
+            "content" => Ok(Self::Content),
+            "llm" => Ok(Self::Llm),
+            other => Err(format!(
+                "Unknown classification mode '{}' (expected glob, content or llm)",
+                other
+            )),
+        }
+    }
+}
+
+/// カテゴリ分類の設定（config.toml の `[classification]`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationConfig {
+    /// LLM無効時の分類モード（デフォルト: content）
+    #[serde(default = "default_config_mode")]
+    pub mode: ClassificationMode,
+}
+
+fn default_config_mode() -> ClassificationMode {
+    ClassificationMode::Content
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        Self {
+            mode: default_config_mode(),
+        }
+    }
+}
+
 /// ファイルの分類結果
 #[derive(Debug, Clone)]
 pub struct FileClassification {
@@ -45,6 +107,8 @@ pub struct ClassifiedProfile {
     pub mode: ClassificationMode,
     /// 分類中の警告メッセージ
     pub warnings: Vec<String>,
+    /// キャッシュから取得したファイル数（LLMモードのみ）
+    pub cached: usize,
 }
 
 impl ClassifiedProfile {
@@ -83,6 +147,8 @@ pub struct CategoryClassifier {
     mode: ClassificationMode,
     store: CategoryStore,
     compiled_patterns: HashMap<String, Vec<Pattern>>,
+    refresh: bool,
+    backend: Arc<dyn LlmBackend>,
 }
 
 impl CategoryClassifier {
@@ -103,9 +169,23 @@ impl CategoryClassifier {
             mode,
             store,
             compiled_patterns,
+            refresh: false,
+            backend: Arc::new(ClaudeCliBackend),
         })
     }
 
+    /// LLMモードで使うバックエンドを設定（デフォルト: Claude CLI）
+    pub fn with_backend(mut self, backend: Arc<dyn LlmBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// キャッシュを使わずに全ファイルを再分類する（LLMモード）
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
     /// ProfileからCategoryStoreを取得して分類器を構築
     pub fn from_profile(profile: &Profile, mode: ClassificationMode) -> Result<Self> {
         let store = profile.category_store()?;
@@ -117,6 +197,11 @@ impl CategoryClassifier {
         Self::new(mode, CategoryStore::builtin())
     }
 
+    /// 分類モードを取得
+    pub fn mode(&self) -> ClassificationMode {
+        self.mode
+    }
+
     /// 利用可能なカテゴリ名を取得
     pub fn category_names(&self) -> Vec<&str> {
         self.store.names()
@@ -131,9 +216,14 @@ impl CategoryClassifier {
     pub fn classify(&self, profile: &Profile) -> Result<ClassifiedProfile> {
         let files = profile.list_files()?;
 
-        let (classifications, warnings) = match self.mode {
-            ClassificationMode::Glob => (self.classify_by_glob(&files)?, Vec::new()),
-            ClassificationMode::Llm => self.classify_by_llm_with_warnings(profile, &files)?,
+        let mut warnings = Vec::new();
+        let mut cached = 0;
+        let classifications = match self.mode {
+            ClassificationMode::Glob => self.classify_by_glob(&files)?,
+            ClassificationMode::Content => self.classify_by_content(&profile.path, &files)?,
+            ClassificationMode::Llm => {
+                self.classify_by_llm_cached(profile, &files, &mut warnings, &mut cached)?
+            }
         };
 
         Ok(ClassifiedProfile {
@@ -141,6 +231,7 @@ impl CategoryClassifier {
             files: classifications,
             mode: self.mode,
             warnings,
+            cached,
         })
     }
 
@@ -170,19 +261,147 @@ impl CategoryClassifier {
         Ok(results)
     }
 
-    /// LLMで分類（警告情報付き）
-    fn classify_by_llm_with_warnings(
+    /// フロントマターと見出しで分類
+    ///
+    /// Globの結果にキーワード照合で見つかったカテゴリを加える。
+    /// フロントマターに `category:` / `categories:` があればそれだけを使う。
+    pub fn classify_by_content(
+        &self,
+        root: &Path,
+        files: &[PathBuf],
+    ) -> Result<Vec<FileClassification>> {
+        let index = KeywordIndex::new(&self.store.all());
+        let mut results = self.classify_by_glob(files)?;
+
+        for result in &mut results {
+            let Some(signals) = ContentSignals::read(&root.join(&result.path)) else {
+                continue;
+            };
+
+            let explicit: Vec<String> = signals
+                .explicit
+                .iter()
+                .filter(|c| self.store.get(c).is_some())
+                .cloned()
+                .collect();
+            if !explicit.is_empty() {
+                result.categories = explicit;
+                continue;
+            }
+
+            for (name, _) in index.score(&signals.words) {
+                if !result.categories.contains(&name) {
+                    result.categories.push(name);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// キャッシュを使ってLLMで分類
+    ///
+    /// ハッシュがキャッシュと一致しないファイルだけをLLMに送る。
+    /// LLMが使えない場合、未キャッシュのファイルはコンテンツ分類にフォールバックする。
+    fn classify_by_llm_cached(
         &self,
         profile: &Profile,
         files: &[PathBuf],
-    ) -> Result<(Vec<FileClassification>, Vec<String>)> {
-        use crate::llm::{check_claude_cli, execute_claude};
+        warnings: &mut Vec<String>,
+        cached: &mut usize,
+    ) -> Result<Vec<FileClassification>> {
+        let version = category_set_version(&self.store.all());
+        let mut cache = if self.refresh {
+            ClassificationCache::new(&version)
+        } else {
+            ClassificationCache::load(&profile.path, &version)
+        };
 
-        let mut warnings = Vec::new();
+        let hashes: Vec<String> = files
+            .iter()
+            .map(|f| {
+                fs::read(profile.path.join(f))
+                    .map(|content| compute_hash(&content))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let pending: Vec<PathBuf> = files
+            .iter()
+            .zip(&hashes)
+            .filter(|(file, hash)| cache.get(file, hash).is_none())
+            .map(|(file, _)| file.clone())
+            .collect();
+        *cached = files.len() - pending.len();
+
+        let mut fallback = Vec::new();
+        if !pending.is_empty() {
+            match self.classify_by_llm_with_warnings(profile, &pending, warnings)? {
+                Some(classified) => {
+                    for c in classified {
+                        let Some(idx) = files.iter().position(|f| *f == c.path) else {
+                            continue;
+                        };
+                        cache.insert(
+                            &c.path,
+                            CachedClassification {
+                                hash: hashes[idx].clone(),
+                                categories: c.categories,
+                                confidence: c.confidence,
+                            },
+                        );
+                    }
+                }
+                None => fallback = self.classify_by_content(&profile.path, &pending)?,
+            }
+        }
 
-        if !check_claude_cli() {
-            warnings.push("Claude CLI not found, falling back to glob classification".to_string());
-            return Ok((self.classify_by_glob(files)?, warnings));
+        let before = cache.files.len();
+        cache.retain(files);
+        if !pending.is_empty() || cache.files.len() != before {
+            if let Err(e) = cache.save(&profile.path) {
+                warnings.push(format!("Failed to save classification cache: {}", e));
+            }
+        }
+
+        Ok(files
+            .iter()
+            .zip(&hashes)
+            .map(|(file, hash)| {
+                if let Some(c) = fallback.iter().find(|c| c.path == *file) {
+                    return c.clone();
+                }
+                match cache.get(file, hash) {
+                    Some(c) => FileClassification {
+                        path: file.clone(),
+                        categories: c.categories.clone(),
+                        confidence: c.confidence,
+                    },
+                    None => FileClassification {
+                        path: file.clone(),
+                        categories: Vec::new(),
+                        confidence: None,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    /// LLMで分類（警告情報付き）
+    ///
+    /// LLMに返されたファイルの結果のみを返す。LLMが使えない場合はNone。
+    fn classify_by_llm_with_warnings(
+        &self,
+        profile: &Profile,
+        files: &[PathBuf],
+        warnings: &mut Vec<String>,
+    ) -> Result<Option<Vec<FileClassification>>> {
+        if let Err(e) = self.backend.check() {
+            warnings.push(format!(
+                "{} unavailable, falling back to content classification: {}",
+                self.backend.name(),
+                e
+            ));
+            return Ok(None);
         }
 
         let mut category_descriptions = String::new();
@@ -226,18 +445,18 @@ Output ONLY the JSON array, no other text.
 "#
         );
 
-        let output = match execute_claude(&profile.path, &prompt) {
+        let output = match self.backend.complete(&profile.path, &prompt) {
             Ok(output) => output,
             Err(e) => {
                 warnings.push(format!(
-                    "LLM classification failed, falling back to glob: {}",
+                    "LLM classification failed, falling back to content: {}",
                     e
                 ));
-                return Ok((self.classify_by_glob(files)?, warnings));
+                return Ok(None);
             }
         };
 
-        self.parse_llm_output_with_warnings(&output, files, warnings)
+        Ok(self.parse_llm_output_with_warnings(&output, files, warnings))
     }
 
     /// LLM出力をパース（警告情報付き）
@@ -245,8 +464,8 @@ Output ONLY the JSON array, no other text.
         &self,
         output: &str,
         files: &[PathBuf],
-        mut warnings: Vec<String>,
-    ) -> Result<(Vec<FileClassification>, Vec<String>)> {
+        warnings: &mut Vec<String>,
+    ) -> Option<Vec<FileClassification>> {
         let json_str = extract_json_from_output(output);
 
         #[derive(serde::Deserialize)]
@@ -260,39 +479,35 @@ Output ONLY the JSON array, no other text.
             Ok(c) => c,
             Err(e) => {
                 warnings.push(format!(
-                    "Failed to parse LLM output as JSON, falling back to glob: {}",
+                    "Failed to parse LLM output as JSON, falling back to content: {}",
                     e
                 ));
-                return Ok((self.classify_by_glob(files)?, warnings));
+                return None;
             }
         };
 
         let mut results = Vec::with_capacity(files.len());
         for file in files {
             let file_str = file.to_string_lossy();
-            let classification = classifications.iter().find(|c| c.path == file_str.as_ref());
-
-            let (categories, confidence) = match classification {
-                Some(c) => {
-                    let valid_cats: Vec<String> = c
-                        .categories
-                        .iter()
-                        .filter(|cat| self.store.get(cat).is_some())
-                        .cloned()
-                        .collect();
-                    (valid_cats, c.confidence)
-                }
-                None => (Vec::new(), None),
+            let Some(c) = classifications.iter().find(|c| c.path == file_str.as_ref()) else {
+                continue;
             };
 
+            let valid_cats: Vec<String> = c
+                .categories
+                .iter()
+                .filter(|cat| self.store.get(cat).is_some())
+                .cloned()
+                .collect();
+
             results.push(FileClassification {
                 path: file.clone(),
-                categories,
-                confidence,
+                categories: valid_cats,
+                confidence: c.confidence,
             });
         }
 
-        Ok((results, warnings))
+        Some(results)
     }
 }
 
@@ -332,7 +547,7 @@ mod tests {
     fn get_categories(results: &[FileClassification], path: &str) -> Vec<String> {
         results
             .iter()
-            .find(|f| f.path == PathBuf::from(path))
+            .find(|f| f.path == std::path::Path::new(path))
             .map(|f| f.categories.clone())
             .unwrap_or_default()
     }
@@ -397,6 +612,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_llm_mode_uses_cache() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = PathBuf::from("agents/helper.md");
+        fs::create_dir_all(temp.path().join("agents")).unwrap();
+        fs::write(temp.path().join(&file), "# Helper\n").unwrap();
+        let profile = Profile::new("p".to_string(), temp.path().to_path_buf());
+
+        let classifier = CategoryClassifier::builtin(ClassificationMode::Llm).unwrap();
+        let mut cache = ClassificationCache::new(&category_set_version(&classifier.store.all()));
+        cache.insert(
+            &file,
+            CachedClassification {
+                hash: compute_hash(b"# Helper\n"),
+                categories: vec!["plan".to_string()],
+                confidence: Some(0.7),
+            },
+        );
+        cache.save(temp.path()).unwrap();
+
+        let result = classifier.classify(&profile).unwrap();
+        assert_eq!(result.cached, 1);
+        assert_eq!(result.files[0].categories, vec!["plan"]);
+        assert_eq!(result.files[0].confidence, Some(0.7));
+        assert!(result.warnings.is_empty());
+    }
+
     #[test]
     fn test_glob_various_patterns() {
         let results = classify_paths(&[
@@ -0,0 +1,271 @@
+//! Content-based Classification
+//!
+//! SKILL.md やエージェント定義のフロントマター（`description`, `tags`,
+//! 明示的な `category:`）と先頭の見出しを読み、カテゴリ説明
+//! （`CategoryDef.description`）から抽出したキーワードと照合する。
+//! LLMを使わない決定的な分類。
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::profile::parse_frontmatter;
+
+use super::builtin::CategoryDef;
+
+/// 読み込む最大バイト数（フロントマターと先頭の見出しだけを見る）
+const MAX_READ_BYTES: u64 = 16 * 1024;
+/// 参照する見出しの数
+const MAX_HEADINGS: usize = 5;
+/// カテゴリに割り当てる最低スコア
+const MIN_SCORE: f32 = 1.0;
+/// カテゴリ名そのものに一致した場合の重み
+const NAME_WEIGHT: f32 = 2.0;
+/// カテゴリ説明から除外する語
+const STOPWORDS: &[&str] = &[
+    "actual", "after", "and", "any", "before", "for", "from", "into", "phase", "such", "that",
+    "the", "this", "used", "when", "with", "writing",
+];
+
+/// ファイルから読み取った分類の手がかり
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ContentSignals {
+    /// 明示的なカテゴリ指定（`category:` / `categories:`）
+    pub explicit: Vec<String>,
+    /// description・tags・見出しの語（小文字）
+    pub words: Vec<String>,
+}
+
+impl ContentSignals {
+    /// Markdownファイルから読み取る（Markdown以外や読めない場合はNone）
+    pub fn read(path: &Path) -> Option<Self> {
+        if path.extension().map_or(true, |e| e != "md") {
+            return None;
+        }
+        let mut content = String::new();
+        fs::File::open(path)
+            .ok()?
+            .take(MAX_READ_BYTES)
+            .read_to_string(&mut content)
+            .ok()?;
+        Some(Self::parse(&content))
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let fields = parse_frontmatter(content);
+
+        let mut explicit = frontmatter_list(content, &fields, "category");
+        explicit.extend(frontmatter_list(content, &fields, "categories"));
+        let explicit = explicit.iter().map(|c| c.to_lowercase()).collect();
+
+        let mut text = Vec::new();
+        if let Some(description) = fields.get("description") {
+            text.push(description.clone());
+        }
+        text.extend(frontmatter_list(content, &fields, "tags"));
+        text.extend(
+            body(content)
+                .lines()
+                .map(str::trim)
+                .filter(|line| line.starts_with('#'))
+                .take(MAX_HEADINGS)
+                .map(|line| line.trim_start_matches('#').to_string()),
+        );
+
+        Self {
+            explicit,
+            words: text.iter().flat_map(|t| tokenize(t)).collect(),
+        }
+    }
+}
+
+/// カテゴリごとの重み付きキーワード
+pub(crate) struct KeywordIndex {
+    categories: Vec<(String, Vec<(String, f32)>)>,
+}
+
+impl KeywordIndex {
+    /// カテゴリ名と説明からキーワードを作る
+    ///
+    /// 複数カテゴリの説明に現れる語は、その数で重みを割る。
+    pub fn new(defs: &[&CategoryDef]) -> Self {
+        let keywords: Vec<Vec<String>> = defs
+            .iter()
+            .map(|def| {
+                let mut words: Vec<String> = tokenize(&def.description)
+                    .into_iter()
+                    .filter(|w| !STOPWORDS.contains(&w.as_str()))
+                    .collect();
+                words.sort();
+                words.dedup();
+                words
+            })
+            .collect();
+
+        let categories = defs
+            .iter()
+            .zip(&keywords)
+            .map(|(def, words)| {
+                let name = def.name.to_lowercase();
+                let mut weighted: Vec<(String, f32)> = words
+                    .iter()
+                    .filter(|w| **w != name)
+                    .map(|w| {
+                        let shared = keywords.iter().filter(|k| k.contains(w)).count();
+                        (w.clone(), 1.0 / shared as f32)
+                    })
+                    .collect();
+                weighted.push((name, NAME_WEIGHT));
+                (def.name.clone(), weighted)
+            })
+            .collect();
+
+        Self { categories }
+    }
+
+    /// 語に一致するカテゴリ（スコア降順）
+    ///
+    /// 最低スコア以上かつ最高スコアの半分以上のカテゴリを返す。
+    pub fn score(&self, words: &[String]) -> Vec<(String, f32)> {
+        let mut scores: Vec<(String, f32)> = self
+            .categories
+            .iter()
+            .map(|(name, keywords)| {
+                let score = keywords
+                    .iter()
+                    .filter(|(keyword, _)| words.iter().any(|w| word_matches(keyword, w)))
+                    .map(|(_, weight)| weight)
+                    .sum();
+                (name.clone(), score)
+            })
+            .filter(|(_, score)| *score >= MIN_SCORE)
+            .collect();
+
+        let best = scores.iter().map(|(_, s)| *s).fold(0.0, f32::max);
+        scores.retain(|(_, score)| *score >= best / 2.0);
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scores
+    }
+}
+
+/// 語形の違いを許して一致を判定する（"review" と "reviewing" など）
+fn word_matches(keyword: &str, word: &str) -> bool {
+    if keyword == word {
+        return true;
+    }
+    let common = keyword
+        .chars()
+        .zip(word.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let shorter = keyword.chars().count().min(word.chars().count());
+    (common >= 4 && common == shorter) || common >= 6
+}
+
+/// 3文字以上の英数字の語に分割（小文字）
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// フロントマターを除いた本文
+fn body(content: &str) -> &str {
+    if !content.starts_with("---") {
+        return content;
+    }
+    let mut offset = 0;
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        offset += line.len();
+        if idx > 0 && line.trim() == "---" {
+            return &content[offset..];
+        }
+    }
+    content
+}
+
+/// フロントマターのリスト値
+///
+/// `key: a, b`、`key: [a, b]` とブロック形式（`- a`）に対応する。
+fn frontmatter_list(
+    content: &str,
+    fields: &std::collections::HashMap<String, String>,
+    key: &str,
+) -> Vec<String> {
+    let split = |value: &str| -> Vec<String> {
+        value
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(',')
+            .map(|v| v.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+            .filter(|v| !v.is_empty())
+            .collect()
+    };
+    if let Some(value) = fields.get(key) {
+        return split(value);
+    }
+
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) != Some("---") {
+        return Vec::new();
+    }
+    let mut values = Vec::new();
+    let mut in_list = false;
+    for line in lines {
+        if line.trim() == "---" {
+            break;
+        }
+        if in_list {
+            match line.trim().strip_prefix("- ") {
+                Some(item) if line.starts_with([' ', '\t', '-']) => values.extend(split(item)),
+                _ => in_list = false,
+            }
+        }
+        if !in_list && line.trim_end() == format!("{}:", key) {
+            in_list = true;
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category::CategoryStore;
+
+    #[test]
+    fn test_parse_signals() {
+        let signals = ContentSignals::parse(
+            "---\nname: x\ndescription: Reviews pull requests\ntags:\n  - security\n  - Audit\ncategories: [Review]\n---\n\n# Pull request checklist\n\nbody text\n",
+        );
+        assert_eq!(signals.explicit, vec!["review"]);
+        assert!(signals.words.contains(&"reviews".to_string()));
+        assert!(signals.words.contains(&"audit".to_string()));
+        assert!(signals.words.contains(&"checklist".to_string()));
+        assert!(!signals.words.contains(&"body".to_string()));
+    }
+
+    #[test]
+    fn test_keyword_scoring() {
+        let store = CategoryStore::builtin();
+        let index = KeywordIndex::new(&store.all());
+        let score = |text: &str| -> Vec<String> {
+            index
+                .score(&tokenize(text))
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+
+        assert_eq!(
+            score("Use when reviewing pull requests for security issues"),
+            vec!["review"]
+        );
+        assert_eq!(
+            score("Troubleshooting runtime errors and debugging crashes"),
+            vec!["debug"]
+        );
+        assert_eq!(score("Brainstorm the system architecture"), vec!["plan"]);
+        assert!(score("Formats markdown tables").is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! Category Filter
+//!
+//! install/upgrade/diff の対象ファイルをカテゴリで絞り込む。
+//! 選択はインストール先のメタデータに記録され、以降のupgradeでも使われる。
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+use crate::llm::LlmBackend;
+use crate::Profile;
+
+use super::classifier::{CategoryClassifier, ClassificationMode};
+
+/// カテゴリによるファイル選択
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryFilter {
+    /// 含めるカテゴリ（空なら全ファイル）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// 除外するカテゴリ
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// 分類モード
+    #[serde(default)]
+    pub mode: ClassificationMode,
+}
+
+impl CategoryFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self {
+            include,
+            exclude,
+            mode: ClassificationMode::default(),
+        }
+    }
+
+    /// 分類モードを設定
+    pub fn with_mode(mut self, mode: ClassificationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 絞り込みなし（全ファイル対象）か
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// 分類結果のカテゴリがこの選択に合うか
+    ///
+    /// `include` 指定時は未分類ファイルを含めない。
+    pub fn matches(&self, categories: &[String]) -> bool {
+        let included =
+            self.include.is_empty() || categories.iter().any(|c| self.include.contains(c));
+        included && !categories.iter().any(|c| self.exclude.contains(c))
+    }
+
+    /// Profile内で選択されるファイル（Profile相対パス）
+    ///
+    /// 未定義のカテゴリを指定した場合は `CategoryNotFound`。
+    pub fn select(&self, profile: &Profile) -> Result<HashSet<PathBuf>> {
+        self.select_with(
+            profile,
+            CategoryClassifier::from_profile(profile, self.mode)?,
+        )
+    }
+
+    /// 指定したLLMバックエンドで分類して選択する
+    pub fn select_with_backend(
+        &self,
+        profile: &Profile,
+        backend: Arc<dyn LlmBackend>,
+    ) -> Result<HashSet<PathBuf>> {
+        let classifier =
+            CategoryClassifier::from_profile(profile, self.mode)?.with_backend(backend);
+        self.select_with(profile, classifier)
+    }
+
+    fn select_with(
+        &self,
+        profile: &Profile,
+        classifier: CategoryClassifier,
+    ) -> Result<HashSet<PathBuf>> {
+        if let Some(name) = self
+            .include
+            .iter()
+            .chain(&self.exclude)
+            .find(|name| classifier.get_category(name).is_none())
+        {
+            return Err(DotAgentError::CategoryNotFound { name: name.clone() });
+        }
+
+        Ok(classifier
+            .classify(profile)?
+            .files
+            .into_iter()
+            .filter(|f| self.matches(&f.categories))
+            .map(|f| f.path)
+            .collect())
+    }
+}
+
+impl fmt::Display for CategoryFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.include.is_empty() {
+            f.write_str("all")?;
+        } else {
+            f.write_str(&self.include.join(", "))?;
+        }
+        if !self.exclude.is_empty() {
+            write!(f, " (excluding {})", self.exclude.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let cats = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let filter = CategoryFilter::new(cats(&["review", "debug"]), cats(&["plan"]));
+        assert!(filter.matches(&cats(&["review"])));
+        assert!(!filter.matches(&cats(&["review", "plan"])));
+        assert!(!filter.matches(&cats(&["execute"])));
+        assert!(!filter.matches(&[]));
+        assert_eq!(filter.to_string(), "review, debug (excluding plan)");
+
+        let filter = CategoryFilter::new(Vec::new(), cats(&["plan"]));
+        assert!(filter.matches(&[]));
+        assert!(!filter.matches(&cats(&["plan"])));
+        assert_eq!(filter.to_string(), "all (excluding plan)");
+    }
+}
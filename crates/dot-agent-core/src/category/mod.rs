@@ -17,6 +17,9 @@
 //! - `builtin`: ビルトインカテゴリ定義
 //! - `store`: カテゴリ定義のランタイムストア
 //! - `classifier`: 分類器
+//! - `cache`: LLM分類結果のキャッシュ
+//! - `content`: フロントマター・見出しによる決定的な分類
+//! - `filter`: install/upgrade/diff 用のカテゴリ選択
 //!
 //! ## 使用例
 //!
@@ -55,12 +58,17 @@
 //! ```
 
 mod builtin;
+mod cache;
 mod classifier;
+mod content;
+mod filter;
 mod store;
 
 // Re-exports
 pub use builtin::{BuiltinCategory, CategoryDef, BUILTIN_CATEGORIES, DEFAULT_CATEGORY_PRIORITY};
 pub use classifier::{
-    CategoryClassifier, ClassificationMode, ClassifiedProfile, FileClassification,
+    CategoryClassifier, ClassificationConfig, ClassificationMode, ClassifiedProfile,
+    FileClassification,
 };
+pub use filter::CategoryFilter;
 pub use store::{CategoriesConfig, CategoryConfigEntry, CategoryStore};
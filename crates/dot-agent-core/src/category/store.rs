@@ -52,7 +52,7 @@ impl CategoryStore {
     /// 全カテゴリを取得（priority順）
     pub fn all(&self) -> Vec<&CategoryDef> {
         let mut categories: Vec<_> = self.categories.values().collect();
-        categories.sort_by(|a, b| b.priority.cmp(&a.priority));
+        categories.sort_by_key(|c| std::cmp::Reverse(c.priority));
         categories
     }
 
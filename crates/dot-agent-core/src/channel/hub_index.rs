@@ -0,0 +1,589 @@
+//! Hub index format
+//!
+//! A hub repository (or local share) publishes its channel list as
+//! `hub.toml` or `hub.json` at the root:
+//!
+//! ```toml
+//! schema_version = 1
+//! name = "company"
+//! description = "Company-approved channels"
+//!
+//! [[channels]]
+//! name = "platform-skills"
+//! type = "marketplace"
+//! url = "company:platform/claude-marketplace"
+//! description = "Platform team plugins"
+//! tags = ["internal", "platform"]
+//!
+//! [[rules]]
+//! name = "pnpm"
+//! url = "rules/pnpm.md"
+//! version = "1.2.0"
+//! description = "Use pnpm instead of npm"
+//! ```
+//!
+//! Rule URLs are absolute (`https://`, `file://`) or relative to the hub
+//! (its directory, or the `main` branch of its repository).
+//!
+//! Parsing checks syntax, unknown fields and the schema rules below, and
+//! reports every problem with its location (`channels[2].url`).
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+
+use super::types::{Channel, ChannelType};
+
+/// Current hub index schema version
+pub const HUB_SCHEMA_VERSION: u32 = 1;
+
+/// Hub index file names, in lookup order
+pub const HUB_INDEX_FILES: [&str; 2] = ["hub.toml", "hub.json"];
+
+/// Channel types a hub may list
+const HUB_CHANNEL_TYPES: [ChannelType; 5] = [
+    ChannelType::AwesomeList,
+    ChannelType::Marketplace,
+    ChannelType::CodexCatalog,
+    ChannelType::Direct,
+    ChannelType::Local,
+];
+
+/// Serialization format of a hub index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HubFormat {
+    Toml,
+    Json,
+}
+
+impl HubFormat {
+    /// Detect from a file name (`hub.toml` / `hub.json`)
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        if name.ends_with(".toml") {
+            Some(Self::Toml)
+        } else if name.ends_with(".json") {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Toml => "hub.toml",
+            Self::Json => "hub.json",
+        }
+    }
+}
+
+/// A hub index (`hub.toml` / `hub.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HubIndex {
+    /// Schema version (must be [`HUB_SCHEMA_VERSION`])
+    pub schema_version: u32,
+    /// Hub name
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub channels: Vec<HubChannel>,
+    /// Rules published by the hub
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<HubRule>,
+}
+
+/// A channel listed in a hub index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HubChannel {
+    /// Channel name (used as the default local channel name)
+    pub name: String,
+    /// Channel type: awesome, marketplace, codex-catalog, direct, local
+    #[serde(rename = "type")]
+    pub channel_type: String,
+    /// Repository reference, URL, or (for `local`) a path relative to the hub
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Base path within the repository (codex-catalog only, default "skills")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+}
+
+/// A rule listed in a hub index or a channel's `rules.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HubRule {
+    /// Rule name (used as the default local rule name)
+    pub name: String,
+    /// URL or path of the rule markdown, relative to the index
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl HubChannel {
+    /// Parsed channel type (valid after [`HubIndex::parse`])
+    pub fn kind(&self) -> Option<ChannelType> {
+        HUB_CHANNEL_TYPES
+            .into_iter()
+            .find(|t| t.as_str() == self.channel_type)
+    }
+
+    /// Build a local channel entry for this hub channel
+    ///
+    /// `root` resolves relative paths of `local` channels.
+    pub fn to_channel(&self, name: &str, root: Option<&Path>) -> Option<Channel> {
+        let channel = match self.kind()? {
+            ChannelType::AwesomeList => Channel::awesome_list(name, &self.url),
+            ChannelType::Marketplace => Channel::claude_plugin_github(name, &self.url),
+            ChannelType::CodexCatalog => {
+                let mut channel = Channel::codex_skills();
+                channel.name = name.to_string();
+                channel.builtin = false;
+                channel.source = super::types::ChannelSource::codex_catalog(
+                    &self.url,
+                    self.base_path.as_deref().unwrap_or("skills"),
+                );
+                channel
+            }
+            ChannelType::Direct => {
+                let mut channel = Channel::from_url(name, &self.url);
+                channel.channel_type = ChannelType::Direct;
+                channel
+            }
+            ChannelType::Local => {
+                let path = match root {
+                    Some(root) if !self.url.starts_with("file://") => {
+                        root.join(&self.url).display().to_string()
+                    }
+                    _ => self.url.clone(),
+                };
+                Channel::local(name, path)
+            }
+            _ => return None,
+        };
+        Some(match &self.description {
+            Some(desc) => channel.with_description(desc),
+            None => channel,
+        })
+    }
+}
+
+impl HubIndex {
+    /// A new, empty index
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            schema_version: HUB_SCHEMA_VERSION,
+            name: name.into(),
+            description: None,
+            channels: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Parse and validate index content
+    ///
+    /// `source` names the file in error messages.
+    pub fn parse(content: &str, format: HubFormat, source: &str) -> Result<Self> {
+        let invalid = |issues: Vec<String>| DotAgentError::InvalidHubIndex {
+            location: source.to_string(),
+            issues,
+        };
+
+        // Check the version first so future schemas fail with a clear message
+        let version = match format {
+            HubFormat::Toml => toml::from_str::<toml::Value>(content)
+                .map_err(|e| invalid(vec![e.to_string().trim().to_string()]))?
+                .get("schema_version")
+                .and_then(|v| v.as_integer()),
+            HubFormat::Json => serde_json::from_str::<serde_json::Value>(content)
+                .map_err(|e| invalid(vec![e.to_string()]))?
+                .get("schema_version")
+                .and_then(|v| v.as_i64()),
+        };
+        match version {
+            None => {
+                return Err(invalid(vec![
+                    "missing integer field `schema_version`".into()
+                ]))
+            }
+            Some(v) if v != i64::from(HUB_SCHEMA_VERSION) => {
+                return Err(invalid(vec![format!(
+                    "unsupported schema_version {} (supported: {})",
+                    v, HUB_SCHEMA_VERSION
+                )]))
+            }
+            Some(_) => {}
+        }
+
+        let index: Self = match format {
+            HubFormat::Toml => toml::from_str(content)
+                .map_err(|e| invalid(vec![e.to_string().trim().to_string()]))?,
+            HubFormat::Json => {
+                serde_json::from_str(content).map_err(|e| invalid(vec![e.to_string()]))?
+            }
+        };
+
+        let issues = index.validate();
+        if issues.is_empty() {
+            Ok(index)
+        } else {
+            Err(invalid(issues))
+        }
+    }
+
+    /// Find and parse `hub.toml` or `hub.json` in a directory
+    pub fn load_dir(dir: &Path) -> Result<Self> {
+        let path = Self::find(dir).ok_or_else(|| DotAgentError::InvalidHubIndex {
+            location: dir.display().to_string(),
+            issues: vec![format!(
+                "no hub index found (expected {})",
+                HUB_INDEX_FILES.join(" or ")
+            )],
+        })?;
+        Self::load(&path)
+    }
+
+    /// Parse an index file
+    pub fn load(path: &Path) -> Result<Self> {
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let format = HubFormat::from_file_name(file_name).unwrap_or(HubFormat::Toml);
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content, format, &path.display().to_string())
+    }
+
+    /// Path of the index file in a directory, if any
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        HUB_INDEX_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|p| p.is_file())
+    }
+
+    /// Check schema rules, returning one message per problem
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        if self.name.trim().is_empty() {
+            issues.push("name: must not be empty".to_string());
+        }
+
+        let mut seen = HashSet::new();
+        for (i, channel) in self.channels.iter().enumerate() {
+            let at = format!("channels[{}]", i);
+
+            if !is_valid_channel_name(&channel.name) {
+                issues.push(format!(
+                    "{}.name: '{}' must start with a letter and contain only letters, digits, '-', '_' or '.'",
+                    at, channel.name
+                ));
+            } else if !seen.insert(channel.name.as_str()) {
+                issues.push(format!("{}.name: duplicate channel '{}'", at, channel.name));
+            }
+
+            let kind = channel.kind();
+            if kind.is_none() {
+                let valid: Vec<&str> = HUB_CHANNEL_TYPES.iter().map(|t| t.as_str()).collect();
+                issues.push(format!(
+                    "{}.type: unknown type '{}' (valid: {})",
+                    at,
+                    channel.channel_type,
+                    valid.join(", ")
+                ));
+            }
+
+            let url = channel.url.trim();
+            if url.is_empty() {
+                issues.push(format!("{}.url: must not be empty", at));
+            } else if url.chars().any(char::is_whitespace) {
+                issues.push(format!("{}.url: must not contain whitespace", at));
+            } else if let Some((scheme, _)) = url.split_once("://") {
+                let allowed: &[&str] = if kind == Some(ChannelType::Local) {
+                    &["file"]
+                } else {
+                    &["http", "https"]
+                };
+                if !allowed.contains(&scheme) {
+                    issues.push(format!(
+                        "{}.url: scheme '{}' is not allowed for type '{}'",
+                        at, scheme, channel.channel_type
+                    ));
+                }
+            } else if kind != Some(ChannelType::Local) && !url.contains('/') {
+                issues.push(format!(
+                    "{}.url: '{}' is not a URL or owner/repo reference",
+                    at, url
+                ));
+            }
+
+            if channel.base_path.is_some() && kind != Some(ChannelType::CodexCatalog) {
+                issues.push(format!(
+                    "{}.base_path: only allowed for type 'codex-catalog'",
+                    at
+                ));
+            }
+
+            for (j, tag) in channel.tags.iter().enumerate() {
+                if tag.is_empty() || tag.chars().any(char::is_whitespace) {
+                    issues.push(format!(
+                        "{}.tags[{}]: '{}' must be a non-empty word without spaces",
+                        at, j, tag
+                    ));
+                }
+            }
+        }
+
+        issues.extend(validate_rules(&self.rules));
+        issues
+    }
+
+    /// Serialize in the given format
+    pub fn to_string(&self, format: HubFormat) -> Result<String> {
+        match format {
+            HubFormat::Toml => Ok(toml::to_string_pretty(self)?),
+            HubFormat::Json => Ok(serde_json::to_string_pretty(self)? + "\n"),
+        }
+    }
+
+    /// Get a channel by name
+    pub fn get(&self, name: &str) -> Option<&HubChannel> {
+        self.channels.iter().find(|c| c.name == name)
+    }
+
+    /// Get a rule by name
+    pub fn get_rule(&self, name: &str) -> Option<&HubRule> {
+        self.rules.iter().find(|r| r.name == name)
+    }
+
+    /// Scaffold a hub directory with an example index
+    ///
+    /// Returns the path of the written index file.
+    pub fn init_dir(dir: &Path, name: &str, format: HubFormat, force: bool) -> Result<PathBuf> {
+        if let Some(existing) = Self::find(dir) {
+            if !force {
+                return Err(DotAgentError::Conflict { path: existing });
+            }
+        }
+
+        let mut index = Self::new(name);
+        index.description = Some(format!("{} channel hub", name));
+        index.channels.push(HubChannel {
+            name: "example-skills".to_string(),
+            channel_type: ChannelType::Local.as_str().to_string(),
+            url: "channels/example-skills".to_string(),
+            description: Some("Profiles stored in this hub".to_string()),
+            tags: vec!["example".to_string()],
+            base_path: None,
+        });
+
+        fs::create_dir_all(dir.join("channels").join("example-skills"))?;
+        let path = dir.join(format.file_name());
+        let content = match format {
+            HubFormat::Toml => format!(
+                "# dot-agent hub index (validate with: dot-agent hub validate .)\n\
+                 # Channel types: {}\n\n{}",
+                HUB_CHANNEL_TYPES
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                index.to_string(format)?
+            ),
+            HubFormat::Json => index.to_string(format)?,
+        };
+        fs::write(&path, content)?;
+
+        Ok(path)
+    }
+}
+
+/// Check `[[rules]]` entries, returning one message per problem
+pub(crate) fn validate_rules(rules: &[HubRule]) -> Vec<String> {
+    let mut issues = Vec::new();
+    let mut seen = HashSet::new();
+    for (i, rule) in rules.iter().enumerate() {
+        let at = format!("rules[{}]", i);
+
+        if !is_valid_channel_name(&rule.name) {
+            issues.push(format!(
+                "{}.name: '{}' must start with a letter and contain only letters, digits, '-', '_' or '.'",
+                at, rule.name
+            ));
+        } else if !seen.insert(rule.name.as_str()) {
+            issues.push(format!("{}.name: duplicate rule '{}'", at, rule.name));
+        }
+
+        let url = rule.url.trim();
+        if url.is_empty() {
+            issues.push(format!("{}.url: must not be empty", at));
+        } else if url.chars().any(char::is_whitespace) {
+            issues.push(format!("{}.url: must not contain whitespace", at));
+        } else if let Some((scheme, _)) = url.split_once("://") {
+            if !["http", "https", "file"].contains(&scheme) {
+                issues.push(format!("{}.url: scheme '{}' is not allowed", at, scheme));
+            }
+        } else if !url.ends_with(".md") {
+            issues.push(format!("{}.url: '{}' is not a markdown file", at, url));
+        }
+    }
+    issues
+}
+
+fn is_valid_channel_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const VALID: &str = r#"
+schema_version = 1
+name = "company"
+
+[[channels]]
+name = "platform"
+type = "marketplace"
+url = "company:platform/marketplace"
+tags = ["internal"]
+
+[[channels]]
+name = "codex"
+type = "codex-catalog"
+url = "https://github.com/openai/skills"
+base_path = "skills"
+"#;
+
+    fn issues(content: &str, format: HubFormat) -> Vec<String> {
+        match HubIndex::parse(content, format, "hub") {
+            Err(DotAgentError::InvalidHubIndex { issues, .. }) => issues,
+            other => panic!("expected InvalidHubIndex, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_valid_toml() {
+        let index = HubIndex::parse(VALID, HubFormat::Toml, "hub.toml").unwrap();
+        assert_eq!(index.channels.len(), 2);
+        assert_eq!(
+            index.get("platform").unwrap().kind(),
+            Some(ChannelType::Marketplace)
+        );
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let index = HubIndex::parse(VALID, HubFormat::Toml, "hub.toml").unwrap();
+        let json = index.to_string(HubFormat::Json).unwrap();
+        let parsed = HubIndex::parse(&json, HubFormat::Json, "hub.json").unwrap();
+        assert_eq!(parsed, index);
+    }
+
+    #[test]
+    fn reports_each_problem_with_location() {
+        let content = r#"
+schema_version = 1
+name = "company"
+
+[[channels]]
+name = "dup"
+type = "awesome"
+url = "https://github.com/a/awesome"
+
+[[channels]]
+name = "dup"
+type = "gopher"
+url = "ftp://example.com/x"
+base_path = "skills"
+tags = ["two words"]
+"#;
+        let issues = issues(content, HubFormat::Toml);
+        assert!(issues
+            .iter()
+            .any(|i| i.starts_with("channels[1].name: duplicate")));
+        assert!(issues
+            .iter()
+            .any(|i| i.starts_with("channels[1].type: unknown type 'gopher'")));
+        assert!(issues
+            .iter()
+            .any(|i| i.starts_with("channels[1].url: scheme 'ftp'")));
+        assert!(issues
+            .iter()
+            .any(|i| i.starts_with("channels[1].base_path")));
+        assert!(issues.iter().any(|i| i.starts_with("channels[1].tags[0]")));
+    }
+
+    #[test]
+    fn validates_rules() {
+        let content = r#"
+schema_version = 1
+name = "company"
+
+[[rules]]
+name = "pnpm"
+url = "rules/pnpm.md"
+version = "1.0.0"
+
+[[rules]]
+name = "pnpm"
+url = "ftp://example.com/pnpm.md"
+
+[[rules]]
+name = "docs"
+url = "rules/docs.txt"
+"#;
+        let issues = issues(content, HubFormat::Toml);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("rules[1].name: duplicate"));
+        assert!(issues[1].starts_with("rules[1].url: scheme 'ftp'"));
+        assert!(issues[2].starts_with("rules[2].url"));
+
+        let valid = "schema_version = 1\nname = \"x\"\n\n[[rules]]\nname = \"pnpm\"\nurl = \"https://example.com/pnpm.md\"\n";
+        let index = HubIndex::parse(valid, HubFormat::Toml, "hub.toml").unwrap();
+        assert_eq!(index.get_rule("pnpm").unwrap().version, None);
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_versions() {
+        let unknown = "schema_version = 1\nname = \"x\"\nchannel = []\n";
+        assert!(issues(unknown, HubFormat::Toml)[0].contains("unknown field `channel`"));
+
+        let future = r#"{"schema_version": 2, "name": "x"}"#;
+        assert_eq!(
+            issues(future, HubFormat::Json),
+            vec!["unsupported schema_version 2 (supported: 1)".to_string()]
+        );
+
+        let missing = "name = \"x\"\n";
+        assert!(issues(missing, HubFormat::Toml)[0].contains("schema_version"));
+    }
+
+    #[test]
+    fn init_dir_validates() {
+        let temp = TempDir::new().unwrap();
+        let path = HubIndex::init_dir(temp.path(), "company", HubFormat::Toml, false).unwrap();
+        assert_eq!(path, temp.path().join("hub.toml"));
+
+        let index = HubIndex::load_dir(temp.path()).unwrap();
+        assert_eq!(index.name, "company");
+        assert!(temp.path().join("channels/example-skills").is_dir());
+
+        assert!(HubIndex::init_dir(temp.path(), "company", HubFormat::Toml, false).is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Offline search index
+//!
+//! A persistent inverted index over cached channel content and local
+//! profiles, stored at `~/.dot-agent/cache/index/index.json`.
+//!
+//! Documents are grouped by source (`channel:<name>` or `profile:<name>`)
+//! so a refresh replaces exactly the documents it produced. Queries match
+//! every term as a token prefix and never touch the network.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+use crate::profile::Profile;
+
+use super::types::ProfileRef;
+
+const INDEX_DIR: &str = "index";
+const INDEX_FILE: &str = "index.json";
+const INDEX_VERSION: u32 = 1;
+
+/// Channel name given to documents from local profiles
+pub const PROFILES_SOURCE: &str = "profiles";
+
+/// Metadata fields indexed in addition to name and description
+const INDEXED_METADATA: [&str; 5] = ["category", "kind", "keywords", "tags", "profile"];
+
+/// A document in the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedDoc {
+    /// Source key (`channel:<name>` / `profile:<name>`)
+    source: String,
+    profile: ProfileRef,
+}
+
+/// Persistent inverted index of searchable entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    /// When each source was last indexed
+    #[serde(default)]
+    sources: BTreeMap<String, String>,
+    #[serde(default)]
+    docs: Vec<IndexedDoc>,
+    /// Token -> document positions
+    #[serde(default)]
+    postings: BTreeMap<String, Vec<usize>>,
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            sources: BTreeMap::new(),
+            docs: Vec::new(),
+            postings: BTreeMap::new(),
+        }
+    }
+}
+
+impl SearchIndex {
+    /// Index directory (`<base>/cache/index`)
+    pub fn dir(base_dir: &Path) -> PathBuf {
+        base_dir.join("cache").join(INDEX_DIR)
+    }
+
+    /// Load the index; an empty index if it does not exist yet
+    ///
+    /// An index written by another version is discarded (it is a cache).
+    pub fn load(base_dir: &Path) -> Result<Self> {
+        let path = Self::dir(base_dir).join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let index: Self =
+            serde_json::from_str(&content).map_err(|e| DotAgentError::JsonParseError {
+                message: format!("Invalid search index {}: {}", path.display(), e),
+            })?;
+        if index.version != INDEX_VERSION {
+            return Ok(Self::default());
+        }
+        Ok(index)
+    }
+
+    /// Save the index
+    pub fn save(&self, base_dir: &Path) -> Result<()> {
+        let dir = Self::dir(base_dir);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(INDEX_FILE), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Source key for a channel
+    pub fn channel_source(channel_name: &str) -> String {
+        format!("channel:{}", channel_name)
+    }
+
+    /// Source key for a local profile
+    pub fn profile_source(profile_name: &str) -> String {
+        format!("profile:{}", profile_name)
+    }
+
+    /// Whether nothing has been indexed yet
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Indexed sources with the time they were last updated
+    pub fn sources(&self) -> &BTreeMap<String, String> {
+        &self.sources
+    }
+
+    /// Number of indexed documents
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// Replace all documents of a source
+    pub fn replace_source(&mut self, source: &str, entries: Vec<ProfileRef>) {
+        self.docs.retain(|d| d.source != source);
+        self.add_docs(source, entries);
+    }
+
+    /// Add or update documents of a source, keeping the others (matched by id)
+    pub fn upsert(&mut self, source: &str, entries: Vec<ProfileRef>) {
+        let ids: BTreeSet<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        self.docs
+            .retain(|d| d.source != source || !ids.contains(d.profile.id.as_str()));
+        self.add_docs(source, entries);
+    }
+
+    /// Remove a source
+    pub fn remove_source(&mut self, source: &str) {
+        self.docs.retain(|d| d.source != source);
+        self.sources.remove(source);
+        self.rebuild_postings();
+    }
+
+    /// Keep only the sources for which `keep` returns true
+    pub fn retain_sources(&mut self, keep: impl Fn(&str) -> bool) {
+        self.docs.retain(|d| keep(&d.source));
+        self.sources.retain(|s, _| keep(s));
+        self.rebuild_postings();
+    }
+
+    /// Replace the documents of one local profile (the profile and its components)
+    pub fn index_profile(&mut self, profile: &Profile) -> Result<()> {
+        let source = Self::profile_source(&profile.name);
+        let url = format!("file://{}", profile.path.display());
+
+        let mut entries = vec![ProfileRef {
+            id: source.clone(),
+            name: profile.name.clone(),
+            owner: PROFILES_SOURCE.to_string(),
+            description: profile.description().ok().flatten().unwrap_or_default(),
+            url: url.clone(),
+            stars: None,
+            channel: PROFILES_SOURCE.to_string(),
+            metadata: [("kind".to_string(), "profile".to_string())].into(),
+            score: 0.0,
+        }];
+
+        for component in profile.components()? {
+            let path = component.path.display().to_string();
+            entries.push(ProfileRef {
+                id: format!("{}/{}", source, path),
+                name: component.name,
+                owner: profile.name.clone(),
+                description: component.description.unwrap_or_default(),
+                url: format!("{}/{}", url, path),
+                stars: None,
+                channel: PROFILES_SOURCE.to_string(),
+                metadata: [
+                    ("kind".to_string(), component.kind.to_string()),
+                    ("profile".to_string(), profile.name.clone()),
+                    ("path".to_string(), path),
+                ]
+                .into(),
+                score: 0.0,
+            });
+        }
+
+        self.replace_source(&source, entries);
+        Ok(())
+    }
+
+    /// Find entries containing every query term (as a token prefix)
+    ///
+    /// `channels` limits results to those channels (empty = all).
+    pub fn search(&self, query: &str, keywords: &[String], channels: &[String]) -> Vec<ProfileRef> {
+        let terms: Vec<String> = tokenize(query)
+            .chain(keywords.iter().flat_map(|k| tokenize(k)))
+            .collect();
+
+        let mut matches: Option<BTreeSet<usize>> = None;
+        for term in &terms {
+            let hits: BTreeSet<usize> = self
+                .postings
+                .range(term.clone()..)
+                .take_while(|(token, _)| token.starts_with(term.as_str()))
+                .flat_map(|(_, docs)| docs.iter().copied())
+                .collect();
+            matches = Some(match matches {
+                Some(prev) => prev.intersection(&hits).copied().collect(),
+                None => hits,
+            });
+        }
+
+        let positions: Vec<usize> = match matches {
+            Some(set) => set.into_iter().collect(),
+            None => (0..self.docs.len()).collect(),
+        };
+
+        positions
+            .into_iter()
+            .map(|i| &self.docs[i].profile)
+            .filter(|p| channels.is_empty() || channels.contains(&p.channel))
+            .cloned()
+            .collect()
+    }
+
+    fn add_docs(&mut self, source: &str, entries: Vec<ProfileRef>) {
+        self.docs
+            .extend(entries.into_iter().map(|profile| IndexedDoc {
+                source: source.to_string(),
+                profile,
+            }));
+        self.sources.insert(
+            source.to_string(),
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        );
+        self.rebuild_postings();
+    }
+
+    fn rebuild_postings(&mut self) {
+        self.postings.clear();
+        for (i, doc) in self.docs.iter().enumerate() {
+            let p = &doc.profile;
+            let text = INDEXED_METADATA
+                .iter()
+                .filter_map(|k| p.metadata.get(*k))
+                .fold(format!("{} {}", p.name, p.description), |acc, v| {
+                    acc + " " + v
+                });
+            let tokens: BTreeSet<String> = tokenize(&text).collect();
+            for token in tokens {
+                self.postings.entry(token).or_default().push(i);
+            }
+        }
+    }
+}
+
+/// Lowercased alphanumeric tokens
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn entry(name: &str, description: &str, channel: &str) -> ProfileRef {
+        ProfileRef {
+            id: format!("{}@{}", name, channel),
+            name: name.to_string(),
+            owner: "owner".to_string(),
+            description: description.to_string(),
+            url: format!("https://github.com/owner/{}", name),
+            stars: None,
+            channel: channel.to_string(),
+            metadata: HashMap::new(),
+            score: 0.0,
+        }
+    }
+
+    #[test]
+    fn prefix_and_terms() {
+        let mut index = SearchIndex::default();
+        index.replace_source(
+            "channel:mp",
+            vec![
+                entry("rust-analyzer", "Rust language server", "mp"),
+                entry("pyright", "Python type checker", "mp"),
+            ],
+        );
+
+        assert_eq!(index.search("rus", &[], &[]).len(), 1);
+        assert_eq!(index.search("language rust", &[], &[]).len(), 1);
+        assert!(index.search("rust python", &[], &[]).is_empty());
+        assert_eq!(index.search("", &[], &[]).len(), 2);
+        assert!(index.search("", &[], &["other".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn replace_upsert_and_persist() {
+        let temp = TempDir::new().unwrap();
+        let mut index = SearchIndex::default();
+        index.replace_source("channel:a", vec![entry("one", "", "a")]);
+        index.replace_source("channel:a", vec![entry("two", "", "a")]);
+        index.upsert("channel:gh", vec![entry("three", "", "gh")]);
+        index.upsert("channel:gh", vec![entry("four", "", "gh")]);
+        assert!(index.search("one", &[], &[]).is_empty());
+        assert_eq!(index.len(), 3);
+
+        index.save(temp.path()).unwrap();
+        let mut loaded = SearchIndex::load(temp.path()).unwrap();
+        assert_eq!(loaded.search("four", &[], &[]).len(), 1);
+
+        loaded.remove_source("channel:gh");
+        assert_eq!(loaded.len(), 1);
+    }
+
+    #[test]
+    fn indexes_profile_components() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("rust");
+        fs::create_dir_all(root.join("skills/tdd")).unwrap();
+        fs::write(
+            root.join("skills/tdd/SKILL.md"),
+            "---\nname: tdd\ndescription: Test driven development\n---\n",
+        )
+        .unwrap();
+
+        let mut index = SearchIndex::default();
+        index
+            .index_profile(&Profile::new("rust".to_string(), root))
+            .unwrap();
+
+        let hits = index.search("driven", &[], &[]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].owner, "rust");
+        assert_eq!(hits[0].metadata["kind"], "skill");
+    }
+}
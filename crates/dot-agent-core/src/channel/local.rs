@@ -0,0 +1,424 @@
+//! Local directory channels
+//!
+//! A local channel points at a directory (or mounted share, or `file://` URL)
+//! so teams without network access can search and import approved profiles.
+//! The directory may contain, in order of precedence:
+//!
+//! ```text
+//! <dir>/.claude-plugin/marketplace.json   # or <dir>/marketplace.json
+//! <dir>/hub.toml                          # hub index (or hub.json, channels.toml)
+//! <dir>/profiles/<name>/                  # or <dir>/<name>/
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::{DotAgentError, Result};
+use crate::path::validate_relative_path;
+use crate::profile::Profile;
+
+use super::hub_index::HubIndex;
+use super::search::MarketplacePlugin;
+use super::types::ChannelRef;
+
+/// Layout of a local channel directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalLayout {
+    /// Claude Code marketplace catalog (path to marketplace.json)
+    Marketplace(PathBuf),
+    /// Hub index (path to hub.toml, hub.json or legacy channels.toml)
+    HubIndex(PathBuf),
+    /// Directory of profile directories
+    Profiles(PathBuf),
+}
+
+/// An entry listed by a local channel
+#[derive(Debug, Clone)]
+pub struct LocalEntry {
+    pub name: String,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub category: Option<String>,
+    /// Directory to import (None when the entry lives elsewhere, e.g., a remote URL)
+    pub path: Option<PathBuf>,
+    /// Location as written in the index (for entries without a local path)
+    pub url: Option<String>,
+    /// Marketplace plugin entry (marketplace layout only)
+    pub plugin: Option<MarketplacePlugin>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HubIndexFile {
+    #[serde(default)]
+    channels: Vec<ChannelRef>,
+}
+
+/// Convert a channel location (`/path`, `./path`, `file:///path`) to a path
+pub fn local_path(location: &str) -> PathBuf {
+    PathBuf::from(location.strip_prefix("file://").unwrap_or(location))
+}
+
+/// Resolve a path listed in a channel or hub index against its directory
+///
+/// Listed paths may not leave the directory (`..` or absolute paths).
+pub(crate) fn listed_path(root: &Path, location: &str) -> Result<PathBuf> {
+    let location = location.strip_prefix("file://").unwrap_or(location);
+    Ok(root.join(validate_relative_path(Path::new(location))?))
+}
+
+/// A local directory channel
+pub struct LocalChannel {
+    root: PathBuf,
+}
+
+impl LocalChannel {
+    /// Open a local channel; the directory must exist
+    pub fn open(location: &str) -> Result<Self> {
+        let root = local_path(location);
+        if !root.is_dir() {
+            return Err(DotAgentError::TargetNotFound { path: root });
+        }
+        Ok(Self { root })
+    }
+
+    /// Channel root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Detect the directory layout
+    pub fn layout(&self) -> LocalLayout {
+        let candidates = [
+            self.root.join(".claude-plugin").join("marketplace.json"),
+            self.root.join("marketplace.json"),
+        ];
+        if let Some(catalog) = candidates.into_iter().find(|p| p.is_file()) {
+            return LocalLayout::Marketplace(catalog);
+        }
+
+        let legacy = self.root.join("channels.toml");
+        if let Some(index) =
+            HubIndex::find(&self.root).or_else(|| legacy.is_file().then_some(legacy))
+        {
+            return LocalLayout::HubIndex(index);
+        }
+
+        let profiles = self.root.join("profiles");
+        if profiles.is_dir() {
+            LocalLayout::Profiles(profiles)
+        } else {
+            LocalLayout::Profiles(self.root.clone())
+        }
+    }
+
+    /// List all entries
+    pub fn entries(&self) -> Result<Vec<LocalEntry>> {
+        match self.layout() {
+            LocalLayout::Marketplace(catalog) => self.marketplace_entries(&catalog),
+            LocalLayout::HubIndex(index) => self.hub_entries(&index),
+            LocalLayout::Profiles(dir) => Self::profile_entries(&dir),
+        }
+    }
+
+    /// Find an entry by name
+    pub fn find(&self, name: &str) -> Result<Option<LocalEntry>> {
+        Ok(self.entries()?.into_iter().find(|e| e.name == name))
+    }
+
+    fn marketplace_entries(&self, catalog: &Path) -> Result<Vec<LocalEntry>> {
+        let content = fs::read_to_string(catalog)?;
+        let data: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| DotAgentError::JsonParseError {
+                message: format!("Invalid {}: {}", catalog.display(), e),
+            })?;
+
+        let plugins = data
+            .get("plugins")
+            .and_then(|p| p.as_array())
+            .ok_or_else(|| DotAgentError::JsonParseError {
+                message: format!("{} has no plugins array", catalog.display()),
+            })?;
+
+        // An entry with a bad path is skipped; the rest stay usable
+        Ok(plugins
+            .iter()
+            .filter_map(|value| {
+                let plugin = MarketplacePlugin::from_json(value);
+                // Only relative sources are available without network access
+                let path = match plugin
+                    .source_path()
+                    .map(|p| listed_path(&self.root, p))
+                    .transpose()
+                {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: skipping {} in {}: {}",
+                            plugin.name,
+                            catalog.display(),
+                            e
+                        );
+                        return None;
+                    }
+                };
+                let url = plugin
+                    .source_url()
+                    .map(str::to_string)
+                    .or_else(|| plugin.source_github_repo().map(str::to_string));
+                Some(LocalEntry {
+                    name: plugin.name.clone(),
+                    description: plugin.description.clone(),
+                    version: plugin.version.clone(),
+                    category: plugin.category.clone(),
+                    path,
+                    url,
+                    plugin: Some(plugin),
+                })
+            })
+            .collect())
+    }
+
+    fn hub_entries(&self, index: &Path) -> Result<Vec<LocalEntry>> {
+        let channels: Vec<ChannelRef> = if index.ends_with("channels.toml") {
+            let content = fs::read_to_string(index)?;
+            let file: HubIndexFile =
+                toml::from_str(&content).map_err(|e| DotAgentError::TomlError {
+                    path: index.to_path_buf(),
+                    message: e.to_string(),
+                })?;
+            file.channels
+        } else {
+            HubIndex::load(index)?
+                .channels
+                .into_iter()
+                .map(|c| ChannelRef {
+                    name: c.name,
+                    url: c.url,
+                    channel_type: Some(c.channel_type),
+                    description: c.description,
+                })
+                .collect()
+        };
+
+        Ok(channels
+            .into_iter()
+            .filter_map(|channel| {
+                let is_remote = channel.url.contains("://") && !channel.url.starts_with("file://");
+                let is_local = !is_remote
+                    && channel
+                        .channel_type
+                        .as_deref()
+                        .map_or(true, |t| t == "local");
+                let path = match is_local
+                    .then(|| listed_path(&self.root, &channel.url))
+                    .transpose()
+                {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: skipping {} in {}: {}",
+                            channel.name,
+                            index.display(),
+                            e
+                        );
+                        return None;
+                    }
+                };
+                Some(LocalEntry {
+                    name: channel.name,
+                    description: channel.description,
+                    version: None,
+                    category: channel.channel_type,
+                    path,
+                    url: (!is_local).then_some(channel.url),
+                    plugin: None,
+                })
+            })
+            .collect())
+    }
+
+    fn profile_entries(dir: &Path) -> Result<Vec<LocalEntry>> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !path.is_dir() || name.starts_with('.') {
+                continue;
+            }
+
+            let profile = Profile::new(name.to_string(), path.clone());
+            let description = profile.description().ok().flatten().or_else(|| {
+                profile
+                    .manifest()
+                    .ok()
+                    .flatten()
+                    .and_then(|m| m.description.clone())
+            });
+
+            entries.push(LocalEntry {
+                name: name.to_string(),
+                description,
+                version: profile.version().ok().flatten(),
+                category: None,
+                path: Some(path),
+                url: None,
+                plugin: None,
+            });
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::HubFormat;
+    use tempfile::TempDir;
+
+    #[test]
+    fn profiles_layout() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("profiles/rust/skills")).unwrap();
+        fs::create_dir_all(temp.path().join("profiles/.hidden")).unwrap();
+        fs::write(
+            temp.path().join("profiles/rust/.dot-agent.toml"),
+            "[profile]\nname = \"rust\"\ndescription = \"Rust setup\"\n",
+        )
+        .unwrap();
+
+        let location = format!("file://{}", temp.path().display());
+        let channel = LocalChannel::open(&location).unwrap();
+        assert!(matches!(channel.layout(), LocalLayout::Profiles(_)));
+
+        let entries = channel.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "rust");
+        assert_eq!(entries[0].description.as_deref(), Some("Rust setup"));
+    }
+
+    #[test]
+    fn marketplace_layout() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".claude-plugin")).unwrap();
+        fs::create_dir_all(temp.path().join("plugins/fmt")).unwrap();
+        fs::write(
+            temp.path().join(".claude-plugin/marketplace.json"),
+            r#"{"name": "internal", "plugins": [
+                {"name": "fmt", "source": "./plugins/fmt", "version": "1.0.0"},
+                {"name": "remote", "source": {"source": "github", "repo": "a/b"}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let channel = LocalChannel::open(temp.path().to_str().unwrap()).unwrap();
+        let fmt = channel.find("fmt").unwrap().unwrap();
+        assert_eq!(fmt.path, Some(temp.path().join("plugins/fmt")));
+        assert_eq!(fmt.version.as_deref(), Some("1.0.0"));
+
+        let remote = channel.find("remote").unwrap().unwrap();
+        assert!(remote.path.is_none());
+        assert_eq!(remote.url.as_deref(), Some("a/b"));
+    }
+
+    #[test]
+    fn hub_index_layout() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("channels.toml"),
+            r#"
+[[channels]]
+name = "approved"
+url = "approved/base"
+description = "Approved base profile"
+
+[[channels]]
+name = "upstream"
+url = "https://github.com/a/b"
+"#,
+        )
+        .unwrap();
+
+        let channel = LocalChannel::open(temp.path().to_str().unwrap()).unwrap();
+        let entries = channel.entries().unwrap();
+        assert_eq!(entries[0].path, Some(temp.path().join("approved/base")));
+        assert!(entries[1].path.is_none());
+    }
+
+    #[test]
+    fn hub_toml_layout() {
+        let temp = TempDir::new().unwrap();
+        HubIndex::init_dir(temp.path(), "team", HubFormat::Json, false).unwrap();
+
+        let channel = LocalChannel::open(temp.path().to_str().unwrap()).unwrap();
+        assert_eq!(
+            channel.layout(),
+            LocalLayout::HubIndex(temp.path().join("hub.json"))
+        );
+        let entry = channel.find("example-skills").unwrap().unwrap();
+        assert_eq!(
+            entry.path,
+            Some(temp.path().join("channels/example-skills"))
+        );
+    }
+
+    #[test]
+    fn open_missing_directory() {
+        assert!(LocalChannel::open("/nonexistent/dot-agent-channel").is_err());
+    }
+
+    #[test]
+    fn listed_paths_stay_in_channel() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        assert_eq!(
+            listed_path(root, "./plugins/fmt").unwrap(),
+            root.join("plugins/fmt")
+        );
+        assert_eq!(
+            listed_path(root, "file://approved/base").unwrap(),
+            root.join("approved/base")
+        );
+
+        for escape in ["../outside", "a/../../outside", "/etc", "file:///etc"] {
+            assert!(matches!(
+                listed_path(root, escape),
+                Err(DotAgentError::InvalidComponentPath { .. })
+            ));
+
+            // A bad entry is skipped without hiding the rest of the listing
+            fs::write(
+                root.join("channels.toml"),
+                format!(
+                    "[[channels]]\nname = \"x\"\nurl = \"{}\"\n\n[[channels]]\nname = \"ok\"\nurl = \"./ok\"\n",
+                    escape
+                ),
+            )
+            .unwrap();
+            let channel = LocalChannel::open(root.to_str().unwrap()).unwrap();
+            let entries = channel.entries().unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].path, Some(root.join("ok")));
+        }
+
+        fs::create_dir_all(root.join(".claude-plugin")).unwrap();
+        fs::write(
+            root.join(".claude-plugin/marketplace.json"),
+            r#"{"name": "internal", "plugins": [
+                {"name": "up", "source": "../up"},
+                {"name": "fmt", "source": "./plugins/fmt"}
+            ]}"#,
+        )
+        .unwrap();
+        let channel = LocalChannel::open(root.to_str().unwrap()).unwrap();
+        let entries = channel.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "fmt");
+    }
+}
@@ -10,6 +10,7 @@
 //!
 //! ```text
 //! Hub (github.com/xxx/dot-agent-hub)
+//! ├── hub.toml               # Channel and rule index (or hub.json, see HubIndex)
 //! ├── channels/
 //! │   ├── awesome-dotfiles.toml
 //! │   └── awesome-neovim.toml
@@ -21,18 +22,35 @@
 //! Local (~/.dot-agent/)
 //! ├── hubs.toml              # Registered Hubs
 //! ├── channels.toml          # Enabled Channels
+//! ├── trust.toml             # Trusted content fingerprints (TOFU)
 //! ├── cache/
 //! │   ├── hubs/              # Hub content cache
-//! │   └── channels/          # Channel content cache
+//! │   ├── channels/          # Channel content cache
+//! │   └── index/             # Offline search index
 //! └── profiles/              # Imported profiles
 //! ```
 
 mod channel_registry;
+mod hub_index;
 mod hub_registry;
+mod index;
+mod local;
+mod ranking;
+mod rules;
 mod search;
+mod trust;
 mod types;
 
 pub use channel_registry::ChannelRegistry;
+pub use hub_index::{
+    HubChannel, HubFormat, HubIndex, HubRule, HUB_INDEX_FILES, HUB_SCHEMA_VERSION,
+};
 pub use hub_registry::HubRegistry;
+pub use index::{SearchIndex, PROFILES_SOURCE};
+pub use local::{local_path, LocalChannel, LocalEntry, LocalLayout};
+pub use rules::{ChannelRule, RULES_INDEX_FILE};
 pub use search::{ChannelManager, MarketplacePlugin};
+pub use trust::{
+    fingerprint, DiffLine, RefreshOutcome, TrustDiff, TrustGate, TrustKind, TrustRecord, TrustStore,
+};
 pub use types::{Channel, ChannelRef, ChannelSource, ChannelType, Hub, ProfileRef, SearchOptions};
@@ -0,0 +1,316 @@
+//! Search result ranking
+//!
+//! Cross-channel results are merged, deduplicated by canonical repository
+//! URL and ordered by a score combining:
+//!
+//! - text relevance of the query over name, description and keywords
+//! - popularity (stars, log-scaled)
+//! - recency (`updated_at` metadata, halving every year)
+//!
+//! Signals a source does not provide (marketplace entries have no stars)
+//! are left out rather than counted as zero, so curated sources do not sink.
+//! The combined value is multiplied by the channel weight.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use super::types::ProfileRef;
+
+const RELEVANCE_WEIGHT: f64 = 0.6;
+const POPULARITY_WEIGHT: f64 = 0.25;
+const RECENCY_WEIGHT: f64 = 0.15;
+
+/// Star count that maps to full popularity
+const STARS_SATURATION: f64 = 10_000.0;
+
+/// Days after which recency halves
+const RECENCY_HALF_LIFE_DAYS: f64 = 365.0;
+
+/// Metadata fields searched in addition to name and description
+const KEYWORD_FIELDS: [&str; 5] = ["category", "keywords", "tags", "kind", "profile"];
+
+/// Ranks and deduplicates search results
+pub(crate) struct Ranker<'a> {
+    terms: Vec<String>,
+    weights: &'a HashMap<String, f64>,
+    now: DateTime<Utc>,
+}
+
+impl<'a> Ranker<'a> {
+    /// `weights` maps channel names to their weight (missing = 1.0)
+    pub(crate) fn new(query: &str, keywords: &[String], weights: &'a HashMap<String, f64>) -> Self {
+        let terms = query
+            .split_whitespace()
+            .chain(keywords.iter().map(String::as_str))
+            .map(str::to_lowercase)
+            .collect();
+        Self {
+            terms,
+            weights,
+            now: Utc::now(),
+        }
+    }
+
+    /// Deduplicate, score and sort results (highest score first)
+    ///
+    /// `sort` may be "stars" or "updated" to order by that field instead,
+    /// with the score breaking ties.
+    pub(crate) fn rank(&self, results: Vec<ProfileRef>, sort: Option<&str>) -> Vec<ProfileRef> {
+        let mut ranked: Vec<ProfileRef> = dedupe(results, self.weights)
+            .into_iter()
+            .map(|mut r| {
+                r.score = self.score(&r);
+                r
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+        match sort {
+            Some("stars") => ranked.sort_by_key(|r| std::cmp::Reverse(r.stars)),
+            Some("updated") => ranked.sort_by_key(|r| {
+                std::cmp::Reverse(r.metadata.get("updated_at").and_then(|u| parse_time(u)))
+            }),
+            _ => {}
+        }
+        ranked
+    }
+
+    /// Score a single result
+    pub(crate) fn score(&self, r: &ProfileRef) -> f64 {
+        let mut signals = vec![(self.relevance(r), RELEVANCE_WEIGHT)];
+        if let Some(stars) = r.stars {
+            signals.push((popularity(stars), POPULARITY_WEIGHT));
+        }
+        if let Some(recency) = r
+            .metadata
+            .get("updated_at")
+            .and_then(|u| parse_time(u))
+            .map(|t| self.recency(t))
+        {
+            signals.push((recency, RECENCY_WEIGHT));
+        }
+
+        let total: f64 = signals.iter().map(|(_, w)| w).sum();
+        let combined = signals.iter().map(|(v, w)| v * w).sum::<f64>() / total;
+        combined * channel_weight(self.weights, &r.channel)
+    }
+
+    /// Fraction of query terms matched, weighted by where they match
+    fn relevance(&self, r: &ProfileRef) -> f64 {
+        if self.terms.is_empty() {
+            return 1.0;
+        }
+
+        let name = r.name.to_lowercase();
+        let description = r.description.to_lowercase();
+        let keywords: Vec<String> = KEYWORD_FIELDS
+            .iter()
+            .filter_map(|f| r.metadata.get(*f))
+            .map(|v| v.to_lowercase())
+            .collect();
+
+        let matched: f64 = self
+            .terms
+            .iter()
+            .map(|term| {
+                if name == *term || name.split(['-', '_', '.']).any(|part| part == term) {
+                    1.0
+                } else if name.contains(term.as_str()) {
+                    0.8
+                } else if keywords.iter().any(|k| k.contains(term.as_str())) {
+                    0.6
+                } else if description.contains(term.as_str()) {
+                    0.5
+                } else {
+                    0.0
+                }
+            })
+            .sum();
+
+        matched / self.terms.len() as f64
+    }
+
+    fn recency(&self, updated: DateTime<Utc>) -> f64 {
+        let age_days = (self.now - updated).num_days().max(0) as f64;
+        0.5f64.powf(age_days / RECENCY_HALF_LIFE_DAYS)
+    }
+}
+
+fn popularity(stars: u64) -> f64 {
+    ((stars as f64).ln_1p() / STARS_SATURATION.ln_1p()).min(1.0)
+}
+
+fn channel_weight(weights: &HashMap<String, f64>, channel: &str) -> f64 {
+    weights.get(channel).copied().unwrap_or(1.0)
+}
+
+/// Parse RFC 3339 (API) or "%Y-%m-%d %H:%M:%S" (local) timestamps
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|t| t.and_utc()))
+        .ok()
+}
+
+/// Canonical form of a repository URL used as the deduplication key
+///
+/// `https://github.com/Owner/Repo.git/` and `http://www.github.com/owner/repo`
+/// are the same repository.
+pub(crate) fn canonical_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url
+        .split_once("://")
+        .map(|(_, rest)| rest.to_string())
+        .unwrap_or(url);
+    let url = url.strip_prefix("www.").unwrap_or(&url);
+    let url = url.trim_end_matches('/');
+    url.strip_suffix(".git").unwrap_or(url).to_string()
+}
+
+/// Merge results that point at the same repository
+///
+/// The entry from the highest-weighted channel (first seen on ties) is kept;
+/// the others fill in stars, description and metadata. Contributing
+/// channels are listed in the `channels` metadata field.
+fn dedupe(results: Vec<ProfileRef>, weights: &HashMap<String, f64>) -> Vec<ProfileRef> {
+    let mut merged: Vec<ProfileRef> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+
+    for r in results {
+        let key = canonical_url(&r.url);
+        let Some(&i) = by_key.get(&key).filter(|_| !key.is_empty()) else {
+            by_key.insert(key, merged.len());
+            merged.push(r);
+            continue;
+        };
+
+        let existing = &mut merged[i];
+        let (mut primary, other) =
+            if channel_weight(weights, &r.channel) > channel_weight(weights, &existing.channel) {
+                (r, existing.clone())
+            } else {
+                (existing.clone(), r)
+            };
+
+        let mut channels: Vec<String> = primary
+            .metadata
+            .get("channels")
+            .map(|c| c.split(", ").map(str::to_string).collect())
+            .unwrap_or_else(|| vec![primary.channel.clone()]);
+        let other_channels: Vec<String> = other
+            .metadata
+            .get("channels")
+            .map(|c| c.split(", ").map(str::to_string).collect())
+            .unwrap_or_else(|| vec![other.channel.clone()]);
+        for channel in other_channels {
+            if !channels.contains(&channel) {
+                channels.push(channel);
+            }
+        }
+
+        primary.stars = primary.stars.max(other.stars);
+        if primary.description.is_empty() || primary.description == "No description" {
+            primary.description = other.description;
+        }
+        for (k, v) in other.metadata {
+            primary.metadata.entry(k).or_insert(v);
+        }
+        primary
+            .metadata
+            .insert("channels".to_string(), channels.join(", "));
+
+        *existing = primary;
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, url: &str, channel: &str, stars: Option<u64>) -> ProfileRef {
+        ProfileRef {
+            id: format!("{}:{}", channel, name),
+            name: name.to_string(),
+            owner: "owner".to_string(),
+            description: String::new(),
+            url: url.to_string(),
+            stars,
+            channel: channel.to_string(),
+            metadata: HashMap::new(),
+            score: 0.0,
+        }
+    }
+
+    #[test]
+    fn canonical_url_normalizes() {
+        assert_eq!(
+            canonical_url("https://github.com/Owner/Repo.git/"),
+            "github.com/owner/repo"
+        );
+        assert_eq!(
+            canonical_url("http://www.github.com/owner/repo"),
+            "github.com/owner/repo"
+        );
+    }
+
+    #[test]
+    fn curated_match_outranks_popular_partial_match() {
+        let weights = HashMap::new();
+        let ranker = Ranker::new("rust", &[], &weights);
+        let ranked = ranker.rank(
+            vec![
+                profile(
+                    "dotfiles",
+                    "https://github.com/a/dotfiles",
+                    "github",
+                    Some(5000),
+                ),
+                profile("rust", "https://github.com/b/plugins", "marketplace", None),
+            ],
+            None,
+        );
+        assert_eq!(ranked[0].name, "rust");
+        assert!(ranked[0].score > ranked[1].score);
+    }
+
+    #[test]
+    fn dedupes_and_merges_metadata() {
+        let weights = HashMap::from([("awesome".to_string(), 1.5)]);
+        let mut from_github = profile("tool", "https://github.com/a/tool", "github", Some(42));
+        from_github
+            .metadata
+            .insert("updated_at".to_string(), "2024-01-01T00:00:00Z".to_string());
+        from_github.description = "A tool".to_string();
+        let from_awesome = profile("tool", "https://github.com/A/tool/", "awesome", None);
+
+        let ranker = Ranker::new("tool", &[], &weights);
+        let ranked = ranker.rank(vec![from_github, from_awesome], None);
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].channel, "awesome");
+        assert_eq!(ranked[0].stars, Some(42));
+        assert_eq!(ranked[0].description, "A tool");
+        assert!(ranked[0].metadata.contains_key("updated_at"));
+        assert_eq!(ranked[0].metadata["channels"], "awesome, github");
+    }
+
+    #[test]
+    fn channel_weight_and_sort_override() {
+        let weights = HashMap::from([("low".to_string(), 0.5)]);
+        let ranker = Ranker::new("", &[], &weights);
+        let ranked = ranker.rank(
+            vec![
+                profile("a", "https://x/a", "low", Some(10)),
+                profile("b", "https://x/b", "normal", Some(1)),
+            ],
+            None,
+        );
+        assert_eq!(ranked[0].name, "b");
+
+        let by_stars = ranker.rank(ranked, Some("stars"));
+        assert_eq!(by_stars[0].name, "a");
+    }
+}
@@ -0,0 +1,528 @@
+//! Rules published by channels and hubs
+//!
+//! Hubs list rules under `[[rules]]` in their index. Channels publish rules
+//! with a `rules.toml` at their root using the same entries:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "pnpm"
+//! url = "rules/pnpm.md"      # relative to rules.toml
+//! version = "1.2.0"
+//! description = "Use pnpm instead of npm"
+//! ```
+//!
+//! Local channels without a `rules.toml` publish every `rules/<name>.md`.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::{DotAgentError, Result};
+use crate::rule::params::declared_version;
+use crate::rule::{FetchedRule, Rule, RuleOrigin};
+
+use super::channel_registry::ChannelRegistry;
+use super::hub_index::{validate_rules, HubRule};
+use super::hub_registry::HubRegistry;
+use super::local::{listed_path, local_path, LocalChannel};
+use super::search::ChannelManager;
+use super::types::ChannelSource;
+
+/// Rule index file published by a channel
+pub const RULES_INDEX_FILE: &str = "rules.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesIndex {
+    #[serde(default)]
+    rules: Vec<HubRule>,
+}
+
+/// A rule available from a channel or hub
+#[derive(Debug, Clone)]
+pub struct ChannelRule {
+    pub name: String,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub tags: Vec<String>,
+    pub origin: RuleOrigin,
+    /// URL or file path of the rule markdown
+    pub location: String,
+    /// File in a channel or hub repository, fetched with the host token
+    repo_file: Option<RepoFile>,
+}
+
+/// A file in a repository URL, on the first of `branches` that has it
+#[derive(Debug, Clone)]
+struct RepoFile {
+    repo_url: String,
+    branches: Vec<String>,
+    path: String,
+}
+
+impl ChannelRule {
+    fn from_entry(entry: HubRule, origin: RuleOrigin, location: String) -> Self {
+        Self {
+            name: entry.name,
+            description: entry.description,
+            version: entry.version,
+            tags: entry.tags,
+            origin,
+            location,
+            repo_file: None,
+        }
+    }
+
+    /// Fetch the rule from `path` in `repo_url`, trying `branches` in order
+    fn in_repo(mut self, repo_url: &str, branches: &[&str], path: &str) -> Self {
+        self.repo_file = Some(RepoFile {
+            repo_url: repo_url.to_string(),
+            branches: branches.iter().map(|b| b.to_string()).collect(),
+            path: path.to_string(),
+        });
+        self
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        query.is_empty()
+            || self.name.to_lowercase().contains(query)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|d| d.to_lowercase().contains(query))
+            || self.tags.iter().any(|t| t.to_lowercase() == query)
+    }
+}
+
+/// Parse and validate a channel's `rules.toml`
+fn parse_rules_index(content: &str, source: &str) -> Result<Vec<HubRule>> {
+    let invalid = |issues: Vec<String>| DotAgentError::InvalidHubIndex {
+        location: source.to_string(),
+        issues,
+    };
+    let index: RulesIndex =
+        toml::from_str(content).map_err(|e| invalid(vec![e.to_string().trim().to_string()]))?;
+    let issues = validate_rules(&index.rules);
+    if issues.is_empty() {
+        Ok(index.rules)
+    } else {
+        Err(invalid(issues))
+    }
+}
+
+fn is_url(location: &str) -> bool {
+    location.contains("://")
+}
+
+fn is_remote(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+impl ChannelManager {
+    /// Rules published by a channel
+    ///
+    /// Local channels are read in place; repository channels fetch
+    /// `rules.toml` from the `main` (or `master`) branch.
+    pub fn channel_rules(&self, channel_name: &str) -> Result<Vec<ChannelRule>> {
+        let channel =
+            self.registry()
+                .get(channel_name)
+                .ok_or_else(|| DotAgentError::ChannelNotFound {
+                    name: channel_name.to_string(),
+                })?;
+        let origin = |name: &str| RuleOrigin::Channel {
+            channel: channel_name.to_string(),
+            name: name.to_string(),
+        };
+
+        let repo_url = match &channel.source {
+            ChannelSource::Local { path } => {
+                let local = LocalChannel::open(path)?;
+                return local_rules(local.root(), origin);
+            }
+            ChannelSource::Marketplace { repo } => self.resolve_repo(repo)?.url(),
+            ChannelSource::Url { url } => url.clone(),
+            _ => return Ok(Vec::new()),
+        };
+
+        let cache_dir = ChannelRegistry::cache_dir(&self.base_dir, channel_name).join("rules");
+        for branch in ["main", "master"] {
+            let index =
+                self.fetch_repo_file(&repo_url, branch, RULES_INDEX_FILE, Some(&cache_dir))?;
+            if let Some(content) = index {
+                let index_url = self.to_raw_file_url(&repo_url, branch, RULES_INDEX_FILE);
+                return Ok(parse_rules_index(&content, &index_url)?
+                    .into_iter()
+                    .map(|entry| {
+                        let origin = origin(&entry.name);
+                        if is_url(&entry.url) {
+                            let location = entry.url.clone();
+                            return ChannelRule::from_entry(entry, origin, location);
+                        }
+                        let path = entry.url.clone();
+                        let location = self.to_raw_file_url(&repo_url, branch, &path);
+                        ChannelRule::from_entry(entry, origin, location).in_repo(
+                            &repo_url,
+                            &[branch],
+                            &path,
+                        )
+                    })
+                    .collect());
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Rules listed in a hub's cached index (empty before the first refresh)
+    ///
+    /// Relative rule paths of repository hubs are fetched from the `main`
+    /// (or `master`) branch, like the index itself.
+    pub fn hub_rules(&self, hub_name: &str) -> Result<Vec<ChannelRule>> {
+        let hubs = HubRegistry::load(&self.base_dir)?;
+        let hub = hubs
+            .get(hub_name)
+            .ok_or_else(|| DotAgentError::HubNotFound {
+                name: hub_name.to_string(),
+            })?;
+        let Some(index) = self.hub_index(hub_name)? else {
+            return Ok(Vec::new());
+        };
+
+        let dir = local_path(&hub.url);
+        index
+            .rules
+            .into_iter()
+            .map(|entry| {
+                let origin = RuleOrigin::Hub {
+                    hub: hub_name.to_string(),
+                    name: entry.name.clone(),
+                };
+                Ok(if is_url(&entry.url) {
+                    let location = entry.url.clone();
+                    ChannelRule::from_entry(entry, origin, location)
+                } else if dir.is_dir() {
+                    let location = listed_path(&dir, &entry.url)?.display().to_string();
+                    ChannelRule::from_entry(entry, origin, location)
+                } else {
+                    let path = entry.url.clone();
+                    let location = self.to_raw_file_url(&hub.url, "main", &path);
+                    ChannelRule::from_entry(entry, origin, location).in_repo(
+                        &hub.url,
+                        &["main", "master"],
+                        &path,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Find `name` in a channel or hub (channels take precedence)
+    pub fn find_rule(&self, name: &str, source: &str) -> Result<ChannelRule> {
+        let rules = if self.registry().contains(source) {
+            self.channel_rules(source)?
+        } else if HubRegistry::load(&self.base_dir)?.get(source).is_some() {
+            self.hub_rules(source)?
+        } else {
+            return Err(DotAgentError::ChannelNotFound {
+                name: source.to_string(),
+            });
+        };
+
+        rules
+            .into_iter()
+            .find(|r| r.name == name)
+            .ok_or_else(|| DotAgentError::RuleNotFound {
+                name: format!("{}@{}", name, source),
+            })
+    }
+
+    /// Fetch a rule from a URL, `name@channel` or `name@hub`
+    pub fn fetch_rule(&self, spec: &str) -> Result<FetchedRule> {
+        if is_url(spec) {
+            let content = self.read_rule(spec)?;
+            return Ok(FetchedRule {
+                origin: RuleOrigin::Url {
+                    url: spec.to_string(),
+                },
+                version: declared_version(&content),
+                content,
+            });
+        }
+
+        let (name, source) = spec
+            .split_once('@')
+            .filter(|(name, source)| !name.is_empty() && !source.is_empty())
+            .ok_or_else(|| DotAgentError::ConfigParseSimple {
+                message: format!(
+                    "invalid rule reference '{}': expected a URL or name@channel",
+                    spec
+                ),
+            })?;
+        let rule = self.find_rule(name, source)?;
+        let content = match &rule.repo_file {
+            Some(file) => {
+                let mut content = None;
+                for branch in &file.branches {
+                    content = self.fetch_repo_file(&file.repo_url, branch, &file.path, None)?;
+                    if content.is_some() {
+                        break;
+                    }
+                }
+                content.ok_or_else(|| DotAgentError::RuleNotFound {
+                    name: rule.location.clone(),
+                })?
+            }
+            None => self.read_rule(&rule.location)?,
+        };
+        Ok(FetchedRule {
+            version: rule.version.or_else(|| declared_version(&content)),
+            origin: rule.origin,
+            content,
+        })
+    }
+
+    /// Search rules by name, description or tag across enabled channels and
+    /// registered hubs (`sources` limits the channels/hubs searched)
+    pub fn search_rules(&self, query: &str, sources: &[String]) -> Result<Vec<ChannelRule>> {
+        let wanted = |name: &str| sources.is_empty() || sources.iter().any(|s| s == name);
+        let mut results = Vec::new();
+
+        for channel in self.registry().list_enabled() {
+            if !wanted(&channel.name) {
+                continue;
+            }
+            match self.channel_rules(&channel.name) {
+                Ok(rules) => results.extend(rules),
+                Err(e) => eprintln!("Warning: {} rule search failed: {}", channel.name, e),
+            }
+        }
+        for hub in HubRegistry::load(&self.base_dir)?.list() {
+            if !wanted(&hub.name) {
+                continue;
+            }
+            match self.hub_rules(&hub.name) {
+                Ok(rules) => results.extend(rules),
+                Err(e) => eprintln!("Warning: {} rule search failed: {}", hub.name, e),
+            }
+        }
+
+        let query = query.to_lowercase();
+        results.retain(|r| r.matches(&query));
+        results.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.origin.spec().cmp(&b.origin.spec()))
+        });
+        Ok(results)
+    }
+
+    /// Read rule markdown from a URL or path
+    fn read_rule(&self, location: &str) -> Result<String> {
+        let not_found = || DotAgentError::RuleNotFound {
+            name: location.to_string(),
+        };
+        if is_remote(location) {
+            return self.http.get_text(location, None)?.ok_or_else(not_found);
+        }
+        let path = local_path(location);
+        if !path.is_file() {
+            return Err(not_found());
+        }
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+/// Rules of a local channel directory
+fn local_rules(root: &Path, origin: impl Fn(&str) -> RuleOrigin) -> Result<Vec<ChannelRule>> {
+    let index = root.join(RULES_INDEX_FILE);
+    if index.is_file() {
+        let entries =
+            parse_rules_index(&fs::read_to_string(&index)?, &index.display().to_string())?;
+        return entries
+            .into_iter()
+            .map(|entry| {
+                let location = if is_url(&entry.url) {
+                    entry.url.clone()
+                } else {
+                    listed_path(root, &entry.url)?.display().to_string()
+                };
+                let origin = origin(&entry.name);
+                Ok(ChannelRule::from_entry(entry, origin, location))
+            })
+            .collect();
+    }
+
+    let dir = root.join("rules");
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut rules = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if !path.extension().is_some_and(|ext| ext == "md") {
+            continue;
+        }
+        let rule = Rule::load(&path)?;
+        let summary = rule.summary();
+        rules.push(ChannelRule {
+            description: (!summary.is_empty()).then_some(summary),
+            version: rule.version(),
+            tags: Vec::new(),
+            origin: origin(&rule.name),
+            location: path.display().to_string(),
+            repo_file: None,
+            name: rule.name,
+        });
+    }
+    rules.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::{Channel, Hub, HubIndex};
+    use crate::rule::RuleManager;
+    use tempfile::TempDir;
+
+    #[test]
+    fn import_rules_from_local_channel_and_hub() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().join("base");
+
+        // Local channel publishing rules/<name>.md
+        let share = temp.path().join("share");
+        fs::create_dir_all(share.join("rules")).unwrap();
+        fs::write(
+            share.join("rules/pnpm.md"),
+            "+++\nversion = \"1.0\"\n+++\n# pnpm\n\nUse pnpm.\n",
+        )
+        .unwrap();
+
+        // Local hub listing a rule
+        let hub_dir = temp.path().join("hub");
+        fs::create_dir_all(hub_dir.join("rules")).unwrap();
+        fs::write(hub_dir.join("rules/docs.md"), "# Docs\n").unwrap();
+        let mut index = HubIndex::new("company");
+        index.rules.push(HubRule {
+            name: "docs".to_string(),
+            url: "rules/docs.md".to_string(),
+            version: Some("2.0".to_string()),
+            description: Some("Documentation conventions".to_string()),
+            tags: vec!["docs".to_string()],
+        });
+        fs::write(
+            hub_dir.join("hub.toml"),
+            index.to_string(crate::channel::HubFormat::Toml).unwrap(),
+        )
+        .unwrap();
+        let mut hubs = HubRegistry::load(&base).unwrap();
+        hubs.add(Hub::new("company", hub_dir.display().to_string()))
+            .unwrap();
+        hubs.save(&base).unwrap();
+
+        let mut registry = ChannelRegistry::load(&base).unwrap();
+        registry
+            .add(Channel::local("team", share.display().to_string()))
+            .unwrap();
+        let manager = ChannelManager::with_registry(base.clone(), registry);
+        manager.refresh_hub(hubs.get("company").unwrap()).unwrap();
+
+        let found = manager.search_rules("", &[]).unwrap();
+        let specs: Vec<String> = found.iter().map(|r| r.origin.spec()).collect();
+        assert_eq!(specs, vec!["docs@company", "pnpm@team"]);
+        assert_eq!(found[1].description.as_deref(), Some("Use pnpm."));
+        assert_eq!(manager.search_rules("documentation", &[]).unwrap().len(), 1);
+
+        let fetched = manager.fetch_rule("pnpm@team").unwrap();
+        assert_eq!(fetched.version.as_deref(), Some("1.0"));
+        let fetched = manager.fetch_rule("docs@company").unwrap();
+        assert_eq!(fetched.version.as_deref(), Some("2.0"));
+        assert_eq!(fetched.content, "# Docs\n");
+
+        let rules = RuleManager::new(base.clone());
+        rules.install("docs", &fetched, false).unwrap();
+        assert_eq!(
+            rules.source("docs").unwrap().unwrap().origin,
+            RuleOrigin::Hub {
+                hub: "company".to_string(),
+                name: "docs".to_string()
+            }
+        );
+
+        assert!(matches!(
+            manager.fetch_rule("missing@team"),
+            Err(DotAgentError::RuleNotFound { .. })
+        ));
+        assert!(matches!(
+            manager.fetch_rule("pnpm@nowhere"),
+            Err(DotAgentError::ChannelNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_hub_rules_fetched_with_host_token() {
+        use crate::git_host::{GitHostConfig, GitHostKind, GitHostRegistry};
+        use crate::http::tests::TestServer;
+        use crate::http::{HttpConfig, HttpFetcher};
+        use std::sync::Arc;
+
+        let mut index = HubIndex::new("company");
+        index.rules.push(HubRule {
+            name: "docs".to_string(),
+            url: "rules/docs.md".to_string(),
+            version: None,
+            description: None,
+            tags: Vec::new(),
+        });
+        let hub_toml = index.to_string(crate::channel::HubFormat::Toml).unwrap();
+        let server = TestServer::start(Arc::new(move |path, headers| {
+            let authorized = headers
+                .iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case("authorization") && v == "token secret");
+            match path {
+                _ if !authorized => (401, vec![], String::new()),
+                "/api/v1/repos/team/hub/raw/hub.toml?ref=main" => (200, vec![], hub_toml.clone()),
+                "/api/v1/repos/team/hub/raw/rules/docs.md?ref=main" => {
+                    (200, vec![], "# Docs\n".to_string())
+                }
+                "/api/v1/repos/team/legacy/raw/hub.toml?ref=master" => {
+                    (200, vec![], hub_toml.clone())
+                }
+                "/api/v1/repos/team/legacy/raw/rules/docs.md?ref=master" => {
+                    (200, vec![], "# Legacy docs\n".to_string())
+                }
+                _ => (404, vec![], String::new()),
+            }
+        }));
+
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().to_path_buf();
+        let hub = Hub::new("company", format!("{}/team/hub", server.url));
+        let legacy = Hub::new("legacy", format!("{}/team/legacy", server.url));
+        let mut hubs = HubRegistry::load(&base).unwrap();
+        hubs.add(hub.clone()).unwrap();
+        hubs.add(legacy.clone()).unwrap();
+        hubs.save(&base).unwrap();
+
+        let mut forge = GitHostConfig::new("forge", GitHostKind::Gitea, &server.url);
+        forge.token = Some("secret".to_string());
+        let http = HttpConfig {
+            retries: 0,
+            ..Default::default()
+        };
+        let manager = ChannelManager::with_registry(base, ChannelRegistry::default())
+            .with_hosts(GitHostRegistry::new(&http, &[forge]))
+            .with_http(HttpFetcher::new(&http).unwrap());
+
+        manager.refresh_hub(&hub).unwrap();
+        let fetched = manager.fetch_rule("docs@company").unwrap();
+        assert_eq!(fetched.content, "# Docs\n");
+
+        // Hubs on master serve their rules from master too
+        manager.refresh_hub(&legacy).unwrap();
+        let fetched = manager.fetch_rule("docs@legacy").unwrap();
+        assert_eq!(fetched.content, "# Legacy docs\n");
+    }
+}
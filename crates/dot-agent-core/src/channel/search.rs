@@ -3,15 +3,28 @@
 //! Provides search capabilities across different channel types.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::process::Command;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::Config;
 use crate::error::{DotAgentError, Result};
+use crate::git_host::{GitHost, GitHostRegistry, HostedRepo, RepoQuery};
+use crate::http::HttpFetcher;
+use crate::profile::ProfileManager;
 
 use super::channel_registry::ChannelRegistry;
-use super::types::{Channel, ChannelSource, ChannelType, ProfileRef, SearchOptions};
+use super::hub_index::{HubFormat, HubIndex, HUB_INDEX_FILES};
+use super::hub_registry::HubRegistry;
+use super::index::SearchIndex;
+use super::local::{local_path, LocalChannel};
+use super::ranking::Ranker;
+use super::trust::{fingerprint, RefreshOutcome, TrustGate, TrustKind};
+use super::types::{Channel, ChannelSource, ChannelType, Hub, ProfileRef, SearchOptions};
+
+/// Marketplace catalog path in a repository
+const MARKETPLACE_FILE: &str = ".claude-plugin/marketplace.json";
 
 /// A skill entry from OpenAI Codex Skills Catalog
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -193,20 +206,63 @@ impl MarketplacePlugin {
 
 /// Channel manager for search operations
 pub struct ChannelManager {
-    base_dir: PathBuf,
+    pub(super) base_dir: PathBuf,
     registry: ChannelRegistry,
+    pub(super) http: HttpFetcher,
+    hosts: GitHostRegistry,
 }
 
 impl ChannelManager {
     /// Create a new channel manager
+    ///
+    /// HTTP settings (proxy, timeout, retries, GitHub token) come from
+    /// `[http]` in config.toml, git hosts from `[[hosts]]`.
     pub fn new(base_dir: PathBuf) -> Result<Self> {
         let registry = ChannelRegistry::load(&base_dir)?;
-        Ok(Self { base_dir, registry })
+        let config = Config::load(&base_dir).unwrap_or_default();
+        let http = HttpFetcher::new(&config.http)?;
+        Ok(Self {
+            base_dir,
+            registry,
+            http,
+            hosts: GitHostRegistry::from_config(&config),
+        })
     }
 
     /// Create with a specific registry
     pub fn with_registry(base_dir: PathBuf, registry: ChannelRegistry) -> Self {
-        Self { base_dir, registry }
+        Self {
+            base_dir,
+            registry,
+            http: HttpFetcher::native_default(),
+            hosts: GitHostRegistry::default(),
+        }
+    }
+
+    /// Use a specific HTTP fetcher (e.g., one backed by a test server)
+    pub fn with_http(mut self, http: HttpFetcher) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Use a specific set of git hosts
+    pub fn with_hosts(mut self, hosts: GitHostRegistry) -> Self {
+        self.hosts = hosts;
+        self
+    }
+
+    /// Get the configured git hosts
+    pub fn hosts(&self) -> &GitHostRegistry {
+        &self.hosts
+    }
+
+    /// Resolve a repository reference against the configured git hosts
+    pub fn resolve_repo(&self, reference: &str) -> Result<HostedRepo> {
+        self.hosts
+            .resolve(reference)
+            .ok_or_else(|| DotAgentError::UnresolvedRepo {
+                reference: reference.to_string(),
+            })
     }
 
     /// Get the registry
@@ -225,6 +281,9 @@ impl ChannelManager {
     }
 
     /// Search across all enabled searchable channels
+    ///
+    /// Results are deduplicated by repository URL and ordered by score
+    /// (relevance, stars, recency and channel weight); see `ProfileRef::score`.
     pub fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<ProfileRef>> {
         let mut results = Vec::new();
 
@@ -239,17 +298,35 @@ impl ChannelManager {
                 .collect()
         };
 
+        let mut seen_online = Vec::new();
         for channel in channels {
             match self.search_channel(channel, query, options) {
-                Ok(refs) => results.extend(refs),
+                Ok(refs) => {
+                    if !Self::has_cache(channel) && !refs.is_empty() {
+                        seen_online.push((channel.name.clone(), refs.clone()));
+                    }
+                    results.extend(refs);
+                }
                 Err(e) => {
                     eprintln!("Warning: {} search failed: {}", channel.name, e);
                 }
             }
         }
 
-        // Sort by stars (descending)
-        results.sort_by(|a, b| b.stars.cmp(&a.stars));
+        // Remember network-only results so they can be found offline later
+        if !seen_online.is_empty() {
+            let _ = self.update_index(|index| {
+                for (name, refs) in seen_online {
+                    index.upsert(&SearchIndex::channel_source(&name), refs);
+                }
+                Ok(())
+            });
+        }
+
+        // Deduplicate and rank across channels
+        let weights = self.channel_weights();
+        let mut results =
+            Ranker::new(query, &options.keywords, &weights).rank(results, options.sort.as_deref());
 
         // Apply limit
         if options.limit > 0 {
@@ -259,6 +336,157 @@ impl ChannelManager {
         Ok(results)
     }
 
+    /// Search the offline index (no network access)
+    ///
+    /// Builds the index from cached channel content and local profiles when
+    /// it does not exist yet. Profiles added or removed since the last
+    /// index update are picked up; use [`ChannelManager::rebuild_index`]
+    /// to re-read everything.
+    pub fn search_offline(&self, query: &str, options: &SearchOptions) -> Result<Vec<ProfileRef>> {
+        let index = if SearchIndex::load(&self.base_dir)?.is_empty() {
+            self.rebuild_index()?
+        } else {
+            self.update_index(|_| Ok(()))?
+        };
+
+        let mut results = index.search(query, &options.keywords, &options.channels);
+        if let Some(min) = options.min_stars {
+            results.retain(|r| r.stars.map_or(true, |s| s >= min));
+        }
+
+        let weights = self.channel_weights();
+        let mut results =
+            Ranker::new(query, &options.keywords, &weights).rank(results, options.sort.as_deref());
+        if options.limit > 0 {
+            results.truncate(options.limit);
+        }
+        Ok(results)
+    }
+
+    /// Rebuild the offline index from cached channel content and local profiles
+    ///
+    /// Channels without cached content are skipped (nothing is fetched);
+    /// results remembered from online repository searches are kept.
+    pub fn rebuild_index(&self) -> Result<SearchIndex> {
+        let previous = SearchIndex::load(&self.base_dir)?;
+        let mut index = previous.clone();
+        index.retain_sources(|source| {
+            self.registry
+                .list_enabled()
+                .iter()
+                .any(|c| !Self::has_cache(c) && source == SearchIndex::channel_source(&c.name))
+        });
+
+        for channel in self.registry.list_searchable() {
+            if !Self::has_cache(channel) {
+                continue;
+            }
+            if let Some(entries) = self.cached_entries(channel) {
+                index.replace_source(&SearchIndex::channel_source(&channel.name), entries);
+            }
+        }
+
+        self.index_profiles(&mut index)?;
+        index.save(&self.base_dir)?;
+        Ok(index)
+    }
+
+    /// Apply a change to the offline index, sync local profiles, and save
+    fn update_index(
+        &self,
+        change: impl FnOnce(&mut SearchIndex) -> Result<()>,
+    ) -> Result<SearchIndex> {
+        let mut index = SearchIndex::load(&self.base_dir)?;
+        change(&mut index)?;
+        self.sync_profiles(&mut index)?;
+        index.save(&self.base_dir)?;
+        Ok(index)
+    }
+
+    /// Re-index a channel from its cache after a refresh or approval
+    fn reindex_channel(&self, channel_name: &str) -> Result<()> {
+        let Some(channel) = self.registry.get(channel_name) else {
+            return Ok(());
+        };
+        let Some(entries) = self.cached_entries(channel) else {
+            return Ok(());
+        };
+        let source = SearchIndex::channel_source(channel_name);
+        self.update_index(|index| {
+            index.replace_source(&source, entries);
+            self.index_profiles(index)
+        })?;
+        Ok(())
+    }
+
+    /// All entries of a channel read from its cache (None if not cached)
+    fn cached_entries(&self, channel: &Channel) -> Option<Vec<ProfileRef>> {
+        let cached = match Self::cache_file_name(channel.channel_type) {
+            Some(file) => ChannelRegistry::cache_dir(&self.base_dir, &channel.name)
+                .join(file)
+                .exists(),
+            None => channel.channel_type == ChannelType::Local,
+        };
+        if !cached {
+            return None;
+        }
+        self.search_channel(channel, "", &SearchOptions::default())
+            .ok()
+    }
+
+    /// Whether a channel's content is cached locally (or read in place)
+    fn has_cache(channel: &Channel) -> bool {
+        Self::cache_file_name(channel.channel_type).is_some()
+            || channel.channel_type == ChannelType::Local
+    }
+
+    /// Cache file holding a channel's fetched content
+    fn cache_file_name(channel_type: ChannelType) -> Option<&'static str> {
+        match channel_type {
+            ChannelType::AwesomeList => Some("content.md"),
+            ChannelType::Marketplace => Some("marketplace.json"),
+            ChannelType::CodexCatalog => Some("codex_catalog.json"),
+            _ => None,
+        }
+    }
+
+    /// Re-index every local profile
+    fn index_profiles(&self, index: &mut SearchIndex) -> Result<()> {
+        index.retain_sources(|s| !s.starts_with("profile:"));
+        for profile in ProfileManager::new(self.base_dir.clone()).list_profiles()? {
+            index.index_profile(&profile)?;
+        }
+        Ok(())
+    }
+
+    /// Index profiles added since the last update and drop removed ones
+    fn sync_profiles(&self, index: &mut SearchIndex) -> Result<()> {
+        let profiles = ProfileManager::new(self.base_dir.clone()).list_profiles()?;
+        let current: Vec<String> = profiles
+            .iter()
+            .map(|p| SearchIndex::profile_source(&p.name))
+            .collect();
+        index.retain_sources(|s| !s.starts_with("profile:") || current.iter().any(|c| c == s));
+        for profile in &profiles {
+            if !index
+                .sources()
+                .contains_key(&SearchIndex::profile_source(&profile.name))
+            {
+                index.index_profile(profile)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Configured channel ranking weights
+    fn channel_weights(&self) -> HashMap<String, f64> {
+        self.registry
+            .list()
+            .iter()
+            .filter_map(|c| c.weight.map(|w| (c.name.clone(), w)))
+            .collect()
+    }
+
     /// Search a specific channel
     fn search_channel(
         &self,
@@ -267,10 +495,23 @@ impl ChannelManager {
         options: &SearchOptions,
     ) -> Result<Vec<ProfileRef>> {
         match channel.channel_type {
-            ChannelType::GitHubGlobal => self.search_github(channel, query, options),
+            ChannelType::GitHubGlobal => {
+                self.search_host(channel, self.hosts.default_host(), query, options)
+            }
+            ChannelType::GitHost => match &channel.source {
+                ChannelSource::GitHost { host } => {
+                    let host = self
+                        .hosts
+                        .get(host)
+                        .ok_or_else(|| DotAgentError::GitHostNotFound { name: host.clone() })?;
+                    self.search_host(channel, host, query, options)
+                }
+                _ => Ok(Vec::new()),
+            },
             ChannelType::AwesomeList => self.search_awesome_list(channel, query, options),
-            ChannelType::Marketplace => self.search_marketplace(channel, query, options),
-            ChannelType::CodexCatalog => self.search_codex_catalog(channel, query, options),
+            ChannelType::Marketplace => self.search_marketplace(channel, query),
+            ChannelType::CodexCatalog => self.search_codex_catalog(channel, query),
+            ChannelType::Local => self.search_local(channel, query, options),
             ChannelType::Hub | ChannelType::Direct => {
                 // Not searchable
                 Ok(Vec::new())
@@ -278,103 +519,129 @@ impl ChannelManager {
         }
     }
 
-    /// Search GitHub globally
-    fn search_github(
+    /// Search repositories on a git host
+    fn search_host(
         &self,
         channel: &Channel,
+        host: std::sync::Arc<dyn GitHost>,
         query: &str,
         options: &SearchOptions,
     ) -> Result<Vec<ProfileRef>> {
-        // Check if gh CLI is available
-        if !Self::check_gh_available() {
-            return Err(DotAgentError::GitHubCliNotFound);
-        }
-
-        let mut args = vec!["search".to_string(), "repos".to_string()];
-
-        // Build query
-        let search_query = if options.keywords.is_empty() {
-            query.to_string()
-        } else {
-            format!("{} {}", query, options.keywords.join(" "))
-        };
-
-        if !search_query.is_empty() {
-            args.push(search_query);
-        }
-
-        // Topic filter
-        if let Some(topic) = &options.topic {
-            args.push("--topic".to_string());
-            args.push(topic.clone());
+        let mut terms: Vec<String> = Vec::new();
+        if !query.is_empty() {
+            terms.push(query.to_string());
         }
-
-        // Sort order
-        args.push("--sort".to_string());
-        args.push(options.sort.clone().unwrap_or_else(|| "stars".to_string()));
+        terms.extend(options.keywords.iter().cloned());
 
         // Limit (per channel)
         let limit = if options.limit > 0 { options.limit } else { 10 };
-        args.push("--limit".to_string());
-        args.push(limit.to_string());
+        let repo_query = RepoQuery {
+            text: terms.join(" "),
+            topic: options.topic.clone(),
+            min_stars: options.min_stars,
+            sort: options.sort.clone().filter(|s| s != "relevance"),
+            limit,
+        };
 
-        // Stars filter
-        if let Some(min) = options.min_stars {
-            args.push("--stars".to_string());
-            args.push(format!(">={}", min));
-        }
+        let cache_dir = ChannelRegistry::cache_dir(&self.base_dir, &channel.name);
+        let repos = host.search(&self.http, &repo_query, Some(&cache_dir))?;
+
+        let results = repos
+            .into_iter()
+            .map(|repo| {
+                let mut metadata = HashMap::new();
+                if let Some(updated) = repo.updated_at {
+                    metadata.insert("updated_at".to_string(), updated);
+                }
 
-        // JSON output
-        args.push("--json".to_string());
-        args.push("name,owner,description,url,stargazersCount".to_string());
+                ProfileRef {
+                    id: format!("{}:{}", host.name(), repo.full_name),
+                    name: repo.name,
+                    owner: repo.owner,
+                    description: repo
+                        .description
+                        .unwrap_or_else(|| "No description".to_string()),
+                    url: repo.url,
+                    stars: repo.stars,
+                    channel: channel.name.clone(),
+                    metadata,
+                    score: 0.0,
+                }
+            })
+            .collect();
 
-        let output = Command::new("gh").args(&args).output().map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                DotAgentError::GitHubCliNotFound
-            } else {
-                DotAgentError::Io(e)
-            }
-        })?;
+        Ok(results)
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(DotAgentError::GitHubApiError {
-                message: stderr.to_string(),
-            });
-        }
+    /// Open a local directory channel
+    pub fn local_channel(&self, channel_name: &str) -> Result<LocalChannel> {
+        let channel =
+            self.registry
+                .get(channel_name)
+                .ok_or_else(|| DotAgentError::ChannelNotFound {
+                    name: channel_name.to_string(),
+                })?;
 
-        let json: serde_json::Value =
-            serde_json::from_slice(&output.stdout).unwrap_or(serde_json::Value::Array(vec![]));
+        match &channel.source {
+            ChannelSource::Local { path } => LocalChannel::open(path),
+            _ => Err(DotAgentError::ConfigParseSimple {
+                message: format!("'{}' is not a local channel", channel_name),
+            }),
+        }
+    }
 
-        let repos = json
-            .as_array()
-            .ok_or_else(|| DotAgentError::GitHubApiError {
-                message: "Invalid JSON response".to_string(),
-            })?;
+    /// Search a local directory channel (read directly, no cache)
+    fn search_local(
+        &self,
+        channel: &Channel,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<ProfileRef>> {
+        let local = self.local_channel(&channel.name)?;
+        let query_lower = query.to_lowercase();
+        let keywords: Vec<String> = options.keywords.iter().map(|k| k.to_lowercase()).collect();
 
         let mut results = Vec::new();
 
-        for repo in repos {
-            let name = repo["name"].as_str().unwrap_or("").to_string();
-            let owner = repo["owner"]["login"].as_str().unwrap_or("").to_string();
-            let description = repo["description"]
-                .as_str()
-                .unwrap_or("No description")
-                .to_string();
-            let url = repo["url"].as_str().unwrap_or("").to_string();
-            let stars = repo["stargazersCount"].as_u64();
+        for entry in local.entries()? {
+            let text = format!(
+                "{} {} {}",
+                entry.name,
+                entry.description.as_deref().unwrap_or(""),
+                entry.category.as_deref().unwrap_or("")
+            )
+            .to_lowercase();
+
+            let matches_query = query.is_empty() || text.contains(&query_lower);
+            let matches_keywords = keywords.is_empty() || keywords.iter().all(|k| text.contains(k));
+            if !matches_query || !matches_keywords {
+                continue;
+            }
 
-            let id = format!("github:{}/{}", owner, name);
+            let mut metadata = HashMap::new();
+            if let Some(version) = &entry.version {
+                metadata.insert("version".to_string(), version.clone());
+            }
+            if let Some(category) = &entry.category {
+                metadata.insert("category".to_string(), category.clone());
+            }
+
+            let url = match (&entry.path, &entry.url) {
+                (Some(path), _) => format!("file://{}", path.display()),
+                (None, Some(url)) => url.clone(),
+                (None, None) => String::new(),
+            };
 
             results.push(ProfileRef {
-                id,
-                name,
-                owner,
-                description,
+                id: format!("local:{}@{}", entry.name, channel.name),
+                name: entry.name,
+                owner: channel.name.clone(),
+                description: entry.description.unwrap_or_default(),
                 url,
-                stars,
+                stars: None,
                 channel: channel.name.clone(),
-                metadata: HashMap::new(),
+                metadata,
+                score: 0.0,
             });
         }
 
@@ -403,7 +670,7 @@ impl ChannelManager {
         let mut results = Vec::new();
 
         for line in content.lines() {
-            if let Some(profile_ref) = Self::parse_awesome_line(line, &channel.name) {
+            if let Some(profile_ref) = self.parse_awesome_line(line, &channel.name) {
                 // Check if matches query
                 let text = format!(
                     "{} {} {}",
@@ -421,11 +688,6 @@ impl ChannelManager {
             }
         }
 
-        // Apply limit
-        if options.limit > 0 {
-            results.truncate(options.limit);
-        }
-
         Ok(results)
     }
 
@@ -441,29 +703,73 @@ impl ChannelManager {
             }
         }
 
-        // Fetch from URL, try main first, then master
-        let content = if let Some(c) = Self::fetch_url(&Self::to_raw_url(url, "main"))? {
-            c
-        } else if let Some(c) = Self::fetch_url(&Self::to_raw_url(url, "master"))? {
-            c
-        } else {
-            return Err(DotAgentError::GitHubApiError {
-                message: format!(
-                    "Failed to fetch README from: {} (tried main and master)",
-                    url
-                ),
-            });
-        };
-
-        // Cache it
-        std::fs::create_dir_all(&cache_dir)?;
-        std::fs::write(&cache_file, &content)?;
+        // Fetch and cache it (subject to trust)
+        let content = self.fetch_readme(url, &cache_dir)?;
+        let outcome = self.channel_gate(channel_name).store(
+            "content.md",
+            &content,
+            &fingerprint(&content),
+        )?;
+        Self::require_trusted(TrustKind::Channel, channel_name, &outcome)?;
 
         Ok(content)
     }
 
+    /// Fetch README content from a repository URL, trying main then master
+    fn fetch_readme(&self, url: &str, cache_dir: &Path) -> Result<String> {
+        let hosted = self.hosts.find_by_url(url);
+        for branch in ["main", "master"] {
+            let content = match &hosted {
+                Some(hosted) => {
+                    hosted.fetch_raw(&self.http, branch, "README.md", Some(cache_dir))?
+                }
+                None => self.fetch_url(&self.to_raw_url(url, branch), cache_dir)?,
+            };
+            if let Some(content) = content {
+                return Ok(content);
+            }
+        }
+        Err(DotAgentError::GitHubApiError {
+            message: format!(
+                "Failed to fetch README from: {} (tried main and master)",
+                url
+            ),
+        })
+    }
+
+    /// Trust gate for a channel's cache directory
+    fn channel_gate<'a>(&'a self, channel_name: &'a str) -> TrustGate<'a> {
+        TrustGate::new(
+            &self.base_dir,
+            TrustKind::Channel,
+            channel_name,
+            ChannelRegistry::cache_dir(&self.base_dir, channel_name),
+        )
+    }
+
+    /// Trust gate for a hub's cache directory
+    fn hub_gate<'a>(&'a self, hub_name: &'a str) -> TrustGate<'a> {
+        TrustGate::new(
+            &self.base_dir,
+            TrustKind::Hub,
+            hub_name,
+            HubRegistry::cache_dir(&self.base_dir, hub_name),
+        )
+    }
+
+    /// Fail when fetched content is waiting for approval
+    fn require_trusted(kind: TrustKind, name: &str, outcome: &RefreshOutcome) -> Result<()> {
+        match outcome {
+            RefreshOutcome::PendingApproval(_) => Err(DotAgentError::UntrustedChange {
+                kind: kind.as_str().to_string(),
+                name: name.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
     /// Parse a line from Awesome List markdown
-    fn parse_awesome_line(line: &str, channel_name: &str) -> Option<ProfileRef> {
+    fn parse_awesome_line(&self, line: &str, channel_name: &str) -> Option<ProfileRef> {
         // Format: - [name](url) - description
         // or: * [name](url) - description
         let line = line.trim();
@@ -501,7 +807,7 @@ impl ChannelManager {
         };
 
         // Extract owner from URL
-        let owner = Self::extract_owner_from_url(url);
+        let owner = self.extract_owner_from_url(url);
 
         let id = format!("awesome:{}#{}", channel_name, name);
 
@@ -514,94 +820,83 @@ impl ChannelManager {
             stars: None,
             channel: channel_name.to_string(),
             metadata: HashMap::new(),
+            score: 0.0,
         })
     }
 
-    /// Extract owner from GitHub URL
-    fn extract_owner_from_url(url: &str) -> String {
+    /// Extract owner from a repository URL on a configured git host
+    fn extract_owner_from_url(&self, url: &str) -> String {
         // https://github.com/owner/repo -> owner
-        if url.contains("github.com") {
-            let parts: Vec<&str> = url.split('/').collect();
-            if parts.len() >= 4 {
-                return parts[3].to_string();
-            }
-        }
-        "unknown".to_string()
-    }
-
-    /// Convert GitHub URL to raw content URL with specified branch
-    fn to_raw_url(url: &str, branch: &str) -> String {
-        if url.contains("github.com") && !url.contains("raw.githubusercontent.com") {
-            // https://github.com/user/repo -> raw README
-            let url = url
-                .replace("github.com", "raw.githubusercontent.com")
-                .trim_end_matches('/')
-                .to_string();
-            format!("{}/{}/README.md", url, branch)
-        } else {
-            url.to_string()
+        match self.hosts.find_by_url(url) {
+            Some(hosted) => hosted.owner().to_string(),
+            None => "unknown".to_string(),
         }
     }
 
-    /// Normalize repo string to owner/repo format
-    /// Accepts: "owner/repo", "https://github.com/owner/repo", "github.com/owner/repo"
-    fn normalize_repo(repo: &str) -> String {
-        let repo = repo.trim_end_matches('/');
-
-        // Already in owner/repo format
-        if !repo.contains("://") && !repo.contains("github.com") {
-            return repo.to_string();
-        }
-
-        // Extract owner/repo from URL
-        // https://github.com/owner/repo -> owner/repo
-        if let Some(pos) = repo.find("github.com/") {
-            let after = &repo[pos + 11..]; // skip "github.com/"
-            return after.to_string();
+    /// Convert a repository URL to the raw README URL on the specified branch
+    ///
+    /// URLs that aren't repositories on a configured host (e.g., direct links
+    /// to raw files) are returned unchanged.
+    fn to_raw_url(&self, url: &str, branch: &str) -> String {
+        match self.hosts.find_by_url(url) {
+            Some(hosted) => hosted.raw_file_url(branch, "README.md"),
+            None => url.to_string(),
         }
-
-        repo.to_string()
     }
 
-    /// Fetch URL content, returns None for 404
-    fn fetch_url(url: &str) -> Result<Option<String>> {
-        let output = Command::new("curl")
-            .args(["-sL", "-w", "%{http_code}", "-o", "-", url])
-            .output()
-            .map_err(DotAgentError::Io)?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-
-        // Extract HTTP status code (last 3 chars)
-        if stdout.len() >= 3 {
-            let (content, status) = stdout.split_at(stdout.len() - 3);
-            if status == "404" {
-                return Ok(None);
-            }
-            if status.starts_with('2') {
-                return Ok(Some(content.to_string()));
-            }
+    /// Convert repository URL to the raw URL of a file on the specified branch
+    pub(super) fn to_raw_file_url(&self, url: &str, branch: &str, file: &str) -> String {
+        match self.hosts.find_by_url(url) {
+            Some(hosted) => hosted.raw_file_url(branch, file),
+            None => format!("{}/{}", url.trim_end_matches('/'), file),
         }
+    }
 
-        // Fallback: check if output looks like an error
-        if !output.status.success() || stdout.contains("404") {
-            return Ok(None);
+    /// Fetch a file from a repository URL, returns None for 404
+    ///
+    /// Repositories on a configured host are fetched with the host token;
+    /// other URLs are treated as directories of plain files.
+    pub(super) fn fetch_repo_file(
+        &self,
+        url: &str,
+        branch: &str,
+        file: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<Option<String>> {
+        match self.hosts.find_by_url(url) {
+            Some(hosted) => hosted.fetch_raw(&self.http, branch, file, cache_dir),
+            None => self
+                .http
+                .get_text(&self.to_raw_file_url(url, branch, file), cache_dir),
         }
-
-        Ok(Some(stdout.to_string()))
     }
 
-    /// Check if gh CLI is available
-    fn check_gh_available() -> bool {
-        Command::new("gh")
-            .arg("--version")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    /// Fetch URL content, returns None for 404
+    ///
+    /// Responses are revalidated with ETag/If-Modified-Since against
+    /// `cache_dir`.
+    pub(super) fn fetch_url(&self, url: &str, cache_dir: &Path) -> Result<Option<String>> {
+        self.http.get_text(url, Some(cache_dir))
     }
 
     /// Refresh a channel's cache
-    pub fn refresh_channel(&self, channel_name: &str) -> Result<()> {
+    ///
+    /// The first fetch is trusted on first use. If the content changed since
+    /// it was last trusted, the new version is staged and returned as
+    /// [`RefreshOutcome::PendingApproval`]; the cache is only overwritten
+    /// after [`ChannelManager::approve_channel`].
+    ///
+    /// Trusted content is also written to the offline search index.
+    pub fn refresh_channel(&self, channel_name: &str) -> Result<RefreshOutcome> {
+        let outcome = self.fetch_channel(channel_name)?;
+        if !matches!(outcome, RefreshOutcome::PendingApproval(_)) {
+            self.reindex_channel(channel_name)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Fetch a channel's content into its cache (subject to trust)
+    fn fetch_channel(&self, channel_name: &str) -> Result<RefreshOutcome> {
         let channel =
             self.registry
                 .get(channel_name)
@@ -611,62 +906,131 @@ impl ChannelManager {
 
         match &channel.source {
             ChannelSource::Marketplace { repo } => {
-                self.fetch_marketplace_catalog(repo, channel_name)?;
+                self.fetch_marketplace_catalog(repo, channel_name)
             }
             ChannelSource::CodexCatalog { repo, base_path } => {
-                self.fetch_codex_catalog(repo, base_path, channel_name)?;
+                self.fetch_codex_catalog(repo, base_path, channel_name)
             }
-            _ => {
-                if let Some(url) = channel.source.url() {
-                    // Try main first, then master
-                    let content = if let Some(c) = Self::fetch_url(&Self::to_raw_url(url, "main"))?
-                    {
-                        c
-                    } else if let Some(c) = Self::fetch_url(&Self::to_raw_url(url, "master"))? {
-                        c
-                    } else {
-                        return Err(DotAgentError::GitHubApiError {
-                            message: format!(
-                                "Failed to fetch README from: {} (tried main and master)",
-                                url
-                            ),
-                        });
-                    };
-
+            ChannelSource::Local { path } => {
+                // Read in place; only check that the directory is reachable
+                LocalChannel::open(path)?;
+                Ok(RefreshOutcome::Skipped)
+            }
+            _ => match channel.source.url() {
+                Some(url) => {
                     let cache_dir = ChannelRegistry::cache_dir(&self.base_dir, channel_name);
-                    std::fs::create_dir_all(&cache_dir)?;
-                    std::fs::write(cache_dir.join("content.md"), content)?;
+                    let content = self.fetch_readme(url, &cache_dir)?;
+                    self.channel_gate(channel_name).store(
+                        "content.md",
+                        &content,
+                        &fingerprint(&content),
+                    )
+                }
+                None => Ok(RefreshOutcome::Skipped),
+            },
+        }
+    }
+
+    /// Approve a staged channel change, replacing the cached content
+    pub fn approve_channel(&self, channel_name: &str) -> Result<String> {
+        let fingerprint = self.channel_gate(channel_name).approve()?;
+        self.reindex_channel(channel_name)?;
+        Ok(fingerprint)
+    }
+
+    /// Reject a staged channel change, keeping the trusted content
+    pub fn reject_channel(&self, channel_name: &str) -> Result<()> {
+        self.channel_gate(channel_name).reject()
+    }
+
+    /// Refresh a hub's cached channel index (`hub.toml` or `hub.json`)
+    ///
+    /// The index is validated against the hub schema before it reaches the
+    /// trust gate, so malformed content is never cached. Uses the same
+    /// trust-on-first-use policy as [`ChannelManager::refresh_channel`].
+    pub fn refresh_hub(&self, hub: &Hub) -> Result<RefreshOutcome> {
+        let (file, content) = self.fetch_hub_index(hub)?;
+        let format = HubFormat::from_file_name(file).unwrap_or(HubFormat::Toml);
+        HubIndex::parse(&content, format, &format!("{} ({})", file, hub.url))?;
+
+        self.hub_gate(&hub.name)
+            .store(file, &content, &fingerprint(&content))
+    }
+
+    /// Read the trusted hub index from the cache (None before the first refresh)
+    pub fn hub_index(&self, hub_name: &str) -> Result<Option<HubIndex>> {
+        let cache_dir = HubRegistry::cache_dir(&self.base_dir, hub_name);
+        match HubIndex::find(&cache_dir) {
+            Some(path) => HubIndex::load(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch the raw hub index, trying each file name on main then master
+    fn fetch_hub_index(&self, hub: &Hub) -> Result<(&'static str, String)> {
+        let dir = local_path(&hub.url);
+        if dir.is_dir() {
+            if let Some(path) = HubIndex::find(&dir) {
+                let file = HUB_INDEX_FILES
+                    .into_iter()
+                    .find(|f| path.ends_with(f))
+                    .unwrap_or(HUB_INDEX_FILES[0]);
+                return Ok((file, fs::read_to_string(&path)?));
+            }
+        }
+
+        let cache_dir = HubRegistry::cache_dir(&self.base_dir, &hub.name);
+        for branch in ["main", "master"] {
+            for file in HUB_INDEX_FILES {
+                if let Some(content) =
+                    self.fetch_repo_file(&hub.url, branch, file, Some(&cache_dir))?
+                {
+                    return Ok((file, content));
                 }
             }
         }
 
-        Ok(())
+        Err(DotAgentError::GitHubApiError {
+            message: format!(
+                "Failed to fetch {} from: {} (tried main and master)",
+                HUB_INDEX_FILES.join(" or "),
+                hub.url
+            ),
+        })
+    }
+
+    /// Approve a staged hub change, replacing the cached content
+    pub fn approve_hub(&self, hub_name: &str) -> Result<String> {
+        self.hub_gate(hub_name).approve()
+    }
+
+    /// Reject a staged hub change, keeping the trusted content
+    pub fn reject_hub(&self, hub_name: &str) -> Result<()> {
+        self.hub_gate(hub_name).reject()
     }
 
-    /// Fetch marketplace catalog from GitHub repository
+    /// Fetch marketplace catalog from a git repository
     ///
     /// Downloads `.claude-plugin/marketplace.json` from the repository
-    /// and caches it locally.
-    fn fetch_marketplace_catalog(&self, repo: &str, channel_name: &str) -> Result<()> {
-        // Normalize repo: extract owner/repo from full URL if needed
-        let repo = Self::normalize_repo(repo);
+    /// and caches it locally. The fingerprint is the hash of the file.
+    fn fetch_marketplace_catalog(&self, repo: &str, channel_name: &str) -> Result<RefreshOutcome> {
+        let hosted = self.resolve_repo(repo)?;
+        let cache_dir = ChannelRegistry::cache_dir(&self.base_dir, channel_name);
 
         // Try main first, then master
-        let content = if let Some(c) = Self::fetch_url(&format!(
-            "https://raw.githubusercontent.com/{}/main/.claude-plugin/marketplace.json",
-            repo
-        ))? {
+        let content = if let Some(c) =
+            hosted.fetch_raw(&self.http, "main", MARKETPLACE_FILE, Some(&cache_dir))?
+        {
             c
-        } else if let Some(c) = Self::fetch_url(&format!(
-            "https://raw.githubusercontent.com/{}/master/.claude-plugin/marketplace.json",
-            repo
-        ))? {
+        } else if let Some(c) =
+            hosted.fetch_raw(&self.http, "master", MARKETPLACE_FILE, Some(&cache_dir))?
+        {
             c
         } else {
             return Err(DotAgentError::GitHubApiError {
                 message: format!(
                     "Failed to fetch marketplace.json from: {} (tried main and master)",
-                    repo
+                    hosted.url()
                 ),
             });
         };
@@ -684,12 +1048,9 @@ impl ChannelManager {
             });
         }
 
-        // Cache the catalog
-        let cache_dir = ChannelRegistry::cache_dir(&self.base_dir, channel_name);
-        std::fs::create_dir_all(&cache_dir)?;
-        std::fs::write(cache_dir.join("marketplace.json"), content)?;
-
-        Ok(())
+        // Cache the catalog (subject to trust)
+        self.channel_gate(channel_name)
+            .store("marketplace.json", &content, &fingerprint(&content))
     }
 
     /// Get a plugin entry from marketplace catalog
@@ -729,17 +1090,13 @@ impl ChannelManager {
     }
 
     /// Search a Marketplace channel
-    fn search_marketplace(
-        &self,
-        channel: &Channel,
-        query: &str,
-        options: &SearchOptions,
-    ) -> Result<Vec<ProfileRef>> {
+    fn search_marketplace(&self, channel: &Channel, query: &str) -> Result<Vec<ProfileRef>> {
         let repo = match &channel.source {
             ChannelSource::Marketplace { repo } => repo,
             _ => return Ok(Vec::new()),
         };
 
+        let hosted = self.resolve_repo(repo)?;
         let query_lower = query.to_lowercase();
         let mut results = Vec::new();
 
@@ -749,7 +1106,8 @@ impl ChannelManager {
 
         // Auto-fetch if cache doesn't exist
         if !cache_file.exists() {
-            self.fetch_marketplace_catalog(repo, &channel.name)?;
+            let outcome = self.fetch_marketplace_catalog(repo, &channel.name)?;
+            Self::require_trusted(TrustKind::Channel, &channel.name, &outcome)?;
         }
 
         // Parse cached marketplace data
@@ -783,15 +1141,31 @@ impl ChannelManager {
                                 metadata.insert("category".to_string(), category.to_string());
                             }
 
+                            // Point at the plugin itself so it deduplicates
+                            // against other channels listing the same repo
+                            let source = MarketplacePlugin::from_json(plugin);
+                            let url = if let Some(url) = source.source_url() {
+                                url.to_string()
+                            } else if let Some(repo) = source.source_github_repo() {
+                                self.resolve_repo(repo)
+                                    .map(|r| r.url())
+                                    .unwrap_or_else(|_| hosted.url())
+                            } else if let Some(path) = source.source_path() {
+                                hosted.tree_url("main", path.trim_start_matches("./"))
+                            } else {
+                                hosted.url()
+                            };
+
                             results.push(ProfileRef {
                                 id: format!("marketplace:{}@{}", name, channel.name),
                                 name: name.to_string(),
-                                owner: repo.split('/').next().unwrap_or("unknown").to_string(),
+                                owner: hosted.owner().to_string(),
                                 description: description.to_string(),
-                                url: format!("https://github.com/{}", repo),
+                                url,
                                 stars: None,
                                 channel: channel.name.clone(),
                                 metadata,
+                                score: 0.0,
                             });
                         }
                     }
@@ -799,22 +1173,24 @@ impl ChannelManager {
             }
         }
 
-        // Apply limit
-        if options.limit > 0 {
-            results.truncate(options.limit);
-        }
-
         Ok(results)
     }
 
-    /// Fetch Codex skills catalog from GitHub repository using GitHub API
+    /// Fetch Codex skills catalog by listing directories through the git host API
     ///
     /// Scans directory structure like Codex CLI's $skill-installer does:
     /// - skills/.system (preinstalled system skills)
     /// - skills/.curated (recommended skills)
     /// - skills/.experimental (experimental skills)
-    fn fetch_codex_catalog(&self, repo: &str, base_path: &str, channel_name: &str) -> Result<()> {
-        let repo = Self::normalize_repo(repo);
+    fn fetch_codex_catalog(
+        &self,
+        repo: &str,
+        base_path: &str,
+        channel_name: &str,
+    ) -> Result<RefreshOutcome> {
+        let hosted = self.resolve_repo(repo)?;
+        let repo = hosted.repo.as_str();
+        let cache_dir = ChannelRegistry::cache_dir(&self.base_dir, channel_name);
 
         // Categories to scan (matching Codex CLI's structure)
         let categories = [".system", ".curated", ".experimental"];
@@ -822,7 +1198,10 @@ impl ChannelManager {
 
         for category in &categories {
             let path = format!("{}/{}", base_path, category);
-            match self.fetch_github_directory_contents(&repo, &path) {
+            match hosted
+                .host
+                .list_dirs(&self.http, repo, &path, Some(&cache_dir))
+            {
                 Ok(skills) => {
                     for skill_name in skills {
                         all_skills.push(CodexSkill {
@@ -849,79 +1228,33 @@ impl ChannelManager {
             });
         }
 
-        // Cache the catalog as JSON
-        let cache_dir = ChannelRegistry::cache_dir(&self.base_dir, channel_name);
-        std::fs::create_dir_all(&cache_dir)?;
-
+        // Cache the catalog as JSON (subject to trust)
         let catalog = serde_json::json!({
             "repo": repo,
             "base_path": base_path,
             "skills": all_skills,
             "fetched_at": chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         });
-
-        std::fs::write(
-            cache_dir.join("codex_catalog.json"),
-            serde_json::to_string_pretty(&catalog).map_err(|e| DotAgentError::GitHubApiError {
+        let to_json = |value: &serde_json::Value| {
+            serde_json::to_string_pretty(value).map_err(|e| DotAgentError::GitHubApiError {
                 message: format!("Failed to serialize catalog: {}", e),
-            })?,
-        )?;
-
-        Ok(())
-    }
-
-    /// Fetch directory contents from GitHub API
-    /// Returns list of directory names (skills)
-    fn fetch_github_directory_contents(&self, repo: &str, path: &str) -> Result<Vec<String>> {
-        // Use gh CLI for GitHub API access
-        if !Self::check_gh_available() {
-            return Err(DotAgentError::GitHubCliNotFound);
-        }
-
-        let api_path = format!("repos/{}/contents/{}", repo, path);
-        let output = Command::new("gh")
-            .args([
-                "api",
-                &api_path,
-                "--jq",
-                "[.[] | select(.type == \"dir\") | .name]",
-            ])
-            .output()
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    DotAgentError::GitHubCliNotFound
-                } else {
-                    DotAgentError::Io(e)
-                }
-            })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(DotAgentError::GitHubApiError {
-                message: format!("GitHub API error for {}/{}: {}", repo, path, stderr),
-            });
-        }
-
-        let json: Vec<String> =
-            serde_json::from_slice(&output.stdout).map_err(|e| DotAgentError::GitHubApiError {
-                message: format!("Invalid JSON response: {}", e),
-            })?;
+            })
+        };
 
-        Ok(json)
+        // Fingerprint the skill list only, so the fetch timestamp doesn't count as a change
+        let skills_fp = fingerprint(&to_json(&catalog["skills"])?);
+        self.channel_gate(channel_name)
+            .store("codex_catalog.json", &to_json(&catalog)?, &skills_fp)
     }
 
     /// Search a Codex Catalog channel
-    fn search_codex_catalog(
-        &self,
-        channel: &Channel,
-        query: &str,
-        options: &SearchOptions,
-    ) -> Result<Vec<ProfileRef>> {
+    fn search_codex_catalog(&self, channel: &Channel, query: &str) -> Result<Vec<ProfileRef>> {
         let (repo, base_path) = match &channel.source {
             ChannelSource::CodexCatalog { repo, base_path } => (repo.clone(), base_path.clone()),
             _ => return Ok(Vec::new()),
         };
 
+        let hosted = self.resolve_repo(&repo)?;
         let query_lower = query.to_lowercase();
         let mut results = Vec::new();
 
@@ -931,7 +1264,8 @@ impl ChannelManager {
 
         // Auto-fetch if cache doesn't exist
         if !cache_file.exists() {
-            self.fetch_codex_catalog(&repo, &base_path, &channel.name)?;
+            let outcome = self.fetch_codex_catalog(&repo, &base_path, &channel.name)?;
+            Self::require_trusted(TrustKind::Channel, &channel.name, &outcome)?;
         }
 
         // Parse cached catalog
@@ -966,16 +1300,17 @@ impl ChannelManager {
                             results.push(ProfileRef {
                                 id: format!("codex:{}@{}", name, channel.name),
                                 name: name.to_string(),
-                                owner: repo.split('/').next().unwrap_or("openai").to_string(),
+                                owner: hosted.owner().to_string(),
                                 description: if description.is_empty() {
                                     format!("Codex skill ({})", category.trim_start_matches('.'))
                                 } else {
                                     description.to_string()
                                 },
-                                url: format!("https://github.com/{}/tree/main/{}", repo, path),
+                                url: hosted.tree_url("main", path),
                                 stars: None,
                                 channel: channel.name.clone(),
                                 metadata,
+                                score: 0.0,
                             });
                         }
                     }
@@ -983,11 +1318,6 @@ impl ChannelManager {
             }
         }
 
-        // Apply limit
-        if options.limit > 0 {
-            results.truncate(options.limit);
-        }
-
         Ok(results)
     }
 
@@ -1026,11 +1356,15 @@ impl ChannelManager {
 mod tests {
     use super::*;
 
+    fn manager() -> ChannelManager {
+        ChannelManager::with_registry(PathBuf::from("/nonexistent"), ChannelRegistry::default())
+    }
+
     #[test]
     fn parse_awesome_line_basic() {
         let line =
             "- [dotbot](https://github.com/anishathalye/dotbot) - A tool for managing dotfiles";
-        let result = ChannelManager::parse_awesome_line(line, "test");
+        let result = manager().parse_awesome_line(line, "test");
 
         assert!(result.is_some());
         let profile = result.unwrap();
@@ -1042,7 +1376,7 @@ mod tests {
     #[test]
     fn parse_awesome_line_star() {
         let line = "* [stow](https://github.com/aspiers/stow) - Symlink farm manager";
-        let result = ChannelManager::parse_awesome_line(line, "test");
+        let result = manager().parse_awesome_line(line, "test");
 
         assert!(result.is_some());
         let profile = result.unwrap();
@@ -1052,7 +1386,7 @@ mod tests {
     #[test]
     fn parse_awesome_line_no_description() {
         let line = "- [tool](https://github.com/user/tool)";
-        let result = ChannelManager::parse_awesome_line(line, "test");
+        let result = manager().parse_awesome_line(line, "test");
 
         assert!(result.is_some());
         let profile = result.unwrap();
@@ -1063,19 +1397,168 @@ mod tests {
     #[test]
     fn parse_awesome_line_skip_non_http() {
         let line = "- [Section](#section)";
-        let result = ChannelManager::parse_awesome_line(line, "test");
+        let result = manager().parse_awesome_line(line, "test");
         assert!(result.is_none());
     }
 
     #[test]
     fn to_raw_url_github() {
         let url = "https://github.com/webpro/awesome-dotfiles";
-        let raw = ChannelManager::to_raw_url(url, "main");
+        let raw = manager().to_raw_url(url, "main");
         assert!(raw.contains("raw.githubusercontent.com"));
         assert!(raw.contains("README.md"));
         assert!(raw.contains("/main/"));
 
-        let raw_master = ChannelManager::to_raw_url(url, "master");
+        let raw_master = manager().to_raw_url(url, "master");
         assert!(raw_master.contains("/master/"));
     }
+
+    #[test]
+    fn refresh_awesome_list_from_local_server() {
+        use crate::http::tests::TestServer;
+        use crate::http::HttpConfig;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let version = Arc::new(AtomicUsize::new(1));
+        let served = version.clone();
+        let server = TestServer::start(Arc::new(move |_, _| {
+            let body = format!(
+                "- [tool-v{0}](https://github.com/user/tool{0}) - Tool\n",
+                served.load(Ordering::SeqCst)
+            );
+            (200, vec![], body)
+        }));
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut registry = ChannelRegistry::default();
+        registry
+            .add(Channel::awesome_list(
+                "local-list",
+                format!("{}/README.md", server.url),
+            ))
+            .unwrap();
+        let config = HttpConfig {
+            retries: 0,
+            ..Default::default()
+        };
+        let manager = ChannelManager::with_registry(temp.path().to_path_buf(), registry)
+            .with_http(HttpFetcher::new(&config).unwrap());
+
+        let outcome = manager.refresh_channel("local-list").unwrap();
+        assert!(matches!(outcome, RefreshOutcome::FirstUse { .. }));
+
+        let options = SearchOptions {
+            channels: vec!["local-list".to_string()],
+            ..Default::default()
+        };
+        let results = manager.search("tool", &options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "tool-v1");
+
+        version.store(2, Ordering::SeqCst);
+        let outcome = manager.refresh_channel("local-list").unwrap();
+        assert!(matches!(outcome, RefreshOutcome::PendingApproval(_)));
+    }
+
+    #[test]
+    fn to_raw_file_url_hub() {
+        let raw = manager().to_raw_file_url(
+            "https://github.com/dot-agent/dot-agent-hub.git",
+            "main",
+            "channels.toml",
+        );
+        assert_eq!(
+            raw,
+            "https://raw.githubusercontent.com/dot-agent/dot-agent-hub/main/channels.toml"
+        );
+    }
+
+    #[test]
+    fn to_raw_file_url_self_hosted() {
+        use crate::git_host::{GitHostConfig, GitHostKind};
+        use crate::http::HttpConfig;
+
+        let hosts = GitHostRegistry::new(
+            &HttpConfig::default(),
+            &[GitHostConfig::new(
+                "company",
+                GitHostKind::Gitlab,
+                "https://gitlab.company.com",
+            )],
+        );
+        let manager = manager().with_hosts(hosts);
+
+        assert_eq!(
+            manager.to_raw_file_url(
+                "https://gitlab.company.com/platform/hub.git",
+                "main",
+                "channels.toml"
+            ),
+            "https://gitlab.company.com/platform/hub/-/raw/main/channels.toml"
+        );
+        assert_eq!(
+            manager.extract_owner_from_url("https://gitlab.company.com/platform/tools/x"),
+            "platform"
+        );
+        assert_eq!(
+            manager.to_raw_url("https://example.com/list.md", "main"),
+            "https://example.com/list.md"
+        );
+    }
+
+    #[test]
+    fn search_local_channel() {
+        let share = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(share.path().join("rust-base/rules")).unwrap();
+        std::fs::create_dir_all(share.path().join("python-base")).unwrap();
+
+        let mut registry = ChannelRegistry::default();
+        registry
+            .add(Channel::local(
+                "share",
+                format!("file://{}", share.path().display()),
+            ))
+            .unwrap();
+        let manager = ChannelManager::with_registry(PathBuf::from("/nonexistent"), registry);
+
+        let options = SearchOptions {
+            channels: vec!["share".to_string()],
+            ..Default::default()
+        };
+        let results = manager.search("rust", &options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "local:rust-base@share");
+        assert!(results[0].url.starts_with("file://"));
+        assert!(matches!(
+            manager.refresh_channel("share").unwrap(),
+            RefreshOutcome::Skipped
+        ));
+    }
+    #[test]
+    fn search_ranks_before_applying_limit() {
+        let share = tempfile::TempDir::new().unwrap();
+        for name in ["a-lintkit", "b-lintkit", "lint"] {
+            std::fs::create_dir_all(share.path().join(name)).unwrap();
+        }
+
+        let mut registry = ChannelRegistry::default();
+        registry
+            .add(Channel::local(
+                "share",
+                format!("file://{}", share.path().display()),
+            ))
+            .unwrap();
+        let manager = ChannelManager::with_registry(PathBuf::from("/nonexistent"), registry);
+
+        // The exact match is listed last in the channel but ranks first
+        let options = SearchOptions {
+            channels: vec!["share".to_string()],
+            limit: 1,
+            ..Default::default()
+        };
+        let results = manager.search("lint", &options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "lint");
+    }
 }
@@ -0,0 +1,395 @@
+//! Trust-on-first-use for channels and hubs
+//!
+//! Records a trust decision and content fingerprint per channel/hub in
+//! ~/.dot-agent/trust.toml. The first fetch is trusted automatically; later
+//! fetches whose fingerprint differs are staged under `<cache>/pending/` and
+//! must be approved before they replace the cached content.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+use crate::error::{DotAgentError, Result};
+use crate::install::metadata::compute_hash;
+
+/// Directory (inside a cache dir) holding content awaiting approval
+const PENDING_DIR: &str = "pending";
+
+/// What kind of source a trust record belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustKind {
+    Channel,
+    Hub,
+}
+
+impl TrustKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Channel => "channel",
+            Self::Hub => "hub",
+        }
+    }
+}
+
+/// Trust decision for a single channel or hub
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustRecord {
+    /// Fingerprint of the trusted content ("sha256:...")
+    pub fingerprint: String,
+    /// When the current fingerprint was trusted
+    pub trusted_at: String,
+    /// Fingerprint of fetched content awaiting approval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending: Option<String>,
+}
+
+/// Persistent trust decisions (trust.toml)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    #[serde(default)]
+    pub channels: BTreeMap<String, TrustRecord>,
+    #[serde(default)]
+    pub hubs: BTreeMap<String, TrustRecord>,
+}
+
+impl TrustStore {
+    const FILENAME: &'static str = "trust.toml";
+
+    /// Load trust store from base directory
+    pub fn load(base_dir: &Path) -> Result<Self> {
+        let path = base_dir.join(Self::FILENAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| DotAgentError::ConfigParseSimple {
+            message: e.to_string(),
+        })
+    }
+
+    /// Save trust store to base directory
+    pub fn save(&self, base_dir: &Path) -> Result<()> {
+        let path = base_dir.join(Self::FILENAME);
+        fs::create_dir_all(base_dir)?;
+        let content =
+            toml::to_string_pretty(self).map_err(|e| DotAgentError::ConfigParseSimple {
+                message: e.to_string(),
+            })?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn records(&self, kind: TrustKind) -> &BTreeMap<String, TrustRecord> {
+        match kind {
+            TrustKind::Channel => &self.channels,
+            TrustKind::Hub => &self.hubs,
+        }
+    }
+
+    fn records_mut(&mut self, kind: TrustKind) -> &mut BTreeMap<String, TrustRecord> {
+        match kind {
+            TrustKind::Channel => &mut self.channels,
+            TrustKind::Hub => &mut self.hubs,
+        }
+    }
+
+    /// Get the trust record for a channel or hub
+    pub fn get(&self, kind: TrustKind, name: &str) -> Option<&TrustRecord> {
+        self.records(kind).get(name)
+    }
+
+    /// Trust a fingerprint (clears any pending change)
+    pub fn trust(&mut self, kind: TrustKind, name: &str, fingerprint: &str) {
+        self.records_mut(kind).insert(
+            name.to_string(),
+            TrustRecord {
+                fingerprint: fingerprint.to_string(),
+                trusted_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                pending: None,
+            },
+        );
+    }
+
+    /// Forget the trust decision for a channel or hub
+    pub fn remove(&mut self, kind: TrustKind, name: &str) -> Option<TrustRecord> {
+        self.records_mut(kind).remove(name)
+    }
+}
+
+/// A single changed line between trusted and fetched content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Added(String),
+    Removed(String),
+}
+
+/// Difference between the trusted content and a newly fetched version
+#[derive(Debug, Clone)]
+pub struct TrustDiff {
+    /// Channel or hub name
+    pub name: String,
+    /// Cached file that changed (e.g., "marketplace.json")
+    pub file: String,
+    /// Currently trusted fingerprint
+    pub previous_fingerprint: String,
+    /// Fingerprint of the fetched content
+    pub new_fingerprint: String,
+    /// Changed lines
+    pub lines: Vec<DiffLine>,
+}
+
+impl TrustDiff {
+    fn compute(name: &str, file: &str, previous: &str, old: &str, new: &str, fp: &str) -> Self {
+        let lines = TextDiff::from_lines(old, new)
+            .iter_all_changes()
+            .filter_map(|change| {
+                let line = change.value().trim_end_matches('\n').to_string();
+                match change.tag() {
+                    ChangeTag::Insert => Some(DiffLine::Added(line)),
+                    ChangeTag::Delete => Some(DiffLine::Removed(line)),
+                    ChangeTag::Equal => None,
+                }
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            file: file.to_string(),
+            previous_fingerprint: previous.to_string(),
+            new_fingerprint: fp.to_string(),
+            lines,
+        }
+    }
+
+    /// Number of added lines
+    pub fn added(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| matches!(l, DiffLine::Added(_)))
+            .count()
+    }
+
+    /// Number of removed lines
+    pub fn removed(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| matches!(l, DiffLine::Removed(_)))
+            .count()
+    }
+}
+
+/// Result of refreshing a channel or hub
+#[derive(Debug, Clone)]
+pub enum RefreshOutcome {
+    /// First fetch: content was trusted on first use
+    FirstUse { fingerprint: String },
+    /// Content matches the trusted fingerprint
+    Unchanged,
+    /// Content changed since it was trusted; staged until approved
+    PendingApproval(TrustDiff),
+    /// Nothing to cache for this source (e.g., GitHub global search)
+    Skipped,
+}
+
+/// Compute the fingerprint of fetched content
+pub fn fingerprint(content: &str) -> String {
+    compute_hash(content.as_bytes())
+}
+
+/// Gate that decides whether fetched content may overwrite a cache directory
+pub struct TrustGate<'a> {
+    base_dir: &'a Path,
+    kind: TrustKind,
+    name: &'a str,
+    cache_dir: PathBuf,
+}
+
+impl<'a> TrustGate<'a> {
+    pub fn new(base_dir: &'a Path, kind: TrustKind, name: &'a str, cache_dir: PathBuf) -> Self {
+        Self {
+            base_dir,
+            kind,
+            name,
+            cache_dir,
+        }
+    }
+
+    /// Store fetched content, honouring the trust decision
+    ///
+    /// `fingerprint` identifies the content (usually [`fingerprint`] of it,
+    /// but callers may exclude volatile fields such as fetch timestamps).
+    pub fn store(&self, file: &str, content: &str, fingerprint: &str) -> Result<RefreshOutcome> {
+        let mut store = TrustStore::load(self.base_dir)?;
+        let cache_file = self.cache_dir.join(file);
+
+        let outcome = match store.get(self.kind, self.name) {
+            None => {
+                fs::create_dir_all(&self.cache_dir)?;
+                fs::write(&cache_file, content)?;
+                store.trust(self.kind, self.name, fingerprint);
+                RefreshOutcome::FirstUse {
+                    fingerprint: fingerprint.to_string(),
+                }
+            }
+            Some(record) if record.fingerprint == fingerprint => {
+                fs::create_dir_all(&self.cache_dir)?;
+                fs::write(&cache_file, content)?;
+                self.discard_pending()?;
+                if record.pending.is_some() {
+                    store.trust(self.kind, self.name, fingerprint);
+                }
+                RefreshOutcome::Unchanged
+            }
+            Some(record) => {
+                let old = fs::read_to_string(&cache_file).unwrap_or_default();
+                let diff = TrustDiff::compute(
+                    self.name,
+                    file,
+                    &record.fingerprint,
+                    &old,
+                    content,
+                    fingerprint,
+                );
+
+                let pending_dir = self.cache_dir.join(PENDING_DIR);
+                fs::create_dir_all(&pending_dir)?;
+                fs::write(pending_dir.join(file), content)?;
+
+                if let Some(record) = store.records_mut(self.kind).get_mut(self.name) {
+                    record.pending = Some(fingerprint.to_string());
+                }
+                RefreshOutcome::PendingApproval(diff)
+            }
+        };
+
+        store.save(self.base_dir)?;
+        Ok(outcome)
+    }
+
+    /// Approve the staged change, replacing the cached content
+    pub fn approve(&self) -> Result<String> {
+        let mut store = TrustStore::load(self.base_dir)?;
+        let pending = store
+            .get(self.kind, self.name)
+            .and_then(|r| r.pending.clone())
+            .ok_or_else(|| DotAgentError::NoPendingChange {
+                name: self.name.to_string(),
+            })?;
+
+        let pending_dir = self.cache_dir.join(PENDING_DIR);
+        if pending_dir.exists() {
+            for entry in fs::read_dir(&pending_dir)? {
+                let entry = entry?;
+                fs::rename(entry.path(), self.cache_dir.join(entry.file_name()))?;
+            }
+            fs::remove_dir_all(&pending_dir)?;
+        }
+
+        store.trust(self.kind, self.name, &pending);
+        store.save(self.base_dir)?;
+        Ok(pending)
+    }
+
+    /// Reject the staged change, keeping the trusted content
+    pub fn reject(&self) -> Result<()> {
+        let mut store = TrustStore::load(self.base_dir)?;
+        if let Some(record) = store.records_mut(self.kind).get_mut(self.name) {
+            record.pending = None;
+        }
+        self.discard_pending()?;
+        store.save(self.base_dir)
+    }
+
+    fn discard_pending(&self) -> Result<()> {
+        let pending_dir = self.cache_dir.join(PENDING_DIR);
+        if pending_dir.exists() {
+            fs::remove_dir_all(&pending_dir)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn gate(base: &Path) -> TrustGate<'_> {
+        TrustGate::new(base, TrustKind::Channel, "test", base.join("cache"))
+    }
+
+    #[test]
+    fn first_use_is_trusted() {
+        let temp = TempDir::new().unwrap();
+        let gate = gate(temp.path());
+
+        let outcome = gate
+            .store("content.md", "a\n", &fingerprint("a\n"))
+            .unwrap();
+        assert!(matches!(outcome, RefreshOutcome::FirstUse { .. }));
+
+        let store = TrustStore::load(temp.path()).unwrap();
+        let record = store.get(TrustKind::Channel, "test").unwrap();
+        assert_eq!(record.fingerprint, fingerprint("a\n"));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("cache/content.md")).unwrap(),
+            "a\n"
+        );
+    }
+
+    #[test]
+    fn changed_content_requires_approval() {
+        let temp = TempDir::new().unwrap();
+        let gate = gate(temp.path());
+        gate.store("content.md", "a\nb\n", &fingerprint("a\nb\n"))
+            .unwrap();
+
+        let outcome = gate
+            .store("content.md", "a\nc\n", &fingerprint("a\nc\n"))
+            .unwrap();
+        let diff = match outcome {
+            RefreshOutcome::PendingApproval(diff) => diff,
+            other => panic!("expected pending approval, got {:?}", other),
+        };
+        assert_eq!(diff.added(), 1);
+        assert_eq!(diff.removed(), 1);
+        assert!(diff.lines.contains(&DiffLine::Added("c".to_string())));
+
+        // Cache is untouched until approved
+        let cache_file = temp.path().join("cache/content.md");
+        assert_eq!(fs::read_to_string(&cache_file).unwrap(), "a\nb\n");
+
+        gate.approve().unwrap();
+        assert_eq!(fs::read_to_string(&cache_file).unwrap(), "a\nc\n");
+
+        let store = TrustStore::load(temp.path()).unwrap();
+        let record = store.get(TrustKind::Channel, "test").unwrap();
+        assert_eq!(record.fingerprint, fingerprint("a\nc\n"));
+        assert!(record.pending.is_none());
+    }
+
+    #[test]
+    fn reject_keeps_trusted_content() {
+        let temp = TempDir::new().unwrap();
+        let gate = gate(temp.path());
+        gate.store("content.md", "a\n", &fingerprint("a\n"))
+            .unwrap();
+        gate.store("content.md", "b\n", &fingerprint("b\n"))
+            .unwrap();
+
+        gate.reject().unwrap();
+        assert!(!temp.path().join("cache/pending").exists());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("cache/content.md")).unwrap(),
+            "a\n"
+        );
+        assert!(matches!(
+            gate.approve(),
+            Err(DotAgentError::NoPendingChange { .. })
+        ));
+    }
+}
@@ -9,6 +9,8 @@
 //!
 //! Channel (Profile source with search capability)
 //! ├── GitHubGlobal: Search all of GitHub (default enabled)
+//! ├── GitHost: Repository search on a configured git host (GitLab, Gitea, ...)
+//! ├── Local: Directory or file:// share (offline)
 //! ├── AwesomeList: Curated markdown lists
 //! ├── Hub: Channel from a Hub repository
 //! └── Direct: Direct URL to a repo
@@ -23,7 +25,7 @@ use serde::{Deserialize, Serialize};
 pub struct Hub {
     /// Unique name for this hub (e.g., "official", "company-internal")
     pub name: String,
-    /// GitHub repository URL
+    /// Repository URL (any configured git host)
     pub url: String,
     /// Optional description
     pub description: Option<String>,
@@ -86,6 +88,10 @@ pub enum ChannelType {
     Marketplace,
     /// OpenAI Codex Skills Catalog (GitHub directory scan)
     CodexCatalog,
+    /// Repository search on a configured git host
+    GitHost,
+    /// Local directory or file:// share (profiles, marketplace.json or hub index)
+    Local,
 }
 
 impl ChannelType {
@@ -97,6 +103,8 @@ impl ChannelType {
             Self::Direct => "direct",
             Self::Marketplace => "marketplace",
             Self::CodexCatalog => "codex-catalog",
+            Self::GitHost => "git-host",
+            Self::Local => "local",
         }
     }
 
@@ -104,7 +112,12 @@ impl ChannelType {
     pub fn is_searchable(&self) -> bool {
         matches!(
             self,
-            Self::GitHubGlobal | Self::AwesomeList | Self::Marketplace | Self::CodexCatalog
+            Self::GitHubGlobal
+                | Self::AwesomeList
+                | Self::Marketplace
+                | Self::CodexCatalog
+                | Self::GitHost
+                | Self::Local
         )
     }
 }
@@ -142,16 +155,27 @@ pub enum ChannelSource {
     },
     /// Claude Code Plugin Marketplace
     Marketplace {
-        /// GitHub repo (e.g., "anthropics/claude-plugins-official")
+        /// Repo reference (e.g., "anthropics/claude-plugins-official",
+        /// "company:group/marketplace" or a URL on a configured host)
         repo: String,
     },
-    /// OpenAI Codex Skills Catalog (GitHub directory scan)
+    /// OpenAI Codex Skills Catalog (repository directory scan)
     CodexCatalog {
-        /// GitHub repo (e.g., "openai/skills")
+        /// Repo reference (e.g., "openai/skills")
         repo: String,
         /// Base path within the repo (e.g., "skills")
         base_path: String,
     },
+    /// Repository search on a configured git host
+    GitHost {
+        /// Host name from config.toml `[[hosts]]`
+        host: String,
+    },
+    /// Local directory
+    Local {
+        /// Directory path or file:// URL
+        path: String,
+    },
 }
 
 impl ChannelSource {
@@ -194,6 +218,16 @@ impl ChannelSource {
         }
     }
 
+    /// Create a git host search source
+    pub fn git_host(host: impl Into<String>) -> Self {
+        Self::GitHost { host: host.into() }
+    }
+
+    /// Create a local directory source
+    pub fn local(path: impl Into<String>) -> Self {
+        Self::Local { path: path.into() }
+    }
+
     /// Get the URL if available
     pub fn url(&self) -> Option<&str> {
         match self {
@@ -203,10 +237,12 @@ impl ChannelSource {
             Self::Anonymous { url, .. } => Some(url),
             Self::Marketplace { .. } => None,
             Self::CodexCatalog { .. } => None,
+            Self::GitHost { .. } => None,
+            Self::Local { .. } => None,
         }
     }
 
-    /// Get the GitHub repo if marketplace or codex catalog
+    /// Get the repo reference if marketplace or codex catalog
     pub fn repo(&self) -> Option<&str> {
         match self {
             Self::Marketplace { repo } => Some(repo),
@@ -243,6 +279,9 @@ pub struct Channel {
     /// Whether this is a built-in channel (cannot be removed)
     #[serde(default)]
     pub builtin: bool,
+    /// Search ranking weight (default 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
 }
 
 fn default_true() -> bool {
@@ -260,6 +299,7 @@ impl Channel {
             added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             enabled: true,
             builtin: true,
+            weight: None,
         }
     }
 
@@ -277,6 +317,7 @@ impl Channel {
             added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             enabled: true,
             builtin: false,
+            weight: None,
         }
     }
 
@@ -297,6 +338,7 @@ impl Channel {
             added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             enabled: true,
             builtin: false,
+            weight: None,
         }
     }
 
@@ -310,6 +352,7 @@ impl Channel {
             added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             enabled: true,
             builtin: false,
+            weight: None,
         }
     }
 
@@ -326,6 +369,7 @@ impl Channel {
             added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             enabled: true,
             builtin: false,
+            weight: None,
         }
     }
 
@@ -339,6 +383,7 @@ impl Channel {
             added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             enabled: true,
             builtin: false,
+            weight: None,
         }
     }
 
@@ -358,6 +403,36 @@ impl Channel {
             added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             enabled: true,
             builtin: true,
+            weight: None,
+        }
+    }
+
+    /// Create a repository search channel for a configured git host
+    pub fn git_host(name: impl Into<String>, host: impl Into<String>) -> Self {
+        let host = host.into();
+        Self {
+            name: name.into(),
+            channel_type: ChannelType::GitHost,
+            description: Some(format!("Search repositories on {}", host)),
+            source: ChannelSource::git_host(host),
+            added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            enabled: true,
+            builtin: false,
+            weight: None,
+        }
+    }
+
+    /// Create a local directory channel (path or file:// URL)
+    pub fn local(name: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            channel_type: ChannelType::Local,
+            source: ChannelSource::local(path),
+            description: None,
+            added_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            enabled: true,
+            builtin: false,
+            weight: None,
         }
     }
 
@@ -373,6 +448,12 @@ impl Channel {
         self
     }
 
+    /// Set the search ranking weight
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
     /// Whether this channel supports search
     pub fn is_searchable(&self) -> bool {
         self.channel_type.is_searchable()
@@ -408,6 +489,9 @@ pub struct ProfileRef {
     pub channel: String,
     /// Additional metadata
     pub metadata: HashMap<String, String>,
+    /// Ranking score (set by [`ChannelManager::search`](super::ChannelManager::search))
+    #[serde(default)]
+    pub score: f64,
 }
 
 /// Search options
@@ -423,7 +507,7 @@ pub struct SearchOptions {
     pub keywords: Vec<String>,
     /// GitHub topic filter
     pub topic: Option<String>,
-    /// Sort order (stars, updated, etc.)
+    /// Sort order: relevance (default), stars or updated
     pub sort: Option<String>,
 }
 
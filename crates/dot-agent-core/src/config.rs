@@ -1,10 +1,15 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::budget::BudgetConfig;
+use crate::category::{ClassificationConfig, ClassificationMode};
 use crate::error::{DotAgentError, Result};
-use crate::llm::LlmConfig;
+use crate::git_host::GitHostConfig;
+use crate::http::HttpConfig;
+use crate::llm::{LlmBackend, LlmConfig};
 use crate::profile::{IgnoreConfig, DEFAULT_EXCLUDED_DIRS};
 
 const CONFIG_FILE: &str = "config.toml";
@@ -32,6 +37,63 @@ include = []
 # When enabled, uses Claude API for semantic classification
 # Default: false
 enabled = false
+
+# Backend used by rules and LLM classification
+# claude-cli: the `claude` command (default)
+# anthropic:  Anthropic Messages API (needs model and an API key)
+# openai:     OpenAI-compatible chat completions (OpenAI, gateways, Ollama, llama.cpp)
+# fixture:    canned responses from a file or directory (tests and CI)
+backend = "claude-cli"
+
+# model = "..."
+# api_url = "http://localhost:11434/v1"   # default: the provider's public endpoint
+# api_key_env = "LLM_GATEWAY_KEY"         # default: ANTHROPIC_API_KEY / OPENAI_API_KEY
+# max_tokens = 8192
+# timeout_secs = 300
+# fixture = "/path/to/responses"
+
+[classification]
+# Category classification used when LLM features are disabled
+# glob: path patterns only
+# content: path patterns plus frontmatter (description, tags, category) and headings
+# Default: "content"
+mode = "content"
+
+[budget]
+# Token budgets for `profile stats`, `status --budget` and fusion plans
+# (rough estimates; 0 disables a warning)
+# Always-loaded content: root CLAUDE.md/AGENTS.md and rules/
+always_loaded_tokens = 10000
+# Any single file
+file_tokens = 5000
+# All content, including on-demand skills, commands and agents
+total_tokens = 50000
+
+[http]
+# Proxy for channel/hub requests (default: HTTP(S)_PROXY environment variables)
+# proxy = "http://proxy.internal:3128"
+
+# Request timeout in seconds
+timeout_secs = 30
+
+# Retries for network errors, 429 and 5xx responses
+retries = 2
+
+# GitHub token for API requests (default: GITHUB_TOKEN or GH_TOKEN)
+# github_token = "ghp_..."
+
+# GitHub REST API endpoint (change for GitHub Enterprise)
+github_api_url = "https://api.github.com"
+
+# Additional git hosts for channels, hubs and profile import.
+# kind: github, gitlab, gitea (also forgejo)
+# Reference repos as "<name>:group/repo" or by their web URL.
+# [[hosts]]
+# name = "company"
+# kind = "gitlab"
+# url = "https://gitlab.company.com"
+# api_url = "https://gitlab.company.com/api/v4"  # default derived from url
+# token_env = "COMPANY_GITLAB_TOKEN"              # or: token = "glpat-..."
 "#;
 
 /// Global configuration
@@ -42,6 +104,18 @@ pub struct Config {
 
     #[serde(default)]
     pub llm: LlmConfig,
+
+    #[serde(default)]
+    pub classification: ClassificationConfig,
+
+    #[serde(default)]
+    pub budget: BudgetConfig,
+
+    #[serde(default)]
+    pub http: HttpConfig,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<GitHostConfig>,
 }
 
 /// Profile-related configuration
@@ -132,7 +206,27 @@ impl Config {
             "profile.include" => Some(format!("{:?}", self.profile.include)),
             "profile.default" => self.profile.default.clone(),
             "llm.enabled" => Some(self.llm.enabled.to_string()),
-            _ => None,
+            "llm.backend" => Some(self.llm.backend.to_string()),
+            "llm.model" => self.llm.model.clone(),
+            "llm.api_url" => self.llm.api_url.clone(),
+            "llm.api_key" => self.llm.api_key.clone(),
+            "llm.api_key_env" => self.llm.api_key_env.clone(),
+            "llm.max_tokens" => Some(self.llm.max_tokens.to_string()),
+            "llm.timeout_secs" => Some(self.llm.timeout_secs.to_string()),
+            "llm.fixture" => self.llm.fixture.as_ref().map(|p| p.display().to_string()),
+            "classification.mode" => Some(self.classification.mode.to_string()),
+            "budget.always_loaded_tokens" => Some(self.budget.always_loaded_tokens.to_string()),
+            "budget.file_tokens" => Some(self.budget.file_tokens.to_string()),
+            "budget.total_tokens" => Some(self.budget.total_tokens.to_string()),
+            "http.proxy" => self.http.proxy.clone(),
+            "http.timeout_secs" => Some(self.http.timeout_secs.to_string()),
+            "http.retries" => Some(self.http.retries.to_string()),
+            "http.github_token" => self.http.github_token.clone(),
+            "http.github_api_url" => Some(self.http.github_api_url.clone()),
+            _ => key
+                .strip_prefix("hosts.")
+                .and_then(|name| self.hosts.iter().find(|h| h.name == name))
+                .map(|h| format!("{} {}", h.kind, h.url)),
         }
     }
 
@@ -161,6 +255,78 @@ impl Config {
                 self.llm.enabled = matches!(trimmed.as_str(), "true" | "1" | "yes");
                 Ok(())
             }
+            "llm.backend" => {
+                self.llm.backend = value
+                    .parse()
+                    .map_err(|message| DotAgentError::ConfigParseSimple { message })?;
+                Ok(())
+            }
+            "llm.model" => {
+                self.llm.model = parse_optional(value);
+                Ok(())
+            }
+            "llm.api_url" => {
+                self.llm.api_url = parse_optional(value);
+                Ok(())
+            }
+            "llm.api_key" => {
+                self.llm.api_key = parse_optional(value);
+                Ok(())
+            }
+            "llm.api_key_env" => {
+                self.llm.api_key_env = parse_optional(value);
+                Ok(())
+            }
+            "llm.max_tokens" => {
+                self.llm.max_tokens = parse_number(key, value)?;
+                Ok(())
+            }
+            "llm.timeout_secs" => {
+                self.llm.timeout_secs = parse_number(key, value)?;
+                Ok(())
+            }
+            "llm.fixture" => {
+                self.llm.fixture = parse_optional(value).map(PathBuf::from);
+                Ok(())
+            }
+            "classification.mode" => {
+                self.classification.mode = value
+                    .parse()
+                    .map_err(|message| DotAgentError::ConfigParseSimple { message })?;
+                Ok(())
+            }
+            "budget.always_loaded_tokens" => {
+                self.budget.always_loaded_tokens = parse_number(key, value)?;
+                Ok(())
+            }
+            "budget.file_tokens" => {
+                self.budget.file_tokens = parse_number(key, value)?;
+                Ok(())
+            }
+            "budget.total_tokens" => {
+                self.budget.total_tokens = parse_number(key, value)?;
+                Ok(())
+            }
+            "http.proxy" => {
+                self.http.proxy = parse_optional(value);
+                Ok(())
+            }
+            "http.timeout_secs" => {
+                self.http.timeout_secs = parse_number(key, value)?;
+                Ok(())
+            }
+            "http.retries" => {
+                self.http.retries = parse_number(key, value)?;
+                Ok(())
+            }
+            "http.github_token" => {
+                self.http.github_token = parse_optional(value);
+                Ok(())
+            }
+            "http.github_api_url" => {
+                self.http.github_api_url = value.trim().to_string();
+                Ok(())
+            }
             _ => Err(DotAgentError::ConfigKeyNotFound {
                 key: key.to_string(),
             }),
@@ -174,7 +340,7 @@ impl Config {
 
     /// List all config keys with their current values
     pub fn list(&self) -> Vec<(String, String)> {
-        vec![
+        let mut entries = vec![
             (
                 "profile.exclude".to_string(),
                 format!("{:?}", self.profile.exclude),
@@ -191,10 +357,118 @@ impl Config {
                     .unwrap_or_else(|| "(not set)".to_string()),
             ),
             ("llm.enabled".to_string(), self.llm.enabled.to_string()),
-        ]
+            ("llm.backend".to_string(), self.llm.backend.to_string()),
+            (
+                "llm.model".to_string(),
+                self.llm
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| "(not set)".to_string()),
+            ),
+            (
+                "llm.api_url".to_string(),
+                self.llm
+                    .api_url
+                    .clone()
+                    .unwrap_or_else(|| "(default)".to_string()),
+            ),
+            (
+                "llm.api_key".to_string(),
+                if self.llm.api_key.is_some() {
+                    "(set)".to_string()
+                } else {
+                    "(not set)".to_string()
+                },
+            ),
+            (
+                "llm.api_key_env".to_string(),
+                self.llm
+                    .api_key_env
+                    .clone()
+                    .unwrap_or_else(|| "(default)".to_string()),
+            ),
+            (
+                "llm.max_tokens".to_string(),
+                self.llm.max_tokens.to_string(),
+            ),
+            (
+                "llm.timeout_secs".to_string(),
+                self.llm.timeout_secs.to_string(),
+            ),
+            (
+                "llm.fixture".to_string(),
+                self.llm
+                    .fixture
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| "(not set)".to_string()),
+            ),
+            (
+                "classification.mode".to_string(),
+                self.classification.mode.to_string(),
+            ),
+            (
+                "budget.always_loaded_tokens".to_string(),
+                self.budget.always_loaded_tokens.to_string(),
+            ),
+            (
+                "budget.file_tokens".to_string(),
+                self.budget.file_tokens.to_string(),
+            ),
+            (
+                "budget.total_tokens".to_string(),
+                self.budget.total_tokens.to_string(),
+            ),
+            (
+                "http.proxy".to_string(),
+                self.http
+                    .proxy
+                    .clone()
+                    .unwrap_or_else(|| "(not set)".to_string()),
+            ),
+            (
+                "http.timeout_secs".to_string(),
+                self.http.timeout_secs.to_string(),
+            ),
+            ("http.retries".to_string(), self.http.retries.to_string()),
+            (
+                "http.github_token".to_string(),
+                if self.http.github_token.is_some() {
+                    "(set)".to_string()
+                } else {
+                    "(not set)".to_string()
+                },
+            ),
+            (
+                "http.github_api_url".to_string(),
+                self.http.github_api_url.clone(),
+            ),
+        ];
+        entries.extend(
+            self.hosts
+                .iter()
+                .map(|h| (format!("hosts.{}", h.name), format!("{} {}", h.kind, h.url))),
+        );
+        entries
     }
 
     /// Convert to IgnoreConfig for use in install/upgrade
+    /// Classification mode for category commands
+    ///
+    /// LLM classification when `llm.enabled`, otherwise `classification.mode`.
+    pub fn classification_mode(&self) -> ClassificationMode {
+        if self.llm.enabled {
+            ClassificationMode::Llm
+        } else {
+            self.classification.mode
+        }
+    }
+
+    /// LLM backend selected by `[llm]`
+    pub fn llm_backend(&self) -> Result<Arc<dyn LlmBackend>> {
+        self.llm.backend(&self.http)
+    }
+
     pub fn to_ignore_config(&self) -> IgnoreConfig {
         IgnoreConfig {
             excluded_dirs: self.profile.exclude.clone(),
@@ -203,6 +477,26 @@ impl Config {
     }
 }
 
+/// Parse an optional string value (empty clears it)
+fn parse_optional(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+/// Parse a numeric config value
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| DotAgentError::ConfigParseSimple {
+            message: format!("Invalid number for {}: '{}'", key, value),
+        })
+}
+
 /// Parse a comma-separated or JSON-like list string
 fn parse_string_list(value: &str) -> Result<Vec<String>> {
     let trimmed = value.trim();
@@ -265,6 +559,91 @@ mod tests {
         assert!(value.contains(".git"));
     }
 
+    #[test]
+    fn test_config_http_keys() {
+        let mut config = Config::default();
+        assert_eq!(config.get("http.timeout_secs").unwrap(), "30");
+
+        config.set("http.timeout_secs", "5").unwrap();
+        config.set("http.proxy", "http://proxy:3128").unwrap();
+        assert_eq!(config.http.timeout_secs, 5);
+        assert_eq!(config.http.proxy.as_deref(), Some("http://proxy:3128"));
+        assert!(config.set("http.retries", "many").is_err());
+
+        config.set("http.proxy", "").unwrap();
+        assert!(config.http.proxy.is_none());
+    }
+
+    #[test]
+    fn test_config_llm_keys() {
+        let mut config = Config::default();
+        assert_eq!(config.get("llm.backend").unwrap(), "claude-cli");
+
+        config.set("llm.backend", "openai").unwrap();
+        config
+            .set("llm.api_url", "http://localhost:11434/v1")
+            .unwrap();
+        config.set("llm.model", "llama3").unwrap();
+        assert_eq!(config.llm_backend().unwrap().name(), "openai");
+
+        assert!(config.set("llm.backend", "gemini").is_err());
+        assert!(config.set("llm.max_tokens", "lots").is_err());
+        config.set("llm.model", "").unwrap();
+        assert!(config.llm.model.is_none());
+    }
+
+    #[test]
+    fn test_config_classification_mode() {
+        let mut config = Config::default();
+        assert_eq!(config.classification_mode(), ClassificationMode::Content);
+
+        config.set("classification.mode", "glob").unwrap();
+        assert_eq!(config.get("classification.mode").unwrap(), "glob");
+        assert!(config.set("classification.mode", "magic").is_err());
+
+        config.llm.enabled = true;
+        assert_eq!(config.classification_mode(), ClassificationMode::Llm);
+    }
+
+    #[test]
+    fn test_default_template_parses() {
+        let config: Config = toml::from_str(DEFAULT_CONFIG_TEMPLATE).unwrap();
+        assert_eq!(config.http.retries, 2);
+        assert_eq!(config.budget, BudgetConfig::default());
+    }
+
+    #[test]
+    fn test_config_budget_keys() {
+        let mut config = Config::default();
+        assert_eq!(config.get("budget.file_tokens").unwrap(), "5000");
+
+        config.set("budget.always_loaded_tokens", "2000").unwrap();
+        assert_eq!(config.budget.always_loaded_tokens, 2000);
+        assert!(config.set("budget.total_tokens", "lots").is_err());
+    }
+
+    #[test]
+    fn test_config_hosts_roundtrip() {
+        let content = r#"
+[[hosts]]
+name = "company"
+kind = "forgejo"
+url = "https://git.company.com"
+token_env = "COMPANY_TOKEN"
+"#;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.hosts.len(), 1);
+        assert_eq!(config.hosts[0].kind, crate::git_host::GitHostKind::Gitea);
+        assert_eq!(
+            config.get("hosts.company").as_deref(),
+            Some("gitea https://git.company.com")
+        );
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.hosts, config.hosts);
+    }
+
     #[test]
     fn test_to_ignore_config() {
         let mut config = Config::default();
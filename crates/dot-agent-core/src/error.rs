@@ -128,6 +128,49 @@ pub enum DotAgentError {
 
     #[error("Operation aborted by user")]
     Aborted,
+
+    #[error("Untrusted changes pending for {name}. Review with 'dot-agent {kind} refresh {name}'")]
+    UntrustedChange { kind: String, name: String },
+
+    #[error("No pending changes to approve for {name}")]
+    NoPendingChange { name: String },
+
+    #[error("HTTP request to {url} failed: {message}")]
+    HttpError { url: String, message: String },
+
+    #[error("Cannot resolve repository '{reference}'. Use owner/repo, <host>:<repo> or a URL on a host configured under [[hosts]] in config.toml")]
+    UnresolvedRepo { reference: String },
+
+    #[error("Git host not found: {name}")]
+    GitHostNotFound { name: String },
+
+    #[error("Invalid hub index {location}:\n  {}", issues.join("\n  "))]
+    InvalidHubIndex {
+        location: String,
+        issues: Vec<String>,
+    },
+
+    #[error("Invalid component path '{path}': must be a relative path inside the profile")]
+    InvalidComponentPath { path: String },
+
+    #[error("LLM backend '{backend}' failed: {message}")]
+    LlmBackendFailed { backend: String, message: String },
+
+    #[error("Invalid rule edit: {message}")]
+    InvalidRuleEdit { message: String },
+
+    #[error("Invalid rule '{name}': {message}")]
+    InvalidRule { name: String, message: String },
+
+    #[error("Pipeline not found: {name}")]
+    PipelineNotFound { name: String },
+
+    #[error("Rule '{name}' failed {failed} of {total} test case(s)")]
+    RuleTestFailed {
+        name: String,
+        failed: usize,
+        total: usize,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, DotAgentError>;
@@ -166,6 +209,18 @@ impl DotAgentError {
             Self::OperationNotFound { .. } => 30,
             Self::CheckpointNotFound { .. } => 31,
             Self::Aborted => 32,
+            Self::UntrustedChange { .. } => 33,
+            Self::NoPendingChange { .. } => 34,
+            Self::HttpError { .. } => 35,
+            Self::UnresolvedRepo { .. } => 36,
+            Self::GitHostNotFound { .. } => 37,
+            Self::InvalidHubIndex { .. } => 38,
+            Self::InvalidComponentPath { .. } => 39,
+            Self::LlmBackendFailed { .. } => 40,
+            Self::InvalidRuleEdit { .. } => 41,
+            Self::InvalidRule { .. } => 42,
+            Self::PipelineNotFound { .. } => 43,
+            Self::RuleTestFailed { .. } => 44,
             _ => 1,
         }
     }
@@ -0,0 +1,1069 @@
+//! Git hosting services
+//!
+//! Channels, hubs and `profile import` reach repositories through the
+//! [`GitHost`] trait, so GitHub, GitLab and Gitea/Forgejo (including
+//! self-hosted instances) share one code path for raw file URLs, repository
+//! search and directory listing.
+//!
+//! Hosts are configured as `[[hosts]]` entries in config.toml. github.com is
+//! always available as the `github` host and is used for bare `owner/repo`
+//! references.
+//!
+//! ```toml
+//! [[hosts]]
+//! name = "company"
+//! kind = "gitlab"
+//! url = "https://gitlab.company.com"
+//! token_env = "COMPANY_GITLAB_TOKEN"
+//! ```
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+use crate::http::{encode_query, HttpConfig, HttpFetcher, HttpRequest, DEFAULT_GITHUB_API_URL};
+
+/// Name of the built-in github.com host
+pub const GITHUB_HOST_NAME: &str = "github";
+
+const GITHUB_WEB_URL: &str = "https://github.com";
+
+const GITHUB_JSON: &str = "application/vnd.github+json";
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Kind of git hosting service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHostKind {
+    /// GitHub and GitHub Enterprise
+    Github,
+    /// GitLab (gitlab.com or self-hosted)
+    Gitlab,
+    /// Gitea and Forgejo (same API)
+    #[serde(alias = "forgejo")]
+    Gitea,
+}
+
+impl GitHostKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Gitlab => "gitlab",
+            Self::Gitea => "gitea",
+        }
+    }
+}
+
+impl std::fmt::Display for GitHostKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for GitHostKind {
+    type Err = DotAgentError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(Self::Github),
+            "gitlab" => Ok(Self::Gitlab),
+            "gitea" | "forgejo" => Ok(Self::Gitea),
+            _ => Err(DotAgentError::ConfigParseSimple {
+                message: format!(
+                    "Unknown git host kind '{}'. Valid: github, gitlab, gitea, forgejo",
+                    s
+                ),
+            }),
+        }
+    }
+}
+
+/// A git host entry (`[[hosts]]` in config.toml)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitHostConfig {
+    /// Name used in references like `company:group/repo`
+    pub name: String,
+
+    /// Hosting service kind
+    pub kind: GitHostKind,
+
+    /// Web URL (e.g., "https://gitlab.company.com")
+    pub url: String,
+
+    /// REST API endpoint (default: derived from `url` and `kind`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+
+    /// Access token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// Environment variable holding the access token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+}
+
+impl GitHostConfig {
+    pub fn new(name: impl Into<String>, kind: GitHostKind, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            url: url.into(),
+            api_url: None,
+            token: None,
+            token_env: None,
+        }
+    }
+
+    /// The built-in github.com host, using the `[http]` token and API endpoint
+    pub fn github(http: &HttpConfig) -> Self {
+        Self {
+            name: GITHUB_HOST_NAME.to_string(),
+            kind: GitHostKind::Github,
+            url: GITHUB_WEB_URL.to_string(),
+            api_url: Some(http.github_api_url.clone()),
+            token: http.resolve_github_token(),
+            token_env: None,
+        }
+    }
+
+    /// Web URL without trailing slash
+    pub fn web_url(&self) -> &str {
+        self.url.trim_end_matches('/')
+    }
+
+    /// REST API endpoint without trailing slash
+    pub fn api_url(&self) -> String {
+        if let Some(api) = &self.api_url {
+            return api.trim_end_matches('/').to_string();
+        }
+        match self.kind {
+            GitHostKind::Github if self.web_url() == GITHUB_WEB_URL => {
+                DEFAULT_GITHUB_API_URL.to_string()
+            }
+            GitHostKind::Github => format!("{}/api/v3", self.web_url()),
+            GitHostKind::Gitlab => format!("{}/api/v4", self.web_url()),
+            GitHostKind::Gitea => format!("{}/api/v1", self.web_url()),
+        }
+    }
+
+    /// Token from `token`, then the `token_env` variable
+    pub fn resolve_token(&self) -> Option<String> {
+        self.token.clone().filter(|t| !t.is_empty()).or_else(|| {
+            self.token_env
+                .as_ref()
+                .and_then(|var| std::env::var(var).ok())
+                .filter(|t| !t.is_empty())
+        })
+    }
+
+    /// Host and optional path prefix of the web URL ("gitlab.company.com")
+    pub fn domain(&self) -> &str {
+        let url = self.web_url();
+        url.split_once("://").map(|(_, rest)| rest).unwrap_or(url)
+    }
+}
+
+// ============================================================================
+// Host trait
+// ============================================================================
+
+/// A repository found by [`GitHost::search`]
+#[derive(Debug, Clone, Default)]
+pub struct RepoSummary {
+    /// Repository name
+    pub name: String,
+    /// Owner (user, organization or group path)
+    pub owner: String,
+    /// Full path on the host ("owner/repo", "group/sub/repo")
+    pub full_name: String,
+    pub description: Option<String>,
+    /// Web URL
+    pub url: String,
+    pub stars: Option<u64>,
+    /// Last activity timestamp (RFC 3339)
+    pub updated_at: Option<String>,
+}
+
+/// Repository search parameters
+#[derive(Debug, Clone, Default)]
+pub struct RepoQuery {
+    /// Free-text query
+    pub text: String,
+    /// Topic filter
+    pub topic: Option<String>,
+    /// Minimum star count
+    pub min_stars: Option<u64>,
+    /// Sort field ("stars" or "updated")
+    pub sort: Option<String>,
+    /// Maximum results
+    pub limit: usize,
+}
+
+/// A git hosting service
+pub trait GitHost: Send + Sync {
+    /// Host configuration
+    fn config(&self) -> &GitHostConfig;
+
+    /// Host name (as configured)
+    fn name(&self) -> &str {
+        &self.config().name
+    }
+
+    /// Web URL of a repository
+    fn repo_url(&self, repo: &str) -> String {
+        format!("{}/{}", self.config().web_url(), repo)
+    }
+
+    /// Clone URL of a repository
+    fn clone_url(&self, repo: &str) -> String {
+        format!("{}.git", self.repo_url(repo))
+    }
+
+    /// Raw content URL of a file on a branch
+    fn raw_file_url(&self, repo: &str, branch: &str, path: &str) -> String;
+
+    /// Request for the raw content of a file on a branch, authenticated with
+    /// the host token
+    ///
+    /// Web raw URLs ignore API tokens on most hosts, so self-hosted instances
+    /// go through the API file endpoints.
+    fn raw_file_request(&self, repo: &str, branch: &str, path: &str) -> HttpRequest;
+
+    /// Fetch the raw content of a file on a branch. Returns `None` for 404.
+    fn fetch_raw(
+        &self,
+        http: &HttpFetcher,
+        repo: &str,
+        branch: &str,
+        path: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<Option<String>> {
+        http.get_request_text(self.raw_file_request(repo, branch, path), cache_dir)
+    }
+
+    /// Web URL of a directory on a branch
+    fn tree_url(&self, repo: &str, branch: &str, path: &str) -> String;
+
+    /// Extract the repository path from a web or clone URL on this host
+    fn parse_repo(&self, url: &str) -> Option<String> {
+        let path = url_path(self.config(), url)?;
+        let segments: Vec<&str> = path.split('/').take(2).collect();
+        (segments.len() == 2 && segments.iter().all(|s| !s.is_empty())).then(|| segments.join("/"))
+    }
+
+    /// Search repositories
+    fn search(
+        &self,
+        http: &HttpFetcher,
+        query: &RepoQuery,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<RepoSummary>>;
+
+    /// Names of the subdirectories of `path` on the default branch
+    fn list_dirs(
+        &self,
+        http: &HttpFetcher,
+        repo: &str,
+        path: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<String>>;
+}
+
+/// Path after the host in a web, clone or scp-style URL ("owner/repo/...")
+fn url_path<'a>(config: &GitHostConfig, url: &'a str) -> Option<&'a str> {
+    let url = url.trim().trim_end_matches('/').trim_end_matches(".git");
+    let domain = config.domain();
+
+    let rest = if let Some(scp) = url.strip_prefix("git@") {
+        scp.strip_prefix(domain)?.strip_prefix(':')?
+    } else {
+        let without_scheme = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+        let without_user = without_scheme
+            .split_once('@')
+            .filter(|(user, _)| !user.contains('/'))
+            .map(|(_, r)| r)
+            .unwrap_or(without_scheme);
+        without_user.strip_prefix(domain)?.strip_prefix('/')?
+    };
+
+    (!rest.is_empty()).then_some(rest)
+}
+
+fn invalid_response(url: &str, what: &str) -> DotAgentError {
+    DotAgentError::HttpError {
+        url: url.to_string(),
+        message: format!("unexpected response: {}", what),
+    }
+}
+
+/// Keep results at or above `min_stars` and cut to `limit`
+fn finish(mut repos: Vec<RepoSummary>, query: &RepoQuery) -> Vec<RepoSummary> {
+    if let Some(min) = query.min_stars {
+        repos.retain(|r| r.stars.unwrap_or(0) >= min);
+    }
+    if query.limit > 0 {
+        repos.truncate(query.limit);
+    }
+    repos
+}
+
+fn page_size(query: &RepoQuery) -> usize {
+    if query.limit > 0 {
+        query.limit.min(100)
+    } else {
+        10
+    }
+}
+
+// ============================================================================
+// GitHub
+// ============================================================================
+
+/// GitHub and GitHub Enterprise
+pub struct GitHubHost {
+    config: GitHostConfig,
+}
+
+impl GitHubHost {
+    pub fn new(config: GitHostConfig) -> Self {
+        Self { config }
+    }
+
+    fn authorize(&self, request: HttpRequest) -> HttpRequest {
+        match self.config.resolve_token() {
+            Some(token) => request.header("Authorization", format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+
+    fn api_request(&self, path: &str, accept: &str) -> HttpRequest {
+        let url = format!("{}/{}", self.config.api_url(), path);
+        self.authorize(
+            HttpRequest::get(url)
+                .header("Accept", accept)
+                .header("X-GitHub-Api-Version", "2022-11-28"),
+        )
+    }
+}
+
+impl GitHost for GitHubHost {
+    fn config(&self) -> &GitHostConfig {
+        &self.config
+    }
+
+    fn raw_file_url(&self, repo: &str, branch: &str, path: &str) -> String {
+        if self.config.web_url() == GITHUB_WEB_URL {
+            format!(
+                "https://raw.githubusercontent.com/{}/{}/{}",
+                repo, branch, path
+            )
+        } else {
+            format!("{}/raw/{}/{}", self.repo_url(repo), branch, path)
+        }
+    }
+
+    fn raw_file_request(&self, repo: &str, branch: &str, path: &str) -> HttpRequest {
+        if self.config.web_url() == GITHUB_WEB_URL {
+            return self.authorize(HttpRequest::get(self.raw_file_url(repo, branch, path)));
+        }
+        self.api_request(
+            &format!(
+                "repos/{}/contents/{}?ref={}",
+                repo,
+                path,
+                encode_query(branch)
+            ),
+            "application/vnd.github.raw",
+        )
+    }
+
+    fn tree_url(&self, repo: &str, branch: &str, path: &str) -> String {
+        format!("{}/tree/{}/{}", self.repo_url(repo), branch, path)
+    }
+
+    fn search(
+        &self,
+        http: &HttpFetcher,
+        query: &RepoQuery,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<RepoSummary>> {
+        let mut terms: Vec<String> = Vec::new();
+        if !query.text.is_empty() {
+            terms.push(query.text.clone());
+        }
+        if let Some(topic) = &query.topic {
+            terms.push(format!("topic:{}", topic));
+        }
+        if let Some(min) = query.min_stars {
+            terms.push(format!("stars:>={}", min));
+        }
+        let sort = query.sort.clone().unwrap_or_else(|| "stars".to_string());
+
+        let request = self.api_request(
+            &format!(
+                "search/repositories?q={}&sort={}&per_page={}",
+                encode_query(&terms.join(" ")),
+                encode_query(&sort),
+                page_size(query)
+            ),
+            GITHUB_JSON,
+        );
+        let url = request.url.clone();
+        let json = http.get_json(request, cache_dir)?;
+        let items = json["items"]
+            .as_array()
+            .ok_or_else(|| invalid_response(&url, "missing items"))?;
+
+        let repos = items
+            .iter()
+            .map(|repo| RepoSummary {
+                name: repo["name"].as_str().unwrap_or("").to_string(),
+                owner: repo["owner"]["login"].as_str().unwrap_or("").to_string(),
+                full_name: repo["full_name"].as_str().unwrap_or("").to_string(),
+                description: repo["description"].as_str().map(str::to_string),
+                url: repo["html_url"].as_str().unwrap_or("").to_string(),
+                stars: repo["stargazers_count"].as_u64(),
+                updated_at: repo["pushed_at"].as_str().map(str::to_string),
+            })
+            .collect();
+
+        Ok(finish(repos, query))
+    }
+
+    fn list_dirs(
+        &self,
+        http: &HttpFetcher,
+        repo: &str,
+        path: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<String>> {
+        let request = self.api_request(&format!("repos/{}/contents/{}", repo, path), GITHUB_JSON);
+        let url = request.url.clone();
+        let json = http.get_json(request, cache_dir)?;
+        let entries = json
+            .as_array()
+            .ok_or_else(|| invalid_response(&url, "not a directory"))?;
+
+        Ok(entries
+            .iter()
+            .filter(|e| e["type"].as_str() == Some("dir"))
+            .filter_map(|e| e["name"].as_str().map(str::to_string))
+            .collect())
+    }
+}
+
+// ============================================================================
+// GitLab
+// ============================================================================
+
+/// GitLab (gitlab.com or self-hosted)
+pub struct GitLabHost {
+    config: GitHostConfig,
+}
+
+impl GitLabHost {
+    pub fn new(config: GitHostConfig) -> Self {
+        Self { config }
+    }
+
+    fn api_request(&self, path: &str) -> HttpRequest {
+        let url = format!("{}/{}", self.config.api_url(), path);
+        match self.config.resolve_token() {
+            Some(token) => HttpRequest::get(url).header("PRIVATE-TOKEN", token),
+            None => HttpRequest::get(url),
+        }
+    }
+}
+
+impl GitHost for GitLabHost {
+    fn config(&self) -> &GitHostConfig {
+        &self.config
+    }
+
+    fn raw_file_url(&self, repo: &str, branch: &str, path: &str) -> String {
+        format!("{}/-/raw/{}/{}", self.repo_url(repo), branch, path)
+    }
+
+    fn raw_file_request(&self, repo: &str, branch: &str, path: &str) -> HttpRequest {
+        self.api_request(&format!(
+            "projects/{}/repository/files/{}/raw?ref={}",
+            encode_query(repo),
+            encode_query(path),
+            encode_query(branch)
+        ))
+    }
+
+    fn tree_url(&self, repo: &str, branch: &str, path: &str) -> String {
+        format!("{}/-/tree/{}/{}", self.repo_url(repo), branch, path)
+    }
+
+    /// Projects may be nested in subgroups, so the path runs up to `/-/`
+    fn parse_repo(&self, url: &str) -> Option<String> {
+        let path = url_path(&self.config, url)?;
+        let repo = path.split("/-/").next().unwrap_or(path);
+        repo.contains('/').then(|| repo.to_string())
+    }
+
+    fn search(
+        &self,
+        http: &HttpFetcher,
+        query: &RepoQuery,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<RepoSummary>> {
+        let order_by = match query.sort.as_deref() {
+            Some("updated") => "last_activity_at",
+            _ => "star_count",
+        };
+        let mut path = format!(
+            "projects?search={}&order_by={}&sort=desc&per_page={}",
+            encode_query(&query.text),
+            order_by,
+            page_size(query)
+        );
+        if let Some(topic) = &query.topic {
+            path.push_str(&format!("&topic={}", encode_query(topic)));
+        }
+
+        let request = self.api_request(&path);
+        let url = request.url.clone();
+        let json = http.get_json(request, cache_dir)?;
+        let items = json
+            .as_array()
+            .ok_or_else(|| invalid_response(&url, "expected a project list"))?;
+
+        let repos = items
+            .iter()
+            .map(|project| RepoSummary {
+                name: project["path"].as_str().unwrap_or("").to_string(),
+                owner: project["namespace"]["full_path"]
+                    .as_str()
+                    .unwrap_or("")
+                    .to_string(),
+                full_name: project["path_with_namespace"]
+                    .as_str()
+                    .unwrap_or("")
+                    .to_string(),
+                description: project["description"].as_str().map(str::to_string),
+                url: project["web_url"].as_str().unwrap_or("").to_string(),
+                stars: project["star_count"].as_u64(),
+                updated_at: project["last_activity_at"].as_str().map(str::to_string),
+            })
+            .collect();
+
+        Ok(finish(repos, query))
+    }
+
+    fn list_dirs(
+        &self,
+        http: &HttpFetcher,
+        repo: &str,
+        path: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<String>> {
+        let request = self.api_request(&format!(
+            "projects/{}/repository/tree?path={}&per_page=100",
+            encode_query(repo),
+            encode_query(path)
+        ));
+        let url = request.url.clone();
+        let json = http.get_json(request, cache_dir)?;
+        let entries = json
+            .as_array()
+            .ok_or_else(|| invalid_response(&url, "not a directory"))?;
+
+        Ok(entries
+            .iter()
+            .filter(|e| e["type"].as_str() == Some("tree"))
+            .filter_map(|e| e["name"].as_str().map(str::to_string))
+            .collect())
+    }
+}
+
+// ============================================================================
+// Gitea / Forgejo
+// ============================================================================
+
+/// Gitea and Forgejo
+pub struct GiteaHost {
+    config: GitHostConfig,
+}
+
+impl GiteaHost {
+    pub fn new(config: GitHostConfig) -> Self {
+        Self { config }
+    }
+
+    fn api_request(&self, path: &str) -> HttpRequest {
+        let url = format!("{}/{}", self.config.api_url(), path);
+        match self.config.resolve_token() {
+            Some(token) => {
+                HttpRequest::get(url).header("Authorization", format!("token {}", token))
+            }
+            None => HttpRequest::get(url),
+        }
+    }
+}
+
+impl GitHost for GiteaHost {
+    fn config(&self) -> &GitHostConfig {
+        &self.config
+    }
+
+    fn raw_file_url(&self, repo: &str, branch: &str, path: &str) -> String {
+        format!("{}/raw/branch/{}/{}", self.repo_url(repo), branch, path)
+    }
+
+    fn raw_file_request(&self, repo: &str, branch: &str, path: &str) -> HttpRequest {
+        self.api_request(&format!(
+            "repos/{}/raw/{}?ref={}",
+            repo,
+            path,
+            encode_query(branch)
+        ))
+    }
+
+    fn tree_url(&self, repo: &str, branch: &str, path: &str) -> String {
+        format!("{}/src/branch/{}/{}", self.repo_url(repo), branch, path)
+    }
+
+    fn search(
+        &self,
+        http: &HttpFetcher,
+        query: &RepoQuery,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<RepoSummary>> {
+        let sort = match query.sort.as_deref() {
+            Some("updated") => "updated",
+            _ => "stars",
+        };
+        // Gitea matches either names or topics; a topic-only query searches topics
+        let (text, topic_only) = match (&query.topic, query.text.is_empty()) {
+            (Some(topic), true) => (topic.clone(), true),
+            _ => (query.text.clone(), false),
+        };
+
+        let request = self.api_request(&format!(
+            "repos/search?q={}&topic={}&sort={}&order=desc&limit={}",
+            encode_query(&text),
+            topic_only,
+            sort,
+            page_size(query)
+        ));
+        let url = request.url.clone();
+        let json = http.get_json(request, cache_dir)?;
+        let items = json["data"]
+            .as_array()
+            .ok_or_else(|| invalid_response(&url, "missing data"))?;
+
+        let topic_filter = query.topic.as_ref().filter(|_| !topic_only);
+        let repos = items
+            .iter()
+            .filter(|repo| match (topic_filter, repo["topics"].as_array()) {
+                (Some(topic), Some(topics)) => topics.iter().any(|t| t.as_str() == Some(topic)),
+                _ => true,
+            })
+            .map(|repo| RepoSummary {
+                name: repo["name"].as_str().unwrap_or("").to_string(),
+                owner: repo["owner"]["login"].as_str().unwrap_or("").to_string(),
+                full_name: repo["full_name"].as_str().unwrap_or("").to_string(),
+                description: repo["description"]
+                    .as_str()
+                    .filter(|d| !d.is_empty())
+                    .map(str::to_string),
+                url: repo["html_url"].as_str().unwrap_or("").to_string(),
+                stars: repo["stars_count"].as_u64(),
+                updated_at: repo["updated_at"].as_str().map(str::to_string),
+            })
+            .collect();
+
+        Ok(finish(repos, query))
+    }
+
+    fn list_dirs(
+        &self,
+        http: &HttpFetcher,
+        repo: &str,
+        path: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<Vec<String>> {
+        let request = self.api_request(&format!("repos/{}/contents/{}", repo, path));
+        let url = request.url.clone();
+        let json = http.get_json(request, cache_dir)?;
+        let entries = json
+            .as_array()
+            .ok_or_else(|| invalid_response(&url, "not a directory"))?;
+
+        Ok(entries
+            .iter()
+            .filter(|e| e["type"].as_str() == Some("dir"))
+            .filter_map(|e| e["name"].as_str().map(str::to_string))
+            .collect())
+    }
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// Create the host implementation for a config entry
+pub fn host_for(config: GitHostConfig) -> Arc<dyn GitHost> {
+    match config.kind {
+        GitHostKind::Github => Arc::new(GitHubHost::new(config)),
+        GitHostKind::Gitlab => Arc::new(GitLabHost::new(config)),
+        GitHostKind::Gitea => Arc::new(GiteaHost::new(config)),
+    }
+}
+
+/// A repository on a specific host
+#[derive(Clone)]
+pub struct HostedRepo {
+    pub host: Arc<dyn GitHost>,
+    /// Repository path on the host ("owner/repo", "group/sub/repo")
+    pub repo: String,
+}
+
+impl HostedRepo {
+    /// Owner (first path segment)
+    pub fn owner(&self) -> &str {
+        self.repo.split('/').next().unwrap_or(&self.repo)
+    }
+
+    pub fn url(&self) -> String {
+        self.host.repo_url(&self.repo)
+    }
+
+    pub fn clone_url(&self) -> String {
+        self.host.clone_url(&self.repo)
+    }
+
+    pub fn raw_file_url(&self, branch: &str, path: &str) -> String {
+        self.host.raw_file_url(&self.repo, branch, path)
+    }
+
+    /// Fetch a file with the host token (see [`GitHost::fetch_raw`])
+    pub fn fetch_raw(
+        &self,
+        http: &HttpFetcher,
+        branch: &str,
+        path: &str,
+        cache_dir: Option<&Path>,
+    ) -> Result<Option<String>> {
+        self.host
+            .fetch_raw(http, &self.repo, branch, path, cache_dir)
+    }
+
+    pub fn tree_url(&self, branch: &str, path: &str) -> String {
+        self.host.tree_url(&self.repo, branch, path)
+    }
+}
+
+impl std::fmt::Debug for HostedRepo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HostedRepo")
+            .field("host", &self.host.name())
+            .field("repo", &self.repo)
+            .finish()
+    }
+}
+
+/// Configured git hosts (github.com plus `[[hosts]]`)
+#[derive(Clone)]
+pub struct GitHostRegistry {
+    hosts: Vec<Arc<dyn GitHost>>,
+}
+
+impl Default for GitHostRegistry {
+    fn default() -> Self {
+        Self::new(&HttpConfig::default(), &[])
+    }
+}
+
+impl GitHostRegistry {
+    /// Build from `[http]` and `[[hosts]]`
+    ///
+    /// A `[[hosts]]` entry named `github` replaces the built-in github.com host.
+    pub fn new(http: &HttpConfig, hosts: &[GitHostConfig]) -> Self {
+        let mut all: Vec<Arc<dyn GitHost>> = Vec::new();
+        if !hosts.iter().any(|h| h.name == GITHUB_HOST_NAME) {
+            all.push(host_for(GitHostConfig::github(http)));
+        }
+        all.extend(hosts.iter().cloned().map(host_for));
+        Self { hosts: all }
+    }
+
+    /// Build from a loaded config
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self::new(&config.http, &config.hosts)
+    }
+
+    /// Get a host by name
+    pub fn get(&self, name: &str) -> Option<Arc<dyn GitHost>> {
+        self.hosts.iter().find(|h| h.name() == name).cloned()
+    }
+
+    /// Host used for bare `owner/repo` references
+    pub fn default_host(&self) -> Arc<dyn GitHost> {
+        self.get(GITHUB_HOST_NAME)
+            .unwrap_or_else(|| self.hosts[0].clone())
+    }
+
+    /// All hosts
+    pub fn list(&self) -> &[Arc<dyn GitHost>] {
+        &self.hosts
+    }
+
+    /// Find the host serving a web or clone URL
+    pub fn find_by_url(&self, url: &str) -> Option<HostedRepo> {
+        self.hosts.iter().find_map(|host| {
+            host.parse_repo(url).map(|repo| HostedRepo {
+                host: host.clone(),
+                repo,
+            })
+        })
+    }
+
+    /// Resolve a repository reference
+    ///
+    /// Accepts:
+    /// - a web or clone URL on a configured host
+    /// - `<host>:<repo>` (e.g., "company:platform/agent-profiles")
+    /// - `<domain>/<repo>` (e.g., "github.com/owner/repo")
+    /// - `owner/repo` (default host)
+    pub fn resolve(&self, reference: &str) -> Option<HostedRepo> {
+        let reference = reference.trim().trim_end_matches('/');
+
+        if reference.contains("://") || reference.starts_with("git@") {
+            return self.find_by_url(reference);
+        }
+
+        if let Some((name, repo)) = reference.split_once(':') {
+            let host = self.get(name)?;
+            let repo = repo.trim_start_matches('/').trim_end_matches(".git");
+            return (!repo.is_empty()).then(|| HostedRepo {
+                host,
+                repo: repo.to_string(),
+            });
+        }
+
+        if let Some(found) = self.find_by_url(&format!("https://{}", reference)) {
+            return Some(found);
+        }
+
+        let repo = reference.trim_end_matches(".git");
+        (repo.split('/').count() >= 2).then(|| HostedRepo {
+            host: self.default_host(),
+            repo: repo.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::tests::TestServer;
+
+    fn registry() -> GitHostRegistry {
+        let mut gitlab = GitHostConfig::new(
+            "company",
+            GitHostKind::Gitlab,
+            "https://gitlab.company.com/",
+        );
+        gitlab.token = Some("glpat".to_string());
+        let gitea = GitHostConfig::new("forge", GitHostKind::Gitea, "https://codeberg.org");
+        GitHostRegistry::new(&HttpConfig::default(), &[gitlab, gitea])
+    }
+
+    fn fetcher() -> HttpFetcher {
+        let config = HttpConfig {
+            retries: 0,
+            ..Default::default()
+        };
+        HttpFetcher::new(&config).unwrap()
+    }
+
+    #[test]
+    fn raw_file_urls_per_host() {
+        let hosts = registry();
+        let github = hosts.resolve("owner/repo").unwrap();
+        assert_eq!(
+            github.raw_file_url("main", "README.md"),
+            "https://raw.githubusercontent.com/owner/repo/main/README.md"
+        );
+
+        let gitlab = hosts.resolve("company:group/sub/repo").unwrap();
+        assert_eq!(
+            gitlab.raw_file_url("main", "README.md"),
+            "https://gitlab.company.com/group/sub/repo/-/raw/main/README.md"
+        );
+
+        let gitea = hosts.resolve("https://codeberg.org/user/skills").unwrap();
+        assert_eq!(
+            gitea.raw_file_url("main", "skills/x"),
+            "https://codeberg.org/user/skills/raw/branch/main/skills/x"
+        );
+    }
+
+    #[test]
+    fn resolve_urls() {
+        let hosts = registry();
+
+        let gitlab = hosts
+            .resolve("https://gitlab.company.com/group/sub/repo/-/tree/main/skills")
+            .unwrap();
+        assert_eq!(gitlab.host.name(), "company");
+        assert_eq!(gitlab.repo, "group/sub/repo");
+        assert_eq!(gitlab.owner(), "group");
+
+        let scp = hosts
+            .resolve("git@gitlab.company.com:group/repo.git")
+            .unwrap();
+        assert_eq!(scp.repo, "group/repo");
+
+        let github = hosts.resolve("github.com/owner/repo").unwrap();
+        assert_eq!(github.host.name(), GITHUB_HOST_NAME);
+        assert_eq!(github.repo, "owner/repo");
+
+        let github_tree = hosts
+            .resolve("https://github.com/owner/repo/tree/main/x")
+            .unwrap();
+        assert_eq!(github_tree.repo, "owner/repo");
+
+        assert!(hosts.resolve("https://example.com/a/b").is_none());
+        assert!(hosts.resolve("unknown:a/b").is_none());
+        assert!(hosts.resolve("single").is_none());
+    }
+
+    #[test]
+    fn gitlab_search_and_listing() {
+        let server = TestServer::start(Arc::new(|path, headers| {
+            let authorized = headers
+                .iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case("private-token") && v == "glpat");
+            if !authorized {
+                return (401, vec![], String::new());
+            }
+            if path.starts_with("/api/v4/projects?search=skills") {
+                let body = r#"[{"path": "skills", "path_with_namespace": "team/skills",
+                    "namespace": {"full_path": "team"}, "description": "Team skills",
+                    "web_url": "https://gitlab.local/team/skills", "star_count": 5,
+                    "last_activity_at": "2026-01-01T00:00:00Z"},
+                    {"path": "old", "path_with_namespace": "team/old",
+                    "namespace": {"full_path": "team"}, "star_count": 0}]"#;
+                (200, vec![], body.to_string())
+            } else if path.starts_with("/api/v4/projects/team%2Fskills/repository/tree") {
+                let body =
+                    r#"[{"name": "tdd", "type": "tree"}, {"name": "README.md", "type": "blob"}]"#;
+                (200, vec![], body.to_string())
+            } else {
+                (404, vec![], String::new())
+            }
+        }));
+
+        let mut config = GitHostConfig::new("local", GitHostKind::Gitlab, &server.url);
+        config.token = Some("glpat".to_string());
+        let host = host_for(config);
+        let http = fetcher();
+
+        let query = RepoQuery {
+            text: "skills".to_string(),
+            min_stars: Some(1),
+            ..Default::default()
+        };
+        let repos = host.search(&http, &query, None).unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].full_name, "team/skills");
+        assert_eq!(repos[0].stars, Some(5));
+
+        let dirs = host
+            .list_dirs(&http, "team/skills", "skills", None)
+            .unwrap();
+        assert_eq!(dirs, vec!["tdd".to_string()]);
+    }
+
+    #[test]
+    fn github_host_sends_token() {
+        let server = TestServer::start(Arc::new(|_, headers| {
+            let authorized = headers
+                .iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case("authorization") && v == "Bearer test-token");
+            if authorized {
+                (
+                    200,
+                    vec![],
+                    r#"[{"name": "pdf", "type": "dir"}]"#.to_string(),
+                )
+            } else {
+                (401, vec![], String::new())
+            }
+        }));
+        let http = HttpConfig {
+            retries: 0,
+            github_token: Some("test-token".to_string()),
+            github_api_url: server.url.clone(),
+            ..Default::default()
+        };
+        let hosts = GitHostRegistry::new(&http, &[]);
+
+        let dirs = hosts
+            .default_host()
+            .list_dirs(&fetcher(), "openai/skills", "skills", None)
+            .unwrap();
+        assert_eq!(dirs, vec!["pdf".to_string()]);
+    }
+
+    #[test]
+    fn raw_file_fetch_uses_host_token() {
+        let server = TestServer::start(Arc::new(|path, headers| {
+            let token = |name: &str, value: &str| {
+                headers
+                    .iter()
+                    .any(|(k, v)| k.eq_ignore_ascii_case(name) && v == value)
+            };
+            match path {
+                "/api/v4/projects/team%2Fhub/repository/files/rules%2Fdocs.md/raw?ref=main"
+                    if token("private-token", "glpat") =>
+                {
+                    (200, vec![], "gitlab".to_string())
+                }
+                "/api/v1/repos/team/hub/raw/rules/docs.md?ref=main"
+                    if token("authorization", "token gtea") =>
+                {
+                    (200, vec![], "gitea".to_string())
+                }
+                "/api/v3/repos/team/hub/contents/rules/docs.md?ref=main"
+                    if token("authorization", "Bearer ghe")
+                        && token("accept", "application/vnd.github.raw") =>
+                {
+                    (200, vec![], "github".to_string())
+                }
+                _ => (401, vec![], String::new()),
+            }
+        }));
+        let http = fetcher();
+
+        for (kind, token, expected) in [
+            (GitHostKind::Gitlab, "glpat", "gitlab"),
+            (GitHostKind::Gitea, "gtea", "gitea"),
+            (GitHostKind::Github, "ghe", "github"),
+        ] {
+            let mut config = GitHostConfig::new("local", kind, &server.url);
+            config.token = Some(token.to_string());
+            let content = host_for(config)
+                .fetch_raw(&http, "team/hub", "main", "rules/docs.md", None)
+                .unwrap();
+            assert_eq!(content.as_deref(), Some(expected));
+        }
+
+        // Without the token the server refuses the request
+        let config = GitHostConfig::new("local", GitHostKind::Gitlab, &server.url);
+        assert!(host_for(config)
+            .fetch_raw(&http, "team/hub", "main", "rules/docs.md", None)
+            .is_err());
+    }
+}
@@ -116,9 +116,19 @@ impl CheckpointManager {
     }
 
     /// Generate a new checkpoint ID
-    fn generate_checkpoint_id() -> String {
+    ///
+    /// Checkpoints created within the same millisecond get a numeric suffix;
+    /// reusing an ID would make an incremental checkpoint its own base.
+    fn generate_checkpoint_id(&self) -> String {
         let now = Utc::now();
-        format!("cp-{}", now.format("%Y%m%d_%H%M%S_%3f"))
+        let id = format!("cp-{}", now.format("%Y%m%d_%H%M%S_%3f"));
+        let mut unique = id.clone();
+        let mut n = 1;
+        while self.base_dir.join(&unique).exists() {
+            unique = format!("{}-{}", id, n);
+            n += 1;
+        }
+        unique
     }
 
     /// Determine if we should create a full snapshot
@@ -144,7 +154,7 @@ impl CheckpointManager {
         operation_id: &str,
         source_dir: &Path,
     ) -> Result<Checkpoint> {
-        let checkpoint_id = Self::generate_checkpoint_id();
+        let checkpoint_id = self.generate_checkpoint_id();
         let is_full = self.should_create_full();
 
         let checkpoint_dir = self.base_dir.join(&checkpoint_id);
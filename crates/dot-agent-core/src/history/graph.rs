@@ -87,14 +87,14 @@ impl OperationGraph {
     /// Get all operations in chronological order
     pub fn operations_chronological(&self) -> Vec<&Operation> {
         let mut ops: Vec<_> = self.operations.values().collect();
-        ops.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        ops.sort_by_key(|a| a.timestamp);
         ops
     }
 
     /// Get operations in reverse chronological order
     pub fn operations_reverse_chronological(&self) -> Vec<&Operation> {
         let mut ops: Vec<_> = self.operations.values().collect();
-        ops.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        ops.sort_by_key(|op| std::cmp::Reverse(op.timestamp));
         ops
     }
 
@@ -260,7 +260,7 @@ mod tests {
                 target: PathBuf::from("/test"),
                 options: InstallOperationOptions::default(),
             },
-            parent: parent.map(|p| OperationId::from_string(p)),
+            parent: parent.map(OperationId::from_string),
             checkpoint_id: checkpoint.into(),
             timestamp: chrono::Utc::now(),
             description: None,
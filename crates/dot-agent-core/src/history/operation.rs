@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Unique identifier for an operation
@@ -81,6 +82,20 @@ pub enum OperationType {
         rule_name: String,
         source_profile: String,
         output_profile: String,
+        /// Parameter values the rule was applied with
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        params: BTreeMap<String, String>,
+    },
+    /// Apply a rule to installed files in a target
+    RuleApplyInstalled {
+        rule_name: String,
+        target: PathBuf,
+        /// Only files installed by this profile were considered
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        profile: Option<String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        params: BTreeMap<String, String>,
+        files_changed: Vec<String>,
     },
     /// User manual edit (auto-detected)
     UserEdit {
@@ -185,10 +200,29 @@ impl Operation {
                 rule_name,
                 source_profile,
                 output_profile,
+                params,
             } => {
                 format!(
-                    "rule-apply {} to {} -> {}",
-                    rule_name, source_profile, output_profile
+                    "rule-apply {}{} to {} -> {}",
+                    rule_name,
+                    format_params(params),
+                    source_profile,
+                    output_profile
+                )
+            }
+            OperationType::RuleApplyInstalled {
+                rule_name,
+                target,
+                params,
+                files_changed,
+                ..
+            } => {
+                format!(
+                    "rule-apply {}{} at {} ({} files)",
+                    rule_name,
+                    format_params(params),
+                    target.display(),
+                    files_changed.len()
                 )
             }
             OperationType::UserEdit {
@@ -220,6 +254,15 @@ impl Operation {
     }
 }
 
+/// ` [k=v, ...]` suffix for rule parameters (empty without any)
+fn format_params(params: &BTreeMap<String, String>) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<_> = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    format!(" [{}]", pairs.join(", "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -391,8 +391,7 @@ impl PackReader {
                 .pack
                 .checkpoints
                 .iter()
-                .filter(|c| c.meta.is_full)
-                .next_back()
+                .rfind(|c| c.meta.is_full)
                 .map(|c| c.id.clone()),
         };
 
@@ -0,0 +1,675 @@
+//! HTTP client layer
+//!
+//! Channels fetch catalogs and query git host APIs through the [`HttpClient`]
+//! trait instead of shelling out to `curl`/`gh`. [`HttpFetcher`] adds
+//! ETag/If-Modified-Since caching and 404 handling on top of any client, so
+//! tests can inject a stand-in implementation or point the native client at a
+//! local server. Host-specific authentication lives in [`crate::git_host`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+use crate::install::metadata::compute_hash;
+
+/// Default GitHub REST API endpoint
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// HTTP settings (`[http]` in config.toml)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    /// Proxy URL (e.g., "http://proxy.internal:3128"); falls back to
+    /// HTTP(S)_PROXY/ALL_PROXY environment variables when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// Request timeout in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Retries for transport errors, 429 and 5xx responses
+    ///
+    /// POSTs (e.g. LLM requests) may already have been processed, so they
+    /// are only retried on 429 or when the connection could not be opened.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+
+    /// GitHub token; falls back to GITHUB_TOKEN/GH_TOKEN when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_token: Option<String>,
+
+    /// GitHub REST API endpoint (change for GitHub Enterprise)
+    #[serde(default = "default_github_api_url")]
+    pub github_api_url: String,
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+fn default_github_api_url() -> String {
+    DEFAULT_GITHUB_API_URL.to_string()
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            timeout_secs: default_timeout_secs(),
+            retries: default_retries(),
+            github_token: None,
+            github_api_url: default_github_api_url(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// GitHub token from config, then GITHUB_TOKEN, then GH_TOKEN
+    pub fn resolve_github_token(&self) -> Option<String> {
+        self.github_token
+            .clone()
+            .filter(|t| !t.is_empty())
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()))
+            .or_else(|| std::env::var("GH_TOKEN").ok().filter(|t| !t.is_empty()))
+    }
+}
+
+// ============================================================================
+// Client trait
+// ============================================================================
+
+/// An HTTP request (the method is chosen by the [`HttpClient`] call)
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn post(url: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            body: Some(body.into()),
+        }
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// An HTTP response (any status; callers decide what is an error)
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Get a header value (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Transport used for all channel HTTP traffic
+pub trait HttpClient: Send + Sync {
+    /// Perform a GET request. Non-2xx statuses are returned, not errors.
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse>;
+
+    /// Perform a POST request with `request.body`. Non-2xx statuses are returned, not errors.
+    fn post(&self, request: &HttpRequest) -> Result<HttpResponse>;
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Method {
+    Get,
+    Post,
+}
+
+/// Native in-process client (ureq) with proxy, timeout and retry support
+pub struct NativeHttpClient {
+    agent: ureq::Agent,
+    retries: u32,
+}
+
+impl NativeHttpClient {
+    pub fn new(config: &HttpConfig) -> Result<Self> {
+        let proxy = match &config.proxy {
+            Some(proxy) => Some(
+                ureq::Proxy::new(proxy).map_err(|e| DotAgentError::HttpError {
+                    url: proxy.clone(),
+                    message: format!("invalid proxy: {}", e),
+                })?,
+            ),
+            None => None,
+        };
+        Ok(Self::build(config, proxy))
+    }
+
+    fn build(config: &HttpConfig, proxy: Option<ureq::Proxy>) -> Self {
+        let mut builder = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(config.timeout_secs)))
+            .user_agent(concat!("dot-agent/", env!("CARGO_PKG_VERSION")));
+
+        // Without an explicit proxy, ureq reads the *_PROXY environment variables
+        if proxy.is_some() {
+            builder = builder.proxy(proxy);
+        }
+
+        Self {
+            agent: builder.build().into(),
+            retries: config.retries,
+        }
+    }
+
+    fn send_once(
+        &self,
+        method: Method,
+        request: &HttpRequest,
+    ) -> std::result::Result<HttpResponse, ureq::Error> {
+        let mut response = match method {
+            Method::Post => {
+                let mut req = self.agent.post(&request.url);
+                for (name, value) in &request.headers {
+                    req = req.header(name.as_str(), value.as_str());
+                }
+                match &request.body {
+                    Some(body) => req.send(body.as_str())?,
+                    None => req.send_empty()?,
+                }
+            }
+            Method::Get => {
+                let mut req = self.agent.get(&request.url);
+                for (name, value) in &request.headers {
+                    req = req.header(name.as_str(), value.as_str());
+                }
+                req.call()?
+            }
+        };
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| {
+                v.to_str()
+                    .ok()
+                    .map(|v| (k.as_str().to_string(), v.to_string()))
+            })
+            .collect();
+        let body = response.body_mut().read_to_string()?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    /// Send with retries (see [`HttpConfig::retries`])
+    fn send(&self, method: Method, request: &HttpRequest) -> Result<HttpResponse> {
+        let mut attempt = 0;
+        loop {
+            let result = self.send_once(method, request);
+            let retryable = match (method, &result) {
+                (_, Ok(resp)) if resp.status == 429 => true,
+                (Method::Get, Ok(resp)) => resp.status >= 500,
+                (Method::Get, Err(_)) => true,
+                (Method::Post, Ok(_)) => false,
+                (Method::Post, Err(e)) => is_unsent(e),
+            };
+
+            if !retryable || attempt >= self.retries {
+                return result.map_err(|e| DotAgentError::HttpError {
+                    url: request.url.clone(),
+                    message: e.to_string(),
+                });
+            }
+
+            attempt += 1;
+            std::thread::sleep(Duration::from_millis(250 * u64::from(attempt)));
+        }
+    }
+}
+
+/// Whether the request failed before anything was sent to the server
+fn is_unsent(error: &ureq::Error) -> bool {
+    matches!(
+        error,
+        ureq::Error::HostNotFound
+            | ureq::Error::ConnectionFailed
+            | ureq::Error::ConnectProxyFailed(_)
+            | ureq::Error::Timeout(ureq::Timeout::Resolve | ureq::Timeout::Connect)
+    )
+}
+
+impl HttpClient for NativeHttpClient {
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        self.send(Method::Get, request)
+    }
+
+    fn post(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        self.send(Method::Post, request)
+    }
+}
+
+// ============================================================================
+// Fetcher (caching + GitHub API)
+// ============================================================================
+
+/// Cached response stored next to channel content
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// High-level fetcher used by channels
+#[derive(Clone)]
+pub struct HttpFetcher {
+    client: Arc<dyn HttpClient>,
+}
+
+impl HttpFetcher {
+    /// Create a fetcher with the native client
+    pub fn new(config: &HttpConfig) -> Result<Self> {
+        Ok(Self::with_client(Arc::new(NativeHttpClient::new(config)?)))
+    }
+
+    /// Create a fetcher with a custom client (e.g., a test stand-in)
+    pub fn with_client(client: Arc<dyn HttpClient>) -> Self {
+        Self { client }
+    }
+
+    /// Native client with default settings (no config file)
+    pub fn native_default() -> Self {
+        let config = HttpConfig::default();
+        Self::with_client(Arc::new(NativeHttpClient::build(&config, None)))
+    }
+
+    /// Fetch a URL as text. Returns `None` for 404.
+    ///
+    /// With a `cache_dir`, responses are stored under `<cache_dir>/http/` and
+    /// revalidated with If-None-Match / If-Modified-Since.
+    pub fn get_text(&self, url: &str, cache_dir: Option<&Path>) -> Result<Option<String>> {
+        self.get_cached(HttpRequest::get(url), cache_dir)
+    }
+
+    /// Like [`get_text`](Self::get_text) for a request with its own headers
+    /// (e.g., host authentication)
+    pub fn get_request_text(
+        &self,
+        request: HttpRequest,
+        cache_dir: Option<&Path>,
+    ) -> Result<Option<String>> {
+        self.get_cached(request, cache_dir)
+    }
+
+    /// Fetch a URL and parse the body as JSON (404 is an error)
+    ///
+    /// Used by [`crate::git_host::GitHost`] implementations, which add their
+    /// own authentication headers to `request`.
+    pub fn get_json(
+        &self,
+        request: HttpRequest,
+        cache_dir: Option<&Path>,
+    ) -> Result<serde_json::Value> {
+        let url = request.url.clone();
+        let body =
+            self.get_cached(request, cache_dir)?
+                .ok_or_else(|| DotAgentError::HttpError {
+                    url: url.clone(),
+                    message: "HTTP 404".to_string(),
+                })?;
+
+        serde_json::from_str(&body).map_err(|e| DotAgentError::HttpError {
+            url,
+            message: format!("invalid JSON response: {}", e),
+        })
+    }
+
+    fn get_cached(
+        &self,
+        mut request: HttpRequest,
+        cache_dir: Option<&Path>,
+    ) -> Result<Option<String>> {
+        let cache_path = cache_dir.map(|dir| Self::cache_path(dir, &request.url));
+        let cached = cache_path.as_deref().and_then(Self::read_cache);
+
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(modified) = &cached.last_modified {
+                request = request.header("If-Modified-Since", modified);
+            }
+        }
+
+        let response = self.client.get(&request)?;
+
+        if response.status == 304 {
+            if let Some(cached) = cached {
+                return Ok(Some(cached.body));
+            }
+        }
+        if response.status == 404 {
+            return Ok(None);
+        }
+        if !response.is_success() {
+            let message = if response.status == 401 || response.status == 403 {
+                format!(
+                    "HTTP {} (check the host token: http.github_token, GITHUB_TOKEN or [[hosts]] token)",
+                    response.status
+                )
+            } else {
+                format!("HTTP {}", response.status)
+            };
+            return Err(DotAgentError::HttpError {
+                url: request.url,
+                message,
+            });
+        }
+
+        if let Some(path) = &cache_path {
+            let entry = CachedResponse {
+                url: request.url.clone(),
+                etag: response.header("ETag").map(str::to_string),
+                last_modified: response.header("Last-Modified").map(str::to_string),
+                body: response.body.clone(),
+            };
+            if entry.etag.is_some() || entry.last_modified.is_some() {
+                Self::write_cache(path, &entry)?;
+            }
+        }
+
+        Ok(Some(response.body))
+    }
+
+    fn cache_path(cache_dir: &Path, url: &str) -> PathBuf {
+        let hash = compute_hash(url.as_bytes());
+        let key = hash.trim_start_matches("sha256:");
+        cache_dir.join("http").join(format!("{}.json", &key[..16]))
+    }
+
+    fn read_cache(path: &Path) -> Option<CachedResponse> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn write_cache(path: &Path, entry: &CachedResponse) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+}
+
+/// Percent-encode a query string component
+pub fn encode_query(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            b' ' => out.push('+'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    /// Minimal local HTTP server for tests
+    ///
+    /// `handler` receives (path, request headers) and returns (status, headers, body).
+    pub(crate) struct TestServer {
+        pub url: String,
+        pub hits: Arc<AtomicUsize>,
+        /// Request lines received ("GET /path HTTP/1.1")
+        pub requests: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    type Handler =
+        dyn Fn(&str, &[(String, String)]) -> (u16, Vec<(String, String)>, String) + Send + Sync;
+
+    impl TestServer {
+        pub(crate) fn start(handler: Arc<Handler>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let hits = Arc::new(AtomicUsize::new(0));
+            let counter = hits.clone();
+            let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let received = requests.clone();
+
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else { continue };
+                    counter.fetch_add(1, Ordering::SeqCst);
+
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    received
+                        .lock()
+                        .unwrap()
+                        .push(request_line.trim_end().to_string());
+                    let path = request_line
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or("/")
+                        .to_string();
+
+                    let mut headers = Vec::new();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        let line = line.trim_end();
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some((k, v)) = line.split_once(':') {
+                            headers.push((k.trim().to_string(), v.trim().to_string()));
+                        }
+                    }
+
+                    // Drain the request body so the client sees a clean response
+                    let length = headers
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case("Content-Length"))
+                        .and_then(|(_, v)| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+
+                    let (status, resp_headers, body) = handler(&path, &headers);
+                    let mut response = format!(
+                        "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n",
+                        status,
+                        body.len()
+                    );
+                    for (k, v) in resp_headers {
+                        response.push_str(&format!("{}: {}\r\n", k, v));
+                    }
+                    response.push_str("\r\n");
+                    response.push_str(&body);
+                    let _ = stream.write_all(response.as_bytes());
+                }
+            });
+
+            Self {
+                url,
+                hits,
+                requests,
+            }
+        }
+    }
+
+    fn fetcher() -> HttpFetcher {
+        let config = HttpConfig {
+            retries: 0,
+            ..Default::default()
+        };
+        HttpFetcher::new(&config).unwrap()
+    }
+
+    #[test]
+    fn get_text_handles_404() {
+        let server = TestServer::start(Arc::new(|path, _| {
+            if path == "/ok" {
+                (200, vec![], "hello".to_string())
+            } else {
+                (404, vec![], String::new())
+            }
+        }));
+        let fetcher = fetcher();
+
+        let ok = fetcher
+            .get_text(&format!("{}/ok", server.url), None)
+            .unwrap();
+        assert_eq!(ok.as_deref(), Some("hello"));
+
+        let missing = fetcher
+            .get_text(&format!("{}/missing", server.url), None)
+            .unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn etag_revalidation_uses_cache() {
+        let server = TestServer::start(Arc::new(|_, headers| {
+            let matches = headers
+                .iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case("if-none-match") && v == "\"v1\"");
+            if matches {
+                (304, vec![], String::new())
+            } else {
+                (
+                    200,
+                    vec![("ETag".to_string(), "\"v1\"".to_string())],
+                    "cached body".to_string(),
+                )
+            }
+        }));
+        let temp = TempDir::new().unwrap();
+        let fetcher = fetcher();
+        let url = format!("{}/README.md", server.url);
+
+        let first = fetcher.get_text(&url, Some(temp.path())).unwrap();
+        let second = fetcher.get_text(&url, Some(temp.path())).unwrap();
+
+        assert_eq!(first.as_deref(), Some("cached body"));
+        assert_eq!(second.as_deref(), Some("cached body"));
+        assert_eq!(server.hits.load(Ordering::SeqCst), 2);
+        assert!(temp.path().join("http").exists());
+    }
+
+    #[test]
+    fn get_json_parses_and_rejects_404() {
+        let server = TestServer::start(Arc::new(|path, _| {
+            if path == "/repos/a/b" {
+                (200, vec![], r#"{"ok": true}"#.to_string())
+            } else {
+                (404, vec![], String::new())
+            }
+        }));
+        let fetcher = fetcher();
+
+        let json = fetcher
+            .get_json(HttpRequest::get(format!("{}/repos/a/b", server.url)), None)
+            .unwrap();
+        assert_eq!(json["ok"], true);
+
+        let missing = fetcher.get_json(HttpRequest::get(format!("{}/nope", server.url)), None);
+        assert!(matches!(missing, Err(DotAgentError::HttpError { .. })));
+    }
+
+    #[test]
+    fn post_without_body_sends_post() {
+        let server = TestServer::start(Arc::new(|_, _| (200, vec![], "ok".to_string())));
+        let client = NativeHttpClient::new(&HttpConfig::default()).unwrap();
+
+        let request = HttpRequest::get(format!("{}/hook", server.url));
+        assert_eq!(client.post(&request).unwrap().body, "ok");
+        client.get(&request).unwrap();
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(*requests, vec!["POST /hook HTTP/1.1", "GET /hook HTTP/1.1"]);
+    }
+
+    #[test]
+    fn post_is_only_retried_when_not_processed() {
+        let server = TestServer::start(Arc::new(|path, _| match path {
+            "/busy" => (429, vec![], String::new()),
+            _ => (500, vec![], String::new()),
+        }));
+        let client = NativeHttpClient::new(&HttpConfig {
+            retries: 1,
+            ..Default::default()
+        })
+        .unwrap();
+
+        // A 5xx POST may have been billed already: sent once
+        let fail = HttpRequest::get(format!("{}/fail", server.url));
+        assert_eq!(client.post(&fail).unwrap().status, 500);
+        assert_eq!(server.hits.load(Ordering::SeqCst), 1);
+
+        // 429 means it was not processed: retried
+        let busy = HttpRequest::get(format!("{}/busy", server.url));
+        assert_eq!(client.post(&busy).unwrap().status, 429);
+        assert_eq!(server.hits.load(Ordering::SeqCst), 3);
+
+        // GETs are still retried on 5xx
+        assert_eq!(client.get(&fail).unwrap().status, 500);
+        assert_eq!(server.hits.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn encode_query_escapes() {
+        assert_eq!(encode_query("a b:c>=1"), "a+b%3Ac%3E%3D1");
+    }
+}
@@ -380,6 +380,29 @@ fn is_empty_container(value: &Value) -> bool {
     }
 }
 
+/// Remove all profile markers, e.g. from JSON merged into a profile rather than a target
+pub fn strip_profile_markers(content: &str) -> Result<String> {
+    let mut value: Value =
+        serde_json::from_str(content).map_err(|e| DotAgentError::JsonParseError {
+            message: e.to_string(),
+        })?;
+    strip_markers(&mut value);
+    serde_json::to_string_pretty(&value).map_err(|e| DotAgentError::JsonParseError {
+        message: e.to_string(),
+    })
+}
+
+fn strip_markers(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove(PROFILE_MARKER);
+            map.values_mut().for_each(strip_markers);
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(strip_markers),
+        _ => {}
+    }
+}
+
 /// Merge a JSON file from source profile into target
 pub fn merge_json_file(
     target_path: &Path,
@@ -6,6 +6,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use crate::category::CategoryFilter;
 use crate::error::Result;
 
 const META_FILENAME: &str = ".dot-agent-meta.toml";
@@ -17,6 +18,9 @@ pub struct Metadata {
     /// Tracks merged JSON entries per profile: profile_name -> file_path -> [json_paths]
     #[serde(default)]
     pub merged: HashMap<String, HashMap<String, Vec<String>>>,
+    /// Category selection per profile (profiles installed with --category/--exclude-category)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub categories: HashMap<String, CategoryFilter>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,6 +40,7 @@ impl Metadata {
             },
             files: HashMap::new(),
             merged: HashMap::new(),
+            categories: HashMap::new(),
         }
     }
 
@@ -98,6 +103,20 @@ impl Metadata {
     pub fn remove_merged(&mut self, profile: &str) {
         self.merged.remove(profile);
     }
+
+    /// Record the category selection for a profile (an empty filter clears it)
+    pub fn set_categories(&mut self, profile: &str, filter: &CategoryFilter) {
+        if filter.is_empty() {
+            self.categories.remove(profile);
+        } else {
+            self.categories.insert(profile.to_string(), filter.clone());
+        }
+    }
+
+    /// Category selection recorded for a profile
+    pub fn get_categories(&self, profile: &str) -> Option<&CategoryFilter> {
+        self.categories.get(profile)
+    }
 }
 
 pub fn compute_hash(content: &[u8]) -> String {
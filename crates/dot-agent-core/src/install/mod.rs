@@ -2,9 +2,12 @@ pub mod json_merge;
 pub mod metadata;
 pub mod snapshot;
 
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::category::{CategoryFilter, ClassificationMode};
+use crate::config::Config;
 use crate::error::{DotAgentError, Result};
 use crate::platform::Platform;
 use crate::profile::{IgnoreConfig, Profile};
@@ -58,12 +61,46 @@ const PREFIXED_DIRS: &[&str] = &["agents", "commands", "rules"];
 // Directories where subdirectories should be prefixed (skills has SKILL.md inside)
 const PREFIXED_SUBDIRS: &[&str] = &["skills"];
 
+/// Category selection to apply: the requested one, else the one recorded for the profile.
+/// Returns the selection and the profile files it allows (`None` = all files).
+/// LLM classification uses the backend configured in `base_dir`.
+fn category_selection(
+    base_dir: &Path,
+    profile: &Profile,
+    metadata: Option<&Metadata>,
+    requested: Option<&CategoryFilter>,
+) -> Result<(CategoryFilter, Option<HashSet<PathBuf>>)> {
+    let filter = requested
+        .or_else(|| metadata.and_then(|m| m.get_categories(&profile.name)))
+        .cloned()
+        .unwrap_or_default();
+    if filter.is_empty() {
+        return Ok((filter, None));
+    }
+    let selected = if filter.mode == ClassificationMode::Llm {
+        let backend = Config::load(base_dir).unwrap_or_default().llm_backend()?;
+        filter.select_with_backend(profile, backend)?
+    } else {
+        filter.select(profile)?
+    };
+    Ok((filter, Some(selected)))
+}
+
 /// Generate metadata key with profile prefix.
 /// Format: "{profile}:{relative_path}"
-fn make_meta_key(profile_name: &str, relative_path: &str) -> String {
+pub(crate) fn make_meta_key(profile_name: &str, relative_path: &str) -> String {
     format!("{}:{}", profile_name, relative_path)
 }
 
+/// Split a metadata key into its profile and relative path.
+/// Keys without a profile prefix (older metadata) return `None`.
+pub(crate) fn parse_meta_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once(':') {
+        Some((profile, relative_path)) => (Some(profile), relative_path),
+        None => (None, key),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FileStatus {
     Unchanged,
@@ -121,6 +158,8 @@ pub struct InstallOptions<'a> {
     pub platform: Option<Platform>,
     /// Strategy for resolving file conflicts (None = skip with CONFLICT report)
     pub conflict_resolver: Option<&'a dyn ConflictResolver>,
+    /// Category selection (None = keep the selection recorded in the target)
+    pub categories: Option<CategoryFilter>,
 }
 
 impl std::fmt::Debug for InstallOptions<'_> {
@@ -134,6 +173,7 @@ impl std::fmt::Debug for InstallOptions<'_> {
             .field("on_file", &self.on_file.is_some())
             .field("platform", &self.platform)
             .field("conflict_resolver", &self.conflict_resolver.is_some())
+            .field("categories", &self.categories)
             .finish()
     }
 }
@@ -195,6 +235,12 @@ impl<'a> InstallOptions<'a> {
         self
     }
 
+    /// Only install files in these categories (an empty filter clears a recorded selection)
+    pub fn categories(mut self, filter: CategoryFilter) -> Self {
+        self.categories = Some(filter);
+        self
+    }
+
     /// Check if a path should be included for the target platform
     pub fn should_include_path(&self, path: &Path) -> bool {
         match self.platform {
@@ -246,9 +292,22 @@ impl Installer {
             fs::create_dir_all(target)?;
         }
 
+        let (categories, selected) = category_selection(
+            &self.base_dir,
+            profile,
+            Some(&metadata),
+            opts.categories.as_ref(),
+        )?;
         let files = profile.list_files_with_config(&opts.ignore_config)?;
 
         for relative_path in files {
+            if selected
+                .as_ref()
+                .is_some_and(|s| !s.contains(&relative_path))
+            {
+                continue;
+            }
+
             // Platform filtering: skip files not supported by target platform
             if !opts.should_include_path(&relative_path) {
                 if let Some(f) = opts.on_file {
@@ -387,6 +446,7 @@ impl Installer {
 
         if !opts.dry_run && result.conflicts == 0 {
             metadata.add_profile(&profile.name);
+            metadata.set_categories(&profile.name, &categories);
             metadata.save(target)?;
         }
 
@@ -394,17 +454,41 @@ impl Installer {
     }
 
     /// Compare profile with installed files
+    ///
+    /// Only files in the category selection recorded for the profile are compared.
     pub fn diff(
         &self,
         profile: &Profile,
         target: &Path,
         ignore_config: &IgnoreConfig,
+    ) -> Result<DiffResult> {
+        self.diff_with_categories(profile, target, ignore_config, None)
+    }
+
+    /// Compare profile with installed files, limited to a category selection
+    ///
+    /// `categories` of `None` uses the selection recorded in the target.
+    pub fn diff_with_categories(
+        &self,
+        profile: &Profile,
+        target: &Path,
+        ignore_config: &IgnoreConfig,
+        categories: Option<&CategoryFilter>,
     ) -> Result<DiffResult> {
         let mut result = DiffResult::default();
 
+        let metadata = Metadata::load(target)?;
+        let (_, selected) =
+            category_selection(&self.base_dir, profile, metadata.as_ref(), categories)?;
+        let profile_files: Vec<PathBuf> = profile
+            .list_files_with_config(ignore_config)?
+            .into_iter()
+            .filter(|p| selected.as_ref().map_or(true, |s| s.contains(p)))
+            .collect();
+
         if !target.exists() {
             // All files are missing
-            for relative_path in profile.list_files_with_config(ignore_config)? {
+            for relative_path in profile_files {
                 let prefixed_path = prefix_path(&relative_path, &profile.name);
                 result.files.push(FileInfo {
                     relative_path: prefixed_path,
@@ -415,9 +499,6 @@ impl Installer {
             return Ok(result);
         }
 
-        let metadata = Metadata::load(target)?;
-        let profile_files = profile.list_files_with_config(ignore_config)?;
-
         // Build set of prefixed paths for comparison
         let prefixed_files: Vec<_> = profile_files
             .iter()
@@ -596,6 +677,7 @@ impl Installer {
         if !opts.dry_run {
             metadata.remove_profile(&profile.name);
             metadata.remove_merged(&profile.name);
+            metadata.set_categories(&profile.name, &CategoryFilter::default());
             if metadata.installed.profiles.is_empty()
                 && metadata.files.is_empty()
                 && metadata.merged.is_empty()
@@ -631,9 +713,21 @@ impl Installer {
         let mut skipped = 0;
         let mut unchanged = 0;
 
+        let (categories, selected) = category_selection(
+            &self.base_dir,
+            profile,
+            Some(&metadata),
+            opts.categories.as_ref(),
+        )?;
         let files = profile.list_files_with_config(&opts.ignore_config)?;
 
         for relative_path in files {
+            if selected
+                .as_ref()
+                .is_some_and(|s| !s.contains(&relative_path))
+            {
+                continue;
+            }
             let src = profile.path.join(&relative_path);
             let prefixed_path = if opts.no_prefix {
                 relative_path.clone()
@@ -709,6 +803,7 @@ impl Installer {
 
         if !opts.dry_run {
             metadata.add_profile(&profile.name);
+            metadata.set_categories(&profile.name, &categories);
             metadata.save(target)?;
         }
 
@@ -788,7 +883,7 @@ fn remove_empty_dirs(dir: &Path, root: &Path) -> std::io::Result<()> {
 ///   rules/testing.md → rules/{profile}-testing.md
 ///   commands/profile:cmd.md → commands/profile:cmd.md (already prefixed)
 ///   CLAUDE.md → CLAUDE.md (no change)
-fn prefix_path(relative_path: &Path, profile_name: &str) -> PathBuf {
+pub(crate) fn prefix_path(relative_path: &Path, profile_name: &str) -> PathBuf {
     let components: Vec<_> = relative_path.components().collect();
 
     if components.is_empty() {
@@ -1080,4 +1175,54 @@ mod tests {
         let content = fs::read(profile_dir.join("rules/my-rule.md")).unwrap();
         assert_eq!(content, b"original");
     }
+
+    #[test]
+    fn test_install_category_selection_is_recorded() {
+        let base = TempDir::new().unwrap();
+        let profile_dir = base.path().join("profile");
+        let target_dir = base.path().join("target");
+
+        write_file(&profile_dir, "commands/debug-build.md", b"debug");
+        write_file(&profile_dir, "commands/review-pr.md", b"review");
+        write_file(&profile_dir, "commands/plan-work.md", b"plan");
+
+        let installer = make_installer(base.path());
+        let profile = make_profile("prof", &profile_dir);
+        let filter = CategoryFilter::new(vec!["review".into(), "debug".into()], Vec::new());
+        let opts = InstallOptions::new().categories(filter.clone());
+        let result = installer.install(&profile, &target_dir, &opts).unwrap();
+
+        assert_eq!(result.installed, 2);
+        assert!(!target_dir.join("commands/prof-plan-work.md").exists());
+        let metadata = Metadata::load(&target_dir).unwrap().unwrap();
+        assert_eq!(metadata.get_categories("prof"), Some(&filter));
+
+        // Upgrade and diff keep the recorded selection
+        write_file(&profile_dir, "commands/fix-typo.md", b"debug too");
+        let (_, new, _, _) = installer
+            .upgrade(&profile, &target_dir, &InstallOptions::new())
+            .unwrap();
+        assert_eq!(new, 1);
+        assert!(!target_dir.join("commands/prof-plan-work.md").exists());
+
+        let diff = installer
+            .diff(&profile, &target_dir, &IgnoreConfig::with_defaults())
+            .unwrap();
+        assert_eq!(diff.missing, 0);
+        assert_eq!(diff.unchanged, 3);
+
+        // An empty selection clears it
+        let opts = InstallOptions::new().categories(CategoryFilter::default());
+        installer.upgrade(&profile, &target_dir, &opts).unwrap();
+        assert!(target_dir.join("commands/prof-plan-work.md").exists());
+        let metadata = Metadata::load(&target_dir).unwrap().unwrap();
+        assert!(metadata.get_categories("prof").is_none());
+
+        let opts =
+            InstallOptions::new().categories(CategoryFilter::new(vec!["nope".into()], Vec::new()));
+        assert!(matches!(
+            installer.install(&profile, &target_dir, &opts),
+            Err(DotAgentError::CategoryNotFound { .. })
+        ));
+    }
 }
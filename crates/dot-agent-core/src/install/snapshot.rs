@@ -45,6 +45,8 @@ pub enum SnapshotTrigger {
     PreUninstall,
     /// Automatically created before update operation
     PreUpdate,
+    /// Automatically created before a rule is applied to installed files
+    PreApply,
     /// Manually created by user
     Manual,
 }
@@ -55,6 +57,7 @@ impl SnapshotTrigger {
             Self::PreInstall => "pre-install",
             Self::PreUninstall => "pre-uninstall",
             Self::PreUpdate => "pre-update",
+            Self::PreApply => "pre-apply",
             Self::Manual => "manual",
         }
     }
@@ -495,9 +498,7 @@ impl<T: SnapshotTarget> GenericSnapshotManager<T> {
             return Ok(vec![]);
         }
 
-        manifest
-            .snapshots
-            .sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        manifest.snapshots.sort_by_key(|a| a.timestamp);
 
         let to_remove = manifest.snapshots.len() - keep;
         let removed: Vec<Snapshot> = manifest.snapshots.drain(..to_remove).collect();
@@ -1,44 +1,63 @@
+pub mod budget;
 pub mod category;
 pub mod channel;
 pub mod config;
 pub mod error;
+pub mod git_host;
 pub mod history;
+pub mod http;
 pub mod install;
 pub mod llm;
+pub mod path;
 pub mod platform;
 pub mod plugin;
 pub mod profile;
 pub mod rule;
 
+pub use budget::{estimate_tokens, BudgetConfig, BudgetReport, ContextLoad, FileEstimate};
 pub use channel::{
     Channel, ChannelManager, ChannelRef, ChannelRegistry, ChannelSource, ChannelType, Hub,
-    HubRegistry, ProfileRef, SearchOptions,
+    HubRegistry, ProfileRef, RefreshOutcome, SearchOptions, TrustDiff, TrustKind, TrustStore,
 };
 pub use config::Config;
 pub use error::{DotAgentError, Result};
+pub use git_host::{GitHost, GitHostConfig, GitHostKind, GitHostRegistry, HostedRepo};
+pub use http::{HttpClient, HttpConfig, HttpFetcher, HttpRequest, HttpResponse, NativeHttpClient};
 pub use install::{
     is_mergeable_json, merge_json, merge_json_file, unmerge_json, unmerge_json_file,
     ConflictResolver, DiffResult, FileInfo, FileStatus, InstallOptions, InstallResult, Installer,
     MergeRecord, MergeResult, Metadata, ProfileSnapshotManager, Resolution, Snapshot, SnapshotDiff,
     SnapshotManager, SnapshotTrigger, SyncBackResult, UnmergeResult,
 };
-pub use llm::{check_claude_cli, execute_claude, require_claude_cli, LlmConfig};
+pub use llm::{
+    check_claude_cli, execute_claude, require_claude_cli, AnthropicBackend, ClaudeCliBackend,
+    FixtureBackend, LlmBackend, LlmBackendKind, LlmConfig, OpenAiBackend,
+};
 pub use platform::{InstallTarget, Platform};
 pub use plugin::{
     FilterConfig, PluginManifest, PluginRegistrar, PluginRegistrationResult, DEFAULT_COMPONENT_DIRS,
 };
 pub use profile::{
-    migrate_existing_profiles, CollectedFile, FusionConfig, FusionConflict, FusionExecutor,
-    FusionPlan, FusionResult, FusionSpec, IgnoreConfig, PluginConfig, PluginScope, Profile,
-    ProfileIndexEntry, ProfileInfo, ProfileManager, ProfileMetadata, ProfileSource, ProfilesIndex,
-    DEFAULT_EXCLUDED_DIRS,
+    migrate_existing_profiles, CollectedFile, Component, ComponentFinder, ComponentKind,
+    ComponentMatch, ComponentOrigin, ComponentProvenance, FileProvenance, FileProvenanceStatus,
+    FusionChange, FusionChangeKind, FusionConfig, FusionConflict, FusionExecutor, FusionPlan,
+    FusionRecipe, FusionRefresh, FusionResolver, FusionResult, FusionSpec, FusionStrategy,
+    IgnoreConfig, MatchField, PluginConfig, PluginScope, Profile, ProfileIndexEntry, ProfileInfo,
+    ProfileManager, ProfileMetadata, ProfileSource, ProfilesIndex, DEFAULT_EXCLUDED_DIRS,
+};
+pub use rule::{
+    extract_rule, generate_rule, ApplyResult, EditPlan, FileChange, FileChangeKind, FileEdit,
+    HunkDecision, InstalledApplyResult, InstalledRuleExecutor, Pipeline, PipelineRule, PipelineRun,
+    PipelineRunner, PipelineStep, PipelineStore, ResponseMode, Rule, RuleExecutor, RuleManager,
+    RuleOp, RuleOps, RuleOrigin, RuleSource, RuleTestResult, RuleTestRunner, RuleUpdate,
+    SearchReplace, SectionPosition, StagedFile, StagedHunk, Staging,
 };
-pub use rule::{extract_rule, generate_rule, ApplyResult, Rule, RuleExecutor, RuleManager};
 
 // Category system
 pub use category::{
     BuiltinCategory, CategoriesConfig, CategoryClassifier, CategoryConfigEntry, CategoryDef,
-    CategoryStore, ClassificationMode, ClassifiedProfile, FileClassification, BUILTIN_CATEGORIES,
+    CategoryFilter, CategoryStore, ClassificationConfig, ClassificationMode, ClassifiedProfile,
+    FileClassification, BUILTIN_CATEGORIES,
 };
 
 // History system
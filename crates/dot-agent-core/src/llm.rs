@@ -1,6 +1,14 @@
 //! LLM Integration Module
 //!
-//! Claude CLIを使用したLLM操作の共通機能を提供する。
+//! LLM操作の共通機能を提供する。
+//!
+//! ルール適用・抽出・生成とLLM分類は [`LlmBackend`] トレイト経由でLLMを呼び出す。
+//! バックエンドは `config.toml` の `[llm]` で選択する：
+//!
+//! - `claude-cli`: `claude --print`（デフォルト）
+//! - `anthropic`: Anthropic Messages API
+//! - `openai`: OpenAI互換のChat Completions API（Ollama・llama.cppのサーバーも可）
+//! - `fixture`: 固定の応答を返す（テスト・CI用）
 //!
 //! ## 使用方法
 //!
@@ -22,6 +30,16 @@
 //! assert!(!config.enabled);
 //! ```
 //!
+//! ### 固定応答のバックエンド
+//!
+//! ```rust
+//! use dot_agent_core::{FixtureBackend, LlmBackend};
+//! use std::path::Path;
+//!
+//! let backend = FixtureBackend::new(vec!["# Rule".to_string()]);
+//! assert_eq!(backend.complete(Path::new("."), "prompt").unwrap(), "# Rule");
+//! ```
+//!
 //! ### 完全な使用例（外部依存あり）
 //!
 //! ```rust,ignore
@@ -35,24 +53,439 @@
 //! }
 //! ```
 
+use std::fmt;
+use std::fs;
 use std::io::Write as IoWrite;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
 use crate::error::{DotAgentError, Result};
+use crate::http::{HttpClient, HttpConfig, HttpRequest, NativeHttpClient};
+
+/// Anthropic APIのデフォルトエンドポイント
+pub const DEFAULT_ANTHROPIC_API_URL: &str = "https://api.anthropic.com";
+/// OpenAI互換APIのデフォルトエンドポイント
+pub const DEFAULT_OPENAI_API_URL: &str = "https://api.openai.com/v1";
+/// Anthropic Messages APIのバージョンヘッダー
+const ANTHROPIC_VERSION: &str = "2023-06-01";
 
 // ============================================================================
 // Configuration
 // ============================================================================
 
+/// LLMバックエンドの種類
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LlmBackendKind {
+    /// Claude CLI（`claude --print`）
+    #[default]
+    ClaudeCli,
+    /// Anthropic Messages API
+    Anthropic,
+    /// OpenAI互換のChat Completions API
+    Openai,
+    /// 固定応答（テスト用）
+    Fixture,
+}
+
+impl LlmBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ClaudeCli => "claude-cli",
+            Self::Anthropic => "anthropic",
+            Self::Openai => "openai",
+            Self::Fixture => "fixture",
+        }
+    }
+}
+
+impl fmt::Display for LlmBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LlmBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "claude-cli" | "claude" => Ok(Self::ClaudeCli),
+            "anthropic" => Ok(Self::Anthropic),
+            "openai" => Ok(Self::Openai),
+            "fixture" => Ok(Self::Fixture),
+            other => Err(format!(
+                "Unknown LLM backend '{}' (expected claude-cli, anthropic, openai or fixture)",
+                other
+            )),
+        }
+    }
+}
+
 /// LLM機能の設定
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
     /// LLM機能を有効にするか（デフォルト: false）
     #[serde(default)]
     pub enabled: bool,
+
+    /// 使用するバックエンド（デフォルト: claude-cli）
+    #[serde(default)]
+    pub backend: LlmBackendKind,
+
+    /// モデル名（anthropic・openaiで必須）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// APIエンドポイント（省略時はバックエンドごとのデフォルト）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+
+    /// APIキー（省略時は `api_key_env` または ANTHROPIC_API_KEY / OPENAI_API_KEY）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+
+    /// APIキーを読む環境変数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+
+    /// 応答の最大トークン数
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+
+    /// HTTPバックエンドのタイムアウト（秒）
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// fixtureバックエンドの応答ファイル（ディレクトリなら名前順に順番に返す）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixture: Option<PathBuf>,
+}
+
+fn default_max_tokens() -> u32 {
+    8192
+}
+
+fn default_timeout_secs() -> u64 {
+    300
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: LlmBackendKind::default(),
+            model: None,
+            api_url: None,
+            api_key: None,
+            api_key_env: None,
+            max_tokens: default_max_tokens(),
+            timeout_secs: default_timeout_secs(),
+            fixture: None,
+        }
+    }
+}
+
+impl LlmConfig {
+    /// APIキーを解決（設定値 → `api_key_env` → バックエンドの標準環境変数）
+    pub fn resolve_api_key(&self) -> Option<String> {
+        if let Some(key) = &self.api_key {
+            return Some(key.clone());
+        }
+        let var = match (&self.api_key_env, self.backend) {
+            (Some(var), _) => var.as_str(),
+            (None, LlmBackendKind::Anthropic) => "ANTHROPIC_API_KEY",
+            (None, LlmBackendKind::Openai) => "OPENAI_API_KEY",
+            (None, _) => return None,
+        };
+        std::env::var(var).ok().filter(|v| !v.is_empty())
+    }
+
+    /// 設定に従ってバックエンドを構築
+    pub fn backend(&self, http: &HttpConfig) -> Result<Arc<dyn LlmBackend>> {
+        Ok(match self.backend {
+            LlmBackendKind::ClaudeCli => Arc::new(ClaudeCliBackend),
+            LlmBackendKind::Anthropic | LlmBackendKind::Openai => {
+                let http = HttpConfig {
+                    timeout_secs: self.timeout_secs,
+                    ..http.clone()
+                };
+                let client: Arc<dyn HttpClient> = Arc::new(NativeHttpClient::new(&http)?);
+                if self.backend == LlmBackendKind::Anthropic {
+                    Arc::new(AnthropicBackend::new(client, self)?)
+                } else {
+                    Arc::new(OpenAiBackend::new(client, self)?)
+                }
+            }
+            LlmBackendKind::Fixture => {
+                let path =
+                    self.fixture
+                        .as_ref()
+                        .ok_or_else(|| DotAgentError::ConfigParseSimple {
+                            message: "llm.fixture is required for the fixture backend".to_string(),
+                        })?;
+                Arc::new(FixtureBackend::from_path(path)?)
+            }
+        })
+    }
+
+    fn require_model(&self) -> Result<String> {
+        self.model
+            .clone()
+            .ok_or_else(|| DotAgentError::ConfigParseSimple {
+                message: format!("llm.model is required for the {} backend", self.backend),
+            })
+    }
+}
+
+// ============================================================================
+// Backend trait
+// ============================================================================
+
+/// LLM呼び出しの抽象
+pub trait LlmBackend: Send + Sync {
+    /// バックエンド名（メッセージ表示用）
+    fn name(&self) -> &str;
+
+    /// 利用可能か確認（利用できなければエラー）
+    fn check(&self) -> Result<()>;
+
+    /// プロンプトを送信して応答テキストを返す
+    ///
+    /// `working_dir` はClaude CLIの作業ディレクトリ。HTTPバックエンドでは使わない。
+    fn complete(&self, working_dir: &Path, prompt: &str) -> Result<String>;
+}
+
+/// Claude CLIバックエンド
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClaudeCliBackend;
+
+impl LlmBackend for ClaudeCliBackend {
+    fn name(&self) -> &str {
+        "Claude CLI"
+    }
+
+    fn check(&self) -> Result<()> {
+        require_claude_cli()
+    }
+
+    fn complete(&self, working_dir: &Path, prompt: &str) -> Result<String> {
+        execute_claude(working_dir, prompt)
+    }
+}
+
+/// Anthropic Messages APIバックエンド
+pub struct AnthropicBackend {
+    client: Arc<dyn HttpClient>,
+    api_url: String,
+    model: String,
+    api_key: Option<String>,
+    max_tokens: u32,
+}
+
+impl AnthropicBackend {
+    pub fn new(client: Arc<dyn HttpClient>, config: &LlmConfig) -> Result<Self> {
+        Ok(Self {
+            client,
+            api_url: config
+                .api_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ANTHROPIC_API_URL.to_string()),
+            model: config.require_model()?,
+            api_key: config.resolve_api_key(),
+            max_tokens: config.max_tokens,
+        })
+    }
+}
+
+impl LlmBackend for AnthropicBackend {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.api_key.is_none() {
+            return Err(backend_error(
+                self,
+                "no API key (set llm.api_key, llm.api_key_env or ANTHROPIC_API_KEY)",
+            ));
+        }
+        Ok(())
+    }
+
+    fn complete(&self, _working_dir: &Path, prompt: &str) -> Result<String> {
+        self.check()?;
+        let body = json!({
+            "model": self.model,
+            "max_tokens": self.max_tokens,
+            "messages": [{ "role": "user", "content": prompt }],
+        });
+        let request = HttpRequest::post(
+            format!("{}/v1/messages", self.api_url.trim_end_matches('/')),
+            body.to_string(),
+        )
+        .header("content-type", "application/json")
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .header("x-api-key", self.api_key.clone().unwrap_or_default());
+
+        let response = post_json(self, self.client.as_ref(), &request)?;
+        let text: Vec<&str> = response["content"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect();
+        if text.is_empty() {
+            return Err(backend_error(self, "response contained no text"));
+        }
+        Ok(text.concat())
+    }
+}
+
+/// OpenAI互換Chat Completions APIバックエンド
+///
+/// `api_url` を変えればOllama（`http://localhost:11434/v1`）や
+/// llama.cppのサーバー、社内ゲートウェイにも使える。
+pub struct OpenAiBackend {
+    client: Arc<dyn HttpClient>,
+    api_url: String,
+    model: String,
+    api_key: Option<String>,
+    max_tokens: u32,
+}
+
+impl OpenAiBackend {
+    pub fn new(client: Arc<dyn HttpClient>, config: &LlmConfig) -> Result<Self> {
+        Ok(Self {
+            client,
+            api_url: config
+                .api_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_OPENAI_API_URL.to_string()),
+            model: config.require_model()?,
+            api_key: config.resolve_api_key(),
+            max_tokens: config.max_tokens,
+        })
+    }
+}
+
+impl LlmBackend for OpenAiBackend {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    /// ローカルサーバーはキー不要のため常に利用可能とみなす
+    fn check(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn complete(&self, _working_dir: &Path, prompt: &str) -> Result<String> {
+        let body = json!({
+            "model": self.model,
+            "max_tokens": self.max_tokens,
+            "messages": [{ "role": "user", "content": prompt }],
+        });
+        let mut request = HttpRequest::post(
+            format!("{}/chat/completions", self.api_url.trim_end_matches('/')),
+            body.to_string(),
+        )
+        .header("content-type", "application/json");
+        if let Some(key) = &self.api_key {
+            request = request.header("authorization", format!("Bearer {}", key));
+        }
+
+        let response = post_json(self, self.client.as_ref(), &request)?;
+        response["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| backend_error(self, "response contained no message content"))
+    }
+}
+
+/// 固定応答のバックエンド（テスト・CI用）
+///
+/// 応答を順番に返し、最後の応答以降は最後のものを繰り返す。
+pub struct FixtureBackend {
+    responses: Vec<String>,
+    next: AtomicUsize,
+}
+
+impl FixtureBackend {
+    pub fn new(responses: Vec<String>) -> Self {
+        Self {
+            responses,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// ファイル（単一の応答）またはディレクトリ（名前順の応答）から読み込む
+    pub fn from_path(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            let mut files: Vec<PathBuf> = fs::read_dir(path)?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect();
+            files.sort();
+            let responses = files
+                .iter()
+                .map(fs::read_to_string)
+                .collect::<std::io::Result<Vec<_>>>()?;
+            return Ok(Self::new(responses));
+        }
+        Ok(Self::new(vec![fs::read_to_string(path)?]))
+    }
+}
+
+impl LlmBackend for FixtureBackend {
+    fn name(&self) -> &str {
+        "fixture"
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.responses.is_empty() {
+            return Err(backend_error(self, "no fixture responses"));
+        }
+        Ok(())
+    }
+
+    fn complete(&self, _working_dir: &Path, _prompt: &str) -> Result<String> {
+        self.check()?;
+        let idx = self.next.fetch_add(1, Ordering::SeqCst);
+        Ok(self.responses[idx.min(self.responses.len() - 1)].clone())
+    }
+}
+
+fn backend_error(backend: &dyn LlmBackend, message: impl Into<String>) -> DotAgentError {
+    DotAgentError::LlmBackendFailed {
+        backend: backend.name().to_string(),
+        message: message.into(),
+    }
+}
+
+/// JSONをPOSTしてJSON応答を返す（2xx以外はエラー）
+fn post_json(
+    backend: &dyn LlmBackend,
+    client: &dyn HttpClient,
+    request: &HttpRequest,
+) -> Result<Value> {
+    let response = client.post(request)?;
+    if !response.is_success() {
+        let detail: String = response.body.chars().take(500).collect();
+        return Err(backend_error(
+            backend,
+            format!("HTTP {}: {}", response.status, detail.trim()),
+        ));
+    }
+    serde_json::from_str(&response.body)
+        .map_err(|e| backend_error(backend, format!("invalid JSON response: {}", e)))
 }
 
 // ============================================================================
@@ -138,6 +571,7 @@ pub fn require_claude_cli() -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::tests::TestServer;
 
     #[test]
     fn test_llm_config_default() {
@@ -159,5 +593,108 @@ mod tests {
         let toml_str = "";
         let config: LlmConfig = toml::from_str(toml_str).unwrap();
         assert!(!config.enabled);
+        assert_eq!(config.backend, LlmBackendKind::ClaudeCli);
+    }
+
+    #[test]
+    fn test_llm_config_backend_selection() {
+        let config: LlmConfig = toml::from_str(
+            r#"
+            backend = "openai"
+            api_url = "http://localhost:11434/v1"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.backend, LlmBackendKind::Openai);
+        // model is required for HTTP backends
+        assert!(config.backend(&HttpConfig::default()).is_err());
+
+        let config = LlmConfig {
+            model: Some("llama3".to_string()),
+            ..config
+        };
+        assert_eq!(
+            config.backend(&HttpConfig::default()).unwrap().name(),
+            "openai"
+        );
+        assert!("gemini".parse::<LlmBackendKind>().is_err());
+    }
+
+    #[test]
+    fn test_fixture_backend_sequence() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(temp.path().join("01.txt"), "first").unwrap();
+        fs::write(temp.path().join("02.txt"), "second").unwrap();
+
+        let backend = FixtureBackend::from_path(temp.path()).unwrap();
+        let dir = Path::new(".");
+        assert_eq!(backend.complete(dir, "a").unwrap(), "first");
+        assert_eq!(backend.complete(dir, "b").unwrap(), "second");
+        assert_eq!(backend.complete(dir, "c").unwrap(), "second");
+
+        assert!(FixtureBackend::new(Vec::new()).check().is_err());
+    }
+
+    fn http_config(url: &str, model: &str, key: Option<&str>) -> LlmConfig {
+        LlmConfig {
+            api_url: Some(url.to_string()),
+            model: Some(model.to_string()),
+            api_key: key.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn client() -> Arc<dyn HttpClient> {
+        let config = HttpConfig {
+            retries: 0,
+            ..Default::default()
+        };
+        Arc::new(NativeHttpClient::new(&config).unwrap())
+    }
+
+    #[test]
+    fn test_anthropic_backend() {
+        let server = TestServer::start(Arc::new(|path, headers| {
+            let authorized = headers
+                .iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case("x-api-key") && v == "sk-test");
+            if path != "/v1/messages" || !authorized {
+                return (401, vec![], r#"{"error":"unauthorized"}"#.to_string());
+            }
+            let body =
+                r#"{"content":[{"type":"text","text":"hello "},{"type":"text","text":"world"}]}"#;
+            (200, vec![], body.to_string())
+        }));
+
+        let backend =
+            AnthropicBackend::new(client(), &http_config(&server.url, "m", Some("sk-test")))
+                .unwrap();
+        assert_eq!(
+            backend.complete(Path::new("."), "hi").unwrap(),
+            "hello world"
+        );
+
+        let backend =
+            AnthropicBackend::new(client(), &http_config(&server.url, "m", Some("wrong"))).unwrap();
+        let err = backend.complete(Path::new("."), "hi").unwrap_err();
+        assert!(matches!(err, DotAgentError::LlmBackendFailed { .. }));
+        assert_eq!(err.exit_code(), 40);
+    }
+
+    #[test]
+    fn test_openai_backend() {
+        let server = TestServer::start(Arc::new(|path, headers| {
+            assert_eq!(path, "/v1/chat/completions");
+            assert!(!headers
+                .iter()
+                .any(|(k, _)| k.eq_ignore_ascii_case("authorization")));
+            let body = r#"{"choices":[{"message":{"role":"assistant","content":"ok"}}]}"#;
+            (200, vec![], body.to_string())
+        }));
+
+        let url = format!("{}/v1", server.url);
+        let backend = OpenAiBackend::new(client(), &http_config(&url, "llama3", None)).unwrap();
+        backend.check().unwrap();
+        assert_eq!(backend.complete(Path::new("."), "hi").unwrap(), "ok");
     }
 }
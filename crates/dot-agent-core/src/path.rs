@@ -0,0 +1,55 @@
+//! Relative path validation
+//!
+//! Paths that come from outside the profile being written (rule edit plans,
+//! channel and hub listings, component arguments) are joined onto a root
+//! directory. They must be relative and must not climb out of it.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{DotAgentError, Result};
+
+/// Check that `path` is relative and stays inside the directory it is
+/// joined onto
+///
+/// Returns the path with `.` components removed. Empty paths, absolute
+/// paths and `..` components are rejected with `InvalidComponentPath`.
+pub fn validate_relative_path(path: &Path) -> Result<PathBuf> {
+    let normalized: PathBuf = path
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+    if path.to_string_lossy().trim().is_empty()
+        || normalized.as_os_str().is_empty()
+        || !normalized
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(DotAgentError::InvalidComponentPath {
+            path: path.display().to_string(),
+        });
+    }
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_relative_path() {
+        assert_eq!(
+            validate_relative_path(Path::new("./skills/tdd")).unwrap(),
+            PathBuf::from("skills/tdd")
+        );
+        for bad in ["", " ", ".", "../x", "a/../../x", "/etc/passwd"] {
+            assert!(
+                matches!(
+                    validate_relative_path(Path::new(bad)),
+                    Err(DotAgentError::InvalidComponentPath { .. })
+                ),
+                "{:?} accepted",
+                bad
+            );
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Profile components (skills, commands, agents, rules)
+//!
+//! Components are discovered from the conventional layout:
+//!
+//! ```text
+//! skills/<name>/SKILL.md     # name/description from frontmatter
+//! commands/**/<name>.md      # name from file stem
+//! agents/<name>.md           # name/description from frontmatter
+//! rules/**/<name>.md         # name from file stem, description from first line
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::error::Result;
+
+/// Kind of profile component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentKind {
+    Skill,
+    Command,
+    Agent,
+    Rule,
+}
+
+impl ComponentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Skill => "skill",
+            Self::Command => "command",
+            Self::Agent => "agent",
+            Self::Rule => "rule",
+        }
+    }
+
+    /// All kinds, in display order
+    pub fn all() -> [Self; 4] {
+        [Self::Skill, Self::Command, Self::Agent, Self::Rule]
+    }
+}
+
+impl std::str::FromStr for ComponentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|k| k.as_str() == s.trim_end_matches('s'))
+            .ok_or_else(|| {
+                format!(
+                    "unknown component kind '{}' (skill, command, agent, rule)",
+                    s
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A skill, command, agent or rule in a profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    pub kind: ComponentKind,
+    pub name: String,
+    pub description: Option<String>,
+    /// Defining file, relative to the profile root
+    pub path: PathBuf,
+}
+
+/// Parse `key: value` pairs from a leading `---` frontmatter block
+///
+/// Only single-line scalar values are read; surrounding quotes are removed.
+pub fn parse_frontmatter(content: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) != Some("---") {
+        return fields;
+    }
+
+    for line in lines {
+        if line.trim() == "---" {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            if !value.is_empty() {
+                fields.insert(key.trim().to_string(), value.to_string());
+            }
+        }
+    }
+
+    fields
+}
+
+/// List the components of a profile directory
+pub fn scan_components(root: &Path) -> Result<Vec<Component>> {
+    let mut components = Vec::new();
+
+    let skills = root.join("skills");
+    if skills.is_dir() {
+        for entry in WalkDir::new(&skills).min_depth(2).max_depth(3) {
+            let entry = entry.map_err(std::io::Error::from)?;
+            if entry.file_name() != "SKILL.md" {
+                continue;
+            }
+            let dir_name = entry
+                .path()
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            components.push(from_markdown(
+                root,
+                entry.path(),
+                ComponentKind::Skill,
+                dir_name,
+            )?);
+        }
+    }
+
+    for (dir, kind) in [
+        ("commands", ComponentKind::Command),
+        ("agents", ComponentKind::Agent),
+        ("rules", ComponentKind::Rule),
+    ] {
+        let dir = root.join(dir);
+        if !dir.is_dir() {
+            continue;
+        }
+        for entry in WalkDir::new(&dir) {
+            let entry = entry.map_err(std::io::Error::from)?;
+            let path = entry.path();
+            if !entry.file_type().is_file()
+                || path.extension().and_then(|e| e.to_str()) != Some("md")
+            {
+                continue;
+            }
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            components.push(from_markdown(root, path, kind, stem)?);
+        }
+    }
+
+    components.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(components)
+}
+
+/// Component defined by a single file, if its path follows the layout
+///
+/// Used for installed files, which are tracked individually rather than as
+/// a profile tree.
+pub(crate) fn component_at(root: &Path, relative: &Path) -> Result<Option<Component>> {
+    if relative.extension().and_then(|e| e.to_str()) != Some("md") {
+        return Ok(None);
+    }
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let stem = || {
+        relative
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    let (kind, default_name) = match parts.first().map(String::as_str) {
+        Some("skills")
+            if (3..=4).contains(&parts.len())
+                && parts.last().map(String::as_str) == Some("SKILL.md") =>
+        {
+            (ComponentKind::Skill, parts[parts.len() - 2].clone())
+        }
+        Some("commands") if parts.len() >= 2 => (ComponentKind::Command, stem()),
+        Some("agents") if parts.len() >= 2 => (ComponentKind::Agent, stem()),
+        Some("rules") if parts.len() >= 2 => (ComponentKind::Rule, stem()),
+        _ => return Ok(None),
+    };
+
+    from_markdown(root, &root.join(relative), kind, default_name).map(Some)
+}
+
+fn from_markdown(
+    root: &Path,
+    path: &Path,
+    kind: ComponentKind,
+    default_name: String,
+) -> Result<Component> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let mut fields = parse_frontmatter(&content);
+
+    // Commands and rules are referenced by file name; skills and agents may rename themselves
+    let name = match kind {
+        ComponentKind::Command | ComponentKind::Rule => default_name,
+        _ => fields.remove("name").unwrap_or(default_name),
+    };
+
+    let description = fields.remove("description").or_else(|| {
+        (kind == ComponentKind::Rule)
+            .then(|| first_line(&content))
+            .flatten()
+    });
+
+    Ok(Component {
+        kind,
+        name,
+        description,
+        path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+    })
+}
+
+/// First non-empty line after any frontmatter, without heading markers
+fn first_line(content: &str) -> Option<String> {
+    let body = content
+        .strip_prefix("---")
+        .and_then(|rest| rest.split_once("\n---").map(|(_, body)| body))
+        .unwrap_or(content);
+    body.lines()
+        .map(|l| l.trim().trim_start_matches('#').trim())
+        .find(|l| !l.is_empty() && *l != "---")
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn frontmatter_fields() {
+        let fields = parse_frontmatter(
+            "---\nname: tdd\ndescription: \"Test first\"\ntools:\n  - Bash\n---\n# Body\nkey: ignored\n",
+        );
+        assert_eq!(fields["name"], "tdd");
+        assert_eq!(fields["description"], "Test first");
+        assert!(!fields.contains_key("tools"));
+        assert!(!fields.contains_key("key"));
+        assert!(parse_frontmatter("# No frontmatter\nname: x\n").is_empty());
+    }
+
+    #[test]
+    fn scans_skills_commands_agents() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("skills/tdd")).unwrap();
+        fs::create_dir_all(root.join("commands/git")).unwrap();
+        fs::create_dir_all(root.join("agents")).unwrap();
+        fs::write(
+            root.join("skills/tdd/SKILL.md"),
+            "---\nname: test-driven\ndescription: Red green refactor\n---\n",
+        )
+        .unwrap();
+        fs::write(root.join("commands/git/pr-review.md"), "Review the PR").unwrap();
+        fs::write(
+            root.join("agents/planner.md"),
+            "---\ndescription: Plans work\n---\n",
+        )
+        .unwrap();
+
+        fs::create_dir_all(root.join("rules")).unwrap();
+        fs::write(
+            root.join("rules/style.md"),
+            "# Prefer small functions\n\nKeep functions short.\n",
+        )
+        .unwrap();
+
+        let components = scan_components(root).unwrap();
+        assert_eq!(components.len(), 4);
+
+        let agent = &components[0];
+        assert_eq!(agent.kind, ComponentKind::Agent);
+        assert_eq!(agent.name, "planner");
+        assert_eq!(agent.description.as_deref(), Some("Plans work"));
+
+        let command = &components[1];
+        assert_eq!(command.name, "pr-review");
+        assert_eq!(command.path, PathBuf::from("commands/git/pr-review.md"));
+
+        let rule = &components[2];
+        assert_eq!(rule.kind, ComponentKind::Rule);
+        assert_eq!(rule.description.as_deref(), Some("Prefer small functions"));
+
+        let skill = &components[3];
+        assert_eq!(skill.name, "test-driven");
+        assert_eq!("skills".parse::<ComponentKind>(), Ok(ComponentKind::Skill));
+    }
+}
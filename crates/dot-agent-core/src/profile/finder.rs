@@ -0,0 +1,361 @@
+//! Component-level search across profiles
+//!
+//! Finds skills, commands, agents and rules by name, description or (for
+//! rules) body text in local profiles and in every known install target.
+//! Each match reports the owning profile, its categories and the targets
+//! it is installed to.
+//!
+//! Targets are the global `~/.claude`, every target recorded in the
+//! operation history, and any extra paths passed in.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::category::{CategoryClassifier, ClassificationMode};
+use crate::error::Result;
+use crate::history::{HistoryManager, OperationType};
+use crate::install::metadata::Metadata;
+use crate::install::{make_meta_key, parse_meta_key, prefix_path};
+
+use super::component::component_at;
+use super::{Component, ComponentKind, Profile, ProfileManager};
+
+/// Which part of a component matched the query (best first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchField {
+    Name,
+    Description,
+    Content,
+}
+
+impl MatchField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Description => "description",
+            Self::Content => "content",
+        }
+    }
+}
+
+/// A component matching a find query
+#[derive(Debug, Clone)]
+pub struct ComponentMatch {
+    /// Owning profile
+    pub profile: String,
+    pub component: Component,
+    pub matched: MatchField,
+    /// Categories of the component file
+    pub categories: Vec<String>,
+    /// Targets the component is installed to
+    pub targets: Vec<PathBuf>,
+    /// Whether the profile exists locally (false = only found in a target)
+    pub local: bool,
+}
+
+/// Searches components of local and installed profiles
+pub struct ComponentFinder {
+    base_dir: PathBuf,
+    extra_targets: Vec<PathBuf>,
+    global: bool,
+    kind: Option<ComponentKind>,
+    profile: Option<String>,
+}
+
+impl ComponentFinder {
+    pub fn new(base_dir: PathBuf) -> Self {
+        Self {
+            base_dir,
+            extra_targets: Vec::new(),
+            global: true,
+            kind: None,
+            profile: None,
+        }
+    }
+
+    /// Also look in these targets
+    pub fn with_targets(mut self, targets: Vec<PathBuf>) -> Self {
+        self.extra_targets = targets;
+        self
+    }
+
+    /// Whether to look in the global `~/.claude` (default: true)
+    pub fn with_global(mut self, global: bool) -> Self {
+        self.global = global;
+        self
+    }
+
+    /// Only return components of this kind
+    pub fn with_kind(mut self, kind: ComponentKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Only search this profile
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Install targets that exist and carry install metadata
+    pub fn known_targets(&self) -> Vec<PathBuf> {
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        if let Some(home) = dirs::home_dir().filter(|_| self.global) {
+            candidates.push(home.join(".claude"));
+        }
+        if let Ok(history) = HistoryManager::new(self.base_dir.clone()) {
+            for op in history.graph().operations_chronological() {
+                match &op.operation_type {
+                    OperationType::Install { target, .. }
+                    | OperationType::Upgrade { target, .. } => candidates.push(target.clone()),
+                    _ => {}
+                }
+            }
+        }
+        candidates.extend(self.extra_targets.iter().cloned());
+
+        let mut seen = BTreeSet::new();
+        candidates
+            .into_iter()
+            .filter(|t| seen.insert(t.clone()))
+            .filter(|t| Metadata::load(t).ok().flatten().is_some())
+            .collect()
+    }
+
+    /// Find components whose name, description or rule text contains `query`
+    ///
+    /// Matching is case-insensitive. Name matches come first, exact names
+    /// before partial ones.
+    pub fn find(&self, query: &str) -> Result<Vec<ComponentMatch>> {
+        let needle = query.trim().to_lowercase();
+        let targets: Vec<(PathBuf, Metadata)> = self
+            .known_targets()
+            .into_iter()
+            .filter_map(|t| Metadata::load(&t).ok().flatten().map(|m| (t, m)))
+            .collect();
+
+        let manager = ProfileManager::new(self.base_dir.clone());
+        let profiles: Vec<Profile> = manager
+            .list_profiles()?
+            .into_iter()
+            .filter(|p| self.profile.as_ref().map_or(true, |name| &p.name == name))
+            .collect();
+
+        let mut matches = Vec::new();
+        for profile in &profiles {
+            let classifier = CategoryClassifier::from_profile(profile, ClassificationMode::Glob)?;
+            for component in profile.components()? {
+                let Some(matched) = self.match_component(&component, &profile.path, &needle) else {
+                    continue;
+                };
+                let installed_at = targets
+                    .iter()
+                    .filter(|(_, meta)| is_installed(meta, &profile.name, &component.path))
+                    .map(|(t, _)| t.clone())
+                    .collect();
+                matches.push(ComponentMatch {
+                    profile: profile.name.clone(),
+                    categories: categories(&classifier, &component.path)?,
+                    component,
+                    matched,
+                    targets: installed_at,
+                    local: true,
+                });
+            }
+        }
+
+        let local: BTreeSet<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        matches.extend(self.find_installed_only(&needle, &targets, &local)?);
+
+        matches.sort_by(|a, b| {
+            let exact = |m: &ComponentMatch| m.component.name.to_lowercase() != needle;
+            (a.matched, exact(a), &a.profile, &a.component.path).cmp(&(
+                b.matched,
+                exact(b),
+                &b.profile,
+                &b.component.path,
+            ))
+        });
+        Ok(matches)
+    }
+
+    /// Components of profiles that are installed but no longer exist locally
+    fn find_installed_only(
+        &self,
+        needle: &str,
+        targets: &[(PathBuf, Metadata)],
+        local: &BTreeSet<&str>,
+    ) -> Result<Vec<ComponentMatch>> {
+        let classifier = CategoryClassifier::builtin(ClassificationMode::Glob)?;
+        let mut found: HashMap<(String, PathBuf), ComponentMatch> = HashMap::new();
+
+        for (target, meta) in targets {
+            for key in meta.files.keys() {
+                let (Some(profile), path) = parse_meta_key(key) else {
+                    continue;
+                };
+                if local.contains(profile)
+                    || self.profile.as_ref().is_some_and(|name| name != profile)
+                {
+                    continue;
+                }
+                let relative = Path::new(path);
+                let Some(mut component) = component_at(target, relative)? else {
+                    continue;
+                };
+                // Installed names carry the profile prefix
+                let prefix = format!("{}-", profile);
+                if let Some(name) = component.name.strip_prefix(&prefix) {
+                    component.name = name.to_string();
+                }
+                let Some(matched) = self.match_component(&component, target, needle) else {
+                    continue;
+                };
+
+                let entry = found
+                    .entry((profile.to_string(), component.path.clone()))
+                    .or_insert_with(|| ComponentMatch {
+                        profile: profile.to_string(),
+                        categories: Vec::new(),
+                        component: component.clone(),
+                        matched,
+                        targets: Vec::new(),
+                        local: false,
+                    });
+                if entry.categories.is_empty() {
+                    entry.categories = categories(&classifier, &component.path)?;
+                }
+                entry.targets.push(target.clone());
+            }
+        }
+
+        Ok(found.into_values().collect())
+    }
+
+    fn match_component(
+        &self,
+        component: &Component,
+        root: &Path,
+        needle: &str,
+    ) -> Option<MatchField> {
+        if self.kind.is_some_and(|k| k != component.kind) {
+            return None;
+        }
+        if needle.is_empty() || component.name.to_lowercase().contains(needle) {
+            return Some(MatchField::Name);
+        }
+        if component
+            .description
+            .as_ref()
+            .is_some_and(|d| d.to_lowercase().contains(needle))
+        {
+            return Some(MatchField::Description);
+        }
+        if component.kind == ComponentKind::Rule
+            && fs::read_to_string(root.join(&component.path))
+                .is_ok_and(|text| text.to_lowercase().contains(needle))
+        {
+            return Some(MatchField::Content);
+        }
+        None
+    }
+}
+
+/// Whether a profile file is recorded in a target's metadata
+fn is_installed(meta: &Metadata, profile: &str, relative: &Path) -> bool {
+    if !meta.installed.profiles.iter().any(|p| p == profile) {
+        return false;
+    }
+    // Installed with or without the profile prefix
+    [prefix_path(relative, profile), relative.to_path_buf()]
+        .iter()
+        .any(|p| {
+            meta.files
+                .contains_key(&make_meta_key(profile, &p.to_string_lossy()))
+        })
+}
+
+fn categories(classifier: &CategoryClassifier, path: &Path) -> Result<Vec<String>> {
+    Ok(classifier
+        .classify_by_glob(&[path.to_path_buf()])?
+        .into_iter()
+        .next()
+        .map(|c| c.categories)
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::install::metadata::compute_hash;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().join("base");
+        let profile = base.join("profiles/git");
+        fs::create_dir_all(profile.join("commands")).unwrap();
+        fs::create_dir_all(profile.join("rules")).unwrap();
+        fs::write(profile.join("commands/pr-review.md"), "Review a PR").unwrap();
+        fs::write(
+            profile.join("rules/commits.md"),
+            "# Commit style\n\nMention the pr-review checklist.\n",
+        )
+        .unwrap();
+
+        let target = temp.path().join("project/.claude");
+        fs::create_dir_all(&target).unwrap();
+        let mut meta = Metadata::new(&base);
+        meta.add_profile("git");
+        meta.add_file(
+            "git:commands/git-pr-review.md",
+            &compute_hash(b"Review a PR"),
+        );
+        meta.add_profile("old");
+        fs::create_dir_all(target.join("agents")).unwrap();
+        fs::write(
+            target.join("agents/old-pr-bot.md"),
+            "---\ndescription: Comments on PRs\n---\n",
+        )
+        .unwrap();
+        meta.add_file("old:agents/old-pr-bot.md", "hash");
+        meta.save(&target).unwrap();
+
+        (temp, target)
+    }
+
+    #[test]
+    fn finds_local_components_with_targets() {
+        let (temp, target) = setup();
+        let finder = ComponentFinder::new(temp.path().join("base"))
+            .with_global(false)
+            .with_targets(vec![target.clone()]);
+
+        let matches = finder.find("PR-REVIEW").unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].component.kind, ComponentKind::Command);
+        assert_eq!(matches[0].matched, MatchField::Name);
+        assert_eq!(matches[0].targets, vec![target]);
+        assert_eq!(matches[1].component.kind, ComponentKind::Rule);
+        assert_eq!(matches[1].matched, MatchField::Content);
+        assert!(matches[1].targets.is_empty());
+    }
+
+    #[test]
+    fn finds_installed_only_profiles_and_filters() {
+        let (temp, target) = setup();
+        let finder = ComponentFinder::new(temp.path().join("base"))
+            .with_global(false)
+            .with_targets(vec![target]);
+
+        let matches = finder.find("pr-bot").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].profile, "old");
+        assert_eq!(matches[0].component.name, "pr-bot");
+        assert!(!matches[0].local);
+
+        let finder = finder.with_kind(ComponentKind::Agent).with_profile("git");
+        assert!(finder.find("pr").unwrap().is_empty());
+    }
+}
@@ -2,14 +2,27 @@
 //!
 //! 複数Profileからカテゴリ単位で合成し、新Profileを作成する。
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+use crate::budget::{BudgetReport, FileEstimate};
 use crate::category::{CategoryClassifier, ClassificationMode};
+use crate::channel::DiffLine;
 use crate::error::{DotAgentError, Result};
+use crate::install::json_merge::{is_mergeable_json, merge_json, strip_profile_markers};
+use crate::install::metadata::compute_hash;
+use crate::install::prefix_path;
+use crate::llm::LlmBackend;
 
-use super::{ProfileManager, ProfileMetadata};
+use super::provenance::provenance_for_copy;
+use super::{
+    ComponentOrigin, FileProvenance, Profile, ProfileManager, ProfileMetadata, ProfileSource,
+};
 
 /// Fusion指定（Profile:Category のペア）
 #[derive(Debug, Clone)]
@@ -36,6 +49,183 @@ impl FusionSpec {
     }
 }
 
+impl std::fmt::Display for FusionSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.profile_name, self.category)
+    }
+}
+
+/// Fusionレシピ
+///
+/// 出力Profileの `.dot-agent.toml` に `[fusion]` として保存され、
+/// `fusion refresh` で同じ入力から再計画するために使う。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FusionRecipe {
+    /// 入力指定（"profile:category" 形式、指定順）
+    pub specs: Vec<String>,
+    /// 分類モード
+    #[serde(default)]
+    pub mode: ClassificationMode,
+    /// 最初のProfileから未分類ファイルを含めるか
+    #[serde(default)]
+    pub include_uncategorized: bool,
+    /// コンフリクト解決戦略
+    #[serde(default)]
+    pub strategy: FusionStrategy,
+    /// Interactive時にコンフリクトごとに選ばれた戦略（パス → 戦略）
+    ///
+    /// refresh時はリゾルバに再度問い合わせず、この選択を再適用する。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resolutions: BTreeMap<String, FusionStrategy>,
+    /// 作成日時
+    pub created_at: String,
+    /// 最終refresh日時
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refreshed_at: Option<String>,
+}
+
+/// refresh時のファイル変更種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FusionChangeKind {
+    /// 入力に新しく現れたファイル
+    Added,
+    /// 入力側で更新されたファイル
+    Updated,
+    /// 入力から無くなったファイル
+    Removed,
+    /// 入力側に変更があるが、出力側が手動編集されているため保持
+    KeptLocal,
+}
+
+/// refreshで検出された1ファイルの変更
+#[derive(Debug, Clone)]
+pub struct FusionChange {
+    /// 出力Profile内での相対パス
+    pub path: String,
+    pub kind: FusionChangeKind,
+    /// 提供元Profile（Removedの場合は以前の提供元）
+    pub source_profile: String,
+    /// 出力側の現在の内容から入力側の内容への差分（テキストのみ）
+    pub diff: Vec<DiffLine>,
+}
+
+/// refresh結果
+#[derive(Debug, Clone)]
+pub struct FusionRefresh {
+    /// 出力Profile名
+    pub profile_name: String,
+    /// 検出された変更
+    pub changes: Vec<FusionChange>,
+    /// 変更のなかったファイル数
+    pub unchanged: usize,
+    /// ドライラン（変更は適用されていない）
+    pub dry_run: bool,
+}
+
+impl FusionRefresh {
+    /// 適用された（ドライランなら適用される）変更の数
+    pub fn applied(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|c| c.kind != FusionChangeKind::KeptLocal)
+            .count()
+    }
+}
+
+/// コンフリクト解決戦略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FusionStrategy {
+    /// 最初の入力を採用
+    FirstWins,
+    /// 最後の入力を採用（従来の挙動）
+    #[default]
+    LastWins,
+    /// すべて採用し、元Profile名をプレフィックスに付けて別名で保存
+    RenameBoth,
+    /// Markdownを連結（各入力をセクションマーカーで囲む）
+    Concat,
+    /// hooks/mcp/settings のJSONを深くマージ
+    MergeJson,
+    /// コンフリクトごとに `FusionResolver` で選択
+    Interactive,
+}
+
+impl FusionStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FirstWins => "first-wins",
+            Self::LastWins => "last-wins",
+            Self::RenameBoth => "rename-both",
+            Self::Concat => "concat",
+            Self::MergeJson => "merge-json",
+            Self::Interactive => "interactive",
+        }
+    }
+
+    /// 全戦略
+    pub fn all() -> &'static [FusionStrategy] {
+        &[
+            Self::FirstWins,
+            Self::LastWins,
+            Self::RenameBoth,
+            Self::Concat,
+            Self::MergeJson,
+            Self::Interactive,
+        ]
+    }
+
+    /// このパスのコンフリクトに適用できるか
+    ///
+    /// `Concat` はMarkdownのみ、`MergeJson` はマージ可能なJSONのみ。
+    pub fn applies_to(&self, path: &Path) -> bool {
+        match self {
+            Self::Concat => path.extension().is_some_and(|e| e == "md"),
+            Self::MergeJson => is_mergeable_json(path),
+            Self::Interactive => false,
+            _ => true,
+        }
+    }
+}
+
+impl std::fmt::Display for FusionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for FusionStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .find(|strategy| strategy.as_str() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::all().iter().map(|s| s.as_str()).collect();
+                format!(
+                    "Unknown fusion strategy '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// コンフリクトを対話的に解決するためのコールバック
+///
+/// `FusionStrategy::Interactive` のとき、レシピに選択が記録されていない
+/// コンフリクトごとに呼ばれる。適用できない戦略を返した場合は後勝ちになる。
+pub trait FusionResolver {
+    /// `candidates` は入力順の競合ファイル
+    fn resolve(
+        &self,
+        conflict: &FusionConflict,
+        candidates: &[CollectedFile],
+    ) -> Result<FusionStrategy>;
+}
+
 /// Fusionコンフリクト
 #[derive(Debug, Clone)]
 pub struct FusionConflict {
@@ -43,6 +233,10 @@ pub struct FusionConflict {
     pub path: PathBuf,
     /// 競合元のProfile:Category ペア
     pub sources: Vec<FusionSpec>,
+    /// 適用された戦略
+    pub strategy: FusionStrategy,
+    /// 解決後に書き込まれる出力パス
+    pub outputs: Vec<String>,
 }
 
 /// Fusion結果
@@ -52,6 +246,8 @@ pub struct FusionResult {
     pub profile_name: String,
     /// コピーされたファイル数
     pub files_copied: usize,
+    /// 設定されたコンフリクト解決戦略（実際に適用された戦略は各コンフリクトに記録）
+    pub strategy: FusionStrategy,
     /// 検出されたコンフリクト
     pub conflicts: Vec<FusionConflict>,
     /// 各Profileからの貢献
@@ -69,6 +265,8 @@ pub struct FusionConfig {
     pub include_uncategorized: bool,
     /// ドライラン（ファイルコピーしない）
     pub dry_run: bool,
+    /// コンフリクト解決戦略
+    pub strategy: FusionStrategy,
 }
 
 impl Default for FusionConfig {
@@ -78,6 +276,7 @@ impl Default for FusionConfig {
             force: false,
             include_uncategorized: false,
             dry_run: false,
+            strategy: FusionStrategy::default(),
         }
     }
 }
@@ -91,6 +290,18 @@ pub struct CollectedFile {
     pub dest_path: String,
     /// 元Profile名
     pub source_profile: String,
+    /// 合成された内容（コンフリクトを連結・マージした場合。src_pathの代わりに書き込む）
+    pub content: Option<String>,
+}
+
+impl CollectedFile {
+    /// 出力に書き込む内容
+    pub fn read(&self) -> Result<Vec<u8>> {
+        match &self.content {
+            Some(content) => Ok(content.clone().into_bytes()),
+            None => Ok(fs::read(&self.src_path)?),
+        }
+    }
 }
 
 /// Fusion実行結果（ドライラン用）
@@ -102,6 +313,8 @@ pub struct FusionPlan {
     pub conflicts: Vec<FusionConflict>,
     /// 各Profileからの貢献数
     pub contributions: HashMap<String, usize>,
+    /// 出力Profileのトークン見積もり
+    pub budget: BudgetReport,
 }
 
 /// Fusion実行器
@@ -110,12 +323,83 @@ pub struct FusionPlan {
 pub struct FusionExecutor {
     specs: Vec<FusionSpec>,
     config: FusionConfig,
+    resolver: Option<Box<dyn FusionResolver>>,
+    resolutions: BTreeMap<String, FusionStrategy>,
+    backend: Option<Arc<dyn LlmBackend>>,
 }
 
 impl FusionExecutor {
     /// 新規実行器を作成
     pub fn new(specs: Vec<FusionSpec>, config: FusionConfig) -> Self {
-        Self { specs, config }
+        Self {
+            specs,
+            config,
+            resolver: None,
+            resolutions: BTreeMap::new(),
+            backend: None,
+        }
+    }
+
+    /// `FusionStrategy::Interactive` で使うリゾルバを設定
+    ///
+    /// 未設定の場合、選択が記録されていないコンフリクトは
+    /// `DotAgentError::FusionConflict` になる。
+    pub fn with_resolver(mut self, resolver: Box<dyn FusionResolver>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// LLM分類で使うバックエンドを設定（未設定ならClaude CLI）
+    pub fn with_backend(mut self, backend: Arc<dyn LlmBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// 保存されたレシピから実行器を作成
+    ///
+    /// `force` は手動編集されたファイルも入力側の内容で上書きする。
+    pub fn from_recipe(recipe: &FusionRecipe, force: bool, dry_run: bool) -> Result<Self> {
+        let specs = recipe
+            .specs
+            .iter()
+            .map(|s| FusionSpec::parse(s))
+            .collect::<Result<Vec<_>>>()?;
+        let mut executor = Self::new(
+            specs,
+            FusionConfig {
+                mode: recipe.mode,
+                force,
+                include_uncategorized: recipe.include_uncategorized,
+                dry_run,
+                strategy: recipe.strategy,
+            },
+        );
+        executor.resolutions = recipe.resolutions.clone();
+        Ok(executor)
+    }
+
+    /// この実行器のレシピ
+    pub fn recipe(&self) -> FusionRecipe {
+        FusionRecipe {
+            specs: self.specs.iter().map(|s| s.to_string()).collect(),
+            mode: self.config.mode,
+            include_uncategorized: self.config.include_uncategorized,
+            strategy: self.config.strategy,
+            resolutions: self.resolutions.clone(),
+            created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            refreshed_at: None,
+        }
+    }
+
+    /// Interactive時に各コンフリクトで適用された戦略（レシピ保存用）
+    fn resolutions(&self, conflicts: &[FusionConflict]) -> BTreeMap<String, FusionStrategy> {
+        if self.config.strategy != FusionStrategy::Interactive {
+            return BTreeMap::new();
+        }
+        conflicts
+            .iter()
+            .map(|c| (c.path.to_string_lossy().to_string(), c.strategy))
+            .collect()
     }
 
     /// Fusionを計画（ドライラン用）
@@ -128,15 +412,22 @@ impl FusionExecutor {
             });
         }
 
-        // dest_path -> CollectedFile
-        let mut all_files: HashMap<String, CollectedFile> = HashMap::new();
-        let mut conflicts: Vec<FusionConflict> = Vec::new();
+        // dest_path -> 入力順の候補
+        let mut candidates: BTreeMap<String, Vec<(FusionSpec, CollectedFile)>> = BTreeMap::new();
         let mut contributions: HashMap<String, usize> = HashMap::new();
+        // src_path -> 元Profileでの分類（トークン見積もり用）
+        let mut categories: HashMap<PathBuf, Vec<String>> = HashMap::new();
 
         for (idx, spec) in self.specs.iter().enumerate() {
             let profile = manager.get_profile(&spec.profile_name)?;
-            let classifier = CategoryClassifier::from_profile(&profile, self.config.mode)?;
+            let mut classifier = CategoryClassifier::from_profile(&profile, self.config.mode)?;
+            if let Some(backend) = &self.backend {
+                classifier = classifier.with_backend(backend.clone());
+            }
             let result = classifier.classify(&profile)?;
+            for file in &result.files {
+                categories.insert(profile.path.join(&file.path), file.categories.clone());
+            }
 
             // Validate category exists
             if classifier.get_category(&spec.category).is_none() {
@@ -151,38 +442,21 @@ impl FusionExecutor {
             for file in files {
                 let dest_key = file.to_string_lossy().to_string();
                 let src_path = profile.path.join(file);
+                let entry = candidates.entry(dest_key.clone()).or_default();
 
-                // Check for conflict before inserting
-                if let Some(existing) = all_files.get(&dest_key) {
-                    let conflict_entry = conflicts
-                        .iter_mut()
-                        .find(|c| c.path.to_string_lossy() == dest_key);
-
-                    if let Some(conflict) = conflict_entry {
-                        conflict.sources.push(spec.clone());
-                    } else {
-                        conflicts.push(FusionConflict {
-                            path: PathBuf::from(&dest_key),
-                            sources: vec![
-                                FusionSpec {
-                                    profile_name: existing.source_profile.clone(),
-                                    category: spec.category.clone(),
-                                },
-                                spec.clone(),
-                            ],
-                        });
-                    }
+                // 同じファイルが複数カテゴリに属する場合はコンフリクトではない
+                if entry.iter().any(|(_, f)| f.src_path == src_path) {
+                    continue;
                 }
-
-                // Insert or overwrite (later spec wins)
-                all_files.insert(
-                    dest_key.clone(),
+                entry.push((
+                    spec.clone(),
                     CollectedFile {
                         src_path,
                         dest_path: dest_key,
                         source_profile: spec.profile_name.clone(),
+                        content: None,
                     },
-                );
+                ));
             }
 
             *contributions.entry(spec.profile_name.clone()).or_insert(0) += file_count;
@@ -193,28 +467,133 @@ impl FusionExecutor {
                 for file in uncategorized {
                     let dest_key = file.to_string_lossy().to_string();
                     let src_path = profile.path.join(file);
-                    all_files
-                        .entry(dest_key.clone())
-                        .or_insert_with(|| CollectedFile {
-                            src_path,
-                            dest_path: dest_key,
-                            source_profile: spec.profile_name.clone(),
-                        });
+                    let entry = candidates.entry(dest_key.clone()).or_default();
+                    if entry.is_empty() {
+                        entry.push((
+                            spec.clone(),
+                            CollectedFile {
+                                src_path,
+                                dest_path: dest_key,
+                                source_profile: spec.profile_name.clone(),
+                                content: None,
+                            },
+                        ));
+                    }
                 }
             }
         }
 
+        let mut files = Vec::new();
+        let mut conflicts = Vec::new();
+        for (dest_key, entries) in candidates {
+            if entries.len() == 1 {
+                files.extend(entries.into_iter().map(|(_, file)| file));
+                continue;
+            }
+            let (sources, entries): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+            let mut conflict = FusionConflict {
+                path: PathBuf::from(&dest_key),
+                sources,
+                strategy: self.config.strategy,
+                outputs: Vec::new(),
+            };
+            let resolved = self.resolve_conflict(&mut conflict, entries)?;
+            conflict.outputs = resolved.iter().map(|f| f.dest_path.clone()).collect();
+            files.extend(resolved);
+            conflicts.push(conflict);
+        }
+
         // Sort files by dest_path for deterministic output
-        let mut files: Vec<_> = all_files.into_values().collect();
         files.sort_by(|a, b| a.dest_path.cmp(&b.dest_path));
 
+        let estimates = files
+            .iter()
+            .map(|file| {
+                Ok(FileEstimate::new(
+                    PathBuf::from(&file.dest_path),
+                    &file.read()?,
+                    categories.get(&file.src_path).cloned().unwrap_or_default(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(FusionPlan {
             files,
             conflicts,
             contributions,
+            budget: BudgetReport::new(estimates),
         })
     }
 
+    /// コンフリクトを戦略に従って解決し、出力するファイルを返す
+    ///
+    /// Interactiveでは、レシピに記録された選択、リゾルバの順に戦略を決める。
+    /// どちらもなければ `DotAgentError::FusionConflict` を返す。
+    /// 適用された戦略を `conflict.strategy` に記録する。
+    fn resolve_conflict(
+        &self,
+        conflict: &mut FusionConflict,
+        mut candidates: Vec<CollectedFile>,
+    ) -> Result<Vec<CollectedFile>> {
+        let mut strategy = self.config.strategy;
+        if strategy == FusionStrategy::Interactive {
+            let saved = self
+                .resolutions
+                .get(conflict.path.to_string_lossy().as_ref())
+                .copied();
+            strategy = match (saved, &self.resolver) {
+                (Some(saved), _) => saved,
+                (None, Some(resolver)) => resolver.resolve(conflict, &candidates)?,
+                (None, None) => {
+                    return Err(DotAgentError::FusionConflict {
+                        path: conflict.path.clone(),
+                    })
+                }
+            };
+        }
+        if !strategy.applies_to(&conflict.path) {
+            strategy = FusionStrategy::LastWins;
+        }
+        conflict.strategy = strategy;
+
+        let files = match strategy {
+            FusionStrategy::FirstWins => {
+                candidates.truncate(1);
+                candidates
+            }
+            FusionStrategy::RenameBoth => candidates
+                .into_iter()
+                .map(|mut file| {
+                    file.dest_path = renamed_path(&file.dest_path, &file.source_profile);
+                    file
+                })
+                .collect(),
+            FusionStrategy::Concat => {
+                let content = concat_markdown(&candidates)?;
+                let mut last = candidates.pop().expect("conflict has candidates");
+                last.content = Some(content);
+                vec![last]
+            }
+            FusionStrategy::MergeJson => {
+                let mut merged: Option<String> = None;
+                for file in &candidates {
+                    let source = fs::read_to_string(&file.src_path)?;
+                    merged =
+                        Some(merge_json(merged.as_deref(), &source, &file.source_profile)?.content);
+                }
+                let content = strip_profile_markers(&merged.unwrap_or_default())?;
+                let mut last = candidates.pop().expect("conflict has candidates");
+                last.content = Some(content);
+                vec![last]
+            }
+            FusionStrategy::LastWins | FusionStrategy::Interactive => {
+                let last = candidates.pop().expect("conflict has candidates");
+                vec![last]
+            }
+        };
+        Ok(files)
+    }
+
     /// Fusionを実行（plan + execute_plan のショートカット）
     pub fn execute(&self, manager: &ProfileManager, output_name: &str) -> Result<FusionResult> {
         let plan = self.plan(manager)?;
@@ -243,6 +622,7 @@ impl FusionExecutor {
             return Ok(FusionResult {
                 profile_name: output_name.to_string(),
                 files_copied: 0,
+                strategy: self.config.strategy,
                 conflicts: plan.conflicts.clone(),
                 contributions: plan.contributions.clone(),
             });
@@ -269,7 +649,7 @@ impl FusionExecutor {
                 })?;
             }
 
-            fs::copy(&file.src_path, &dest_path).map_err(|e| {
+            fs::write(&dest_path, file.read()?).map_err(|e| {
                 std::io::Error::new(
                     e.kind(),
                     format!(
@@ -281,17 +661,309 @@ impl FusionExecutor {
             copied += 1;
         }
 
-        // Create .dot-agent.toml
-        let metadata = ProfileMetadata::new_local(output_name);
+        // Create .dot-agent.toml（レシピと各ファイルの出自を記録）
+        let mut metadata = ProfileMetadata::new_local(output_name);
+        let mut recipe = self.recipe();
+        recipe.resolutions = self.resolutions(&plan.conflicts);
+        metadata.fusion = Some(recipe);
+        let mut sources = SourceCache::default();
+        for file in &plan.files {
+            metadata
+                .files
+                .extend(sources.provenance(manager, file, &output_path)?);
+        }
         metadata.save(&output_path)?;
 
         Ok(FusionResult {
             profile_name: output_name.to_string(),
             files_copied: copied,
+            strategy: self.config.strategy,
             conflicts: plan.conflicts.clone(),
             contributions: plan.contributions.clone(),
         })
     }
+
+    /// レシピから再計画し、入力側の変更を出力Profileに反映する
+    ///
+    /// 前回のFusion時に記録したファイルの出自と比較し、入力側で追加・更新・
+    /// 削除されたファイルだけを反映する。出力側で手動編集されたファイルは
+    /// `force` でない限り保持し、`KeptLocal` として報告する。
+    /// Fusion以外で追加されたファイルには触れない。
+    pub fn refresh(&self, manager: &ProfileManager, output_name: &str) -> Result<FusionRefresh> {
+        let output = manager.get_profile(output_name)?;
+        let mut metadata = ProfileMetadata::load(&output.path)?
+            .unwrap_or_else(|| ProfileMetadata::new_local(output_name));
+        let plan = self.plan(manager)?;
+
+        let mut changes = Vec::new();
+        let mut unchanged = 0;
+        let mut sources = SourceCache::default();
+        let mut updated = BTreeMap::new();
+
+        for file in &plan.files {
+            let dest = output.path.join(&file.dest_path);
+            let recorded = metadata
+                .files
+                .get(&file.dest_path)
+                .filter(|p| p.via == "fusion");
+            let source_path = sources.source_path(manager, file)?;
+            let content = file.read()?;
+            let new_hash = compute_hash(&content);
+
+            let origin = ComponentOrigin::Profile {
+                profile: file.source_profile.clone(),
+            };
+            // 合成されたファイルは書き込んだ内容そのものと比較する
+            let upstream_changed = recorded.map_or(true, |p| {
+                let previous = if file.content.is_some() {
+                    &p.hash
+                } else {
+                    &p.origin_hash
+                };
+                *previous != new_hash || p.origin != origin || p.source_path != source_path
+            });
+            if !upstream_changed {
+                unchanged += 1;
+                continue;
+            }
+
+            let locally_modified = match recorded {
+                Some(p) => file_hash(&dest)?.as_ref() != Some(&p.hash),
+                None => dest.exists(),
+            };
+            let kind = if locally_modified && !self.config.force {
+                FusionChangeKind::KeptLocal
+            } else if recorded.is_none() {
+                FusionChangeKind::Added
+            } else {
+                FusionChangeKind::Updated
+            };
+
+            changes.push(FusionChange {
+                path: file.dest_path.clone(),
+                kind,
+                source_profile: file.source_profile.clone(),
+                diff: text_diff(&dest, &content),
+            });
+
+            if kind != FusionChangeKind::KeptLocal && !self.config.dry_run {
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&dest, &content)?;
+                updated.extend(sources.provenance(manager, file, &output.path)?);
+            }
+        }
+
+        // 入力から無くなったファイル
+        let planned: HashSet<&str> = plan.files.iter().map(|f| f.dest_path.as_str()).collect();
+        let mut removed = Vec::new();
+        for (path, provenance) in &metadata.files {
+            if provenance.via != "fusion" || planned.contains(path.as_str()) {
+                continue;
+            }
+            let dest = output.path.join(path);
+            let locally_modified = file_hash(&dest)?.is_some_and(|h| h != provenance.hash);
+            let kind = if locally_modified && !self.config.force {
+                FusionChangeKind::KeptLocal
+            } else {
+                FusionChangeKind::Removed
+            };
+            let source_profile = match &provenance.origin {
+                ComponentOrigin::Profile { profile } => profile.clone(),
+                other => other.to_string(),
+            };
+            changes.push(FusionChange {
+                path: path.clone(),
+                kind,
+                source_profile,
+                diff: Vec::new(),
+            });
+            if kind == FusionChangeKind::Removed {
+                removed.push(path.clone());
+            }
+        }
+
+        if !self.config.dry_run {
+            for path in &removed {
+                let dest = output.path.join(path);
+                if dest.exists() {
+                    fs::remove_file(&dest)?;
+                }
+                metadata.files.remove(path);
+            }
+            metadata.files.extend(updated);
+            let mut recipe = metadata.fusion.take().unwrap_or_else(|| self.recipe());
+            recipe.resolutions.extend(self.resolutions(&plan.conflicts));
+            recipe.refreshed_at = Some(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string());
+            metadata.fusion = Some(recipe);
+            metadata.save(&output.path)?;
+        }
+
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(FusionRefresh {
+            profile_name: output_name.to_string(),
+            changes,
+            unchanged,
+            dry_run: self.config.dry_run,
+        })
+    }
+}
+
+/// 入力Profileの読み込みキャッシュ（Profileとgitコミット）
+#[derive(Default)]
+struct SourceCache {
+    profiles: HashMap<String, (Profile, Option<String>)>,
+}
+
+impl SourceCache {
+    fn get(&mut self, manager: &ProfileManager, name: &str) -> Result<&(Profile, Option<String>)> {
+        if !self.profiles.contains_key(name) {
+            let profile = manager.get_profile(name)?;
+            let commit = match profile.source()? {
+                ProfileSource::Git { commit, .. } => commit,
+                _ => None,
+            };
+            self.profiles.insert(name.to_string(), (profile, commit));
+        }
+        Ok(&self.profiles[name])
+    }
+
+    /// 入力Profile内での相対パス
+    fn source_path(&mut self, manager: &ProfileManager, file: &CollectedFile) -> Result<String> {
+        let (profile, _) = self.get(manager, &file.source_profile)?;
+        Ok(file
+            .src_path
+            .strip_prefix(&profile.path)
+            .unwrap_or(&file.src_path)
+            .to_string_lossy()
+            .replace('\\', "/"))
+    }
+
+    /// 出力Profileにコピーされたファイルの出自
+    fn provenance(
+        &mut self,
+        manager: &ProfileManager,
+        file: &CollectedFile,
+        output_path: &Path,
+    ) -> Result<BTreeMap<String, FileProvenance>> {
+        let source_path = self.source_path(manager, file)?;
+        let (_, commit) = self.get(manager, &file.source_profile)?;
+        if let Some(content) = &file.content {
+            let hash = compute_hash(content.as_bytes());
+            let provenance = FileProvenance {
+                origin: ComponentOrigin::Profile {
+                    profile: file.source_profile.clone(),
+                },
+                source_path,
+                commit: commit.clone(),
+                origin_hash: compute_hash(&fs::read(&file.src_path)?),
+                hash,
+                via: "fusion".to_string(),
+                recorded_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            };
+            return Ok(BTreeMap::from([(file.dest_path.clone(), provenance)]));
+        }
+        provenance_for_copy(
+            &file.src_path,
+            &source_path,
+            &output_path.join(&file.dest_path),
+            &file.dest_path,
+            &ComponentOrigin::Profile {
+                profile: file.source_profile.clone(),
+            },
+            commit.as_deref(),
+            "fusion",
+        )
+    }
+}
+
+fn file_hash(path: &Path) -> Result<Option<String>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(compute_hash(&fs::read(path)?)))
+}
+
+/// 別名保存先（agents/commands/rules/skills は `install` と同じプレフィックス規則）
+fn renamed_path(dest_path: &str, profile: &str) -> String {
+    let path = Path::new(dest_path);
+    let prefixed = prefix_path(path, profile);
+    if prefixed != path {
+        return prefixed.to_string_lossy().replace('\\', "/");
+    }
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}-{}", profile, file_name))
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Markdownを入力順に連結する
+///
+/// 各入力をセクションマーカーで囲む。フロントマターは最初の入力のものだけを残す。
+fn concat_markdown(candidates: &[CollectedFile]) -> Result<String> {
+    let mut frontmatter = None;
+    let mut sections = Vec::new();
+    for file in candidates {
+        let content = fs::read_to_string(&file.src_path)?;
+        let (front, body) = split_frontmatter(&content);
+        if frontmatter.is_none() {
+            frontmatter = front.map(str::to_string);
+        }
+        sections.push(format!(
+            "<!-- dot-agent:fusion begin {} -->\n{}\n<!-- dot-agent:fusion end {} -->\n",
+            file.source_profile,
+            body.trim_matches('\n'),
+            file.source_profile
+        ));
+    }
+    let mut output = frontmatter.unwrap_or_default();
+    output.push_str(&sections.join("\n"));
+    Ok(output)
+}
+
+/// フロントマター（区切り線込み）と本文に分ける
+fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    if !content.starts_with("---\n") {
+        return (None, content);
+    }
+    match content[4..].find("\n---\n") {
+        Some(end) => {
+            let split = 4 + end + 5;
+            (Some(&content[..split]), &content[split..])
+        }
+        None => (None, content),
+    }
+}
+
+/// 行単位の差分（どちらかがテキストでなければ空）
+fn text_diff(old: &Path, new: &[u8]) -> Vec<DiffLine> {
+    let old = if old.exists() {
+        match fs::read_to_string(old) {
+            Ok(content) => content,
+            Err(_) => return Vec::new(),
+        }
+    } else {
+        String::new()
+    };
+    let Ok(new) = std::str::from_utf8(new) else {
+        return Vec::new();
+    };
+
+    TextDiff::from_lines(old.as_str(), new)
+        .iter_all_changes()
+        .filter_map(|change| {
+            let line = change.value().trim_end_matches('\n').to_string();
+            match change.tag() {
+                ChangeTag::Insert => Some(DiffLine::Added(line)),
+                ChangeTag::Delete => Some(DiffLine::Removed(line)),
+                ChangeTag::Equal => None,
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -313,4 +985,237 @@ mod tests {
         assert_eq!(spec.profile_name, "my");
         assert_eq!(spec.category, "profile:plan");
     }
+
+    fn conflicting_profiles() -> (tempfile::TempDir, ProfileManager) {
+        let temp = tempfile::TempDir::new().unwrap();
+        let manager = ProfileManager::new(temp.path().to_path_buf());
+        for (name, body, server) in [("a", "A body", "one"), ("b", "B body", "two")] {
+            let dir = temp.path().join("profiles").join(name);
+            fs::create_dir_all(dir.join("commands")).unwrap();
+            fs::create_dir_all(dir.join("hooks/debug")).unwrap();
+            fs::write(
+                dir.join("commands/debug.md"),
+                format!("---\ndescription: {}\n---\n{}\n", name, body),
+            )
+            .unwrap();
+            fs::write(
+                dir.join("hooks/debug/settings.json"),
+                format!(r#"{{"mcpServers": {{"{}": {{"command": "x"}}}}}}"#, server),
+            )
+            .unwrap();
+        }
+        (temp, manager)
+    }
+
+    fn plan_with(manager: &ProfileManager, strategy: FusionStrategy) -> FusionPlan {
+        let config = FusionConfig {
+            strategy,
+            ..Default::default()
+        };
+        let specs = vec![
+            FusionSpec::parse("a:debug").unwrap(),
+            FusionSpec::parse("b:debug").unwrap(),
+        ];
+        FusionExecutor::new(specs, config).plan(manager).unwrap()
+    }
+
+    #[test]
+    fn test_conflict_strategies() {
+        let (_temp, manager) = conflicting_profiles();
+
+        let plan = plan_with(&manager, FusionStrategy::FirstWins);
+        assert_eq!(plan.conflicts.len(), 2);
+        assert!(plan.files.iter().all(|f| f.source_profile == "a"));
+
+        let plan = plan_with(&manager, FusionStrategy::RenameBoth);
+        let paths: Vec<_> = plan.files.iter().map(|f| f.dest_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "commands/a-debug.md",
+                "commands/b-debug.md",
+                "hooks/debug/a-settings.json",
+                "hooks/debug/b-settings.json",
+            ]
+        );
+        // The budget follows the output paths and source categories
+        assert_eq!(plan.budget.files.len(), 4);
+        assert_eq!(
+            plan.budget.tokens_for(crate::budget::ContextLoad::OnDemand),
+            plan.budget.files[..2]
+                .iter()
+                .map(|f| f.tokens)
+                .sum::<usize>()
+        );
+        assert!(plan.budget.by_category().contains_key("debug"));
+
+        // Concat applies to markdown only; JSON falls back to last-wins
+        let plan = plan_with(&manager, FusionStrategy::Concat);
+        let md = &plan.files[0];
+        let content = md.content.as_deref().unwrap();
+        assert!(content.starts_with("---\ndescription: a\n---\n"));
+        assert!(content.contains("<!-- dot-agent:fusion begin a -->\nA body\n"));
+        assert!(content.contains("<!-- dot-agent:fusion end b -->"));
+        assert!(!content.contains("description: b"));
+        assert_eq!(plan.conflicts[1].strategy, FusionStrategy::LastWins);
+
+        let plan = plan_with(&manager, FusionStrategy::MergeJson);
+        let json: serde_json::Value =
+            serde_json::from_str(plan.files[1].content.as_deref().unwrap()).unwrap();
+        assert!(json["mcpServers"]["one"].is_object());
+        assert!(json["mcpServers"]["two"].is_object());
+        assert!(!plan.files[1]
+            .content
+            .as_deref()
+            .unwrap()
+            .contains("_dot_agent_profile"));
+        assert_eq!(plan.conflicts[0].strategy, FusionStrategy::LastWins);
+        assert_eq!(plan.conflicts[1].strategy, FusionStrategy::MergeJson);
+    }
+
+    #[test]
+    fn test_interactive_strategy_uses_resolver() {
+        struct PickFirst;
+        impl FusionResolver for PickFirst {
+            fn resolve(
+                &self,
+                _conflict: &FusionConflict,
+                candidates: &[CollectedFile],
+            ) -> Result<FusionStrategy> {
+                assert_eq!(candidates.len(), 2);
+                Ok(FusionStrategy::FirstWins)
+            }
+        }
+        struct Unreachable;
+        impl FusionResolver for Unreachable {
+            fn resolve(
+                &self,
+                conflict: &FusionConflict,
+                _candidates: &[CollectedFile],
+            ) -> Result<FusionStrategy> {
+                panic!("saved choice not replayed for {}", conflict.path.display());
+            }
+        }
+
+        let (_temp, manager) = conflicting_profiles();
+        let config = FusionConfig {
+            strategy: FusionStrategy::Interactive,
+            ..Default::default()
+        };
+        let specs = vec![
+            FusionSpec::parse("a:debug").unwrap(),
+            FusionSpec::parse("b:debug").unwrap(),
+        ];
+
+        // Without a resolver an unresolved conflict is an error, not last-wins
+        let err = FusionExecutor::new(specs.clone(), config.clone())
+            .plan(&manager)
+            .unwrap_err();
+        assert!(matches!(err, DotAgentError::FusionConflict { .. }));
+
+        let executor = FusionExecutor::new(specs, config).with_resolver(Box::new(PickFirst));
+        let result = executor.execute(&manager, "out").unwrap();
+        assert_eq!(result.strategy, FusionStrategy::Interactive);
+        assert!(result
+            .conflicts
+            .iter()
+            .all(|c| c.strategy == FusionStrategy::FirstWins));
+
+        let out = manager.profiles_dir().join("out");
+        assert!(fs::read_to_string(out.join("commands/debug.md"))
+            .unwrap()
+            .contains("A body"));
+        let recipe = ProfileMetadata::load(&out)
+            .unwrap()
+            .unwrap()
+            .fusion
+            .unwrap();
+        assert_eq!(recipe.strategy, FusionStrategy::Interactive);
+        assert_eq!(recipe.resolutions.len(), result.conflicts.len());
+        assert!(recipe
+            .resolutions
+            .values()
+            .all(|s| *s == FusionStrategy::FirstWins));
+
+        // refresh replays the saved choices instead of asking again
+        let refresh = FusionExecutor::from_recipe(&recipe, false, false)
+            .unwrap()
+            .with_resolver(Box::new(Unreachable))
+            .refresh(&manager, "out")
+            .unwrap();
+        assert!(refresh.changes.is_empty());
+    }
+
+    #[test]
+    fn test_refresh_preserves_manual_edits() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let manager = ProfileManager::new(temp.path().to_path_buf());
+        let src = temp.path().join("profiles/src/commands");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("debug-a.md"), "a v1\n").unwrap();
+        fs::write(src.join("fix-b.md"), "b v1\n").unwrap();
+
+        let config = FusionConfig {
+            mode: ClassificationMode::Glob,
+            ..Default::default()
+        };
+        let executor = FusionExecutor::new(vec![FusionSpec::parse("src:debug").unwrap()], config);
+        executor.execute(&manager, "out").unwrap();
+
+        let out = temp.path().join("profiles/out");
+        let recipe = ProfileMetadata::load(&out)
+            .unwrap()
+            .unwrap()
+            .fusion
+            .unwrap();
+        assert_eq!(recipe.specs, vec!["src:debug"]);
+
+        // Upstream changes, plus a manual edit and a manual addition in the output
+        fs::write(src.join("debug-a.md"), "a v2\n").unwrap();
+        fs::write(src.join("fix-b.md"), "b v2\n").unwrap();
+        fs::write(src.join("error-c.md"), "c v1\n").unwrap();
+        fs::write(out.join("commands/debug-a.md"), "a edited\n").unwrap();
+        fs::write(out.join("commands/mine.md"), "mine\n").unwrap();
+
+        let executor = FusionExecutor::from_recipe(&recipe, false, false).unwrap();
+        let refresh = executor.refresh(&manager, "out").unwrap();
+        let kinds: Vec<_> = refresh
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("commands/debug-a.md", FusionChangeKind::KeptLocal),
+                ("commands/error-c.md", FusionChangeKind::Added),
+                ("commands/fix-b.md", FusionChangeKind::Updated),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(out.join("commands/debug-a.md")).unwrap(),
+            "a edited\n"
+        );
+        assert_eq!(
+            fs::read_to_string(out.join("commands/fix-b.md")).unwrap(),
+            "b v2\n"
+        );
+        assert!(out.join("commands/mine.md").exists());
+
+        // Upstream removal of an unedited file
+        fs::remove_file(src.join("fix-b.md")).unwrap();
+        let refresh = executor.refresh(&manager, "out").unwrap();
+        assert!(refresh
+            .changes
+            .iter()
+            .any(|c| c.path == "commands/fix-b.md" && c.kind == FusionChangeKind::Removed));
+        assert!(!out.join("commands/fix-b.md").exists());
+        assert!(ProfileMetadata::load(&out)
+            .unwrap()
+            .unwrap()
+            .fusion
+            .unwrap()
+            .refreshed_at
+            .is_some());
+    }
 }
@@ -5,7 +5,7 @@
 //! - `~/.dot-agent/profiles.toml` - Profile index (all profiles)
 //! - `~/.dot-agent/profiles/<name>/.dot-agent.toml` - Per-profile metadata
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
@@ -14,7 +14,7 @@ use serde::{Deserialize, Serialize};
 use crate::error::{DotAgentError, Result};
 
 const PROFILES_INDEX_FILE: &str = "profiles.toml";
-const PROFILE_METADATA_FILE: &str = ".dot-agent.toml";
+pub(crate) const PROFILE_METADATA_FILE: &str = ".dot-agent.toml";
 
 /// Profile source specification
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -41,6 +41,14 @@ pub enum ProfileSource {
         plugin: String,
         version: String,
     },
+
+    /// Imported from a local directory channel
+    Channel {
+        channel: String,
+        name: String,
+        /// Directory the profile was copied from
+        path: String,
+    },
 }
 
 /// Profile entry in profiles.toml
@@ -108,6 +116,21 @@ impl ProfileIndexEntry {
         }
     }
 
+    /// Create a new local channel profile entry
+    pub fn new_channel(name: &str, channel: &str, entry: &str, path: &str) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            path: format!("profiles/{}", name),
+            source: ProfileSource::Channel {
+                channel: channel.to_string(),
+                name: entry.to_string(),
+                path: path.to_string(),
+            },
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
     /// Update timestamp
     pub fn touch(&mut self) {
         self.updated_at = chrono::Utc::now().to_rfc3339();
@@ -256,6 +279,18 @@ pub struct ProfileMetadata {
     /// Category configurations (overrides builtin categories)
     #[serde(default)]
     pub categories: Option<crate::category::CategoriesConfig>,
+
+    /// Recipe for `fusion refresh` (set on fusion output profiles)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fusion: Option<super::FusionRecipe>,
+
+    /// Components imported individually from elsewhere
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<super::ComponentProvenance>,
+
+    /// Where each copied file came from (profile-relative path -> provenance)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, super::FileProvenance>,
 }
 
 /// Profile info section in .dot-agent.toml
@@ -290,6 +325,9 @@ impl ProfileMetadata {
             source: ProfileSource::Local,
             plugin: PluginConfig::default(),
             categories: None,
+            fusion: None,
+            components: Vec::new(),
+            files: BTreeMap::new(),
         }
     }
 
@@ -316,6 +354,9 @@ impl ProfileMetadata {
             },
             plugin: PluginConfig::default(),
             categories: None,
+            fusion: None,
+            components: Vec::new(),
+            files: BTreeMap::new(),
         }
     }
 
@@ -335,6 +376,31 @@ impl ProfileMetadata {
             },
             plugin: PluginConfig::default(),
             categories: None,
+            fusion: None,
+            components: Vec::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Create new local channel profile metadata
+    pub fn new_channel(name: &str, channel: &str, entry: &str, path: &str) -> Self {
+        Self {
+            profile: ProfileInfo {
+                name: name.to_string(),
+                version: None,
+                description: None,
+                author: None,
+            },
+            source: ProfileSource::Channel {
+                channel: channel.to_string(),
+                name: entry.to_string(),
+                path: path.to_string(),
+            },
+            plugin: PluginConfig::default(),
+            categories: None,
+            fusion: None,
+            components: Vec::new(),
+            files: BTreeMap::new(),
         }
     }
 
@@ -368,6 +434,18 @@ impl ProfileMetadata {
         Ok(())
     }
 
+    /// Provenance of an imported component
+    pub fn component(&self, path: &str) -> Option<&super::ComponentProvenance> {
+        self.components.iter().find(|c| c.path == path)
+    }
+
+    /// Record (or replace) the provenance of an imported component
+    pub fn record_component(&mut self, provenance: super::ComponentProvenance) {
+        self.components.retain(|c| c.path != provenance.path);
+        self.components.push(provenance);
+        self.components.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
     /// Check if profile has plugin features (hooks, MCP, LSP)
     pub fn has_plugin_features(profile_dir: &Path) -> bool {
         let hooks_dir = profile_dir.join("hooks");
@@ -423,6 +501,11 @@ pub fn migrate_existing_profiles(base_dir: &Path) -> Result<ProfilesIndex> {
                     plugin,
                     version,
                 } => ProfileIndexEntry::new_marketplace(name, channel, plugin, version),
+                ProfileSource::Channel {
+                    channel,
+                    name: entry_name,
+                    path,
+                } => ProfileIndexEntry::new_channel(name, channel, entry_name, path),
             };
             index.upsert(name, entry);
         } else {
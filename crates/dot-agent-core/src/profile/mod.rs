@@ -1,5 +1,8 @@
+mod component;
+mod finder;
 mod fusion;
 mod metadata;
+mod provenance;
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -9,17 +12,25 @@ use once_cell::unsync::OnceCell;
 use walkdir::WalkDir;
 
 use crate::error::{DotAgentError, Result};
+use crate::path::validate_relative_path;
 use crate::plugin::manifest::{FilterConfig, PluginManifest, DEFAULT_COMPONENT_DIRS};
 
 // Re-exports
+pub use component::{parse_frontmatter, scan_components, Component, ComponentKind};
+pub use finder::{ComponentFinder, ComponentMatch, MatchField};
 pub use fusion::{
-    CollectedFile, FusionConfig, FusionConflict, FusionExecutor, FusionPlan, FusionResult,
-    FusionSpec,
+    CollectedFile, FusionChange, FusionChangeKind, FusionConfig, FusionConflict, FusionExecutor,
+    FusionPlan, FusionRecipe, FusionRefresh, FusionResolver, FusionResult, FusionSpec,
+    FusionStrategy,
 };
 pub use metadata::{
     migrate_existing_profiles, PluginConfig, PluginScope, ProfileIndexEntry, ProfileInfo,
     ProfileMetadata, ProfileSource, ProfilesIndex,
 };
+pub use provenance::{
+    check_provenance, tree_hash, ComponentOrigin, ComponentProvenance, FileProvenance,
+    FileProvenanceStatus, LocalState, UpstreamState,
+};
 
 const PROFILES_DIR: &str = "profiles";
 const IGNORED_FILES: &[&str] = &[".DS_Store", ".gitignore", ".gitkeep"];
@@ -307,6 +318,11 @@ impl Profile {
         Ok(store)
     }
 
+    /// List skills, commands and agents in this profile
+    pub fn components(&self) -> Result<Vec<Component>> {
+        scan_components(&self.path)
+    }
+
     /// Check if profile has plugin features (hooks, MCP, LSP)
     pub fn has_plugin_features(&self) -> bool {
         ProfileMetadata::has_plugin_features(&self.path)
@@ -561,6 +577,7 @@ dot-agent install {}
             let metadata = ProfileMetadata::new_local(dest_name);
             metadata.save(&dest_path)?;
         }
+        self.record_profile_copy(&dest_path, &source, "copy")?;
 
         // Update profiles index
         let mut index = ProfilesIndex::load(&self.base_dir)?;
@@ -614,6 +631,23 @@ dot-agent install {}
         self.import_profile_with_source(source, name, force, source_info)
     }
 
+    /// Import a directory as a profile from a local directory channel
+    pub fn import_profile_from_channel(
+        &self,
+        source: &Path,
+        name: &str,
+        force: bool,
+        channel: &str,
+        entry: &str,
+    ) -> Result<Profile> {
+        let source_info = ProfileSource::Channel {
+            channel: channel.to_string(),
+            name: entry.to_string(),
+            path: source.display().to_string(),
+        };
+        self.import_profile_with_source(source, name, force, source_info)
+    }
+
     /// Import a directory as a profile with source information
     fn import_profile_with_source(
         &self,
@@ -667,6 +701,11 @@ dot-agent install {}
                 plugin,
                 version,
             } => ProfileMetadata::new_marketplace(name, channel, plugin, version),
+            ProfileSource::Channel {
+                channel,
+                name: entry,
+                path,
+            } => ProfileMetadata::new_channel(name, channel, entry, path),
         };
         metadata.save(&dest)?;
 
@@ -691,6 +730,11 @@ dot-agent install {}
                 plugin,
                 version,
             } => ProfileIndexEntry::new_marketplace(name, channel, plugin, version),
+            ProfileSource::Channel {
+                channel,
+                name: entry,
+                path,
+            } => ProfileIndexEntry::new_channel(name, channel, entry, path),
         };
         index.upsert(name, entry);
         index.save(&self.base_dir)?;
@@ -698,6 +742,163 @@ dot-agent install {}
         Ok(Profile::new(name.to_string(), dest))
     }
 
+    /// Copy a single component (file or directory) into a profile
+    ///
+    /// `source_path` is the component's path relative to `source_root` (the
+    /// origin checked out on disk), `dest` its path relative to the profile
+    /// root; neither may leave its root. The origin is recorded in
+    /// `.dot-agent.toml` so the component can be updated on its own later.
+    pub fn add_component(
+        &self,
+        profile_name: &str,
+        source_root: &Path,
+        source_path: &Path,
+        dest: &Path,
+        origin: ComponentOrigin,
+        force: bool,
+    ) -> Result<ComponentProvenance> {
+        let profile = self.get_profile(profile_name)?;
+
+        let source_path = validate_relative_path(source_path)?;
+        let dest = &validate_relative_path(dest)?;
+        let source = &source_root.join(&source_path);
+        let source_path = &source_path.to_string_lossy().replace('\\', "/");
+        if !source.exists() {
+            return Err(DotAgentError::TargetNotFound {
+                path: source.to_path_buf(),
+            });
+        }
+
+        let dest_path = profile.path.join(dest);
+        if dest_path.exists() {
+            if !force {
+                return Err(DotAgentError::Conflict { path: dest_path });
+            }
+            if dest_path.is_dir() {
+                fs::remove_dir_all(&dest_path)?;
+            } else {
+                fs::remove_file(&dest_path)?;
+            }
+        }
+
+        if source.is_dir() {
+            copy_dir_recursive(source, &dest_path)?;
+        } else {
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(source, &dest_path)?;
+        }
+
+        let dest_str = dest.to_string_lossy().replace('\\', "/");
+        let commit = match &origin {
+            ComponentOrigin::Git { commit, .. } => commit.clone(),
+            _ => None,
+        };
+        let files = provenance::provenance_for_copy(
+            source,
+            source_path,
+            &dest_path,
+            &dest_str,
+            &origin,
+            commit.as_deref(),
+            "component",
+        )?;
+        let provenance = ComponentProvenance {
+            path: dest_str.clone(),
+            source_path: source_path.to_string(),
+            hash: tree_hash(&dest_path)?,
+            imported_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            origin,
+        };
+
+        let mut metadata = ProfileMetadata::load(&profile.path)?
+            .unwrap_or_else(|| ProfileMetadata::new_local(profile_name));
+        metadata.record_component(provenance.clone());
+        let dir_prefix = format!("{}/", dest_str);
+        metadata
+            .files
+            .retain(|path, _| path != &dest_str && !path.starts_with(&dir_prefix));
+        metadata.files.extend(files);
+        metadata.save(&profile.path)?;
+
+        let mut index = ProfilesIndex::load(&self.base_dir)?;
+        if let Some(entry) = index.profiles.get_mut(profile_name) {
+            entry.touch();
+            index.save(&self.base_dir)?;
+        }
+
+        Ok(provenance)
+    }
+
+    /// Record every file of `dest` copied from profile `source` as coming from it
+    ///
+    /// Replaces the file provenance `dest` may have inherited from `source`.
+    pub(crate) fn record_profile_copy(
+        &self,
+        dest: &Path,
+        source: &Profile,
+        via: &str,
+    ) -> Result<()> {
+        let origin = ComponentOrigin::Profile {
+            profile: source.name.clone(),
+        };
+        let commit = match source.source()? {
+            ProfileSource::Git { commit, .. } => commit,
+            _ => None,
+        };
+        let files = provenance::provenance_for_copy(
+            &source.path,
+            "",
+            dest,
+            "",
+            &origin,
+            commit.as_deref(),
+            via,
+        )?;
+
+        let name = dest
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut metadata =
+            ProfileMetadata::load(dest)?.unwrap_or_else(|| ProfileMetadata::new_local(&name));
+        metadata.files = files;
+        metadata.save(dest)
+    }
+
+    /// Directory an origin's source paths are relative to, if it is local
+    ///
+    /// Local profiles and directories resolve; git repositories and
+    /// channels need fetching and return `None`.
+    pub fn origin_root(&self, origin: &ComponentOrigin) -> Option<PathBuf> {
+        let root = match origin {
+            ComponentOrigin::Profile { profile } => self.profiles_dir().join(profile),
+            ComponentOrigin::Path { path } => PathBuf::from(path),
+            _ => return None,
+        };
+        root.is_dir().then_some(root)
+    }
+
+    /// Compare a profile's files with their recorded origins
+    ///
+    /// Local origins are resolved with [`Self::origin_root`]; `resolve` is
+    /// asked for the others (return `None` to leave them unchecked).
+    pub fn check_file_provenance(
+        &self,
+        name: &str,
+        mut resolve: impl FnMut(&ComponentOrigin) -> Option<PathBuf>,
+    ) -> Result<Vec<FileProvenanceStatus>> {
+        let profile = self.get_profile(name)?;
+        let files = profile
+            .metadata()?
+            .map(|m| m.files.clone())
+            .unwrap_or_default();
+        check_provenance(&profile.path, &files, |origin| {
+            self.origin_root(origin).or_else(|| resolve(origin))
+        })
+    }
+
     /// Get metadata for a profile
     pub fn get_profile_metadata(&self, name: &str) -> Result<Option<ProfileMetadata>> {
         let profile = self.get_profile(name)?;
@@ -711,7 +912,7 @@ dot-agent install {}
     }
 }
 
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
+pub(crate) fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     copy_dir_recursive_with_config(src, dst, &IgnoreConfig::with_defaults())
 }
 
@@ -881,6 +1082,103 @@ mod tests {
         assert!(profile.filter_config().unwrap().is_none());
     }
 
+    #[test]
+    fn add_component_records_provenance() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let manager = ProfileManager::new(tmp.path().to_path_buf());
+        let source = manager.create_profile("rust").unwrap();
+        manager.create_profile("mine").unwrap();
+        fs::create_dir_all(source.path.join("skills/tdd")).unwrap();
+        fs::write(
+            source.path.join("skills/tdd/SKILL.md"),
+            "---\nname: tdd\n---\n",
+        )
+        .unwrap();
+
+        let origin = ComponentOrigin::Profile {
+            profile: "rust".to_string(),
+        };
+        let skill = Path::new("skills/tdd");
+        let provenance = manager
+            .add_component("mine", &source.path, skill, skill, origin.clone(), false)
+            .unwrap();
+
+        let mine = manager.get_profile("mine").unwrap();
+        assert!(mine.path.join("skills/tdd/SKILL.md").exists());
+        assert_eq!(provenance.hash, tree_hash(&mine.path.join(skill)).unwrap());
+
+        let metadata = ProfileMetadata::load(&mine.path).unwrap().unwrap();
+        assert_eq!(metadata.component("skills/tdd").unwrap().origin, origin);
+        assert_eq!(metadata.files["skills/tdd/SKILL.md"].via, "component");
+
+        // Existing components need force; paths must stay inside the profile
+        let again =
+            manager.add_component("mine", &source.path, skill, skill, origin.clone(), false);
+        assert!(matches!(again, Err(DotAgentError::Conflict { .. })));
+        let escape = manager.add_component(
+            "mine",
+            &source.path,
+            skill,
+            Path::new("../escape"),
+            origin.clone(),
+            true,
+        );
+        assert!(matches!(
+            escape,
+            Err(DotAgentError::InvalidComponentPath { .. })
+        ));
+
+        // The source path may not leave the origin either
+        fs::write(tmp.path().join("secret.md"), "secret\n").unwrap();
+        for outside in ["../../secret.md", "/etc/passwd"] {
+            let escape = manager.add_component(
+                "mine",
+                &source.path,
+                Path::new(outside),
+                Path::new("rules/secret.md"),
+                origin.clone(),
+                true,
+            );
+            assert!(matches!(
+                escape,
+                Err(DotAgentError::InvalidComponentPath { .. })
+            ));
+        }
+        assert!(!mine.path.join("rules/secret.md").exists());
+    }
+
+    #[test]
+    fn copy_profile_records_file_provenance() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let manager = ProfileManager::new(tmp.path().to_path_buf());
+        let source = manager.create_profile("base").unwrap();
+        fs::create_dir_all(source.path.join("rules")).unwrap();
+        fs::write(source.path.join("rules/style.md"), "short functions").unwrap();
+
+        manager.copy_profile("base", "copy", false).unwrap();
+        let statuses = manager.check_file_provenance("copy", |_| None).unwrap();
+        let style = statuses
+            .iter()
+            .find(|s| s.path == "rules/style.md")
+            .unwrap();
+        assert_eq!(
+            style.provenance.origin,
+            ComponentOrigin::Profile {
+                profile: "base".to_string()
+            }
+        );
+        assert!(!style.drifted() && !style.has_upstream_update());
+        assert!(statuses.iter().all(|s| s.path != ".dot-agent.toml"));
+
+        fs::write(source.path.join("rules/style.md"), "tiny functions").unwrap();
+        let statuses = manager.check_file_provenance("copy", |_| None).unwrap();
+        let style = statuses
+            .iter()
+            .find(|s| s.path == "rules/style.md")
+            .unwrap();
+        assert_eq!(style.upstream, UpstreamState::Updated);
+    }
+
     #[test]
     fn profile_source_defaults_to_local() {
         let tmp = tempfile::TempDir::new().unwrap();
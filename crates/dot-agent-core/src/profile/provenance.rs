@@ -0,0 +1,385 @@
+//! Component and file provenance
+//!
+//! Components cherry-picked from another profile, a directory, a git
+//! repository or a channel are recorded in the profile's `.dot-agent.toml`:
+//!
+//! ```toml
+//! [[components]]
+//! path = "skills/tdd"
+//! source_path = "skills/tdd"
+//! hash = "sha256:..."
+//! imported_at = "2026-01-01 12:00:00"
+//!
+//! [components.origin]
+//! type = "profile"
+//! profile = "rust"
+//! ```
+//!
+//! The origin is enough to fetch the component again, so it can be updated
+//! on its own without re-importing the whole profile.
+//!
+//! Every file written by a copy, fusion, rule apply or component import also
+//! gets a `[files."<path>"]` entry with its origin and two hashes: the
+//! origin's content when recorded and the content written here. Comparing
+//! them with the current files tells local drift from upstream updates.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::error::Result;
+use crate::install::metadata::compute_hash;
+
+/// Where a component was imported from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ComponentOrigin {
+    /// Another local profile
+    Profile { profile: String },
+
+    /// A local directory
+    Path { path: String },
+
+    /// A git repository
+    Git {
+        url: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        commit: Option<String>,
+    },
+
+    /// A plugin in a marketplace channel
+    Marketplace {
+        channel: String,
+        plugin: String,
+        version: String,
+    },
+
+    /// An entry of a local directory channel
+    Channel { channel: String, name: String },
+
+    /// A skill in a Codex catalog channel
+    Codex { channel: String, skill: String },
+}
+
+impl ComponentOrigin {
+    /// Source reference as accepted by `profile add-component --from`
+    pub fn spec(&self) -> String {
+        match self {
+            Self::Profile { profile } => profile.clone(),
+            Self::Path { path } => path.clone(),
+            Self::Git { url, .. } => url.clone(),
+            Self::Marketplace {
+                channel, plugin, ..
+            } => format!("{}@{}", plugin, channel),
+            Self::Channel { channel, name } => format!("{}@{}", name, channel),
+            Self::Codex { channel, skill } => format!("{}@{}", skill, channel),
+        }
+    }
+}
+
+impl std::fmt::Display for ComponentOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Profile { profile } => write!(f, "profile {}", profile),
+            Self::Path { path } => write!(f, "{}", path),
+            Self::Git { url, commit, .. } => match commit {
+                Some(commit) => write!(f, "{} @ {}", url, &commit[..commit.len().min(7)]),
+                None => write!(f, "{}", url),
+            },
+            Self::Marketplace {
+                channel,
+                plugin,
+                version,
+            } => write!(f, "{}@{} v{}", plugin, channel, version),
+            Self::Channel { channel, name } => write!(f, "{}@{}", name, channel),
+            Self::Codex { channel, skill } => write!(f, "{}@{} (codex)", skill, channel),
+        }
+    }
+}
+
+/// Provenance of one imported component
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComponentProvenance {
+    /// Component path in this profile (e.g. "skills/tdd")
+    pub path: String,
+    /// Component path within the origin
+    pub source_path: String,
+    /// Content hash at import time (see [`tree_hash`])
+    pub hash: String,
+    pub imported_at: String,
+    pub origin: ComponentOrigin,
+}
+
+/// Content hash of a file or directory tree
+///
+/// For directories, every file's relative path and content hash are hashed
+/// in path order, so renames count as changes.
+pub fn tree_hash(path: &Path) -> Result<String> {
+    if path.is_file() {
+        return Ok(compute_hash(&fs::read(path)?));
+    }
+
+    let mut files: Vec<_> = WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+
+    let mut manifest = String::new();
+    for file in files {
+        let relative = file.strip_prefix(path).unwrap_or(&file);
+        manifest.push_str(&format!(
+            "{} {}\n",
+            relative.to_string_lossy().replace('\\', "/"),
+            compute_hash(&fs::read(&file)?)
+        ));
+    }
+    Ok(compute_hash(manifest.as_bytes()))
+}
+
+/// Provenance of one file in a profile
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileProvenance {
+    pub origin: ComponentOrigin,
+    /// Path within the origin
+    pub source_path: String,
+    /// Commit of the origin, when it is (or was imported from) a git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Content hash of the origin file when recorded
+    pub origin_hash: String,
+    /// Content hash of the file as written to this profile
+    pub hash: String,
+    /// Operation that wrote the file (copy, fusion, rule:<name>, component)
+    pub via: String,
+    pub recorded_at: String,
+}
+
+/// State of a profile file compared with what was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalState {
+    Unchanged,
+    Modified,
+    Missing,
+}
+
+/// State of the origin file compared with what was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpstreamState {
+    Unchanged,
+    Updated,
+    Removed,
+    /// The origin could not be reached (remote origin not fetched)
+    Unknown,
+}
+
+/// Provenance check result for one file
+#[derive(Debug, Clone)]
+pub struct FileProvenanceStatus {
+    pub path: String,
+    pub provenance: FileProvenance,
+    pub local: LocalState,
+    pub upstream: UpstreamState,
+}
+
+impl FileProvenanceStatus {
+    /// Whether the file has drifted from its origin in this profile
+    pub fn drifted(&self) -> bool {
+        self.local != LocalState::Unchanged
+    }
+
+    /// Whether the origin has changed since the file was recorded
+    pub fn has_upstream_update(&self) -> bool {
+        matches!(
+            self.upstream,
+            UpstreamState::Updated | UpstreamState::Removed
+        )
+    }
+}
+
+/// Provenance entries for files copied from `source` to `dest`
+///
+/// Both may be a single file or a directory; for directories every file
+/// under `dest` that also exists under `source` is recorded. Paths are
+/// reported relative to the origin (`source_path`) and the profile
+/// (`dest_path`). The profile's own `.dot-agent.toml` is skipped.
+pub(crate) fn provenance_for_copy(
+    source: &Path,
+    source_path: &str,
+    dest: &Path,
+    dest_path: &str,
+    origin: &ComponentOrigin,
+    commit: Option<&str>,
+    via: &str,
+) -> Result<BTreeMap<String, FileProvenance>> {
+    let join = |base: &str, rel: &str| match (base, rel) {
+        ("" | ".", rel) => rel.to_string(),
+        (base, "") => base.to_string(),
+        (base, rel) => format!("{}/{}", base.trim_end_matches('/'), rel),
+    };
+    let recorded_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let files: Vec<PathBuf> = if dest.is_file() {
+        vec![PathBuf::new()]
+    } else {
+        WalkDir::new(dest)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.path().strip_prefix(dest).ok().map(Path::to_path_buf))
+            .filter(|rel| {
+                !dest_path.is_empty() || rel != Path::new(super::metadata::PROFILE_METADATA_FILE)
+            })
+            .collect()
+    };
+
+    let mut entries = BTreeMap::new();
+    for rel in files {
+        let (src_file, dest_file) = if rel.as_os_str().is_empty() {
+            (source.to_path_buf(), dest.to_path_buf())
+        } else {
+            (source.join(&rel), dest.join(&rel))
+        };
+        if !src_file.is_file() {
+            continue;
+        }
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        entries.insert(
+            join(dest_path, &rel),
+            FileProvenance {
+                origin: origin.clone(),
+                source_path: join(source_path, &rel),
+                commit: commit.map(str::to_string),
+                origin_hash: compute_hash(&fs::read(&src_file)?),
+                hash: compute_hash(&fs::read(&dest_file)?),
+                via: via.to_string(),
+                recorded_at: recorded_at.clone(),
+            },
+        );
+    }
+    Ok(entries)
+}
+
+/// Compare recorded file provenance with the profile and its origins
+///
+/// `resolve` maps an origin to the directory its `source_path`s are
+/// relative to, or `None` if it cannot be reached; it is called once per
+/// distinct origin.
+pub fn check_provenance(
+    profile_dir: &Path,
+    files: &BTreeMap<String, FileProvenance>,
+    mut resolve: impl FnMut(&ComponentOrigin) -> Option<PathBuf>,
+) -> Result<Vec<FileProvenanceStatus>> {
+    let mut roots: HashMap<String, Option<PathBuf>> = HashMap::new();
+    let mut statuses = Vec::with_capacity(files.len());
+
+    for (path, provenance) in files {
+        let local_file = profile_dir.join(path);
+        let local = if !local_file.is_file() {
+            LocalState::Missing
+        } else if compute_hash(&fs::read(&local_file)?) != provenance.hash {
+            LocalState::Modified
+        } else {
+            LocalState::Unchanged
+        };
+
+        let key = serde_json::to_string(&provenance.origin)?;
+        let root = roots
+            .entry(key)
+            .or_insert_with(|| resolve(&provenance.origin));
+        let upstream = match root {
+            None => UpstreamState::Unknown,
+            Some(root) => {
+                let origin_file = root.join(&provenance.source_path);
+                if !origin_file.is_file() {
+                    UpstreamState::Removed
+                } else if compute_hash(&fs::read(&origin_file)?) != provenance.origin_hash {
+                    UpstreamState::Updated
+                } else {
+                    UpstreamState::Unchanged
+                }
+            }
+        };
+
+        statuses.push(FileProvenanceStatus {
+            path: path.clone(),
+            provenance: provenance.clone(),
+            local,
+            upstream,
+        });
+    }
+
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn detects_drift_and_upstream_updates() {
+        let temp = TempDir::new().unwrap();
+        let origin_dir = temp.path().join("origin");
+        let profile_dir = temp.path().join("profile");
+        for dir in [&origin_dir, &profile_dir] {
+            fs::create_dir_all(dir.join("skills/tdd")).unwrap();
+            fs::write(dir.join("skills/tdd/SKILL.md"), "skill").unwrap();
+            fs::write(dir.join("skills/tdd/notes.md"), "notes").unwrap();
+            fs::write(dir.join("skills/tdd/extra.md"), "extra").unwrap();
+        }
+
+        let origin = ComponentOrigin::Path {
+            path: origin_dir.display().to_string(),
+        };
+        let files = provenance_for_copy(
+            &origin_dir.join("skills/tdd"),
+            "skills/tdd",
+            &profile_dir.join("skills/tdd"),
+            "skills/tdd",
+            &origin,
+            None,
+            "component",
+        )
+        .unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(
+            files["skills/tdd/SKILL.md"].source_path,
+            "skills/tdd/SKILL.md"
+        );
+
+        fs::write(profile_dir.join("skills/tdd/SKILL.md"), "edited").unwrap();
+        fs::write(origin_dir.join("skills/tdd/notes.md"), "new notes").unwrap();
+        fs::remove_file(origin_dir.join("skills/tdd/extra.md")).unwrap();
+
+        let statuses =
+            check_provenance(&profile_dir, &files, |_| Some(origin_dir.clone())).unwrap();
+        let by_path: HashMap<&str, &FileProvenanceStatus> =
+            statuses.iter().map(|s| (s.path.as_str(), s)).collect();
+        assert_eq!(by_path["skills/tdd/SKILL.md"].local, LocalState::Modified);
+        assert_eq!(
+            by_path["skills/tdd/SKILL.md"].upstream,
+            UpstreamState::Unchanged
+        );
+        assert_eq!(
+            by_path["skills/tdd/notes.md"].upstream,
+            UpstreamState::Updated
+        );
+        assert_eq!(
+            by_path["skills/tdd/extra.md"].upstream,
+            UpstreamState::Removed
+        );
+
+        let unreachable = check_provenance(&profile_dir, &files, |_| None).unwrap();
+        assert!(unreachable
+            .iter()
+            .all(|s| s.upstream == UpstreamState::Unknown));
+    }
+}
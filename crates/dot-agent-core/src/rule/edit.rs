@@ -0,0 +1,463 @@
+//! Structured edit schema for rule application.
+//!
+//! The LLM answers with a JSON edit plan instead of rewriting whole files:
+//!
+//! ```json
+//! {"edits": [
+//!   {"action": "create", "path": "rules/rust.md", "content": "# Rust\n"},
+//!   {"action": "modify", "path": "CLAUDE.md",
+//!    "hunks": [{"search": "npm test", "replace": "cargo test"}]},
+//!   {"action": "modify", "path": "agents/reviewer.md", "diff": "@@ -1,2 +1,2 @@\n..."},
+//!   {"action": "delete", "path": "rules/python.md"}
+//! ]}
+//! ```
+//!
+//! Plans are resolved against the profile in memory, so every change can be
+//! validated and previewed before anything is written.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+use crate::channel::DiffLine;
+use crate::error::{DotAgentError, Result};
+use crate::path::validate_relative_path;
+
+/// Edit plan returned by the LLM
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditPlan {
+    pub edits: Vec<FileEdit>,
+}
+
+/// A single file operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum FileEdit {
+    /// Create a file (or replace it entirely)
+    Create { path: String, content: String },
+    /// Change an existing file with search/replace hunks and/or a unified diff
+    Modify {
+        path: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        hunks: Vec<SearchReplace>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        diff: Option<String>,
+    },
+    /// Delete a file
+    Delete { path: String },
+}
+
+impl FileEdit {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Create { path, .. } | Self::Modify { path, .. } | Self::Delete { path } => path,
+        }
+    }
+}
+
+/// Replace one exact occurrence of `search` with `replace`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchReplace {
+    pub search: String,
+    pub replace: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Create,
+    Modify,
+    Delete,
+}
+
+impl FileChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Modify => "modify",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Resolved change to one file (profile-relative path)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub kind: FileChangeKind,
+    /// Content before the change (`None` when created)
+    pub before: Option<String>,
+    /// Content after the change (`None` when deleted)
+    pub after: Option<String>,
+}
+
+impl FileChange {
+    /// Line diff between `before` and `after`
+    pub fn diff(&self) -> Vec<DiffLine> {
+        let before = self.before.as_deref().unwrap_or("");
+        let after = self.after.as_deref().unwrap_or("");
+        TextDiff::from_lines(before, after)
+            .iter_all_changes()
+            .filter_map(|change| {
+                let line = change.value().trim_end_matches('\n').to_string();
+                match change.tag() {
+                    ChangeTag::Insert => Some(DiffLine::Added(line)),
+                    ChangeTag::Delete => Some(DiffLine::Removed(line)),
+                    ChangeTag::Equal => None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl EditPlan {
+    /// Parse an edit plan from LLM output (bare JSON or a fenced block)
+    pub fn parse(output: &str) -> Result<Self> {
+        let mut error = None;
+        for candidate in json_candidates(output) {
+            match serde_json::from_str(candidate) {
+                Ok(plan) => return Ok(plan),
+                Err(e) => error = Some(e),
+            }
+        }
+        let message = error.map_or_else(|| "empty response".to_string(), |e| e.to_string());
+        Err(invalid(format!(
+            "response is not a valid JSON edit plan: {}",
+            message
+        )))
+    }
+
+    /// Resolve the plan against the files under `root` without writing anything
+    ///
+    /// Edits to the same path apply in order. Paths must stay inside `root`.
+    pub fn resolve(&self, root: &Path) -> Result<Vec<FileChange>> {
+        // path -> (original, current)
+        let mut files: BTreeMap<PathBuf, (Option<String>, Option<String>)> = BTreeMap::new();
+
+        for edit in &self.edits {
+            let rel = validate_relative_path(Path::new(edit.path()))?;
+            let (_, current) = files.entry(rel.clone()).or_insert_with(|| {
+                let original = fs::read_to_string(root.join(&rel)).ok();
+                (original.clone(), original)
+            });
+
+            match edit {
+                FileEdit::Create { content, .. } => *current = Some(content.clone()),
+                FileEdit::Modify { path, hunks, diff } => {
+                    let Some(text) = current.as_deref() else {
+                        return Err(invalid(format!("{}: cannot modify a missing file", path)));
+                    };
+                    if hunks.is_empty() && diff.is_none() {
+                        return Err(invalid(format!("{}: modify needs hunks or a diff", path)));
+                    }
+                    let mut text = apply_search_replace(text, hunks)
+                        .map_err(|e| invalid(format!("{}: {}", path, e)))?;
+                    if let Some(diff) = diff {
+                        text = apply_unified_diff(&text, diff)
+                            .map_err(|e| invalid(format!("{}: {}", path, e)))?;
+                    }
+                    *current = Some(text);
+                }
+                FileEdit::Delete { path } => {
+                    if current.is_none() {
+                        return Err(invalid(format!("{}: cannot delete a missing file", path)));
+                    }
+                    *current = None;
+                }
+            }
+        }
+
+        Ok(collect_changes(files))
+    }
+}
+
+/// Turn `path -> (original, current)` into the changes that differ
+pub(crate) fn collect_changes(
+    files: BTreeMap<PathBuf, (Option<String>, Option<String>)>,
+) -> Vec<FileChange> {
+    files
+        .into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(path, (before, after))| FileChange {
+            kind: match (&before, &after) {
+                (None, _) => FileChangeKind::Create,
+                (_, None) => FileChangeKind::Delete,
+                _ => FileChangeKind::Modify,
+            },
+            path,
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// Write resolved changes under `root`. Returns the number of files touched.
+pub fn write_changes(root: &Path, changes: &[FileChange]) -> Result<usize> {
+    for change in changes {
+        let target = root.join(&change.path);
+        match &change.after {
+            Some(content) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, content)?;
+            }
+            None => {
+                if target.exists() {
+                    fs::remove_file(&target)?;
+                }
+            }
+        }
+    }
+    Ok(changes.len())
+}
+
+fn invalid(message: String) -> DotAgentError {
+    DotAgentError::InvalidRuleEdit { message }
+}
+
+/// Possible JSON objects in LLM output, most likely first: the whole output,
+/// the outermost `{...}` span, then a ```` ```json ```` block
+///
+/// Edit contents may contain code fences themselves, so a fenced block only
+/// ends at a ```` ``` ```` on its own line.
+fn json_candidates(output: &str) -> Vec<&str> {
+    let mut candidates = vec![output.trim()];
+
+    if let (Some(start), Some(end)) = (output.find('{'), output.rfind('}')) {
+        if start < end {
+            candidates.push(&output[start..=end]);
+        }
+    }
+
+    if let Some(fence) = output.find("```json") {
+        let body = &output[fence..];
+        if let Some(newline) = body.find('\n') {
+            let body = &body[newline + 1..];
+            let mut end = body.len();
+            let mut offset = 0;
+            for line in body.split_inclusive('\n') {
+                if line.trim() == "```" {
+                    end = offset;
+                    break;
+                }
+                offset += line.len();
+            }
+            candidates.push(body[..end].trim());
+        }
+    }
+
+    candidates
+}
+
+fn apply_search_replace(
+    content: &str,
+    hunks: &[SearchReplace],
+) -> std::result::Result<String, String> {
+    let mut content = content.to_string();
+    for (idx, hunk) in hunks.iter().enumerate() {
+        if hunk.search.is_empty() {
+            return Err(format!("hunk {} has an empty search text", idx + 1));
+        }
+        match content.matches(&hunk.search).count() {
+            0 => return Err(format!("hunk {}: search text not found", idx + 1)),
+            1 => content = content.replacen(&hunk.search, &hunk.replace, 1),
+            n => {
+                return Err(format!(
+                    "hunk {}: search text matches {} times; include more context",
+                    idx + 1,
+                    n
+                ))
+            }
+        }
+    }
+    Ok(content)
+}
+
+/// One `@@` hunk of a unified diff
+struct DiffHunk {
+    /// 1-based start line in the original (0 for insertion at the top)
+    old_start: usize,
+    old: Vec<String>,
+    new: Vec<String>,
+}
+
+fn parse_unified_diff(diff: &str) -> std::result::Result<Vec<DiffHunk>, String> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("@@") {
+            let old_start = header
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('-'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .ok_or_else(|| format!("malformed hunk header '{}'", line))?;
+            hunks.push(DiffHunk {
+                old_start,
+                old: Vec::new(),
+                new: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            // File headers (---/+++, diff --git) before the first hunk
+            continue;
+        };
+        match line.chars().next() {
+            Some('+') => hunk.new.push(line[1..].to_string()),
+            Some('-') => hunk.old.push(line[1..].to_string()),
+            Some(' ') => {
+                hunk.old.push(line[1..].to_string());
+                hunk.new.push(line[1..].to_string());
+            }
+            None => {
+                hunk.old.push(String::new());
+                hunk.new.push(String::new());
+            }
+            Some('\\') => {}
+            Some(_) => return Err(format!("unexpected diff line '{}'", line)),
+        }
+    }
+    if hunks.is_empty() {
+        return Err("diff has no hunks".to_string());
+    }
+    Ok(hunks)
+}
+
+/// Apply a unified diff, locating each hunk by its context lines
+fn apply_unified_diff(content: &str, diff: &str) -> std::result::Result<String, String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut cursor = 0;
+
+    for (idx, hunk) in parse_unified_diff(diff)?.into_iter().enumerate() {
+        let position = if hunk.old.is_empty() {
+            hunk.old_start.min(lines.len()).max(cursor)
+        } else {
+            let hint = hunk.old_start.saturating_sub(1).max(cursor);
+            let matches_at = |pos: &usize| lines[*pos..].starts_with(&hunk.old);
+            (hint..lines.len())
+                .find(matches_at)
+                .or_else(|| (cursor..hint.min(lines.len())).find(matches_at))
+                .ok_or_else(|| format!("diff hunk {} does not match the file", idx + 1))?
+        };
+        let new_len = hunk.new.len();
+        lines.splice(position..position + hunk.old.len(), hunk.new);
+        cursor = position + new_len;
+    }
+
+    let mut result = lines.join("\n");
+    if content.is_empty() || content.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("rules")).unwrap();
+        fs::write(
+            temp.path().join("CLAUDE.md"),
+            "# Guide\nRun npm test\nEnd\n",
+        )
+        .unwrap();
+        fs::write(temp.path().join("rules/python.md"), "# Python\n").unwrap();
+        fs::write(temp.path().join("rules/style.md"), "a\nb\nc\n").unwrap();
+
+        let output = r##"Here is the plan:
+```json
+{"edits": [
+  {"action": "modify", "path": "CLAUDE.md",
+   "hunks": [{"search": "npm test", "replace": "cargo test"}]},
+  {"action": "modify", "path": "rules/style.md", "diff": "--- a\n+++ b\n@@ -2,2 +2,2 @@\n b\n-c\n+d\n"},
+  {"action": "create", "path": "./rules/rust.md", "content": "# Rust\n"},
+  {"action": "delete", "path": "rules/python.md"}
+]}
+```"##;
+        let changes = EditPlan::parse(output)
+            .unwrap()
+            .resolve(temp.path())
+            .unwrap();
+
+        let find = |p: &str| changes.iter().find(|c| c.path == Path::new(p)).unwrap();
+        assert_eq!(changes.len(), 4);
+        assert_eq!(
+            find("CLAUDE.md").after.as_deref(),
+            Some("# Guide\nRun cargo test\nEnd\n")
+        );
+        assert_eq!(find("rules/style.md").after.as_deref(), Some("a\nb\nd\n"));
+        assert_eq!(find("rules/rust.md").kind, FileChangeKind::Create);
+        assert_eq!(find("rules/python.md").kind, FileChangeKind::Delete);
+        assert_eq!(
+            find("CLAUDE.md").diff(),
+            vec![
+                DiffLine::Removed("Run npm test".to_string()),
+                DiffLine::Added("Run cargo test".to_string()),
+            ]
+        );
+
+        write_changes(temp.path(), &changes).unwrap();
+        assert!(!temp.path().join("rules/python.md").exists());
+        assert!(temp.path().join("rules/rust.md").exists());
+    }
+
+    #[test]
+    fn test_parse_content_with_code_fences() {
+        let plan = r##"{"edits": [{"action": "create", "path": "rules/shell.md",
+  "content": "# Shell\n\n```bash\nls -la\n```\n"}]}"##;
+        let content = "# Shell\n\n```bash\nls -la\n```\n";
+
+        // Fenced, with trailing prose that breaks the outermost {...} span
+        let fenced = format!(
+            "Plan:\n```json\n{}\n```\nThe {{braces}} above are escaped.",
+            plan
+        );
+        let expected = vec![FileEdit::Create {
+            path: "rules/shell.md".to_string(),
+            content: content.to_string(),
+        }];
+        for output in [plan.to_string(), format!("Plan: {} Done.", plan), fenced] {
+            assert_eq!(EditPlan::parse(&output).unwrap().edits, expected);
+        }
+
+        assert!(matches!(
+            EditPlan::parse("no plan here"),
+            Err(DotAgentError::InvalidRuleEdit { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_edits() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.md"), "x x\n").unwrap();
+
+        let resolve = |json: &str| EditPlan::parse(json).unwrap().resolve(temp.path());
+
+        assert!(matches!(
+            resolve(r#"{"edits":[{"action":"create","path":"../escape.md","content":""}]}"#),
+            Err(DotAgentError::InvalidComponentPath { .. })
+        ));
+        assert!(matches!(
+            resolve(r#"{"edits":[{"action":"create","path":"/etc/passwd","content":""}]}"#),
+            Err(DotAgentError::InvalidComponentPath { .. })
+        ));
+        assert!(matches!(
+            resolve(
+                r#"{"edits":[{"action":"modify","path":"a.md","hunks":[{"search":"x","replace":"y"}]}]}"#
+            ),
+            Err(DotAgentError::InvalidRuleEdit { .. })
+        ));
+        assert!(matches!(
+            resolve(r#"{"edits":[{"action":"delete","path":"missing.md"}]}"#),
+            Err(DotAgentError::InvalidRuleEdit { .. })
+        ));
+        assert!(EditPlan::parse("ACTION: CREATE").is_err());
+    }
+}
@@ -0,0 +1,392 @@
+//! Applying rules to installed files.
+//!
+//! [`RuleExecutor`](super::RuleExecutor) writes a customized copy of a
+//! profile; [`InstalledRuleExecutor`] edits the files a profile installed
+//! into a target directory (e.g. `~/.claude`) in place. Every installed file
+//! goes to the LLM in one request. Committing takes a snapshot first,
+//! records the operation in history and refreshes the install metadata
+//! hashes, so `upgrade` does not treat the rule's edits as user changes.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::{DotAgentError, Result};
+use crate::history::{HistoryManager, OperationType};
+use crate::install::metadata::compute_hash;
+use crate::install::{parse_meta_key, Metadata, SnapshotManager, SnapshotTrigger};
+use crate::llm::{ClaudeCliBackend, LlmBackend};
+
+use super::edit::{write_changes, FileChange};
+use super::{request_edit_plan, validate_changes, Rule, EDIT_PLAN_FORMAT};
+
+/// Result of applying a rule to installed files.
+#[derive(Debug)]
+pub struct InstalledApplyResult {
+    pub files_modified: usize,
+    /// Snapshot taken before writing (restore it to undo)
+    pub snapshot_id: String,
+    /// History operation recorded for the change
+    pub operation_id: String,
+}
+
+/// Applies a rule to the files installed in a target directory.
+pub struct InstalledRuleExecutor<'a> {
+    rule: &'a Rule,
+    base_dir: PathBuf,
+    target_dir: PathBuf,
+    profile: Option<String>,
+    backend: Arc<dyn LlmBackend>,
+}
+
+impl<'a> InstalledRuleExecutor<'a> {
+    pub fn new(rule: &'a Rule, base_dir: PathBuf, target_dir: PathBuf) -> Self {
+        Self {
+            rule,
+            base_dir,
+            target_dir,
+            profile: None,
+            backend: Arc::new(ClaudeCliBackend),
+        }
+    }
+
+    /// Use a different LLM backend (default: Claude CLI).
+    pub fn with_backend(mut self, backend: Arc<dyn LlmBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Only consider files installed by `profile`.
+    pub fn profile(mut self, profile: Option<&str>) -> Self {
+        self.profile = profile.map(String::from);
+        self
+    }
+
+    fn metadata(&self) -> Result<Metadata> {
+        Metadata::load(&self.target_dir)?.ok_or_else(|| DotAgentError::TargetNotFound {
+            path: self.target_dir.clone(),
+        })
+    }
+
+    /// Installed files the rule may change, relative to the target.
+    ///
+    /// Files that are not valid UTF-8 (images, compiled hooks, ...) are left
+    /// out; see [`skipped_files`](Self::skipped_files).
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        let files = self.installed_files()?;
+        Ok(files.into_iter().filter(|rel| self.is_text(rel)).collect())
+    }
+
+    /// Installed files the rule leaves alone because they are not text.
+    pub fn skipped_files(&self) -> Result<Vec<PathBuf>> {
+        let files = self.installed_files()?;
+        Ok(files.into_iter().filter(|rel| !self.is_text(rel)).collect())
+    }
+
+    fn is_text(&self, rel: &Path) -> bool {
+        fs::read_to_string(self.target_dir.join(rel)).is_ok()
+    }
+
+    fn installed_files(&self) -> Result<Vec<PathBuf>> {
+        let meta = self.metadata()?;
+        if let Some(profile) = &self.profile {
+            if !meta.installed.profiles.contains(profile) {
+                return Err(DotAgentError::ProfileNotFound {
+                    name: profile.clone(),
+                });
+            }
+        }
+
+        let mut files: Vec<PathBuf> = meta
+            .files
+            .keys()
+            .filter_map(|key| {
+                let (profile, rel) = parse_meta_key(key);
+                let wanted = self.profile.as_deref().map_or(true, |p| Some(p) == profile);
+                wanted.then(|| PathBuf::from(rel))
+            })
+            .filter(|rel| self.target_dir.join(rel).is_file())
+            .collect();
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Generate the prompt for AI.
+    ///
+    /// Files that are not valid UTF-8 are listed as skipped instead of
+    /// being included.
+    pub fn generate_prompt(&self, files: &[PathBuf]) -> Result<String> {
+        let mut files_content = String::new();
+        let mut skipped = Vec::new();
+        for rel in files {
+            let content = match fs::read_to_string(self.target_dir.join(rel)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    skipped.push(rel.display().to_string());
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            files_content.push_str(&format!("### {}\n```\n{}\n```\n\n", rel.display(), content));
+        }
+        if !skipped.is_empty() {
+            files_content.push_str(&format!(
+                "Skipped (not text, do not modify): {}\n\n",
+                skipped.join(", ")
+            ));
+        }
+
+        let mut parameters = String::new();
+        if !self.rule.params.is_empty() {
+            parameters.push_str("\n### Parameters\n\n");
+            for (name, value) in &self.rule.params {
+                parameters.push_str(&format!("- {} = {}\n", name, value));
+            }
+        }
+
+        Ok(format!(
+            r#"You are customizing installed Claude Code configuration files.
+
+## Installed Files
+
+{}
+## Customization Rule
+
+{}
+{}
+## Your Task
+
+Apply the customization rule to the installed files. Only modify or delete
+the files listed above; paths are relative to the target directory. {}"#,
+            files_content, self.rule.content, parameters, EDIT_PLAN_FORMAT
+        ))
+    }
+
+    /// Plan the changes: declarative rules run directly, other rules send
+    /// every installed file to the LLM in one request.
+    ///
+    /// Either way, changes may only touch installed files: creating or
+    /// renaming files would leave them without install metadata.
+    pub fn plan(&self) -> Result<Vec<FileChange>> {
+        let files = self.files()?;
+        if files.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if let Some(ops) = self.rule.ops()? {
+            let changes = ops.plan_files(&self.target_dir, &files)?;
+            validate_changes(&changes)?;
+            only_installed(&files, &changes)?;
+            return Ok(changes);
+        }
+
+        let prompt = self.generate_prompt(&files)?;
+        request_edit_plan(
+            self.backend.as_ref(),
+            &prompt,
+            &self.target_dir,
+            3,
+            |changes| only_installed(&files, changes),
+        )
+    }
+
+    /// Write planned changes after taking a snapshot, then record them in
+    /// history and the install metadata.
+    ///
+    /// Returns `None` without a snapshot or history entry when `changes` is
+    /// empty.
+    pub fn commit(&self, changes: &[FileChange]) -> Result<Option<InstalledApplyResult>> {
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut meta = self.metadata()?;
+        let profiles = match &self.profile {
+            Some(profile) => vec![profile.clone()],
+            None => meta.installed.profiles.clone(),
+        };
+
+        let snapshot = SnapshotManager::new(self.base_dir.clone()).save_target(
+            &self.target_dir,
+            SnapshotTrigger::PreApply,
+            Some(&format!("before rule {}", self.rule.name)),
+            &profiles,
+        )?;
+
+        let files_modified = write_changes(&self.target_dir, changes)?;
+
+        // Rule edits are not user modifications: refresh the recorded hashes
+        for change in changes {
+            let rel = change.path.to_string_lossy();
+            let keys: Vec<String> = meta
+                .files
+                .keys()
+                .filter(|key| parse_meta_key(key).1 == rel)
+                .cloned()
+                .collect();
+            for key in keys {
+                match &change.after {
+                    Some(content) => meta.add_file(&key, &compute_hash(content.as_bytes())),
+                    None => meta.remove_file(&key),
+                }
+            }
+        }
+        meta.save(&self.target_dir)?;
+
+        let mut history = HistoryManager::new(self.base_dir.clone())?;
+        let operation = history.record_operation(
+            OperationType::RuleApplyInstalled {
+                rule_name: self.rule.name.clone(),
+                target: self.target_dir.clone(),
+                profile: self.profile.clone(),
+                params: self.rule.params.clone(),
+                files_changed: changes
+                    .iter()
+                    .map(|c| c.path.display().to_string())
+                    .collect(),
+            },
+            &self.target_dir,
+        )?;
+
+        Ok(Some(InstalledApplyResult {
+            files_modified,
+            snapshot_id: snapshot.id,
+            operation_id: operation.id.as_str().to_string(),
+        }))
+    }
+}
+
+/// Reject changes to paths that are not in `files`
+fn only_installed(files: &[PathBuf], changes: &[FileChange]) -> Result<()> {
+    match changes.iter().find(|c| !files.contains(&c.path)) {
+        Some(change) => Err(DotAgentError::InvalidRuleEdit {
+            message: format!("'{}' is not an installed file", change.path.display()),
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::FixtureBackend;
+    use crate::rule::RuleManager;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_apply_to_installed_files() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().join("base");
+        let target = temp.path().join(".claude");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("CLAUDE.md"), "# Team\n\nUse npm.\n").unwrap();
+        fs::write(target.join("local.md"), "not installed\n").unwrap();
+        fs::write(target.join("logo.png"), [0x89, 0xff, 0xfe]).unwrap();
+
+        let mut meta = Metadata::new(&base);
+        meta.add_profile("p1");
+        meta.add_file("p1:CLAUDE.md", &compute_hash(b"# Team\n\nUse npm.\n"));
+        meta.add_file("p1:logo.png", &compute_hash(&[0x89, 0xff, 0xfe]));
+        meta.save(&target).unwrap();
+
+        let rules = RuleManager::new(base.clone());
+        fs::create_dir_all(rules.rules_dir()).unwrap();
+        fs::write(rules.rules_dir().join("pnpm.md"), "# pnpm\n").unwrap();
+        let rule = rules.get("pnpm").unwrap();
+
+        // The first plan touches a file that was not installed and is retried
+        let backend = FixtureBackend::new(vec![
+            r#"{"edits": [{"action": "delete", "path": "local.md"}]}"#.to_string(),
+            r#"{"edits": [{"action": "modify", "path": "CLAUDE.md", "hunks": [{"search": "npm", "replace": "pnpm"}]}]}"#.to_string(),
+        ]);
+        let executor = InstalledRuleExecutor::new(&rule, base.clone(), target.clone())
+            .with_backend(Arc::new(backend))
+            .profile(Some("p1"));
+        assert_eq!(executor.files().unwrap(), vec![PathBuf::from("CLAUDE.md")]);
+
+        // Binary files are skipped, not an error
+        assert_eq!(
+            executor.skipped_files().unwrap(),
+            vec![PathBuf::from("logo.png")]
+        );
+        let prompt = executor
+            .generate_prompt(&[PathBuf::from("CLAUDE.md"), PathBuf::from("logo.png")])
+            .unwrap();
+        assert!(prompt.contains("### CLAUDE.md"));
+        assert!(prompt.contains("Skipped (not text, do not modify): logo.png"));
+
+        let changes = executor.plan().unwrap();
+        assert_eq!(changes.len(), 1);
+
+        // Nothing accepted: no snapshot, no history entry
+        assert!(executor.commit(&[]).unwrap().is_none());
+        assert!(SnapshotManager::new(base.clone())
+            .list_target(&target)
+            .unwrap()
+            .is_empty());
+
+        let result = executor.commit(&changes).unwrap().unwrap();
+        assert_eq!(result.files_modified, 1);
+
+        let content = fs::read_to_string(target.join("CLAUDE.md")).unwrap();
+        assert_eq!(content, "# Team\n\nUse pnpm.\n");
+        assert!(target.join("local.md").exists());
+
+        // Metadata tracks the rule's edit, so it is not a user modification
+        let meta = Metadata::load(&target).unwrap().unwrap();
+        assert_eq!(
+            meta.get_file_hash("p1:CLAUDE.md").unwrap(),
+            &compute_hash(content.as_bytes())
+        );
+
+        // The pre-apply snapshot restores the original content
+        let snapshots = SnapshotManager::new(base.clone());
+        let snapshot = snapshots.get_target(&target, &result.snapshot_id).unwrap();
+        assert_eq!(snapshot.trigger, SnapshotTrigger::PreApply);
+        snapshots
+            .restore_target(&target, &result.snapshot_id)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(target.join("CLAUDE.md")).unwrap(),
+            "# Team\n\nUse npm.\n"
+        );
+
+        let history = HistoryManager::new(base).unwrap();
+        let entry = &history.list_history(Some(1))[0];
+        assert_eq!(entry.id, result.operation_id);
+        assert!(entry.summary.starts_with("rule-apply pnpm at"));
+    }
+
+    #[test]
+    fn test_rename_op_on_installed_files_is_rejected() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().join("base");
+        let target = temp.path().join(".claude");
+        fs::create_dir_all(target.join("rules")).unwrap();
+        fs::write(target.join("rules/style.md"), "# Style\n").unwrap();
+
+        let mut meta = Metadata::new(&base);
+        meta.add_profile("p1");
+        meta.add_file("p1:rules/style.md", &compute_hash(b"# Style\n"));
+        meta.save(&target).unwrap();
+
+        let rules = RuleManager::new(base.clone());
+        fs::create_dir_all(rules.rules_dir()).unwrap();
+        fs::write(
+            rules.rules_dir().join("prefix.md"),
+            "# Prefix\n\n```toml\n[[ops]]\ntype = \"rename\"\npattern = '^rules/(.*)$'\nreplacement = \"rules/team-$1\"\n```\n",
+        )
+        .unwrap();
+        let rule = rules.get("prefix").unwrap();
+
+        // The renamed file would have no metadata key, so remove/upgrade
+        // would lose track of it
+        let executor = InstalledRuleExecutor::new(&rule, base, target.clone());
+        let err = executor.plan().unwrap_err();
+        assert!(matches!(err, DotAgentError::InvalidRuleEdit { .. }));
+        assert!(err.to_string().contains("rules/team-style.md"));
+        assert!(target.join("rules/style.md").exists());
+    }
+}
@@ -3,13 +3,37 @@
 //! Rules are simple markdown files that describe how to customize a profile.
 //! They are applied to base profiles to create new customized profiles.
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use crate::error::{DotAgentError, Result};
-use crate::llm::{check_claude_cli, execute_claude};
+use crate::install::metadata::compute_hash;
+use crate::llm::{ClaudeCliBackend, LlmBackend};
 use crate::profile::{Profile, ProfileManager};
 
+pub mod edit;
+pub mod installed;
+pub mod ops;
+pub mod params;
+pub mod pipeline;
+pub mod source;
+pub mod staging;
+pub mod testing;
+
+pub use edit::write_changes;
+pub use edit::{EditPlan, FileChange, FileChangeKind, FileEdit, SearchReplace};
+pub use installed::{InstalledApplyResult, InstalledRuleExecutor};
+pub use ops::{RuleOp, RuleOps, SectionPosition};
+pub use params::{parse_param, RuleParam};
+pub use pipeline::{
+    Pipeline, PipelineRule, PipelineRun, PipelineRunner, PipelineStep, PipelineStore,
+};
+pub use source::{FetchedRule, RuleOrigin, RuleSource, RuleSources, RuleUpdate};
+pub use staging::{HunkDecision, StagedFile, StagedHunk, Staging};
+pub use testing::{Assertion, ResponseMode, RuleTestCase, RuleTestResult, RuleTestRunner};
+
 const RULES_DIR: &str = "rules";
 
 // ============================================================================
@@ -22,6 +46,8 @@ pub struct Rule {
     pub name: String,
     pub path: PathBuf,
     pub content: String,
+    /// Parameter values substituted into `content` (see [`Rule::with_params`])
+    pub params: BTreeMap<String, String>,
 }
 
 impl Rule {
@@ -46,13 +72,49 @@ impl Rule {
             name,
             path: path.to_path_buf(),
             content,
+            params: BTreeMap::new(),
+        })
+    }
+
+    /// Parameters declared in the rule's `+++` frontmatter.
+    pub fn declared_params(&self) -> Result<Vec<RuleParam>> {
+        params::declared_params(&self.name, &self.content)
+    }
+
+    /// Resolve parameter values (falling back to defaults) and substitute
+    /// them for `{{name}}` placeholders. The frontmatter is dropped.
+    pub fn with_params(&self, values: &BTreeMap<String, String>) -> Result<Rule> {
+        let declared = self.declared_params()?;
+        let resolved = params::resolve_params(&self.name, &declared, values)?;
+        let (_, body) = params::split_frontmatter(&self.content);
+
+        Ok(Rule {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            content: params::render(body, &resolved),
+            params: resolved,
         })
     }
 
+    /// Version declared in the rule's frontmatter (`version = "..."`).
+    pub fn version(&self) -> Option<String> {
+        params::declared_version(&self.content)
+    }
+
+    /// Declarative operations of this rule (`None` when it is applied by an LLM).
+    pub fn ops(&self) -> Result<Option<RuleOps>> {
+        RuleOps::from_markdown(&self.name, &self.content)
+    }
+
+    /// Directory holding the rule's test cases (`rules/<name>.tests/`).
+    pub fn tests_dir(&self) -> PathBuf {
+        self.path.with_extension("tests")
+    }
+
     /// Get a short summary (first non-empty, non-heading line).
     pub fn summary(&self) -> String {
-        self.content
-            .lines()
+        let (_, body) = params::split_frontmatter(&self.content);
+        body.lines()
             .find(|line| !line.is_empty() && !line.starts_with('#'))
             .unwrap_or("(no description)")
             .chars()
@@ -151,6 +213,15 @@ impl RuleManager {
     pub fn remove(&self, name: &str) -> Result<()> {
         let rule = self.get(name)?;
         fs::remove_file(&rule.path)?;
+        let tests_dir = rule.tests_dir();
+        if tests_dir.is_dir() {
+            fs::remove_dir_all(&tests_dir)?;
+        }
+
+        let mut sources = self.sources()?;
+        if sources.rules.remove(name).is_some() {
+            sources.save(&self.rules_dir())?;
+        }
         Ok(())
     }
 
@@ -173,6 +244,16 @@ impl RuleManager {
         }
 
         fs::rename(&old_path, &new_path)?;
+        let old_tests = old_path.with_extension("tests");
+        if old_tests.is_dir() {
+            fs::rename(&old_tests, new_path.with_extension("tests"))?;
+        }
+
+        let mut sources = self.sources()?;
+        if let Some(source) = sources.rules.remove(name) {
+            sources.rules.insert(new_name.to_string(), source);
+            sources.save(&self.rules_dir())?;
+        }
         Rule::load(&new_path)
     }
 
@@ -200,12 +281,19 @@ pub struct ApplyResult {
     pub new_profile_name: String,
     pub new_profile_path: PathBuf,
     pub files_modified: usize,
+    /// Changes proposed by the rule (written unless this was a dry run)
+    pub changes: Vec<FileChange>,
 }
 
 /// Executes a rule against a profile to create a new customized profile.
+///
+/// The LLM returns a JSON edit plan (see [`edit`]) which is resolved against
+/// the source profile in memory. [`RuleExecutor::plan`] returns the validated
+/// changes for preview; [`RuleExecutor::commit`] writes them into a copy.
 pub struct RuleExecutor<'a> {
     rule: &'a Rule,
     profile_manager: &'a ProfileManager,
+    backend: Arc<dyn LlmBackend>,
 }
 
 impl<'a> RuleExecutor<'a> {
@@ -213,7 +301,43 @@ impl<'a> RuleExecutor<'a> {
         Self {
             rule,
             profile_manager,
+            backend: Arc::new(ClaudeCliBackend),
+        }
+    }
+
+    /// Use a different LLM backend (default: Claude CLI).
+    pub fn with_backend(mut self, backend: Arc<dyn LlmBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Name of the profile created for `profile`.
+    ///
+    /// Defaults to `{profile}-{rule}`, followed by each parameter value.
+    /// Values that are not plain alphanumerics also get a short hash of the
+    /// value, so different values never share a name.
+    pub fn output_name(&self, profile: &Profile, new_name: Option<&str>) -> String {
+        if let Some(name) = new_name {
+            return name.to_string();
+        }
+        let mut name = format!("{}-{}", profile.name, self.rule.name);
+        for value in self.rule.params.values() {
+            let sanitized: String = value
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            let sanitized = sanitized.trim_matches('-');
+            if !sanitized.is_empty() {
+                name.push('-');
+                name.push_str(sanitized);
+            }
+            if sanitized != value {
+                let hash = compute_hash(value.as_bytes());
+                name.push('-');
+                name.push_str(&hash.trim_start_matches("sha256:")[..8]);
+            }
         }
+        name
     }
 
     /// Generate the full prompt for AI.
@@ -243,6 +367,16 @@ impl<'a> RuleExecutor<'a> {
             }
         }
 
+        let parameters = if self.rule.params.is_empty() {
+            String::new()
+        } else {
+            let mut section = String::from("\n### Parameters\n\n");
+            for (name, value) in &self.rule.params {
+                section.push_str(&format!("- {} = {}\n", name, value));
+            }
+            section
+        };
+
         Ok(format!(
             r#"You are customizing a Claude Code configuration profile.
 
@@ -254,25 +388,18 @@ impl<'a> RuleExecutor<'a> {
 ## Customization Rule
 
 {}
-
+{}
 ## Your Task
 
-Apply the customization rule to the profile. Output the changes in this format:
-
-```
-ACTION: CREATE|MODIFY|DELETE
-FILE: <relative path>
-CONTENT:
-<file content>
-```
-
-Only output file changes. No explanations needed.
-"#,
-            profile.name, files_content, self.rule.content
+Apply the customization rule to the profile. Paths are relative to the
+profile root. {}"#,
+            profile.name, files_content, self.rule.content, parameters, EDIT_PLAN_FORMAT
         ))
     }
 
     /// Apply the rule to create a new profile.
+    ///
+    /// With `dry_run`, the changes are planned (using the LLM) but nothing is written.
     pub fn apply(
         &self,
         profile: &Profile,
@@ -290,69 +417,168 @@ Only output file changes. No explanations needed.
         dry_run: bool,
         max_retries: usize,
     ) -> Result<ApplyResult> {
-        if !check_claude_cli() {
-            return Err(DotAgentError::ClaudeNotFound);
-        }
-
-        // Determine new profile name
-        let new_profile_name = new_name
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| format!("{}-{}", profile.name, self.rule.name));
-
-        let new_profile_path = self.profile_manager.profiles_dir().join(&new_profile_name);
-
+        let changes = self.plan_with_retries(profile, max_retries)?;
         if dry_run {
+            let new_profile_name = self.output_name(profile, new_name);
             return Ok(ApplyResult {
+                new_profile_path: self.profile_manager.profiles_dir().join(&new_profile_name),
                 new_profile_name,
-                new_profile_path,
                 files_modified: 0,
+                changes,
             });
         }
+        self.commit(profile, new_name, changes)
+    }
+
+    /// Ask the LLM for an edit plan and resolve it against `profile`.
+    pub fn plan(&self, profile: &Profile) -> Result<Vec<FileChange>> {
+        self.plan_with_retries(profile, 3)
+    }
+
+    /// Plan with automatic retries.
+    ///
+    /// Declarative rules (see [`ops`]) are planned directly, without the LLM.
+    /// Responses that are not valid edit plans, touch paths outside the
+    /// profile, do not apply cleanly or produce invalid TOML are retried with
+    /// the error appended to the prompt.
+    pub fn plan_with_retries(
+        &self,
+        profile: &Profile,
+        max_retries: usize,
+    ) -> Result<Vec<FileChange>> {
+        // Declarative rules run without the LLM
+        if let Some(ops) = self.rule.ops()? {
+            let changes = ops.plan(&profile.path)?;
+            validate_changes(&changes)?;
+            return Ok(changes);
+        }
 
-        // Copy base profile to new location
+        let prompt = self.generate_prompt(profile)?;
+        request_edit_plan(
+            self.backend.as_ref(),
+            &prompt,
+            &profile.path,
+            max_retries,
+            |_| Ok(()),
+        )
+    }
+
+    /// Copy `profile` and write planned changes into the copy.
+    pub fn commit(
+        &self,
+        profile: &Profile,
+        new_name: Option<&str>,
+        changes: Vec<FileChange>,
+    ) -> Result<ApplyResult> {
+        let new_profile_name = self.output_name(profile, new_name);
         let new_profile =
             self.profile_manager
                 .import_profile(&profile.path, &new_profile_name, false)?;
 
-        // Generate prompt and execute AI with retry on validation failure
-        let prompt = self.generate_prompt(profile)?;
-        let mut last_error = None;
+        let files_modified = write_changes(&new_profile.path, &changes)?;
+        self.profile_manager.record_profile_copy(
+            &new_profile.path,
+            profile,
+            &format!("rule:{}", self.rule.name),
+        )?;
+
+        Ok(ApplyResult {
+            new_profile_name,
+            new_profile_path: new_profile.path,
+            files_modified,
+            changes,
+        })
+    }
+}
+
+/// Empty working directory for the LLM, removed on drop
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("dot-agent-rule-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
 
-        for attempt in 0..=max_retries {
-            if attempt > 0 {
+/// Output format instructions shared by the rule prompts
+const EDIT_PLAN_FORMAT: &str = r#"Output ONLY a JSON edit plan:
+
+```json
+{"edits": [
+  {"action": "create", "path": "rules/example.md", "content": "full file content"},
+  {"action": "modify", "path": "CLAUDE.md",
+    "hunks": [{"search": "exact existing text", "replace": "new text"}]},
+  {"action": "modify", "path": "agents/reviewer.md", "diff": "@@ -3,2 +3,2 @@\n context\n-old line\n+new line\n"},
+  {"action": "delete", "path": "rules/obsolete.md"}
+]}
+```
+
+- Prefer "modify" with small search/replace hunks over rewriting files. Each
+  search text must match exactly once in the current file.
+- "diff" may be used instead of hunks; it is a unified diff of that one file.
+- Output nothing but the JSON.
+"#;
+
+/// Ask the LLM for an edit plan and resolve it against `root`.
+///
+/// Plans that fail to parse, resolve, validate or pass `check` are retried
+/// with the error appended to the prompt.
+fn request_edit_plan(
+    backend: &dyn LlmBackend,
+    prompt: &str,
+    root: &Path,
+    max_retries: usize,
+    check: impl Fn(&[FileChange]) -> Result<()>,
+) -> Result<Vec<FileChange>> {
+    backend.check()?;
+
+    let workdir = ScratchDir::new()?;
+    let mut last_error = None;
+
+    for attempt in 0..=max_retries {
+        let prompt = match &last_error {
+            Some(e) => {
                 eprintln!(
                     "Validation failed, retrying... (attempt {}/{})",
                     attempt + 1,
                     max_retries + 1
                 );
+                format!(
+                        "{}\n## Previous Attempt\n\nYour previous edit plan was rejected: {}\nReturn a corrected edit plan.\n",
+                        prompt, e
+                    )
             }
-
-            let output = execute_claude(&new_profile.path, &prompt)?;
-            let files_modified = apply_ai_output(&new_profile.path, &output)?;
-
-            // Validate the generated profile
-            match validate_profile(&new_profile.path) {
-                Ok(()) => {
-                    return Ok(ApplyResult {
-                        new_profile_name,
-                        new_profile_path: new_profile.path,
-                        files_modified,
-                    });
-                }
-                Err(e) => {
-                    last_error = Some(e);
-                    // Continue to retry
-                }
-            }
+            None => prompt.to_string(),
+        };
+
+        let output = backend.complete(&workdir.0, &prompt)?;
+        let result = EditPlan::parse(&output)
+            .and_then(|plan| plan.resolve(root))
+            .and_then(|changes| {
+                validate_changes(&changes)?;
+                check(&changes)?;
+                Ok(changes)
+            });
+        match result {
+            Ok(changes) => return Ok(changes),
+            Err(e) => last_error = Some(e),
         }
-
-        // All retries exhausted
-        Err(
-            last_error.unwrap_or_else(|| DotAgentError::ClaudeExecutionFailed {
-                message: "Validation failed after all retries".to_string(),
-            }),
-        )
     }
+
+    // All retries exhausted
+    Err(
+        last_error.unwrap_or_else(|| DotAgentError::ClaudeExecutionFailed {
+            message: "Validation failed after all retries".to_string(),
+        }),
+    )
 }
 
 // ============================================================================
@@ -360,10 +586,13 @@ Only output file changes. No explanations needed.
 // ============================================================================
 
 /// Extract a rule from an existing profile using AI.
-pub fn extract_rule(profile: &Profile, rule_name: &str, manager: &RuleManager) -> Result<Rule> {
-    if !check_claude_cli() {
-        return Err(DotAgentError::ClaudeNotFound);
-    }
+pub fn extract_rule(
+    profile: &Profile,
+    rule_name: &str,
+    manager: &RuleManager,
+    backend: &dyn LlmBackend,
+) -> Result<Rule> {
+    backend.check()?;
 
     // Collect profile files
     let mut files_content = String::new();
@@ -408,7 +637,7 @@ Output ONLY the rule content in markdown format. Start with a heading.
     let rules_dir = manager.rules_dir();
     fs::create_dir_all(&rules_dir)?;
 
-    let generated_content = execute_claude(&rules_dir, &extract_prompt)?;
+    let generated_content = backend.complete(&rules_dir, &extract_prompt)?;
 
     // Create the rule file
     let rule_path = manager.rules_dir().join(format!("{}.md", rule_name));
@@ -423,10 +652,9 @@ pub fn generate_rule(
     instruction: &str,
     rule_name: Option<&str>,
     manager: &RuleManager,
+    backend: &dyn LlmBackend,
 ) -> Result<Rule> {
-    if !check_claude_cli() {
-        return Err(DotAgentError::ClaudeNotFound);
-    }
+    backend.check()?;
 
     let rules_dir = manager.rules_dir();
     fs::create_dir_all(&rules_dir)?;
@@ -449,7 +677,7 @@ Start with a heading like "# {} Customization Rule"
 "##,
                 instruction, name
             );
-            let content = execute_claude(&rules_dir, &prompt)?;
+            let content = backend.complete(&rules_dir, &prompt)?;
             (name.to_string(), content)
         }
         None => {
@@ -475,7 +703,7 @@ Then output a markdown document that includes:
 "##,
                 instruction
             );
-            let content = execute_claude(&rules_dir, &prompt)?;
+            let content = backend.complete(&rules_dir, &prompt)?;
             let (name, content) = parse_name_from_output(&content)?;
             (name, content)
         }
@@ -518,81 +746,22 @@ fn parse_name_from_output(output: &str) -> Result<(String, String)> {
 // Helpers
 // ============================================================================
 
-/// Validate a profile by checking all TOML files can be parsed.
-fn validate_profile(profile_path: &Path) -> Result<()> {
-    for entry in walkdir::WalkDir::new(profile_path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "toml"))
-    {
-        let path = entry.path();
-        let content = fs::read_to_string(path)?;
-        if let Err(e) = content.parse::<toml::Table>() {
-            return Err(DotAgentError::TomlError {
-                path: path.to_path_buf(),
-                message: e.to_string(),
-            });
-        }
-    }
-    Ok(())
-}
-
-fn apply_ai_output(profile_path: &Path, output: &str) -> Result<usize> {
-    let mut files_modified = 0;
-
-    let mut lines = output.lines().peekable();
-    while let Some(line) = lines.next() {
-        if line.starts_with("ACTION:") {
-            let action = line.trim_start_matches("ACTION:").trim();
-
-            let file_line = lines.next().unwrap_or("");
-            if !file_line.starts_with("FILE:") {
-                continue;
-            }
-            let file_path = file_line.trim_start_matches("FILE:").trim();
-
-            let content_line = lines.next().unwrap_or("");
-            if !content_line.starts_with("CONTENT:") {
-                continue;
-            }
-
-            let mut content = String::new();
-            while let Some(line) = lines.peek() {
-                if line.starts_with("ACTION:") {
-                    break;
-                }
-                let line = lines.next().unwrap_or("");
-                // Skip markdown code block markers
-                if line.trim().starts_with("```") {
-                    continue;
-                }
-                content.push_str(line);
-                content.push('\n');
-            }
-
-            let target_path = profile_path.join(file_path);
-
-            match action {
-                "CREATE" | "MODIFY" => {
-                    if let Some(parent) = target_path.parent() {
-                        fs::create_dir_all(parent)?;
-                    }
-                    fs::write(&target_path, content.trim())?;
-                    files_modified += 1;
-                }
-                "DELETE" => {
-                    if target_path.exists() {
-                        fs::remove_file(&target_path)?;
-                        files_modified += 1;
-                    }
-                }
-                _ => {}
+/// Validate planned changes: every resulting TOML file must parse.
+fn validate_changes(changes: &[FileChange]) -> Result<()> {
+    for change in changes {
+        let Some(content) = &change.after else {
+            continue;
+        };
+        if change.path.extension().is_some_and(|ext| ext == "toml") {
+            if let Err(e) = content.parse::<toml::Table>() {
+                return Err(DotAgentError::TomlError {
+                    path: change.path.clone(),
+                    message: e.to_string(),
+                });
             }
         }
     }
-
-    Ok(files_modified)
+    Ok(())
 }
 
 fn validate_name(name: &str) -> Result<()> {
@@ -714,4 +883,119 @@ mod tests {
             Err(DotAgentError::RuleAlreadyExists { .. })
         ));
     }
+
+    #[test]
+    fn test_apply_and_generate_with_fixture_backend() {
+        use crate::llm::FixtureBackend;
+
+        let temp = TempDir::new().unwrap();
+        let profile_manager = ProfileManager::new(temp.path().to_path_buf());
+        let profile = profile_manager.create_profile("base").unwrap();
+        let rules = RuleManager::new(temp.path().to_path_buf());
+
+        let backend = FixtureBackend::new(vec![
+            "NAME: rust-style\n# Rust Style\n\nUse clippy.\n".to_string()
+        ]);
+        let rule = generate_rule("use clippy", None, &rules, &backend).unwrap();
+        assert_eq!(rule.name, "rust-style");
+        assert!(rule.content.starts_with("# Rust Style"));
+
+        let backend = Arc::new(FixtureBackend::new(vec![
+            r##"{"edits": [{"action": "create", "path": "rules/rust.md", "content": "# Rust\n"}]}"##
+                .to_string(),
+        ]));
+        let result = RuleExecutor::new(&rule, &profile_manager)
+            .with_backend(backend)
+            .apply(&profile, Some("base-rust"), false)
+            .unwrap();
+        assert_eq!(result.files_modified, 1);
+        assert_eq!(
+            fs::read_to_string(result.new_profile_path.join("rules/rust.md"))
+                .unwrap()
+                .trim(),
+            "# Rust"
+        );
+    }
+
+    #[test]
+    fn test_apply_declarative_rule_without_llm() {
+        use crate::llm::FixtureBackend;
+
+        let temp = TempDir::new().unwrap();
+        let profile_manager = ProfileManager::new(temp.path().to_path_buf());
+        let profile = profile_manager.create_profile("base").unwrap();
+        fs::write(profile.path.join("CLAUDE.md"), "Run npm test.\n").unwrap();
+
+        let rules = RuleManager::new(temp.path().to_path_buf());
+        let rule = rules.create("pnpm").unwrap();
+        fs::write(
+            &rule.path,
+            "# pnpm\n\n```toml\n[[ops]]\ntype = \"replace\"\npattern = \"npm\"\nreplacement = \"pnpm\"\n```\n",
+        )
+        .unwrap();
+        let rule = rules.get("pnpm").unwrap();
+
+        // An unusable LLM response proves the backend is never consulted
+        let backend = Arc::new(FixtureBackend::new(vec!["not json".to_string()]));
+        let result = RuleExecutor::new(&rule, &profile_manager)
+            .with_backend(backend)
+            .apply(&profile, None, false)
+            .unwrap();
+        assert_eq!(result.new_profile_name, "base-pnpm");
+        assert_eq!(
+            fs::read_to_string(result.new_profile_path.join("CLAUDE.md")).unwrap(),
+            "Run pnpm test.\n"
+        );
+    }
+
+    #[test]
+    fn test_parameterized_rule() {
+        let temp = TempDir::new().unwrap();
+        let profile_manager = ProfileManager::new(temp.path().to_path_buf());
+        let profile = profile_manager.create_profile("base").unwrap();
+        let rules = RuleManager::new(temp.path().to_path_buf());
+        let rule = rules.create("convert").unwrap();
+        fs::write(
+            &rule.path,
+            "+++\n[[params]]\nname = \"lang\"\nvalues = [\"go\", \"rust\"]\n+++\n# Convert\n\nRewrite examples in {{lang}}.\n",
+        )
+        .unwrap();
+        let rule = rules.get("convert").unwrap();
+        assert_eq!(rule.summary(), "Rewrite examples in {{lang}}.");
+        assert!(rule.with_params(&BTreeMap::new()).is_err());
+
+        let values = BTreeMap::from([("lang".to_string(), "go".to_string())]);
+        let rule = rule.with_params(&values).unwrap();
+        let executor = RuleExecutor::new(&rule, &profile_manager);
+        assert_eq!(executor.output_name(&profile, None), "base-convert-go");
+
+        // Values that do not sanitize cleanly stay distinct and never end in '-'
+        fs::write(
+            rules.rules_dir().join("tag.md"),
+            "+++\n[[params]]\nname = \"tag\"\n+++\n# Tag\n\nTag with {{tag}}.\n",
+        )
+        .unwrap();
+        let tag = rules.get("tag").unwrap();
+        let name_for = |value: &str| {
+            let values = BTreeMap::from([("tag".to_string(), value.to_string())]);
+            let rule = tag.with_params(&values).unwrap();
+            RuleExecutor::new(&rule, &profile_manager).output_name(&profile, None)
+        };
+        let names: Vec<String> = ["a b", "a-b", "!!!", "???", "ok"]
+            .into_iter()
+            .map(name_for)
+            .collect();
+        assert_eq!(names[4], "base-tag-ok");
+        assert!(names[0].starts_with("base-tag-a-b-"));
+        assert!(names.iter().all(|n| !n.ends_with('-')));
+        let mut unique = names.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len());
+
+        let prompt = executor.generate_prompt(&profile).unwrap();
+        assert!(prompt.contains("Rewrite examples in go."));
+        assert!(prompt.contains("- lang = go"));
+        assert!(!prompt.contains("+++"));
+    }
 }
@@ -0,0 +1,485 @@
+//! Declarative (non-LLM) rule operations.
+//!
+//! A rule whose markdown contains a fenced `toml` block with `[[ops]]` tables
+//! is run mechanically instead of being sent to an LLM:
+//!
+//! ````markdown
+//! # Use pnpm
+//!
+//! ```toml
+//! [[ops]]
+//! type = "replace"
+//! pattern = '\bnpm\b'
+//! replacement = "pnpm"
+//! include = ["**/*.md"]
+//!
+//! [[ops]]
+//! type = "delete-file"
+//! include = ["rules/**"]
+//! matching = "(?i)python"
+//!
+//! [[ops]]
+//! type = "insert-section"
+//! include = ["agents/*.md"]
+//! heading = "## Safety"
+//! content = "Never push to main."
+//!
+//! [[ops]]
+//! type = "rename"
+//! pattern = '^rules/(.*)\.md$'
+//! replacement = "rules/team-$1.md"
+//! ```
+//! ````
+//!
+//! Operations run in order against the files in memory and produce the same
+//! [`FileChange`]s as an LLM edit plan.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+use crate::path::validate_relative_path;
+
+use super::edit::{collect_changes, FileChange};
+
+/// Operations of a declarative rule
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleOps {
+    #[serde(default)]
+    pub ops: Vec<RuleOp>,
+}
+
+/// A single declarative operation
+///
+/// `include` / `exclude` are globs over profile-relative paths. `*` stays
+/// within one directory; use `**` to match across directories (`**/*.md`
+/// matches every markdown file). An empty `include` matches every file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RuleOp {
+    /// Regex replace in file contents (`$1` refers to capture groups)
+    Replace {
+        pattern: String,
+        replacement: String,
+        #[serde(default)]
+        include: Vec<String>,
+        #[serde(default)]
+        exclude: Vec<String>,
+    },
+    /// Add a markdown section unless a line equal to `heading` already exists
+    InsertSection {
+        heading: String,
+        content: String,
+        #[serde(default)]
+        position: SectionPosition,
+        #[serde(default)]
+        include: Vec<String>,
+        #[serde(default)]
+        exclude: Vec<String>,
+    },
+    /// Delete files (only those whose content matches `matching`, if set)
+    DeleteFile {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        matching: Option<String>,
+        #[serde(default)]
+        include: Vec<String>,
+        #[serde(default)]
+        exclude: Vec<String>,
+    },
+    /// Rename files by a regex over their relative path
+    Rename {
+        pattern: String,
+        replacement: String,
+        #[serde(default)]
+        include: Vec<String>,
+        #[serde(default)]
+        exclude: Vec<String>,
+    },
+}
+
+/// Where `insert-section` puts the section
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionPosition {
+    Start,
+    #[default]
+    End,
+}
+
+impl RuleOp {
+    fn scope(&self) -> (&[String], &[String]) {
+        match self {
+            Self::Replace {
+                include, exclude, ..
+            }
+            | Self::InsertSection {
+                include, exclude, ..
+            }
+            | Self::DeleteFile {
+                include, exclude, ..
+            }
+            | Self::Rename {
+                include, exclude, ..
+            } => (include, exclude),
+        }
+    }
+}
+
+impl RuleOps {
+    /// Parse the `[[ops]]` block from rule markdown (`None` for LLM rules)
+    pub fn from_markdown(rule_name: &str, content: &str) -> Result<Option<Self>> {
+        let mut rest = content;
+        while let Some(start) = rest.find("```toml") {
+            let body_start = start + "```toml".len();
+            let Some(len) = rest[body_start..].find("```") else {
+                break;
+            };
+            let body = &rest[body_start..body_start + len];
+            rest = &rest[body_start + len + 3..];
+
+            if !body.contains("[[ops]]") {
+                continue;
+            }
+            let ops: Self = toml::from_str(body).map_err(|e| DotAgentError::InvalidRule {
+                name: rule_name.to_string(),
+                message: e.to_string(),
+            })?;
+            ops.validate(rule_name)?;
+            return Ok(Some(ops));
+        }
+        Ok(None)
+    }
+
+    /// Check that every pattern compiles
+    fn validate(&self, rule_name: &str) -> Result<()> {
+        let invalid = |message: String| DotAgentError::InvalidRule {
+            name: rule_name.to_string(),
+            message,
+        };
+        for (idx, op) in self.ops.iter().enumerate() {
+            let (include, exclude) = op.scope();
+            for glob in include.iter().chain(exclude) {
+                Pattern::new(glob)
+                    .map_err(|e| invalid(format!("op {}: glob '{}': {}", idx + 1, glob, e)))?;
+            }
+            let regex = match op {
+                RuleOp::Replace { pattern, .. } | RuleOp::Rename { pattern, .. } => Some(pattern),
+                RuleOp::DeleteFile { matching, .. } => matching.as_ref(),
+                RuleOp::InsertSection { .. } => None,
+            };
+            if let Some(regex) = regex {
+                Regex::new(regex)
+                    .map_err(|e| invalid(format!("op {}: regex '{}': {}", idx + 1, regex, e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the operations against every text file under `root`
+    pub fn plan(&self, root: &Path) -> Result<Vec<FileChange>> {
+        let files: Vec<PathBuf> = walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .filter_map(|e| e.path().strip_prefix(root).ok().map(Path::to_path_buf))
+            .filter(|rel| !is_internal(rel))
+            .collect();
+        self.plan_files(root, &files)
+    }
+
+    /// Run the operations against the given files (relative to `root`)
+    ///
+    /// Files that are not valid UTF-8 are left alone.
+    pub fn plan_files(&self, root: &Path, files: &[PathBuf]) -> Result<Vec<FileChange>> {
+        let mut state: BTreeMap<PathBuf, (Option<String>, Option<String>)> = files
+            .iter()
+            .filter_map(|rel| {
+                let content = fs::read_to_string(root.join(rel)).ok()?;
+                Some((rel.clone(), (Some(content.clone()), Some(content))))
+            })
+            .collect();
+
+        for op in &self.ops {
+            let (include, exclude) = op.scope();
+            let include = compile_globs(include);
+            let exclude = compile_globs(exclude);
+            let selected: Vec<PathBuf> = state
+                .iter()
+                .filter(|(_, (_, current))| current.is_some())
+                .map(|(path, _)| path.clone())
+                .filter(|path| {
+                    (include.is_empty() || include.iter().any(|p| glob_matches(p, path)))
+                        && !exclude.iter().any(|p| glob_matches(p, path))
+                })
+                .collect();
+
+            for path in selected {
+                let (_, current) = state.get_mut(&path).expect("selected from state");
+                let text = current.clone().unwrap_or_default();
+                match op {
+                    RuleOp::Replace {
+                        pattern,
+                        replacement,
+                        ..
+                    } => {
+                        let re = compile_regex(pattern)?;
+                        *current = Some(re.replace_all(&text, replacement.as_str()).into_owned());
+                    }
+                    RuleOp::InsertSection {
+                        heading,
+                        content,
+                        position,
+                        ..
+                    } => {
+                        *current = Some(insert_section(&text, heading, content, *position));
+                    }
+                    RuleOp::DeleteFile { matching, .. } => {
+                        let matched = match matching {
+                            Some(regex) => compile_regex(regex)?.is_match(&text),
+                            None => true,
+                        };
+                        if matched {
+                            *current = None;
+                        }
+                    }
+                    RuleOp::Rename {
+                        pattern,
+                        replacement,
+                        ..
+                    } => {
+                        let re = compile_regex(pattern)?;
+                        let from = path.to_string_lossy().replace('\\', "/");
+                        if !re.is_match(&from) {
+                            continue;
+                        }
+                        let to = validate_relative_path(Path::new(
+                            re.replace(&from, replacement.as_str()).as_ref(),
+                        ))?;
+                        if to == path {
+                            continue;
+                        }
+                        // Files outside `files` (e.g., not installed) still block the rename
+                        let occupied = match state.get(&to) {
+                            Some((_, current)) => current.is_some(),
+                            None => root.join(&to).exists(),
+                        };
+                        if occupied {
+                            return Err(DotAgentError::InvalidRuleEdit {
+                                message: format!(
+                                    "rename {} -> {}: target already exists",
+                                    from,
+                                    to.display()
+                                ),
+                            });
+                        }
+                        state.get_mut(&path).expect("selected from state").1 = None;
+                        let original = fs::read_to_string(root.join(&to)).ok();
+                        state.entry(to).or_insert((original, None)).1 = Some(text);
+                    }
+                }
+            }
+        }
+
+        Ok(collect_changes(state))
+    }
+}
+
+/// dot-agent's own metadata files are never touched
+pub(crate) fn is_internal(rel: &Path) -> bool {
+    rel.components().any(|c| {
+        let name = c.as_os_str().to_string_lossy();
+        name == ".git" || name.starts_with(".dot-agent")
+    })
+}
+
+fn compile_globs(globs: &[String]) -> Vec<Pattern> {
+    globs.iter().filter_map(|g| Pattern::new(g).ok()).collect()
+}
+
+/// Match a glob with `*` and `?` stopping at `/`
+fn glob_matches(pattern: &Pattern, path: &Path) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    pattern.matches_path_with(path, options)
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| DotAgentError::InvalidRuleEdit {
+        message: format!("regex '{}': {}", pattern, e),
+    })
+}
+
+fn insert_section(text: &str, heading: &str, content: &str, position: SectionPosition) -> String {
+    if text.lines().any(|line| line.trim() == heading.trim()) {
+        return text.to_string();
+    }
+
+    let section = format!("{}\n\n{}\n", heading.trim(), content.trim_end());
+    match position {
+        SectionPosition::Start => format!("{}\n{}", section, text),
+        SectionPosition::End if text.trim().is_empty() => section,
+        SectionPosition::End => format!("{}\n\n{}", text.trim_end(), section),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule::FileChangeKind;
+    use tempfile::TempDir;
+
+    const RULE: &str = r###"# Team conventions
+
+Mechanical cleanup.
+
+```toml
+[[ops]]
+type = "replace"
+pattern = '\bnpm\b'
+replacement = "pnpm"
+include = ["**/*.md"]
+
+[[ops]]
+type = "delete-file"
+include = ["rules/**"]
+matching = "(?i)python"
+
+[[ops]]
+type = "insert-section"
+include = ["agents/*.md"]
+heading = "## Safety"
+content = "Never push to main."
+
+[[ops]]
+type = "rename"
+pattern = '^rules/(.*)\.md$'
+replacement = "rules/team-$1.md"
+```
+"###;
+
+    #[test]
+    fn test_parse_ops() {
+        let ops = RuleOps::from_markdown("team", RULE).unwrap().unwrap();
+        assert_eq!(ops.ops.len(), 4);
+        assert!(matches!(
+            &ops.ops[2],
+            RuleOp::InsertSection {
+                position: SectionPosition::End,
+                ..
+            }
+        ));
+
+        assert!(RuleOps::from_markdown("plain", "# Rust\n\nUse cargo.\n")
+            .unwrap()
+            .is_none());
+
+        let bad =
+            "```toml\n[[ops]]\ntype = \"replace\"\npattern = \"(\"\nreplacement = \"\"\n```\n";
+        assert!(matches!(
+            RuleOps::from_markdown("bad", bad),
+            Err(DotAgentError::InvalidRule { .. })
+        ));
+    }
+
+    #[test]
+    fn test_plan_ops() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("rules")).unwrap();
+        fs::create_dir_all(root.join("agents")).unwrap();
+        fs::write(root.join("CLAUDE.md"), "Run npm test.\n").unwrap();
+        fs::write(root.join("rules/python.md"), "# Python\n").unwrap();
+        fs::write(root.join("rules/rust.md"), "# Rust\nnpm is not used.\n").unwrap();
+        fs::write(root.join("agents/a.md"), "# A\n").unwrap();
+        fs::write(root.join("agents/b.md"), "# B\n\n## Safety\n\nOk.\n").unwrap();
+        fs::write(root.join(".dot-agent.toml"), "npm").unwrap();
+
+        let ops = RuleOps::from_markdown("team", RULE).unwrap().unwrap();
+        let changes = ops.plan(root).unwrap();
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.path.to_string_lossy().to_string(), c.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("CLAUDE.md".to_string(), FileChangeKind::Modify),
+                ("agents/a.md".to_string(), FileChangeKind::Modify),
+                ("rules/python.md".to_string(), FileChangeKind::Delete),
+                ("rules/rust.md".to_string(), FileChangeKind::Delete),
+                ("rules/team-rust.md".to_string(), FileChangeKind::Create),
+            ]
+        );
+        assert_eq!(changes[0].after.as_deref(), Some("Run pnpm test.\n"));
+        assert_eq!(
+            changes[1].after.as_deref(),
+            Some("# A\n\n## Safety\n\nNever push to main.\n")
+        );
+        assert_eq!(
+            changes[4].after.as_deref(),
+            Some("# Rust\npnpm is not used.\n")
+        );
+
+        // Reproducible: the same input gives the same plan
+        assert_eq!(ops.plan(root).unwrap(), changes);
+    }
+
+    #[test]
+    fn test_glob_scope_and_rename_collisions() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("rules/lang")).unwrap();
+        fs::write(root.join("rules/a.md"), "npm\n").unwrap();
+        fs::write(root.join("rules/lang/b.md"), "npm\n").unwrap();
+
+        // `*` does not cross directories, `**` does
+        let ops = |include: &str| RuleOps {
+            ops: vec![RuleOp::Replace {
+                pattern: "npm".to_string(),
+                replacement: "pnpm".to_string(),
+                include: vec![include.to_string()],
+                exclude: Vec::new(),
+            }],
+        };
+        let paths = |include: &str| -> Vec<PathBuf> {
+            ops(include)
+                .plan(root)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.path)
+                .collect()
+        };
+        assert_eq!(paths("rules/*.md"), vec![PathBuf::from("rules/a.md")]);
+        assert_eq!(paths("rules/**/*.md").len(), 2);
+
+        // A rename onto a file outside the planned set is refused
+        fs::write(root.join("rules/team-a.md"), "local\n").unwrap();
+        let rename = RuleOps {
+            ops: vec![RuleOp::Rename {
+                pattern: "^rules/(.*)$".to_string(),
+                replacement: "rules/team-$1".to_string(),
+                include: Vec::new(),
+                exclude: Vec::new(),
+            }],
+        };
+        assert!(matches!(
+            rename.plan_files(root, &[PathBuf::from("rules/a.md")]),
+            Err(DotAgentError::InvalidRuleEdit { .. })
+        ));
+        fs::remove_file(root.join("rules/team-a.md")).unwrap();
+        assert_eq!(
+            rename
+                .plan_files(root, &[PathBuf::from("rules/a.md")])
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Rule parameters.
+//!
+//! A rule declares parameters in TOML frontmatter delimited by `+++` and
+//! refers to them as `{{name}}` in its body:
+//!
+//! ```markdown
+//! +++
+//! version = "1.2.0"
+//!
+//! [[params]]
+//! name = "lang"
+//! description = "Target language"
+//! default = "rust"
+//! values = ["rust", "go", "python"]
+//! +++
+//!
+//! # Convert to {{lang}}
+//!
+//! Rewrite every example in {{lang}}.
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+
+/// A declared rule parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleParam {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Used when no value is given (the parameter is required without one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Allowed values (any value when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Frontmatter {
+    /// Published version (see [`super::RuleSource`])
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    params: Vec<RuleParam>,
+}
+
+/// Split rule markdown into `(frontmatter, body)`
+pub(crate) fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content.strip_prefix("+++") else {
+        return (None, content);
+    };
+    let Some(rest) = rest
+        .strip_prefix('\n')
+        .or_else(|| rest.strip_prefix("\r\n"))
+    else {
+        return (None, content);
+    };
+    let end = if rest.starts_with("+++") {
+        Some(0)
+    } else {
+        rest.find("\n+++").map(|pos| pos + 1)
+    };
+    match end {
+        Some(end) => {
+            let body = &rest[end + 3..];
+            let body = body
+                .strip_prefix('\n')
+                .or_else(|| body.strip_prefix("\r\n"))
+                .unwrap_or(body);
+            (Some(&rest[..end]), body)
+        }
+        None => (None, content),
+    }
+}
+
+/// Parameters declared in the frontmatter of `content`
+pub(crate) fn declared_params(rule_name: &str, content: &str) -> Result<Vec<RuleParam>> {
+    let (Some(frontmatter), _) = split_frontmatter(content) else {
+        return Ok(Vec::new());
+    };
+    let parsed: Frontmatter =
+        toml::from_str(frontmatter).map_err(|e| DotAgentError::InvalidRule {
+            name: rule_name.to_string(),
+            message: format!("frontmatter: {}", e),
+        })?;
+    Ok(parsed.params)
+}
+
+/// Version declared in the frontmatter of `content`, if any
+pub(crate) fn declared_version(content: &str) -> Option<String> {
+    let (frontmatter, _) = split_frontmatter(content);
+    toml::from_str::<Frontmatter>(frontmatter?).ok()?.version
+}
+
+/// Validate `values` against the declarations and fill in defaults
+pub(crate) fn resolve_params(
+    rule_name: &str,
+    declared: &[RuleParam],
+    values: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>> {
+    let invalid = |message: String| DotAgentError::InvalidRule {
+        name: rule_name.to_string(),
+        message,
+    };
+
+    if let Some(unknown) = values
+        .keys()
+        .find(|key| !declared.iter().any(|p| &p.name == *key))
+    {
+        return Err(invalid(format!("unknown parameter '{}'", unknown)));
+    }
+
+    let mut resolved = BTreeMap::new();
+    for param in declared {
+        let value = values
+            .get(&param.name)
+            .or(param.default.as_ref())
+            .ok_or_else(|| invalid(format!("missing required parameter '{}'", param.name)))?;
+        if !param.values.is_empty() && !param.values.contains(value) {
+            return Err(invalid(format!(
+                "'{}' is not an allowed value for '{}' (expected one of: {})",
+                value,
+                param.name,
+                param.values.join(", ")
+            )));
+        }
+        resolved.insert(param.name.clone(), value.clone());
+    }
+    Ok(resolved)
+}
+
+/// Replace `{{name}}` placeholders
+pub(crate) fn render(body: &str, params: &BTreeMap<String, String>) -> String {
+    params.iter().fold(body.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
+
+/// Parse a `key=value` argument
+pub fn parse_param(arg: &str) -> Result<(String, String)> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(DotAgentError::ConfigParseSimple {
+            message: format!("invalid parameter '{}': expected key=value", arg),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULE: &str = "+++\n[[params]]\nname = \"lang\"\ndefault = \"rust\"\nvalues = [\"rust\", \"go\"]\n\n[[params]]\nname = \"team\"\n+++\n# Convert to {{lang}}\n\nFor {{team}}.\n";
+
+    #[test]
+    fn test_frontmatter_and_render() {
+        let (frontmatter, body) = split_frontmatter(RULE);
+        assert!(frontmatter.unwrap().contains("[[params]]"));
+        assert_eq!(body, "# Convert to {{lang}}\n\nFor {{team}}.\n");
+        assert_eq!(split_frontmatter("# Plain\n"), (None, "# Plain\n"));
+
+        let declared = declared_params("convert", RULE).unwrap();
+        assert_eq!(declared.len(), 2);
+        assert_eq!(declared_version(RULE), None);
+        assert_eq!(
+            declared_version("+++\nversion = \"1.2\"\n+++\n# R\n").as_deref(),
+            Some("1.2")
+        );
+
+        let values = BTreeMap::from([("team".to_string(), "infra".to_string())]);
+        let resolved = resolve_params("convert", &declared, &values).unwrap();
+        assert_eq!(resolved["lang"], "rust");
+        assert_eq!(render(body, &resolved), "# Convert to rust\n\nFor infra.\n");
+    }
+
+    #[test]
+    fn test_resolve_rejects_bad_values() {
+        let declared = declared_params("convert", RULE).unwrap();
+        let check = |pairs: &[(&str, &str)]| {
+            let values = pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            resolve_params("convert", &declared, &values)
+        };
+
+        assert!(check(&[("team", "a"), ("lang", "go")]).is_ok());
+        // Missing required, disallowed value, unknown key
+        assert!(check(&[]).is_err());
+        assert!(check(&[("team", "a"), ("lang", "cobol")]).is_err());
+        assert!(check(&[("team", "a"), ("color", "red")]).is_err());
+
+        assert_eq!(
+            parse_param("lang=go").unwrap(),
+            ("lang".to_string(), "go".to_string())
+        );
+        assert!(parse_param("lang").is_err());
+    }
+}
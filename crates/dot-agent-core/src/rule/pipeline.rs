@@ -0,0 +1,758 @@
+//! Rule pipelines.
+//!
+//! A pipeline derives an output profile from a source profile by applying
+//! rules in sequence. Pipelines are stored in `~/.dot-agent/pipelines.toml`:
+//!
+//! ```toml
+//! [pipelines.team]
+//! source = "upstream"
+//! rules = ["use-pnpm", "team-style", "security"]
+//! output = "team"
+//!
+//! [pipelines.docs]
+//! source = "upstream"
+//! rules = [{ rule = "doc-style", params = { language = "ja" } }]
+//! output = "docs"
+//! ```
+//!
+//! Each step's changes are cached under `cache/pipelines/<name>/`, keyed by
+//! the digest of the step's input files and rule, so re-running after an
+//! upstream change only calls the LLM for steps whose input changed.
+//! A run refuses to overwrite an output profile that was edited since the
+//! pipeline last wrote it, unless forced.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+use crate::history::{HistoryManager, OperationType};
+use crate::install::metadata::compute_hash;
+use crate::llm::{ClaudeCliBackend, LlmBackend};
+use crate::profile::{copy_dir_recursive, Profile, ProfileManager};
+
+use super::edit::{write_changes, FileChange, FileChangeKind};
+use super::ops::is_internal;
+use super::{validate_name, Rule, RuleExecutor, RuleManager, ScratchDir};
+
+const PIPELINES_FILE: &str = "pipelines.toml";
+const STATE_FILE: &str = "state.json";
+
+/// A named chain `source → [rules…] → output`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pipeline {
+    pub source: String,
+    pub rules: Vec<PipelineRule>,
+    pub output: String,
+}
+
+impl Pipeline {
+    /// Steps for display ("a, b(key=value)")
+    pub fn describe_rules(&self) -> String {
+        self.rules
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// One pipeline step: a rule name, or a table with parameter values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PipelineRule {
+    Name(String),
+    WithParams {
+        rule: String,
+        #[serde(default)]
+        params: BTreeMap<String, String>,
+    },
+}
+
+impl PipelineRule {
+    pub fn new(rule: impl Into<String>, params: BTreeMap<String, String>) -> Self {
+        if params.is_empty() {
+            Self::Name(rule.into())
+        } else {
+            Self::WithParams {
+                rule: rule.into(),
+                params,
+            }
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(rule) | Self::WithParams { rule, .. } => rule,
+        }
+    }
+
+    /// Parameter values set for this step (unset ones use rule defaults)
+    pub fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::Name(_) => BTreeMap::new(),
+            Self::WithParams { params, .. } => params.clone(),
+        }
+    }
+
+    /// Load the rule with this step's parameters
+    fn load(&self, rules: &RuleManager) -> Result<Rule> {
+        rules.get(self.name())?.with_params(&self.params())
+    }
+}
+
+impl std::fmt::Display for PipelineRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params = self.params();
+        if params.is_empty() {
+            return f.write_str(self.name());
+        }
+        let params: Vec<String> = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        write!(f, "{}({})", self.name(), params.join(", "))
+    }
+}
+
+/// Contents of `pipelines.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PipelineStore {
+    #[serde(default)]
+    pub pipelines: BTreeMap<String, Pipeline>,
+}
+
+impl PipelineStore {
+    /// Load pipelines from base directory
+    pub fn load(base_dir: &Path) -> Result<Self> {
+        let path = base_dir.join(PIPELINES_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| DotAgentError::ConfigParseSimple {
+            message: e.to_string(),
+        })
+    }
+
+    /// Save pipelines to base directory
+    pub fn save(&self, base_dir: &Path) -> Result<()> {
+        fs::create_dir_all(base_dir)?;
+        let content =
+            toml::to_string_pretty(self).map_err(|e| DotAgentError::ConfigParseSimple {
+                message: e.to_string(),
+            })?;
+        fs::write(base_dir.join(PIPELINES_FILE), content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Pipeline> {
+        self.pipelines
+            .get(name)
+            .ok_or_else(|| DotAgentError::PipelineNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Add or replace a pipeline
+    ///
+    /// The source profile and every rule must exist under `base_dir`, and
+    /// each step's parameters must satisfy its rule.
+    pub fn insert(&mut self, base_dir: &Path, name: &str, pipeline: Pipeline) -> Result<()> {
+        validate_name(name)?;
+        if pipeline.rules.is_empty() {
+            return Err(DotAgentError::ConfigParseSimple {
+                message: format!("pipeline '{}' has no rules", name),
+            });
+        }
+        if pipeline.source == pipeline.output {
+            return Err(DotAgentError::ConfigParseSimple {
+                message: format!("pipeline '{}' would overwrite its source profile", name),
+            });
+        }
+        ProfileManager::new(base_dir.to_path_buf()).get_profile(&pipeline.source)?;
+        let rules = RuleManager::new(base_dir.to_path_buf());
+        for step in &pipeline.rules {
+            step.load(&rules)?;
+        }
+        self.pipelines.insert(name.to_string(), pipeline);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<Pipeline> {
+        self.pipelines
+            .remove(name)
+            .ok_or_else(|| DotAgentError::PipelineNotFound {
+                name: name.to_string(),
+            })
+    }
+}
+
+/// Result of one pipeline step
+#[derive(Debug)]
+pub struct PipelineStep {
+    pub rule: String,
+    /// Parameter values the rule ran with
+    pub params: BTreeMap<String, String>,
+    /// Changes came from the step cache (no LLM call)
+    pub cached: bool,
+    pub changes: Vec<FileChange>,
+}
+
+/// Result of [`PipelineRunner::run`]
+#[derive(Debug)]
+pub struct PipelineRun {
+    pub output_profile: String,
+    pub output_path: PathBuf,
+    /// Source and rules are unchanged since the last run; nothing was done
+    pub up_to_date: bool,
+    pub steps: Vec<PipelineStep>,
+    /// History operations recorded for the steps
+    pub operation_ids: Vec<String>,
+}
+
+/// Last successful run of a pipeline
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PipelineState {
+    /// Digest of the source files, rule contents and output name
+    run_key: String,
+    /// Relative path → hash of each file the run wrote to the output
+    /// (`None` for state written before this was recorded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output_files: Option<BTreeMap<String, String>>,
+}
+
+/// Cached output of one step
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedStep {
+    /// Relative path → new content (`None` when deleted)
+    changes: BTreeMap<String, Option<String>>,
+}
+
+/// Runs a pipeline, re-applying each rule to the previous step's output.
+pub struct PipelineRunner<'a> {
+    name: &'a str,
+    pipeline: &'a Pipeline,
+    base_dir: PathBuf,
+    backend: Arc<dyn LlmBackend>,
+    force: bool,
+    refresh: bool,
+}
+
+impl<'a> PipelineRunner<'a> {
+    pub fn new(base_dir: PathBuf, name: &'a str, pipeline: &'a Pipeline) -> Self {
+        Self {
+            name,
+            pipeline,
+            base_dir,
+            backend: Arc::new(ClaudeCliBackend),
+            force: false,
+            refresh: false,
+        }
+    }
+
+    /// Use a different LLM backend (default: Claude CLI).
+    pub fn with_backend(mut self, backend: Arc<dyn LlmBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Run even if the source and rules are unchanged, and allow replacing
+    /// an output profile this pipeline did not create or that was edited
+    /// since the last run.
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Ignore cached step outputs.
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    fn cache_dir(&self) -> PathBuf {
+        self.base_dir
+            .join("cache")
+            .join("pipelines")
+            .join(self.name)
+    }
+
+    /// Apply every rule in order and write the output profile.
+    pub fn run(&self) -> Result<PipelineRun> {
+        validate_name(self.name)?;
+        let profile_manager = ProfileManager::new(self.base_dir.clone());
+        let rule_manager = RuleManager::new(self.base_dir.clone());
+
+        let source = profile_manager.get_profile(&self.pipeline.source)?;
+        let rules = self
+            .pipeline
+            .rules
+            .iter()
+            .map(|step| step.load(&rule_manager))
+            .collect::<Result<Vec<Rule>>>()?;
+
+        let output_path = profile_manager.profiles_dir().join(&self.pipeline.output);
+        let cache_dir = self.cache_dir();
+        let state: PipelineState = fs::read_to_string(cache_dir.join(STATE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let run_key = {
+            let mut key = format!("{}\n{}\n", tree_digest(&source.path)?, self.pipeline.output);
+            for rule in &rules {
+                key.push_str(&format!(
+                    "{}\n{}\n",
+                    rule.name,
+                    compute_hash(rule.content.as_bytes())
+                ));
+            }
+            compute_hash(key.as_bytes())
+        };
+
+        if output_path.exists() && !self.force {
+            if let Some(written) = &state.output_files {
+                let edited = edited_files(written, &tree_hashes(&output_path)?);
+                if !edited.is_empty() {
+                    return Err(DotAgentError::LocalModifications { paths: edited });
+                }
+            }
+            if state.run_key == run_key {
+                return Ok(PipelineRun {
+                    output_profile: self.pipeline.output.clone(),
+                    output_path,
+                    up_to_date: true,
+                    steps: Vec::new(),
+                    operation_ids: Vec::new(),
+                });
+            }
+            if state.run_key.is_empty() {
+                // Never produced by this pipeline
+                return Err(DotAgentError::ProfileAlreadyExists {
+                    name: self.pipeline.output.clone(),
+                });
+            }
+        }
+
+        // Work on a copy of the source; each step feeds the next
+        let work = ScratchDir::new()?;
+        let work_profile = Profile::new(source.name.clone(), work.0.clone());
+        copy_dir_recursive(&source.path, &work.0)?;
+
+        let mut steps = Vec::new();
+        for rule in &rules {
+            let input_digest = tree_digest(&work.0)?;
+            let step_key = compute_hash(
+                format!(
+                    "{}\n{}",
+                    input_digest,
+                    compute_hash(rule.content.as_bytes())
+                )
+                .as_bytes(),
+            );
+            let cache_path = cache_dir
+                .join(&rule.name)
+                .join(format!("{}.json", step_key.trim_start_matches("sha256:")));
+
+            let cached = (!self.refresh)
+                .then(|| fs::read_to_string(&cache_path).ok())
+                .flatten()
+                .and_then(|content| serde_json::from_str::<CachedStep>(&content).ok());
+
+            let (changes, from_cache) = match cached {
+                Some(step) => (step.into_changes(&work.0), true),
+                None => {
+                    let changes = RuleExecutor::new(rule, &profile_manager)
+                        .with_backend(self.backend.clone())
+                        .plan(&work_profile)?;
+                    if let Some(parent) = cache_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(
+                        &cache_path,
+                        serde_json::to_string_pretty(&CachedStep::from_changes(&changes))?,
+                    )?;
+                    (changes, false)
+                }
+            };
+
+            write_changes(&work.0, &changes)?;
+            steps.push(PipelineStep {
+                rule: rule.name.clone(),
+                params: rule.params.clone(),
+                cached: from_cache,
+                changes,
+            });
+        }
+
+        let output = profile_manager.import_profile(&work.0, &self.pipeline.output, true)?;
+        profile_manager.record_profile_copy(
+            &output.path,
+            &source,
+            &format!("pipeline:{}", self.name),
+        )?;
+
+        fs::create_dir_all(&cache_dir)?;
+        fs::write(
+            cache_dir.join(STATE_FILE),
+            serde_json::to_string_pretty(&PipelineState {
+                run_key,
+                output_files: Some(tree_hashes(&output.path)?),
+            })?,
+        )?;
+
+        // Each step is a rule apply; later steps start from the output
+        let mut history = HistoryManager::new(self.base_dir.clone())?;
+        let mut operation_ids = Vec::new();
+        for (i, step) in steps.iter().enumerate() {
+            let source_profile = if i == 0 {
+                self.pipeline.source.clone()
+            } else {
+                self.pipeline.output.clone()
+            };
+            let operation = history.record_operation(
+                OperationType::RuleApply {
+                    rule_name: step.rule.clone(),
+                    source_profile,
+                    output_profile: self.pipeline.output.clone(),
+                    params: step.params.clone(),
+                },
+                &output.path,
+            )?;
+            operation_ids.push(operation.id.as_str().to_string());
+        }
+
+        Ok(PipelineRun {
+            output_profile: self.pipeline.output.clone(),
+            output_path: output.path,
+            up_to_date: false,
+            steps,
+            operation_ids,
+        })
+    }
+}
+
+impl CachedStep {
+    fn from_changes(changes: &[FileChange]) -> Self {
+        Self {
+            changes: changes
+                .iter()
+                .map(|c| (c.path.to_string_lossy().to_string(), c.after.clone()))
+                .collect(),
+        }
+    }
+
+    fn into_changes(self, root: &Path) -> Vec<FileChange> {
+        self.changes
+            .into_iter()
+            .map(|(path, after)| {
+                let path = PathBuf::from(path);
+                let before = fs::read_to_string(root.join(&path)).ok();
+                FileChange {
+                    kind: match (&before, &after) {
+                        (None, _) => FileChangeKind::Create,
+                        (_, None) => FileChangeKind::Delete,
+                        _ => FileChangeKind::Modify,
+                    },
+                    path,
+                    before,
+                    after,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Digest of every file under `root` except dot-agent metadata
+fn tree_digest(root: &Path) -> Result<String> {
+    let key: String = tree_hashes(root)?
+        .iter()
+        .map(|(path, hash)| format!("{}\0{}\n", path, hash))
+        .collect();
+    Ok(compute_hash(key.as_bytes()))
+}
+
+/// Relative path → hash of every file under `root` except dot-agent metadata
+fn tree_hashes(root: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    for entry in walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+    {
+        let rel = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        if is_internal(rel) {
+            continue;
+        }
+        files.insert(
+            rel.to_string_lossy().replace('\\', "/"),
+            compute_hash(&fs::read(entry.path())?),
+        );
+    }
+    Ok(files)
+}
+
+/// Files added, changed or removed in `current` compared to `written`
+fn edited_files(
+    written: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Vec<PathBuf> {
+    let mut paths: Vec<&String> = written.keys().chain(current.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .filter(|path| written.get(*path) != current.get(*path))
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::FixtureBackend;
+    use tempfile::TempDir;
+
+    fn write_rule(base: &Path, name: &str, op: &str) {
+        fs::create_dir_all(base.join("rules")).unwrap();
+        fs::write(
+            base.join("rules").join(format!("{}.md", name)),
+            format!("# {}\n\n```toml\n[[ops]]\n{}\n```\n", name, op),
+        )
+        .unwrap();
+    }
+
+    fn steps(names: &[&str]) -> Vec<PipelineRule> {
+        names
+            .iter()
+            .map(|name| PipelineRule::Name(name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_store_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        ProfileManager::new(base.to_path_buf())
+            .create_profile("upstream")
+            .unwrap();
+        write_rule(
+            base,
+            "a",
+            "type = \"replace\"\npattern = \"a\"\nreplacement = \"b\"",
+        );
+        fs::write(
+            base.join("rules/b.md"),
+            "+++\n[[params]]\nname = \"lang\"\n+++\n# B\n\nUse {{lang}}.\n",
+        )
+        .unwrap();
+
+        let mut store = PipelineStore::load(base).unwrap();
+        let lang = BTreeMap::from([("lang".to_string(), "rust".to_string())]);
+        let pipeline = Pipeline {
+            source: "upstream".to_string(),
+            rules: vec![
+                PipelineRule::Name("a".to_string()),
+                PipelineRule::new("b", lang),
+            ],
+            output: "team".to_string(),
+        };
+        store.insert(base, "team", pipeline.clone()).unwrap();
+        store.save(base).unwrap();
+        assert!(fs::read_to_string(base.join(PIPELINES_FILE))
+            .unwrap()
+            .contains("params = { lang = \"rust\" }"));
+
+        let mut store = PipelineStore::load(base).unwrap();
+        assert_eq!(store.get("team").unwrap(), &pipeline);
+        assert_eq!(pipeline.describe_rules(), "a, b(lang=rust)");
+        assert!(store
+            .insert(
+                base,
+                "self",
+                Pipeline {
+                    output: "upstream".to_string(),
+                    ..pipeline.clone()
+                }
+            )
+            .is_err());
+
+        // Referenced profile and rules must exist, with required params set
+        let missing = |source: &str, rules: Vec<PipelineRule>| Pipeline {
+            source: source.to_string(),
+            rules,
+            output: "other".to_string(),
+        };
+        assert!(matches!(
+            store.insert(base, "x", missing("nowhere", steps(&["a"]))),
+            Err(DotAgentError::ProfileNotFound { .. })
+        ));
+        assert!(matches!(
+            store.insert(base, "x", missing("upstream", steps(&["a", "gone"]))),
+            Err(DotAgentError::RuleNotFound { .. })
+        ));
+        assert!(store
+            .insert(base, "x", missing("upstream", steps(&["b"])))
+            .is_err());
+        assert!(store.get("x").is_err());
+
+        store.remove("team").unwrap();
+        assert!(matches!(
+            store.get("team"),
+            Err(DotAgentError::PipelineNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_pipeline_with_cache() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        let profiles = ProfileManager::new(base.to_path_buf());
+        let upstream = profiles.create_profile("upstream").unwrap();
+        fs::write(upstream.path.join("CLAUDE.md"), "Run npm test.\n").unwrap();
+
+        write_rule(
+            base,
+            "pnpm",
+            "type = \"replace\"\npattern = \"npm\"\nreplacement = \"pnpm\"",
+        );
+        write_rule(
+            base,
+            "yarn",
+            "type = \"replace\"\npattern = \"pnpm\"\nreplacement = \"yarn\"",
+        );
+        fs::write(base.join("rules/llm.md"), "# LLM\n\nAdd a rules file.\n").unwrap();
+
+        let pipeline = Pipeline {
+            source: "upstream".to_string(),
+            rules: steps(&["pnpm", "llm", "yarn"]),
+            output: "team".to_string(),
+        };
+        let backend = Arc::new(FixtureBackend::new(vec![r##"{"edits": [
+            {"action": "create", "path": "rules/team.md", "content": "Use pnpm\n"}
+        ]}"##
+            .to_string()]));
+        let runner = PipelineRunner::new(base.to_path_buf(), "team", &pipeline)
+            .with_backend(backend.clone());
+
+        let run = runner.run().unwrap();
+        assert!(!run.up_to_date);
+        assert_eq!(run.steps.len(), 3);
+        assert_eq!(run.operation_ids.len(), 3);
+        let history = HistoryManager::new(base.to_path_buf()).unwrap();
+        assert_eq!(history.list_history(Some(1))[0].id, run.operation_ids[2]);
+        assert!(run.steps.iter().all(|s| !s.cached));
+        assert_eq!(
+            fs::read_to_string(run.output_path.join("CLAUDE.md")).unwrap(),
+            "Run yarn test.\n"
+        );
+        assert_eq!(
+            fs::read_to_string(run.output_path.join("rules/team.md")).unwrap(),
+            "Use yarn\n"
+        );
+
+        // Nothing changed: skipped
+        assert!(runner.run().unwrap().up_to_date);
+
+        // Source changed: every step sees new input, so nothing is cached
+        fs::write(upstream.path.join("README.md"), "npm\n").unwrap();
+        let run = runner.run().unwrap();
+        assert!(!run.up_to_date);
+        assert!(run.steps.iter().all(|s| !s.cached));
+        assert_eq!(
+            fs::read_to_string(run.output_path.join("README.md")).unwrap(),
+            "yarn\n"
+        );
+
+        // Hand edits to the output are neither reported as up to date nor
+        // overwritten
+        fs::write(run.output_path.join("CLAUDE.md"), "Run yarn test.\nMine\n").unwrap();
+        fs::write(run.output_path.join("NOTES.md"), "mine\n").unwrap();
+        match runner.run() {
+            Err(DotAgentError::LocalModifications { paths }) => assert_eq!(
+                paths,
+                vec![PathBuf::from("CLAUDE.md"), PathBuf::from("NOTES.md")]
+            ),
+            other => panic!("expected LocalModifications, got {:?}", other),
+        }
+        fs::write(upstream.path.join("README.md"), "npm run\n").unwrap();
+        assert!(matches!(
+            runner.run(),
+            Err(DotAgentError::LocalModifications { .. })
+        ));
+        assert!(run.output_path.join("NOTES.md").exists());
+        fs::write(upstream.path.join("README.md"), "npm\n").unwrap();
+
+        // Forced re-run with unchanged inputs comes entirely from the cache
+        let run = runner.force(true).run().unwrap();
+        assert!(run.steps.iter().all(|s| s.cached));
+        assert!(!run.output_path.join("NOTES.md").exists());
+        let runner =
+            PipelineRunner::new(base.to_path_buf(), "team", &pipeline).with_backend(backend);
+        assert!(runner.run().unwrap().up_to_date);
+    }
+
+    #[test]
+    fn test_run_refuses_foreign_output() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        let profiles = ProfileManager::new(base.to_path_buf());
+        profiles.create_profile("upstream").unwrap();
+        profiles.create_profile("team").unwrap();
+        write_rule(
+            base,
+            "pnpm",
+            "type = \"replace\"\npattern = \"npm\"\nreplacement = \"pnpm\"",
+        );
+
+        let pipeline = Pipeline {
+            source: "upstream".to_string(),
+            rules: steps(&["pnpm"]),
+            output: "team".to_string(),
+        };
+        let result = PipelineRunner::new(base.to_path_buf(), "team", &pipeline).run();
+        assert!(matches!(
+            result,
+            Err(DotAgentError::ProfileAlreadyExists { .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_with_step_params() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        let profiles = ProfileManager::new(base.to_path_buf());
+        let upstream = profiles.create_profile("upstream").unwrap();
+        fs::write(upstream.path.join("CLAUDE.md"), "Run npm test.\n").unwrap();
+        fs::create_dir_all(base.join("rules")).unwrap();
+        fs::write(
+            base.join("rules/tool.md"),
+            "+++\n[[params]]\nname = \"to\"\n+++\n# Tool\n\n```toml\n[[ops]]\ntype = \"replace\"\npattern = \"npm\"\nreplacement = \"{{to}}\"\n```\n",
+        )
+        .unwrap();
+
+        let store: PipelineStore = toml::from_str(
+            r#"
+[pipelines.team]
+source = "upstream"
+rules = [{ rule = "tool", params = { to = "bun" } }]
+output = "team"
+"#,
+        )
+        .unwrap();
+        let pipeline = store.get("team").unwrap();
+        let run = PipelineRunner::new(base.to_path_buf(), "team", pipeline)
+            .run()
+            .unwrap();
+        assert_eq!(run.steps[0].params["to"], "bun");
+        assert_eq!(
+            fs::read_to_string(run.output_path.join("CLAUDE.md")).unwrap(),
+            "Run bun test.\n"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Provenance of imported rules.
+//!
+//! Rules imported from a URL, channel or hub are recorded in
+//! `rules/sources.toml` so `rule update` can pull new revisions:
+//!
+//! ```toml
+//! [rules.pnpm]
+//! version = "1.2.0"
+//! hash = "sha256:..."
+//! imported_at = "2026-01-01 12:00:00"
+//!
+//! [rules.pnpm.origin]
+//! type = "hub"
+//! hub = "company"
+//! name = "pnpm"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DotAgentError, Result};
+use crate::install::metadata::compute_hash;
+
+use super::{validate_name, Rule, RuleManager};
+
+const SOURCES_FILE: &str = "sources.toml";
+
+/// Where an imported rule came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RuleOrigin {
+    /// A markdown file at a URL
+    Url { url: String },
+    /// A rule published by a channel
+    Channel { channel: String, name: String },
+    /// A rule listed in a hub index
+    Hub { hub: String, name: String },
+}
+
+impl RuleOrigin {
+    /// Source reference as accepted by `rule import`
+    pub fn spec(&self) -> String {
+        match self {
+            Self::Url { url } => url.clone(),
+            Self::Channel { channel, name } => format!("{}@{}", name, channel),
+            Self::Hub { hub, name } => format!("{}@{}", name, hub),
+        }
+    }
+}
+
+impl std::fmt::Display for RuleOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Url { url } => write!(f, "{}", url),
+            Self::Channel { channel, name } => write!(f, "{}@{}", name, channel),
+            Self::Hub { hub, name } => write!(f, "{}@{} (hub)", name, hub),
+        }
+    }
+}
+
+/// Rule content fetched from an origin
+#[derive(Debug, Clone)]
+pub struct FetchedRule {
+    pub origin: RuleOrigin,
+    /// Published version (index entry, else the rule's frontmatter)
+    pub version: Option<String>,
+    pub content: String,
+}
+
+/// Provenance of one imported rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSource {
+    pub origin: RuleOrigin,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Content hash at import time
+    pub hash: String,
+    pub imported_at: String,
+}
+
+/// `rules/sources.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleSources {
+    #[serde(default)]
+    pub rules: BTreeMap<String, RuleSource>,
+}
+
+impl RuleSources {
+    pub fn load(rules_dir: &Path) -> Result<Self> {
+        let path = rules_dir.join(SOURCES_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| DotAgentError::ConfigParseSimple {
+            message: format!("{}: {}", path.display(), e),
+        })
+    }
+
+    pub fn save(&self, rules_dir: &Path) -> Result<()> {
+        fs::create_dir_all(rules_dir)?;
+        fs::write(rules_dir.join(SOURCES_FILE), toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Outcome of [`RuleManager::update_from`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleUpdate {
+    UpToDate,
+    Updated {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// The local copy was edited since import (not overwritten without force)
+    LocallyModified,
+}
+
+impl RuleManager {
+    /// Provenance of an imported rule (`None` for local rules)
+    pub fn source(&self, name: &str) -> Result<Option<RuleSource>> {
+        Ok(RuleSources::load(&self.rules_dir())?.rules.remove(name))
+    }
+
+    /// Provenance of every imported rule
+    pub fn sources(&self) -> Result<RuleSources> {
+        RuleSources::load(&self.rules_dir())
+    }
+
+    /// Save fetched rule content as `name` and record where it came from.
+    pub fn install(&self, name: &str, fetched: &FetchedRule, force: bool) -> Result<Rule> {
+        validate_name(name)?;
+
+        let path = self.rule_path(name);
+        if path.exists() && !force {
+            return Err(DotAgentError::RuleAlreadyExists {
+                name: name.to_string(),
+            });
+        }
+
+        fs::create_dir_all(self.rules_dir())?;
+        fs::write(&path, &fetched.content)?;
+
+        let mut sources = self.sources()?;
+        sources.rules.insert(
+            name.to_string(),
+            RuleSource {
+                origin: fetched.origin.clone(),
+                version: fetched.version.clone(),
+                hash: compute_hash(fetched.content.as_bytes()),
+                imported_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            },
+        );
+        sources.save(&self.rules_dir())?;
+
+        Rule::load(&path)
+    }
+
+    /// Replace an imported rule with a newer revision.
+    ///
+    /// Local edits are kept unless `force` is set.
+    pub fn update_from(
+        &self,
+        name: &str,
+        fetched: &FetchedRule,
+        force: bool,
+    ) -> Result<RuleUpdate> {
+        let rule = self.get(name)?;
+        let source = self
+            .source(name)?
+            .ok_or_else(|| DotAgentError::InvalidRule {
+                name: name.to_string(),
+                message: "not imported from a channel, hub or URL".to_string(),
+            })?;
+
+        if compute_hash(fetched.content.as_bytes()) == source.hash {
+            return Ok(RuleUpdate::UpToDate);
+        }
+        if compute_hash(rule.content.as_bytes()) != source.hash && !force {
+            return Ok(RuleUpdate::LocallyModified);
+        }
+
+        self.install(name, fetched, true)?;
+        Ok(RuleUpdate::Updated {
+            from: source.version,
+            to: fetched.version.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fetched(content: &str, version: &str) -> FetchedRule {
+        FetchedRule {
+            origin: RuleOrigin::Hub {
+                hub: "company".to_string(),
+                name: "pnpm".to_string(),
+            },
+            version: Some(version.to_string()),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_install_update_and_local_edits() {
+        let temp = TempDir::new().unwrap();
+        let manager = RuleManager::new(temp.path().to_path_buf());
+
+        manager
+            .install("pnpm", &fetched("# v1\n", "1"), false)
+            .unwrap();
+        let source = manager.source("pnpm").unwrap().unwrap();
+        assert_eq!(source.origin.spec(), "pnpm@company");
+        assert_eq!(source.version.as_deref(), Some("1"));
+        assert!(manager
+            .install("pnpm", &fetched("# v1\n", "1"), false)
+            .is_err());
+
+        assert_eq!(
+            manager
+                .update_from("pnpm", &fetched("# v1\n", "1"), false)
+                .unwrap(),
+            RuleUpdate::UpToDate
+        );
+        assert_eq!(
+            manager
+                .update_from("pnpm", &fetched("# v2\n", "2"), false)
+                .unwrap(),
+            RuleUpdate::Updated {
+                from: Some("1".to_string()),
+                to: Some("2".to_string())
+            }
+        );
+        assert_eq!(manager.get("pnpm").unwrap().content, "# v2\n");
+
+        // Local edits are kept unless forced
+        manager.update("pnpm", "# mine\n").unwrap();
+        let v3 = fetched("# v3\n", "3");
+        assert_eq!(
+            manager.update_from("pnpm", &v3, false).unwrap(),
+            RuleUpdate::LocallyModified
+        );
+        assert!(matches!(
+            manager.update_from("pnpm", &v3, true).unwrap(),
+            RuleUpdate::Updated { .. }
+        ));
+
+        // Provenance follows renames and removals
+        manager.rename("pnpm", "pkg").unwrap();
+        assert!(manager.source("pnpm").unwrap().is_none());
+        assert!(manager.source("pkg").unwrap().is_some());
+        manager.remove("pkg").unwrap();
+        assert!(manager.sources().unwrap().rules.is_empty());
+    }
+}
@@ -0,0 +1,294 @@
+//! Staging area for rule changes.
+//!
+//! Planned [`FileChange`]s are split into hunks that can be accepted,
+//! rejected or edited one by one before anything is written. The accepted
+//! result is materialized back into `FileChange`s with [`Staging::changes`].
+
+use std::path::{Path, PathBuf};
+
+use similar::{DiffOp, TextDiff};
+
+use crate::channel::DiffLine;
+use crate::error::{DotAgentError, Result};
+
+use super::edit::{FileChange, FileChangeKind};
+
+/// Decision for a single hunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkDecision {
+    Accept,
+    Reject,
+}
+
+/// A contiguous block of removed/added lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedHunk {
+    /// Index of the first removed line in the original file (0-based)
+    pub start: usize,
+    /// Lines removed from the original (with line endings)
+    pub removed: Vec<String>,
+    /// Lines added in their place (with line endings)
+    pub added: Vec<String>,
+    pub decision: HunkDecision,
+}
+
+impl StagedHunk {
+    /// Diff lines of this hunk (line endings stripped)
+    pub fn diff(&self) -> Vec<DiffLine> {
+        let strip = |l: &String| l.trim_end_matches(['\n', '\r']).to_string();
+        self.removed
+            .iter()
+            .map(|l| DiffLine::Removed(strip(l)))
+            .chain(self.added.iter().map(|l| DiffLine::Added(strip(l))))
+            .collect()
+    }
+
+    /// Replace the added lines with `text` and accept the hunk
+    fn edit(&mut self, text: &str) {
+        let mut text = text.to_string();
+        let had_newline = self
+            .added
+            .last()
+            .or(self.removed.last())
+            .is_some_and(|l| l.ends_with('\n'));
+        if had_newline && !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        self.added = split_lines(&text);
+        self.decision = HunkDecision::Accept;
+    }
+}
+
+/// Staged changes to one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedFile {
+    pub path: PathBuf,
+    pub kind: FileChangeKind,
+    before: Option<String>,
+    pub hunks: Vec<StagedHunk>,
+}
+
+impl StagedFile {
+    fn new(change: FileChange) -> Self {
+        let hunks = match change.kind {
+            // Creating or deleting a file is all-or-nothing
+            FileChangeKind::Create | FileChangeKind::Delete => vec![StagedHunk {
+                start: 0,
+                removed: split_lines(change.before.as_deref().unwrap_or("")),
+                added: split_lines(change.after.as_deref().unwrap_or("")),
+                decision: HunkDecision::Accept,
+            }],
+            FileChangeKind::Modify => {
+                let before = change.before.as_deref().unwrap_or("");
+                let after = change.after.as_deref().unwrap_or("");
+                let old = split_lines(before);
+                let new = split_lines(after);
+                TextDiff::from_lines(before, after)
+                    .ops()
+                    .iter()
+                    .filter(|op| !matches!(op, DiffOp::Equal { .. }))
+                    .map(|op| StagedHunk {
+                        start: op.old_range().start,
+                        removed: old[op.old_range()].to_vec(),
+                        added: new[op.new_range()].to_vec(),
+                        decision: HunkDecision::Accept,
+                    })
+                    .collect()
+            }
+        };
+
+        Self {
+            path: change.path,
+            kind: change.kind,
+            before: change.before,
+            hunks,
+        }
+    }
+
+    /// Replace the added lines of hunk `index` with `text` and accept it
+    ///
+    /// A created file has a single hunk, so editing it replaces the whole
+    /// content. Deletions have no content to edit.
+    pub fn edit(&mut self, index: usize, text: &str) -> Result<()> {
+        let invalid = |message: String| DotAgentError::InvalidRuleEdit { message };
+        if self.kind == FileChangeKind::Delete {
+            return Err(invalid(format!(
+                "'{}' is deleted; accept or reject the deletion instead",
+                self.path.display()
+            )));
+        }
+        let hunk = self.hunks.get_mut(index).ok_or_else(|| {
+            invalid(format!(
+                "'{}' has no hunk {}",
+                self.path.display(),
+                index + 1
+            ))
+        })?;
+        hunk.edit(text);
+        Ok(())
+    }
+
+    /// Materialize the accepted hunks (`None` when nothing is accepted)
+    fn change(&self) -> Option<FileChange> {
+        let accepted = |h: &StagedHunk| h.decision == HunkDecision::Accept;
+        if !self.hunks.iter().any(accepted) {
+            return None;
+        }
+
+        let after = match self.kind {
+            FileChangeKind::Delete => None,
+            FileChangeKind::Create | FileChangeKind::Modify => {
+                let old = split_lines(self.before.as_deref().unwrap_or(""));
+                let mut out = String::new();
+                let mut pos = 0;
+                for hunk in &self.hunks {
+                    out.extend(old[pos..hunk.start].iter().map(String::as_str));
+                    let lines = if accepted(hunk) {
+                        &hunk.added
+                    } else {
+                        &hunk.removed
+                    };
+                    out.extend(lines.iter().map(String::as_str));
+                    pos = hunk.start + hunk.removed.len();
+                }
+                out.extend(old[pos..].iter().map(String::as_str));
+                Some(out)
+            }
+        };
+
+        (after != self.before).then(|| FileChange {
+            path: self.path.clone(),
+            kind: self.kind,
+            before: self.before.clone(),
+            after,
+        })
+    }
+}
+
+/// Changes waiting for review
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Staging {
+    pub files: Vec<StagedFile>,
+}
+
+impl Staging {
+    /// Stage planned changes (every hunk starts out accepted)
+    pub fn new(changes: Vec<FileChange>) -> Self {
+        Self {
+            files: changes.into_iter().map(StagedFile::new).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn hunk_count(&self) -> usize {
+        self.files.iter().map(|f| f.hunks.len()).sum()
+    }
+
+    pub fn get_mut(&mut self, path: &Path) -> Option<&mut StagedFile> {
+        self.files.iter_mut().find(|f| f.path == path)
+    }
+
+    /// Set the decision for every hunk
+    pub fn decide_all(&mut self, decision: HunkDecision) {
+        for hunk in self.files.iter_mut().flat_map(|f| f.hunks.iter_mut()) {
+            hunk.decision = decision;
+        }
+    }
+
+    /// Resulting changes after review (files with nothing accepted are dropped)
+    pub fn changes(&self) -> Vec<FileChange> {
+        self.files.iter().filter_map(StagedFile::change).collect()
+    }
+}
+
+/// Split text into lines, keeping line endings
+fn split_lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modify(before: &str, after: &str) -> FileChange {
+        FileChange {
+            path: PathBuf::from("CLAUDE.md"),
+            kind: FileChangeKind::Modify,
+            before: Some(before.to_string()),
+            after: Some(after.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_hunk_decisions() {
+        let before = "a\nb\nc\nd\ne\n";
+        let after = "a\nB\nc\nd\nE\nf\n";
+        let mut staging = Staging::new(vec![modify(before, after)]);
+        assert_eq!(staging.hunk_count(), 2);
+
+        // Everything accepted reproduces the plan
+        assert_eq!(staging.changes()[0].after.as_deref(), Some(after));
+
+        // Reject the second hunk
+        let file = staging.get_mut(Path::new("CLAUDE.md")).unwrap();
+        file.hunks[1].decision = HunkDecision::Reject;
+        assert_eq!(
+            staging.changes()[0].after.as_deref(),
+            Some("a\nB\nc\nd\ne\n")
+        );
+
+        // Edit the first hunk
+        let file = staging.get_mut(Path::new("CLAUDE.md")).unwrap();
+        file.edit(0, "b2\nb3").unwrap();
+        assert!(file.edit(2, "x").is_err());
+        assert_eq!(
+            staging.changes()[0].after.as_deref(),
+            Some("a\nb2\nb3\nc\nd\ne\n")
+        );
+
+        staging.decide_all(HunkDecision::Reject);
+        assert!(staging.changes().is_empty());
+    }
+
+    #[test]
+    fn test_create_and_delete_are_single_hunks() {
+        let create = FileChange {
+            path: PathBuf::from("rules/new.md"),
+            kind: FileChangeKind::Create,
+            before: None,
+            after: Some("# New\nline\n".to_string()),
+        };
+        let delete = FileChange {
+            path: PathBuf::from("rules/old.md"),
+            kind: FileChangeKind::Delete,
+            before: Some("# Old\n".to_string()),
+            after: None,
+        };
+        let mut staging = Staging::new(vec![create.clone(), delete.clone()]);
+        assert_eq!(staging.hunk_count(), 2);
+        assert_eq!(staging.changes(), vec![create.clone(), delete.clone()]);
+
+        // Editing a created file replaces its content; deletions can't be edited
+        let file = staging.get_mut(Path::new("rules/new.md")).unwrap();
+        file.edit(0, "# Edited\n").unwrap();
+        let file = staging.get_mut(Path::new("rules/old.md")).unwrap();
+        assert!(matches!(
+            file.edit(0, "# Kept\n"),
+            Err(DotAgentError::InvalidRuleEdit { .. })
+        ));
+        assert_eq!(staging.changes()[1], delete);
+
+        let file = staging.get_mut(Path::new("rules/old.md")).unwrap();
+        file.hunks[0].decision = HunkDecision::Reject;
+        assert_eq!(
+            staging.changes(),
+            vec![FileChange {
+                after: Some("# Edited\n".to_string()),
+                ..create
+            }]
+        );
+    }
+}
@@ -0,0 +1,438 @@
+//! Rule regression tests.
+//!
+//! Test cases live next to the rule in `rules/<name>.tests/<case>/`:
+//!
+//! ```text
+//! rules/pnpm.tests/basic/
+//! ├── input/          # profile fixture the rule is applied to
+//! ├── test.toml       # params and assertions
+//! └── response.json   # recorded LLM response (optional)
+//! ```
+//!
+//! ```toml
+//! [params]
+//! lang = "go"
+//!
+//! [[assert]]
+//! file = "CLAUDE.md"
+//! contains = 'pnpm (install|test)'
+//! not_contains = '\bnpm\b'
+//!
+//! [[assert]]
+//! file = ".claude-plugin/plugin.json"
+//! json_path = "hooks.PreToolUse[0].matcher"
+//! equals = "Bash"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::error::{DotAgentError, Result};
+use crate::llm::{ClaudeCliBackend, FixtureBackend, LlmBackend};
+use crate::profile::{copy_dir_recursive, Profile, ProfileManager};
+
+use super::edit::write_changes;
+use super::{Rule, RuleExecutor, ScratchDir};
+
+const TEST_FILE: &str = "test.toml";
+const INPUT_DIR: &str = "input";
+const RESPONSE_FILE: &str = "response.json";
+
+/// Where LLM responses come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseMode {
+    /// Call the configured backend
+    #[default]
+    Live,
+    /// Replay `response.json` (cases without one fail)
+    Recorded,
+    /// Call the backend and save the response as `response.json`
+    Record,
+}
+
+/// `test.toml` of one case
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuleTestCase {
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    #[serde(default, rename = "assert")]
+    pub assertions: Vec<Assertion>,
+}
+
+/// Checks on one output file. Every given check must hold.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Assertion {
+    /// Path relative to the output profile
+    pub file: String,
+    /// File must (or must not) exist. Defaults to `true` when other checks are given.
+    #[serde(default)]
+    pub exists: Option<bool>,
+    /// Regex that must match the file content
+    #[serde(default)]
+    pub contains: Option<String>,
+    /// Regex that must not match the file content
+    #[serde(default)]
+    pub not_contains: Option<String>,
+    /// Dotted path into a JSON file (`a.b[0].c`), compared with `equals`
+    #[serde(default)]
+    pub json_path: Option<String>,
+    #[serde(default)]
+    pub equals: Option<toml::Value>,
+}
+
+/// Outcome of one test case
+#[derive(Debug)]
+pub struct RuleTestResult {
+    pub case: String,
+    /// Failed assertions (empty when the case passed)
+    pub failures: Vec<String>,
+    /// The rule itself failed to apply
+    pub error: Option<String>,
+}
+
+impl RuleTestResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failures.is_empty()
+    }
+}
+
+/// Runs the test cases of a rule.
+pub struct RuleTestRunner<'a> {
+    rule: &'a Rule,
+    base_dir: PathBuf,
+    backend: Arc<dyn LlmBackend>,
+    mode: ResponseMode,
+}
+
+impl<'a> RuleTestRunner<'a> {
+    pub fn new(rule: &'a Rule, base_dir: PathBuf) -> Self {
+        Self {
+            rule,
+            base_dir,
+            backend: Arc::new(ClaudeCliBackend),
+            mode: ResponseMode::Live,
+        }
+    }
+
+    /// Use a different LLM backend (default: Claude CLI).
+    pub fn with_backend(mut self, backend: Arc<dyn LlmBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn mode(mut self, mode: ResponseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Names of the rule's test cases, sorted
+    pub fn cases(&self) -> Result<Vec<String>> {
+        let dir = self.rule.tests_dir();
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut cases: Vec<String> = fs::read_dir(&dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join(TEST_FILE).is_file())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        cases.sort();
+        Ok(cases)
+    }
+
+    /// Run every case (or only `only`)
+    pub fn run(&self, only: Option<&str>) -> Result<Vec<RuleTestResult>> {
+        let cases = self.cases()?;
+        if let Some(name) = only.filter(|name| !cases.iter().any(|c| c == name)) {
+            return Err(DotAgentError::InvalidRule {
+                name: self.rule.name.clone(),
+                message: format!("no test case '{}'", name),
+            });
+        }
+        cases
+            .iter()
+            .filter(|case| only.map_or(true, |name| name == *case))
+            .map(|case| self.run_case(case))
+            .collect()
+    }
+
+    fn run_case(&self, case: &str) -> Result<RuleTestResult> {
+        let case_dir = self.rule.tests_dir().join(case);
+        let content = fs::read_to_string(case_dir.join(TEST_FILE))?;
+        let spec: RuleTestCase =
+            toml::from_str(&content).map_err(|e| DotAgentError::ConfigParseSimple {
+                message: format!("{}: {}", case_dir.join(TEST_FILE).display(), e),
+            })?;
+
+        let result = |error: Option<String>, failures: Vec<String>| RuleTestResult {
+            case: case.to_string(),
+            failures,
+            error,
+        };
+
+        let rule = match self.rule.with_params(&spec.params) {
+            Ok(rule) => rule,
+            Err(e) => return Ok(result(Some(e.to_string()), Vec::new())),
+        };
+
+        // Apply the rule to a copy of the fixture
+        let work = ScratchDir::new()?;
+        let input = case_dir.join(INPUT_DIR);
+        if input.is_dir() {
+            copy_dir_recursive(&input, &work.0)?;
+        }
+        let profile = Profile::new(case.to_string(), work.0.clone());
+
+        let response_path = case_dir.join(RESPONSE_FILE);
+        let recorder = Arc::new(RecordingBackend {
+            inner: match self.mode {
+                ResponseMode::Recorded if !response_path.is_file() => {
+                    return Ok(result(
+                        Some(format!("no recorded response ({})", RESPONSE_FILE)),
+                        Vec::new(),
+                    ));
+                }
+                ResponseMode::Recorded => Arc::new(FixtureBackend::from_path(&response_path)?),
+                ResponseMode::Live | ResponseMode::Record => self.backend.clone(),
+            },
+            last: Mutex::new(None),
+        });
+
+        let profile_manager = ProfileManager::new(self.base_dir.clone());
+        let planned = RuleExecutor::new(&rule, &profile_manager)
+            .with_backend(recorder.clone())
+            .plan(&profile);
+        let changes = match planned {
+            Ok(changes) => changes,
+            Err(e) => return Ok(result(Some(e.to_string()), Vec::new())),
+        };
+        write_changes(&work.0, &changes)?;
+
+        if self.mode == ResponseMode::Record {
+            if let Some(response) = recorder.last.lock().ok().and_then(|last| last.clone()) {
+                fs::write(&response_path, response)?;
+            }
+        }
+
+        let failures = spec
+            .assertions
+            .iter()
+            .flat_map(|assertion| check(&work.0, assertion))
+            .collect();
+        Ok(result(None, failures))
+    }
+}
+
+/// Passes calls through and keeps the last response
+struct RecordingBackend {
+    inner: Arc<dyn LlmBackend>,
+    last: Mutex<Option<String>>,
+}
+
+impl LlmBackend for RecordingBackend {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn check(&self) -> Result<()> {
+        self.inner.check()
+    }
+
+    fn complete(&self, working_dir: &Path, prompt: &str) -> Result<String> {
+        let response = self.inner.complete(working_dir, prompt)?;
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some(response.clone());
+        }
+        Ok(response)
+    }
+}
+
+/// Evaluate one assertion; returns failure messages
+fn check(root: &Path, assertion: &Assertion) -> Vec<String> {
+    let path = root.join(&assertion.file);
+    let content = fs::read_to_string(&path).ok();
+    let file = &assertion.file;
+
+    let has_checks = assertion.contains.is_some()
+        || assertion.not_contains.is_some()
+        || assertion.json_path.is_some();
+    let should_exist = assertion.exists.unwrap_or(true);
+
+    match (&content, should_exist) {
+        (None, true) => return vec![format!("{}: file does not exist", file)],
+        (Some(_), false) => return vec![format!("{}: file should not exist", file)],
+        (None, false) => return Vec::new(),
+        (Some(_), true) if !has_checks => return Vec::new(),
+        (Some(_), true) => {}
+    }
+    let content = content.unwrap_or_default();
+
+    let mut failures = Vec::new();
+    if let Some(pattern) = &assertion.contains {
+        match Regex::new(pattern) {
+            Ok(re) if re.is_match(&content) => {}
+            Ok(_) => failures.push(format!("{}: does not contain /{}/", file, pattern)),
+            Err(e) => failures.push(format!("{}: invalid regex '{}': {}", file, pattern, e)),
+        }
+    }
+    if let Some(pattern) = &assertion.not_contains {
+        match Regex::new(pattern) {
+            Ok(re) if !re.is_match(&content) => {}
+            Ok(_) => failures.push(format!("{}: unexpectedly contains /{}/", file, pattern)),
+            Err(e) => failures.push(format!("{}: invalid regex '{}': {}", file, pattern, e)),
+        }
+    }
+    if let Some(json_path) = &assertion.json_path {
+        let actual = serde_json::from_str::<serde_json::Value>(&content)
+            .map_err(|e| format!("{}: not valid JSON: {}", file, e))
+            .and_then(|json| {
+                json_lookup(&json, json_path)
+                    .cloned()
+                    .ok_or_else(|| format!("{}: no value at '{}'", file, json_path))
+            });
+        match (actual, &assertion.equals) {
+            (Err(message), _) => failures.push(message),
+            (Ok(_), None) => {}
+            (Ok(actual), Some(expected)) => {
+                let expected = serde_json::to_value(expected).unwrap_or_default();
+                if actual != expected {
+                    failures.push(format!(
+                        "{}: '{}' is {}, expected {}",
+                        file, json_path, actual, expected
+                    ));
+                }
+            }
+        }
+    }
+    failures
+}
+
+/// Look up `a.b[0].c` in a JSON value
+fn json_lookup<'v>(value: &'v serde_json::Value, path: &str) -> Option<&'v serde_json::Value> {
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indexes) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indexes.split('[').filter(|s| !s.is_empty()) {
+            current = current.get(index.trim_end_matches(']').parse::<usize>().ok()?)?;
+        }
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule::RuleManager;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_json_lookup() {
+        let json: serde_json::Value =
+            serde_json::from_str(r#"{"hooks": {"Pre": [{"matcher": "Bash"}]}}"#).unwrap();
+        assert_eq!(
+            json_lookup(&json, "hooks.Pre[0].matcher"),
+            Some(&serde_json::json!("Bash"))
+        );
+        assert_eq!(json_lookup(&json, "hooks.Pre[1]"), None);
+    }
+
+    #[test]
+    fn test_run_recorded_and_declarative_cases() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path();
+        let rules = RuleManager::new(base.to_path_buf());
+
+        // LLM rule with a recorded response
+        write(&rules.rules_dir().join("docs.md"), "# Docs\n\nAdd docs.\n");
+        let case = rules.rules_dir().join("docs.tests/adds-file");
+        write(&case.join("input/CLAUDE.md"), "# Base\n");
+        write(
+            &case.join(RESPONSE_FILE),
+            r#"{"edits": [{"action": "create", "path": "docs/a.json", "content": "{\"name\": \"a\"}"}]}"#,
+        );
+        write(
+            &case.join(TEST_FILE),
+            "[[assert]]\nfile = \"docs/a.json\"\njson_path = \"name\"\nequals = \"a\"\n\n[[assert]]\nfile = \"CLAUDE.md\"\ncontains = \"Base\"\nnot_contains = \"Docs\"\n\n[[assert]]\nfile = \"missing.md\"\nexists = false\n",
+        );
+        let failing = rules.rules_dir().join("docs.tests/wrong");
+        write(&failing.join(RESPONSE_FILE), r#"{"edits": []}"#);
+        write(
+            &failing.join(TEST_FILE),
+            "[[assert]]\nfile = \"docs/a.json\"\n",
+        );
+
+        let rule = rules.get("docs").unwrap();
+        let results = RuleTestRunner::new(&rule, base.to_path_buf())
+            .mode(ResponseMode::Recorded)
+            .run(None)
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].passed(), "{:?}", results[0]);
+        assert_eq!(results[1].case, "wrong");
+        assert_eq!(
+            results[1].failures,
+            vec!["docs/a.json: file does not exist"]
+        );
+        assert!(RuleTestRunner::new(&rule, base.to_path_buf())
+            .run(Some("missing"))
+            .is_err());
+
+        // Declarative rule needs no response at all
+        write(
+            &rules.rules_dir().join("pnpm.md"),
+            "# pnpm\n\n```toml\n[[ops]]\ntype = \"replace\"\npattern = \"npm\"\nreplacement = \"pnpm\"\n```\n",
+        );
+        let case = rules.rules_dir().join("pnpm.tests/basic");
+        write(&case.join("input/CLAUDE.md"), "npm test\n");
+        write(
+            &case.join(TEST_FILE),
+            "[[assert]]\nfile = \"CLAUDE.md\"\ncontains = \"^pnpm test\"\n",
+        );
+        let rule = rules.get("pnpm").unwrap();
+        let results = RuleTestRunner::new(&rule, base.to_path_buf())
+            .run(None)
+            .unwrap();
+        assert!(results[0].passed(), "{:?}", results[0]);
+    }
+
+    #[test]
+    fn test_record_mode_saves_response() {
+        let temp = TempDir::new().unwrap();
+        let rules = RuleManager::new(temp.path().to_path_buf());
+        write(&rules.rules_dir().join("docs.md"), "# Docs\n");
+        let case = rules.rules_dir().join("docs.tests/basic");
+        write(
+            &case.join(TEST_FILE),
+            "[[assert]]\nfile = \"a.md\"\ncontains = \"A\"\n",
+        );
+
+        let response = r#"{"edits": [{"action": "create", "path": "a.md", "content": "A"}]}"#;
+        let rule = rules.get("docs").unwrap();
+        let results = RuleTestRunner::new(&rule, temp.path().to_path_buf())
+            .with_backend(Arc::new(FixtureBackend::new(vec![response.to_string()])))
+            .mode(ResponseMode::Record)
+            .run(Some("basic"))
+            .unwrap();
+        assert!(results[0].passed());
+        assert_eq!(
+            fs::read_to_string(case.join(RESPONSE_FILE)).unwrap(),
+            response
+        );
+    }
+}
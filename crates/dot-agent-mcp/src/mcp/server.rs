@@ -15,8 +15,8 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 
 use dot_agent_core::{
-    FileStatus, IgnoreConfig, InstallOptions, Installer, ProfileManager, SnapshotManager,
-    SnapshotTrigger,
+    ComponentFinder, ComponentKind, FileStatus, IgnoreConfig, InstallOptions, Installer,
+    ProfileManager, SnapshotManager, SnapshotTrigger,
 };
 
 // =============================================================================
@@ -149,6 +149,7 @@ impl ServerHandler for DotAgentMcp {
                  \n\
                  Tools:\n\
                  - `list`: List available profiles\n\
+                 - `find`: Find skills, commands, agents and rules across profiles\n\
                  - `installed`: Show installed profiles at a target\n\
                  - `install`: Install a profile\n\
                  - `remove`: Remove an installed profile\n\
@@ -195,6 +196,16 @@ impl ServerHandler for DotAgentMcp {
 #[derive(Debug, Deserialize, JsonSchema)]
 struct ListParams {}
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FindParams {
+    /// Text to look for in component names, descriptions and rule text
+    query: String,
+    /// Only this component kind (skill, command, agent, rule)
+    kind: Option<String>,
+    /// Only this profile
+    profile: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 struct InstalledParams {
     /// Target directory path (default: current dir)
@@ -328,6 +339,67 @@ impl DotAgentMcp {
         ok_text(lines.join("\n"))
     }
 
+    #[tool(
+        name = "find",
+        description = "Find skills, commands, agents and rules in local and installed profiles",
+        annotations(
+            read_only_hint = true,
+            destructive_hint = false,
+            idempotent_hint = true
+        )
+    )]
+    async fn tool_find(
+        &self,
+        Parameters(params): Parameters<FindParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut finder = ComponentFinder::new(self.base_dir.clone());
+        if let Some(kind) = params.kind {
+            let kind: ComponentKind = kind
+                .parse()
+                .map_err(|e: String| McpError::invalid_params(e, None))?;
+            finder = finder.with_kind(kind);
+        }
+        if let Some(profile) = params.profile {
+            finder = finder.with_profile(profile);
+        }
+
+        let matches = finder.find(&params.query).map_err(Self::to_mcp_error)?;
+        if matches.is_empty() {
+            return ok_text(format!("No components matching '{}'.", params.query));
+        }
+
+        let mut lines = vec![format!(
+            "Components matching '{}' ({}):",
+            params.query,
+            matches.len()
+        )];
+        for m in &matches {
+            lines.push(format!(
+                "  [{}] {} — {}:{}",
+                m.component.kind,
+                m.component.name,
+                m.profile,
+                m.component.path.display()
+            ));
+            if let Some(description) = &m.component.description {
+                lines.push(format!("    {}", description));
+            }
+            if !m.categories.is_empty() {
+                lines.push(format!("    categories: {}", m.categories.join(", ")));
+            }
+            let targets: Vec<String> = m.targets.iter().map(|t| t.display().to_string()).collect();
+            lines.push(format!(
+                "    installed: {}",
+                if targets.is_empty() {
+                    "no".to_string()
+                } else {
+                    targets.join(", ")
+                }
+            ));
+        }
+        ok_text(lines.join("\n"))
+    }
+
     #[tool(
         name = "installed",
         description = "Show installed profiles at a target directory",
@@ -726,6 +798,9 @@ impl DotAgentMcp {
                                             "\n  {}: {} unchanged, {} modified, {} missing",
                                             name, diff.unchanged, diff.modified, diff.missing
                                         ));
+                                        if let Some(filter) = meta.get_categories(name) {
+                                            lines.push(format!("    categories: {}", filter));
+                                        }
                                     }
                                     Err(e) => {
                                         lines.push(format!("\n  {}: [error] {}", name, e));